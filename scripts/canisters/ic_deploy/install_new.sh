@@ -7,6 +7,7 @@ dfx build --network=ic user_index
 dfx build --network=ic configuration
 dfx build --network=ic data_backup
 dfx build --network=ic post_cache
+dfx build --network=ic platform_orchestrator
 
 # dfx canister install data_backup --mode reinstall --network ic --argument "(record {
 #   known_principal_ids = opt vec {
@@ -11,4 +11,5 @@ dfx build --network=ic post_cache
 dfx canister install configuration --network ic --mode upgrade --argument "(record {})"
 dfx canister install data_backup --network ic --mode upgrade --argument "(record {})"
 dfx canister install post_cache --network ic --mode upgrade --argument "(record {})"
+./scripts/canisters/upload_individual_user_template_wasm.sh "$(git describe --tags --always)" --network ic
 dfx canister install user_index --network ic --mode upgrade --argument "(record {})"
\ No newline at end of file
@@ -22,6 +22,7 @@ done
 dfx canister create --no-wallet configuration
 dfx canister create --no-wallet data_backup
 dfx canister create --no-wallet individual_user_template
+dfx canister create --no-wallet platform_orchestrator
 dfx canister create --no-wallet post_cache
 dfx canister create --no-wallet user_index
 
@@ -29,6 +30,7 @@ dfx build individual_user_template
 gzip -f -1 ./target/wasm32-unknown-unknown/release/individual_user_template.wasm
 dfx build configuration
 dfx build data_backup
+dfx build platform_orchestrator
 dfx build user_index
 dfx build post_cache
 gzip -f -1 ./target/wasm32-unknown-unknown/release/post_cache.wasm
@@ -126,6 +128,23 @@ dfx canister install post_cache --argument "(record {
   }
 })"
 
+dfx canister install platform_orchestrator --argument "(record {
+  known_principal_ids = opt vec {
+    record {
+      variant { UserIdGlobalSuperAdmin };
+      principal \"$(dfx identity get-principal)\";
+    };
+    record {
+      variant { CanisterIdConfiguration };
+      principal \"$(dfx canister id configuration)\";
+    };
+    record {
+      variant { CanisterIdPlatformOrchestrator };
+      principal \"$(dfx canister id platform_orchestrator)\";
+    };
+  };
+})"
+
 dfx canister install user_index --argument "(record {
   known_principal_ids = opt vec {
     record {
@@ -148,6 +167,10 @@ dfx canister install user_index --argument "(record {
       variant { CanisterIdUserIndex };
       principal \"$(dfx canister id user_index)\";
     };
+    record {
+      variant { CanisterIdPlatformOrchestrator };
+      principal \"$(dfx canister id platform_orchestrator)\";
+    };
   };
   access_control_map = opt vec {
     record {
@@ -157,3 +180,12 @@ dfx canister install user_index --argument "(record {
   };
   version= \"v1.0.0\"
 })"
+
+./scripts/canisters/upload_individual_user_template_wasm.sh v1.0.0
+
+# the local replica runs a single subnet, so its id is left at the management canister
+dfx canister call platform_orchestrator register_user_index_canister "(
+  principal \"$(dfx canister id user_index)\",
+  principal \"aaaaa-aa\",
+  100_000 : nat64
+)"
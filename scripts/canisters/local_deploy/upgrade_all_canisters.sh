@@ -23,6 +23,7 @@ dfx build individual_user_template
 gzip -f -1 ./target/wasm32-unknown-unknown/release/individual_user_template.wasm
 dfx build configuration
 dfx build data_backup
+dfx build platform_orchestrator
 dfx build user_index
 dfx build post_cache
 gzip -f -1 ./target/wasm32-unknown-unknown/release/post_cache.wasm
@@ -34,9 +35,11 @@ fi
 
 dfx canister install configuration --mode upgrade --argument "(record {})"
 dfx canister install data_backup --mode upgrade --argument "(record {})"
+dfx canister install platform_orchestrator --mode upgrade --argument "(record {})"
 dfx canister install post_cache --mode upgrade --argument "(record {
     version= \"v1.1.0\"
 })"
+./scripts/canisters/upload_individual_user_template_wasm.sh v1.1.0
 dfx canister install user_index --mode upgrade --argument "(record {
   version= \"v1.1.0\"
 })"
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Uploads the built individual_user_template wasm to user_index in chunks.
+# Usage: upload_individual_user_template_wasm.sh <version> [extra dfx canister call options, e.g. --network ic]
+
+version=$1
+shift
+
+wasm_path=./target/wasm32-unknown-unknown/release/individual_user_template.wasm.gz
+chunk_size_in_bytes=524288
+
+to_candid_blob() {
+  sed 's/../\\&/g'
+}
+
+wasm_hash=$(sha256sum "$wasm_path" | cut -d ' ' -f 1 | to_candid_blob)
+
+chunks_dir=$(mktemp -d)
+trap 'rm -rf "$chunks_dir"' EXIT
+split -b "$chunk_size_in_bytes" -d -a 3 "$wasm_path" "$chunks_dir/chunk_"
+
+total_chunks=$(ls "$chunks_dir" | wc -l)
+chunk_index=0
+
+for chunk in "$chunks_dir"/chunk_*; do
+  printf '(record { version = "%s"; wasm_hash = blob "%s"; chunk_index = %d : nat32; total_chunks = %d : nat32; chunk = blob "%s" })' \
+    "$version" "$wasm_hash" "$chunk_index" "$total_chunks" \
+    "$(od -An -v -tx1 "$chunk" | tr -d ' \n' | to_candid_blob)" > "$chunks_dir/argument"
+
+  dfx canister call "$@" user_index upload_individual_user_template_wasm_chunk --argument-file "$chunks_dir/argument"
+  chunk_index=$((chunk_index + 1))
+done
@@ -0,0 +1,18 @@
+use shared_utils::canister_specific::data_backup::types::backup_snapshot::BackupSnapshot;
+
+use crate::CANISTER_DATA;
+
+/// The snapshots kept by the retention policy, oldest first
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_backup_snapshots() -> Vec<BackupSnapshot> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .heap_data
+            .backup_snapshots
+            .values()
+            .cloned()
+            .collect()
+    })
+}
@@ -0,0 +1,4 @@
+pub mod get_backup_snapshots;
+pub mod restore_backup_snapshot_to_individual_users_canister;
+pub mod take_backup_snapshot;
+pub mod update_backup_snapshot_retention_policy;
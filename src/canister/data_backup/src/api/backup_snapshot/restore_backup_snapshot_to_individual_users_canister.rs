@@ -0,0 +1,201 @@
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::data_backup::types::{
+        all_user_data::{AllUserData, UserOwnedCanisterData},
+        backup_manifest::BackupSection,
+    },
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{
+    api::individual_user_backup::restore_backed_up_data_to_individual_users_canister::send_all_backed_up_data_to_users_canister,
+    data::memory_layout::CanisterData, CANISTER_DATA,
+};
+
+/// Restores a user's data as it was when a snapshot was taken, to the canister the user has now.
+/// The canister's data and the user's backup are replaced, so items added after the snapshot are
+/// dropped.
+///
+/// # Access Control
+/// Only the global super admin can restore snapshots.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+async fn restore_backup_snapshot_to_individual_users_canister(
+    user_principal_id: Principal,
+    snapshot_id: u64,
+) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    let users_data = CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_backup_snapshot_data_to_restore_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            user_principal_id,
+            snapshot_id,
+        )
+    })?;
+
+    call::call::<_, ()>(
+        users_data.user_canister_id,
+        "clear_backed_up_sections_from_data_backup_canister",
+        (get_sections_to_clear_before_restore(&users_data.canister_data),),
+    )
+    .await
+    .map_err(|e| format!("Failed to call the clear_backed_up_sections_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
+
+    send_all_backed_up_data_to_users_canister(&users_data).await?;
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .user_backup_store
+            .insert_all_user_data(&users_data)
+    });
+
+    Ok(())
+}
+
+/// The collections that are restored item by item. Bets placed are only cleared when the snapshot
+/// holds them, as the canister keeps what it has for sections a backup does not hold.
+fn get_sections_to_clear_before_restore(
+    canister_data: &UserOwnedCanisterData,
+) -> Vec<BackupSection> {
+    let mut sections = vec![
+        BackupSection::Posts,
+        BackupSection::TokenData,
+        BackupSection::PrincipalsIFollow,
+        BackupSection::PrincipalsThatFollowMe,
+    ];
+    if canister_data.all_hot_or_not_bets_placed.is_some() {
+        sections.push(BackupSection::HotOrNotBetsPlaced);
+    }
+
+    sections
+}
+
+fn get_backup_snapshot_data_to_restore_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+    snapshot_id: u64,
+) -> Result<AllUserData, String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    let mut users_data =
+        canister_data.get_backup_snapshot_all_user_data(snapshot_id, &user_principal_id)?;
+
+    // * the user may have been moved to another canister since the snapshot was taken
+    canister_data.migrate_legacy_user_data(&user_principal_id);
+    if let Some(manifest) = canister_data
+        .user_backup_store
+        .get_manifest(&user_principal_id)
+    {
+        users_data.user_canister_id = manifest.user_canister_id;
+    }
+
+    // * the restored canister tracks changes from the start, so its next backup run sends
+    // * everything
+    canister_data
+        .user_backup_store
+        .reset_acknowledged_change_sequence_number(&user_principal_id);
+
+    Ok(users_data)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::{
+        all_user_data::UserOwnedCanisterData, backup_snapshot::BackupSnapshotKind,
+    };
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_backup_snapshot_data_to_restore_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+
+        let snapshot_id = canister_data
+            .start_backup_snapshot(BackupSnapshotKind::Daily, None, SystemTime::now())
+            .unwrap();
+        while canister_data.has_pending_backup_snapshot_work() {
+            canister_data.copy_users_into_backup_snapshot_batch(10);
+        }
+
+        // * the user was reprovisioned after the snapshot and backed up from the new canister
+        let mut manifest = canister_data
+            .user_backup_store
+            .get_manifest(&get_mock_user_alice_principal_id())
+            .unwrap();
+        manifest.user_canister_id = get_mock_user_bob_canister_id();
+        manifest.last_acknowledged_change_sequence_number = Some(5);
+        canister_data.user_backup_store.insert_manifest(manifest);
+
+        assert_eq!(
+            get_backup_snapshot_data_to_restore_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+                snapshot_id,
+            )
+            .unwrap_err(),
+            "Unauthorized"
+        );
+        assert_eq!(
+            get_backup_snapshot_data_to_restore_impl(
+                &mut canister_data,
+                get_global_super_admin_principal_id(),
+                get_mock_user_alice_principal_id(),
+                snapshot_id + 1,
+            )
+            .unwrap_err(),
+            "Snapshot not found"
+        );
+
+        let users_data = get_backup_snapshot_data_to_restore_impl(
+            &mut canister_data,
+            get_global_super_admin_principal_id(),
+            get_mock_user_alice_principal_id(),
+            snapshot_id,
+        )
+        .unwrap();
+        assert_eq!(users_data.user_canister_id, get_mock_user_bob_canister_id());
+        assert!(
+            !get_sections_to_clear_before_restore(&users_data.canister_data)
+                .contains(&BackupSection::HotOrNotBetsPlaced)
+        );
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .last_acknowledged_change_sequence_number,
+            None
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_snapshot::BackupSnapshotKind,
+    common::{
+        types::{known_principal::KnownPrincipalType, version_details::VersionDetails},
+        utils::system_time,
+    },
+};
+
+use crate::{
+    data::memory_layout::CanisterData,
+    util::backup_snapshot_job::run_backup_snapshot_job_in_batches, CANISTER_DATA,
+};
+
+/// Starts copying every user's backup into a new snapshot and returns its ID. The snapshot can
+/// be restored from once `get_backup_snapshots` lists it as complete.
+///
+/// # Access Control
+/// Only the global super admin and the user index canister can take snapshots.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn take_backup_snapshot(
+    kind: BackupSnapshotKind,
+    source_wasm_version: Option<VersionDetails>,
+) -> Result<u64, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    let snapshot_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+        take_backup_snapshot_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            kind,
+            source_wasm_version,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })?;
+
+    run_backup_snapshot_job_in_batches();
+
+    Ok(snapshot_id)
+}
+
+fn take_backup_snapshot_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    kind: BackupSnapshotKind,
+    source_wasm_version: Option<VersionDetails>,
+    current_time: SystemTime,
+) -> Result<u64, String> {
+    let known_principal_ids = &canister_data.heap_data.known_principal_ids;
+    let is_authorized = [
+        KnownPrincipalType::UserIdGlobalSuperAdmin,
+        KnownPrincipalType::CanisterIdUserIndex,
+    ]
+    .iter()
+    .any(|principal_type| known_principal_ids.get(principal_type) == Some(&caller_principal_id));
+
+    if !is_authorized {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.start_backup_snapshot(kind, source_wasm_version, current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::backup_snapshot::BackupSnapshotStatus;
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_user_index,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_take_backup_snapshot_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        assert_eq!(
+            take_backup_snapshot_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                BackupSnapshotKind::Daily,
+                None,
+                SystemTime::now(),
+            ),
+            Err("Unauthorized".to_string())
+        );
+
+        let snapshot_id = take_backup_snapshot_impl(
+            &mut canister_data,
+            get_mock_canister_id_user_index(),
+            BackupSnapshotKind::PreUpgrade,
+            Some(VersionDetails {
+                version_number: 7,
+                version: "v7".to_string(),
+            }),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        let snapshot = &canister_data.heap_data.backup_snapshots[&snapshot_id];
+        assert_eq!(snapshot.kind, BackupSnapshotKind::PreUpgrade);
+        assert_eq!(snapshot.status, BackupSnapshotStatus::InProgress);
+        assert_eq!(
+            snapshot
+                .source_wasm_version
+                .as_ref()
+                .unwrap()
+                .version_number,
+            7
+        );
+
+        // * one snapshot at a time
+        assert!(take_backup_snapshot_impl(
+            &mut canister_data,
+            get_global_super_admin_principal_id(),
+            BackupSnapshotKind::Daily,
+            None,
+            SystemTime::now(),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_snapshot::BackupSnapshotRetentionPolicy,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{
+    data::memory_layout::CanisterData,
+    util::backup_snapshot_job::run_backup_snapshot_job_in_batches, CANISTER_DATA,
+};
+
+/// Sets how many snapshots of each kind are kept. Snapshots the new policy does not keep are
+/// deleted right away.
+///
+/// # Access Control
+/// Only the global super admin can change the policy.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn update_backup_snapshot_retention_policy(
+    retention_policy: BackupSnapshotRetentionPolicy,
+) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_backup_snapshot_retention_policy_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            retention_policy,
+        )
+    })?;
+
+    run_backup_snapshot_job_in_batches();
+
+    Ok(())
+}
+
+fn update_backup_snapshot_retention_policy_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    retention_policy: BackupSnapshotRetentionPolicy,
+) -> Result<(), String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.heap_data.backup_snapshot_retention_policy = retention_policy;
+    canister_data.apply_backup_snapshot_retention_policy();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::backup_snapshot::{
+        BackupSnapshot, BackupSnapshotKind, BackupSnapshotStatus,
+    };
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_update_backup_snapshot_retention_policy_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        (0..3).for_each(|snapshot_id| {
+            canister_data.heap_data.backup_snapshots.insert(
+                snapshot_id,
+                BackupSnapshot {
+                    snapshot_id,
+                    kind: BackupSnapshotKind::Daily,
+                    taken_at: SystemTime::now(),
+                    source_wasm_version: None,
+                    status: BackupSnapshotStatus::Complete,
+                    user_count: 0,
+                    unreadable_user_count: 0,
+                },
+            );
+        });
+        let retention_policy = BackupSnapshotRetentionPolicy {
+            daily_snapshots_to_keep: 1,
+            pre_upgrade_snapshots_to_keep: 5,
+        };
+
+        assert_eq!(
+            update_backup_snapshot_retention_policy_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                retention_policy,
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(canister_data.heap_data.backup_snapshots.len(), 3);
+
+        assert!(update_backup_snapshot_retention_policy_impl(
+            &mut canister_data,
+            get_global_super_admin_principal_id(),
+            retention_policy,
+        )
+        .is_ok());
+        assert_eq!(
+            canister_data.heap_data.backup_snapshot_retention_policy,
+            retention_policy
+        );
+        assert_eq!(
+            canister_data
+                .heap_data
+                .backup_snapshots
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(
+            canister_data.heap_data.backup_snapshot_ids_pending_deletion,
+            vec![0, 1]
+        );
+    }
+}
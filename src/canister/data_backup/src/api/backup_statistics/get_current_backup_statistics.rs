@@ -1,16 +1,86 @@
-use shared_utils::canister_specific::data_backup::types::backup_statistics::BackupStatistics;
+use std::time::SystemTime;
 
-use crate::CANISTER_DATA;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_statistics::BackupStatistics,
+    common::utils::system_time,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+const WASM_PAGE_SIZE_IN_BYTES: u64 = 64 * 1024;
 
 #[ic_cdk::query]
 #[candid::candid_method(query)]
 fn get_current_backup_statistics() -> BackupStatistics {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_current_backup_statistics_impl(
+            &canister_data_ref_cell.borrow(),
+            ic_cdk::api::stable::stable64_size() * WASM_PAGE_SIZE_IN_BYTES,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn get_current_backup_statistics_impl(
+    canister_data: &CanisterData,
+    stable_memory_size_in_bytes: u64,
+    current_time: SystemTime,
+) -> BackupStatistics {
+    let last_completed_backup_coverage = canister_data
+        .heap_data
+        .last_completed_backup_coverage
+        .clone();
+    let oldest_backup_age_in_seconds = last_completed_backup_coverage
+        .as_ref()
+        .and_then(|coverage| coverage.oldest_backup_completed_at)
+        .map(|oldest_backup_completed_at| {
+            current_time
+                .duration_since(oldest_backup_completed_at)
+                .unwrap_or_default()
+                .as_secs()
+        });
+
     BackupStatistics {
-        number_of_user_entries: CANISTER_DATA.with(|canister_data_ref_cell| {
-            canister_data_ref_cell
-                .borrow()
-                .user_principal_id_to_all_user_data_map
-                .len()
-        }),
+        number_of_user_entries: canister_data.get_user_count(),
+        stable_memory_size_in_bytes,
+        oldest_backup_age_in_seconds,
+        last_completed_backup_coverage,
+        last_completed_backup_verification: canister_data
+            .heap_data
+            .last_completed_backup_verification
+            .clone(),
+        backup_verification_in_progress: canister_data
+            .heap_data
+            .backup_verification_in_progress
+            .clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use shared_utils::canister_specific::data_backup::types::backup_statistics::BackupCoverageSummary;
+
+    use super::*;
+
+    #[test]
+    fn test_get_current_backup_statistics_impl() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        let backup_statistics = get_current_backup_statistics_impl(&canister_data, 1024, now);
+        assert_eq!(backup_statistics.stable_memory_size_in_bytes, 1024);
+        assert_eq!(backup_statistics.oldest_backup_age_in_seconds, None);
+
+        canister_data.heap_data.last_completed_backup_coverage = Some(BackupCoverageSummary {
+            oldest_backup_completed_at: Some(now - Duration::from_secs(3600)),
+            ..BackupCoverageSummary::new(now)
+        });
+        assert_eq!(
+            get_current_backup_statistics_impl(&canister_data, 1024, now)
+                .oldest_backup_age_in_seconds,
+            Some(3600)
+        );
     }
 }
@@ -1,7 +1,7 @@
 use candid::Principal;
 use shared_utils::{
     canister_specific::data_backup::types::all_user_data::AllUserData,
-    common::types::{known_principal::KnownPrincipalType, storable_principal::StorablePrincipal},
+    common::types::known_principal::KnownPrincipalType,
 };
 
 use crate::CANISTER_DATA;
@@ -27,12 +27,11 @@ fn get_individual_users_backup_data_entry(
         return None;
     }
 
-    CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell
-            .borrow()
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(
-                principal_id_of_user_whose_data_is_being_queried,
-            ))
-    })
+    CANISTER_DATA
+        .with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow()
+                .get_all_user_data(&principal_id_of_user_whose_data_is_being_queried)
+        })
+        .unwrap_or_else(|e| panic!("Failed to read the user's backup: {}", e))
 }
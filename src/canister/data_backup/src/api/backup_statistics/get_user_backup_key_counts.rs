@@ -0,0 +1,110 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_key_counts::BackupKeyCounts,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// The key counts of a user's backup, which the user index checks before and after upgrading
+/// the user's canister
+///
+/// # Access Control
+/// Only the global super admin and the user index canister can call this.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_backup_key_counts(user_principal_id: Principal) -> Result<BackupKeyCounts, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_backup_key_counts_impl(
+            &canister_data_ref_cell.borrow(),
+            caller_principal_id,
+            user_principal_id,
+        )
+    })
+}
+
+fn get_user_backup_key_counts_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+) -> Result<BackupKeyCounts, String> {
+    let known_principal_ids = &canister_data.heap_data.known_principal_ids;
+    let is_authorized = [
+        KnownPrincipalType::UserIdGlobalSuperAdmin,
+        KnownPrincipalType::CanisterIdUserIndex,
+    ]
+    .iter()
+    .any(|principal_type| known_principal_ids.get(principal_type) == Some(&caller_principal_id));
+
+    if !is_authorized {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data
+        .get_all_user_data(&user_principal_id)?
+        .map(|all_user_data| BackupKeyCounts::from(&all_user_data.canister_data))
+        .ok_or("No user data found".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use shared_utils::canister_specific::data_backup::types::all_user_data::{
+        AllUserData, UserOwnedCanisterData,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_backup_key_counts_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        assert_eq!(
+            get_user_backup_key_counts_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            get_user_backup_key_counts_impl(
+                &canister_data,
+                get_mock_canister_id_user_index(),
+                get_mock_user_alice_principal_id(),
+            ),
+            Err("No user data found".to_string())
+        );
+
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData {
+                    all_hot_or_not_bets_placed: Some(BTreeMap::new()),
+                    ..Default::default()
+                },
+            });
+
+        assert_eq!(
+            get_user_backup_key_counts_impl(
+                &canister_data,
+                get_mock_canister_id_user_index(),
+                get_mock_user_alice_principal_id(),
+            ),
+            Ok(BackupKeyCounts::default())
+        );
+    }
+}
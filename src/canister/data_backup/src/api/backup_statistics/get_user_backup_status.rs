@@ -0,0 +1,99 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_statistics::UserBackupStatus,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// # Access Control
+/// Only the global super admin can call this.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_backup_status(user_principal_id: Principal) -> Result<UserBackupStatus, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_backup_status_impl(
+            &canister_data_ref_cell.borrow(),
+            caller_principal_id,
+            user_principal_id,
+        )
+    })
+}
+
+fn get_user_backup_status_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+) -> Result<UserBackupStatus, String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data
+        .user_backup_store
+        .get_manifest(&user_principal_id)
+        .map(|manifest| UserBackupStatus::from(&manifest))
+        .ok_or("No user data found".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::backup_manifest::UserBackupManifest;
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_backup_status_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        assert_eq!(
+            get_user_backup_status_impl(
+                &canister_data,
+                get_global_super_admin_principal_id(),
+                get_mock_user_alice_principal_id(),
+            ),
+            Err("No user data found".to_string())
+        );
+
+        canister_data
+            .user_backup_store
+            .insert_manifest(UserBackupManifest::new(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ));
+
+        assert_eq!(
+            get_user_backup_status_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            get_user_backup_status_impl(
+                &canister_data,
+                get_global_super_admin_principal_id(),
+                get_mock_user_alice_principal_id(),
+            )
+            .map(|status| status.user_canister_id),
+            Ok(get_mock_user_alice_canister_id())
+        );
+    }
+}
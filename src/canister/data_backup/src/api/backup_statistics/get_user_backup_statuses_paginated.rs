@@ -0,0 +1,132 @@
+use std::ops::Bound;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_statistics::UserBackupStatus,
+    common::types::{known_principal::KnownPrincipalType, storable_principal::StorablePrincipal},
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+const MAX_USER_BACKUP_STATUSES_PER_PAGE: u64 = 100;
+
+/// The backup status of each user, in order of principal, starting after `start_after`
+///
+/// # Access Control
+/// Only the global super admin can call this.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_backup_statuses_paginated(
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Result<Vec<UserBackupStatus>, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_backup_statuses_paginated_impl(
+            &canister_data_ref_cell.borrow(),
+            caller_principal_id,
+            start_after,
+            limit,
+        )
+    })
+}
+
+fn get_user_backup_statuses_paginated_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Result<Vec<UserBackupStatus>, String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    let start_bound = match start_after {
+        Some(start_after) => Bound::Excluded(StorablePrincipal(start_after)),
+        None => Bound::Unbounded,
+    };
+
+    Ok(canister_data
+        .user_backup_store
+        .user_principal_id_to_manifest_map
+        .range((start_bound, Bound::Unbounded))
+        .take(limit.min(MAX_USER_BACKUP_STATUSES_PER_PAGE) as usize)
+        .map(|(_, manifest)| UserBackupStatus::from(&manifest))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::backup_manifest::UserBackupManifest;
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_backup_statuses_paginated_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let mut user_principal_ids = vec![];
+        for (user_principal_id, user_canister_id) in [
+            (
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ),
+            (
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+            ),
+        ] {
+            canister_data
+                .user_backup_store
+                .insert_manifest(UserBackupManifest::new(user_principal_id, user_canister_id));
+            user_principal_ids.push(user_principal_id);
+        }
+        user_principal_ids.sort();
+
+        assert_eq!(
+            get_user_backup_statuses_paginated_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                None,
+                10
+            ),
+            Err("Unauthorized".to_string())
+        );
+
+        let first_page = get_user_backup_statuses_paginated_impl(
+            &canister_data,
+            get_global_super_admin_principal_id(),
+            None,
+            1,
+        )
+        .unwrap();
+        assert_eq!(first_page.len(), 1);
+        assert_eq!(first_page[0].user_principal_id, user_principal_ids[0]);
+        assert_eq!(first_page[0].last_backed_up_at, None);
+
+        let second_page = get_user_backup_statuses_paginated_impl(
+            &canister_data,
+            get_global_super_admin_principal_id(),
+            Some(first_page[0].user_principal_id),
+            10,
+        )
+        .unwrap();
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].user_principal_id, user_principal_ids[1]);
+    }
+}
@@ -0,0 +1,113 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+const MAX_USER_PRINCIPAL_IDS_PER_PAGE: u64 = 1000;
+
+/// Users of the user index that the last completed backup coverage scan found without a backup,
+/// in order, starting after `start_after`
+///
+/// # Access Control
+/// Only the global super admin can call this.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_principal_ids_without_backup_paginated(
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Result<Vec<Principal>, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_principal_ids_without_backup_paginated_impl(
+            &canister_data_ref_cell.borrow(),
+            caller_principal_id,
+            start_after,
+            limit,
+        )
+    })
+}
+
+fn get_user_principal_ids_without_backup_paginated_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Result<Vec<Principal>, String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(canister_data.get_user_principal_ids_without_backup(
+        start_after,
+        limit.min(MAX_USER_PRINCIPAL_IDS_PER_PAGE) as usize,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_principal_ids_without_backup_paginated_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data
+            .start_backup_coverage_scan(SystemTime::now())
+            .unwrap();
+        canister_data.record_user_index_page_for_backup_coverage(&[
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+        ]);
+        canister_data.complete_backup_coverage_scan(SystemTime::now());
+        let mut user_principal_ids = [
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+        ];
+        user_principal_ids.sort();
+
+        assert_eq!(
+            get_user_principal_ids_without_backup_paginated_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                None,
+                10
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            get_user_principal_ids_without_backup_paginated_impl(
+                &canister_data,
+                get_global_super_admin_principal_id(),
+                None,
+                1
+            ),
+            Ok(vec![user_principal_ids[0]])
+        );
+        assert_eq!(
+            get_user_principal_ids_without_backup_paginated_impl(
+                &canister_data,
+                get_global_super_admin_principal_id(),
+                Some(user_principal_ids[0]),
+                10
+            ),
+            Ok(vec![user_principal_ids[1]])
+        );
+    }
+}
@@ -1,2 +1,8 @@
 pub mod get_current_backup_statistics;
 pub mod get_individual_users_backup_data_entry;
+pub mod get_user_backup_key_counts;
+pub mod get_user_backup_status;
+pub mod get_user_backup_statuses_paginated;
+pub mod get_user_principal_ids_without_backup_paginated;
+pub mod start_backup_coverage_scan;
+pub mod start_backup_verification;
@@ -0,0 +1,90 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::common::{types::known_principal::KnownPrincipalType, utils::system_time};
+
+use crate::{
+    data::memory_layout::CanisterData,
+    util::backup_coverage_job::run_backup_coverage_scan_in_batches, CANISTER_DATA,
+};
+
+/// Starts computing the backup coverage aggregates of `get_current_backup_statistics` and the
+/// list of users without a backup. Also runs daily.
+///
+/// # Access Control
+/// Only the global super admin can start a scan.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn start_backup_coverage_scan() -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        start_backup_coverage_scan_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })?;
+
+    run_backup_coverage_scan_in_batches();
+
+    Ok(())
+}
+
+fn start_backup_coverage_scan_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    current_time: SystemTime,
+) -> Result<(), String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.start_backup_coverage_scan(current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_start_backup_coverage_scan_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let now = SystemTime::now();
+
+        assert_eq!(
+            start_backup_coverage_scan_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                now
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            start_backup_coverage_scan_impl(
+                &mut canister_data,
+                get_global_super_admin_principal_id(),
+                now
+            ),
+            Ok(())
+        );
+        assert!(canister_data
+            .heap_data
+            .backup_coverage_scan_in_progress
+            .is_some());
+    }
+}
@@ -1,6 +1,14 @@
 use shared_utils::canister_specific::data_backup::types::args::DataBackupInitArgs;
 
-use crate::{data::heap_data::HeapData, CANISTER_DATA};
+use crate::{
+    data::heap_data::HeapData,
+    util::{
+        backup_coverage_job::enqueue_timer_for_daily_backup_coverage_scan,
+        backup_snapshot_job::enqueue_timer_for_daily_backup_snapshots,
+        backup_verification_job::enqueue_timer_for_daily_backup_verification,
+    },
+    CANISTER_DATA,
+};
 
 #[ic_cdk::init]
 #[candid::candid_method(init)]
@@ -9,6 +17,10 @@ fn init(init_args: DataBackupInitArgs) {
         let mut data = canister_data_ref_cell.borrow_mut();
         init_impl(init_args, &mut data.heap_data);
     });
+
+    enqueue_timer_for_daily_backup_snapshots();
+    enqueue_timer_for_daily_backup_verification();
+    enqueue_timer_for_daily_backup_coverage_scan();
 }
 
 fn init_impl(init_args: DataBackupInitArgs, data: &mut HeapData) {
@@ -4,7 +4,20 @@ use ciborium::de;
 use ic_stable_structures::Memory;
 
 use crate::{
-    api::well_known_principal::update_locally_stored_well_known_principals, data::memory_layout,
+    api::well_known_principal::update_locally_stored_well_known_principals,
+    data::memory_layout,
+    util::{
+        backup_coverage_job::{
+            enqueue_timer_for_daily_backup_coverage_scan, run_backup_coverage_scan_in_batches,
+        },
+        backup_snapshot_job::{
+            enqueue_timer_for_daily_backup_snapshots, run_backup_snapshot_job_in_batches,
+        },
+        backup_verification_job::{
+            enqueue_timer_for_daily_backup_verification, run_backup_verification_job,
+        },
+        restore_run_job::run_restore_run_job,
+    },
     CANISTER_DATA,
 };
 
@@ -12,6 +25,14 @@ use crate::{
 fn post_upgrade() {
     restore_data_from_stable_memory();
     refetch_well_known_principals();
+    migrate_legacy_user_data_in_batches();
+    enqueue_timer_for_daily_backup_snapshots();
+    resume_backup_snapshot_job();
+    enqueue_timer_for_daily_backup_verification();
+    resume_backup_verification_job();
+    enqueue_timer_for_daily_backup_coverage_scan();
+    resume_backup_coverage_scan();
+    resume_restore_run_job();
 }
 
 fn restore_data_from_stable_memory() {
@@ -40,3 +61,65 @@ fn refetch_well_known_principals() {
         ic_cdk::spawn(update_locally_stored_well_known_principals::update_locally_stored_well_known_principals())
     });
 }
+
+fn resume_backup_snapshot_job() {
+    if CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .has_pending_backup_snapshot_work()
+    }) {
+        run_backup_snapshot_job_in_batches();
+    }
+}
+
+/// Runs from a timer, as post_upgrade can't make the inter-canister calls the job makes
+fn resume_backup_verification_job() {
+    if CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .heap_data
+            .backup_verification_in_progress
+            .is_some()
+    }) {
+        ic_cdk_timers::set_timer(Duration::from_secs(1), run_backup_verification_job);
+    }
+}
+
+fn resume_backup_coverage_scan() {
+    if CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .heap_data
+            .backup_coverage_scan_in_progress
+            .is_some()
+    }) {
+        run_backup_coverage_scan_in_batches();
+    }
+}
+
+/// Resumed from a timer too, as restoring calls the users' canisters
+fn resume_restore_run_job() {
+    if CANISTER_DATA
+        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().is_restore_run_in_progress())
+    {
+        ic_cdk_timers::set_timer(Duration::from_secs(1), run_restore_run_job);
+    }
+}
+
+const LEGACY_USER_DATA_MIGRATION_BATCH_SIZE: usize = 50;
+
+/// Moves backups out of the legacy map a batch per timer tick, so that no single message runs out
+/// of instructions
+fn migrate_legacy_user_data_in_batches() {
+    ic_cdk_timers::set_timer(Duration::from_secs(1), || {
+        let migrated_user_count = CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .migrate_legacy_user_data_batch(LEGACY_USER_DATA_MIGRATION_BATCH_SIZE)
+        });
+
+        if migrated_user_count == LEGACY_USER_DATA_MIGRATION_BATCH_SIZE {
+            migrate_legacy_user_data_in_batches();
+        }
+    });
+}
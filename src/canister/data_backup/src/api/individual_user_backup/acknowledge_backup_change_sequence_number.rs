@@ -0,0 +1,153 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::{BackupSection, UserBackupRunSummary},
+    common::{types::version_details::VersionDetails, utils::system_time},
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Records that a user's canister finished a backup run, so that the next run only sends what
+/// changed after `change_sequence_number` in the same `backup_epoch`, along with a summary of what
+/// the backup holds
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn acknowledge_backup_change_sequence_number(
+    canister_owner_principal_id: Principal,
+    backup_epoch: u64,
+    change_sequence_number: u64,
+) -> Result<(), String> {
+    // * Get the caller principal ID.
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        acknowledge_backup_change_sequence_number_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            &caller_principal_id,
+            &canister_owner_principal_id,
+            backup_epoch,
+            change_sequence_number,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn acknowledge_backup_change_sequence_number_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: &Principal,
+    canister_owner_principal_id: &Principal,
+    backup_epoch: u64,
+    change_sequence_number: u64,
+    current_time: SystemTime,
+) -> Result<(), String> {
+    let mut manifest = canister_data
+        .get_manifest_of_backup_sent_by_user_canister(
+            canister_owner_principal_id,
+            caller_principal_id,
+        )
+        .ok_or("Unauthorized")?;
+
+    let source_wasm_version: Option<VersionDetails> = canister_data
+        .user_backup_store
+        .read_section(canister_owner_principal_id, BackupSection::VersionDetails)?;
+    let get_item_count = |section| {
+        manifest
+            .sections
+            .get(&section)
+            .map(|section_manifest| section_manifest.item_count)
+            .unwrap_or_default()
+    };
+
+    manifest.last_acknowledged_change_sequence_number = Some(change_sequence_number);
+    manifest.last_acknowledged_backup_epoch = Some(backup_epoch);
+    manifest.last_completed_run = Some(UserBackupRunSummary {
+        completed_at: current_time,
+        post_count: get_item_count(BackupSection::Posts),
+        token_event_count: get_item_count(BackupSection::TokenData),
+        source_wasm_version,
+    });
+    canister_data.user_backup_store.insert_manifest(manifest);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::backup_manifest::UserBackupManifest;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_acknowledge_backup_change_sequence_number_impl() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        canister_data
+            .user_backup_store
+            .insert_manifest(UserBackupManifest::new(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ));
+
+        acknowledge_backup_change_sequence_number_impl(
+            &mut canister_data,
+            &get_mock_user_bob_canister_id(),
+            &get_mock_user_alice_principal_id(),
+            1,
+            10,
+            now,
+        )
+        .unwrap_err();
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .last_acknowledged_change_sequence_number,
+            None
+        );
+
+        acknowledge_backup_change_sequence_number_impl(
+            &mut canister_data,
+            &get_mock_user_alice_canister_id(),
+            &get_mock_user_alice_principal_id(),
+            1,
+            10,
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .last_acknowledged_change_sequence_number,
+            Some(10)
+        );
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .last_acknowledged_backup_epoch,
+            Some(1)
+        );
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .last_completed_run,
+            Some(UserBackupRunSummary {
+                completed_at: now,
+                post_count: 0,
+                token_event_count: 0,
+                source_wasm_version: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use candid::Principal;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// The change sequence number a user's canister can send changes after. `None` when the next
+/// backup run has to send everything, including when the acknowledgement was made in another
+/// `backup_epoch` of the canister.
+///
+/// # Access Control
+/// Only the user's canister gets an answer.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_last_acknowledged_backup_change_sequence_number(
+    canister_owner_principal_id: Principal,
+    backup_epoch: u64,
+) -> Option<u64> {
+    // * Get the caller principal ID.
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_last_acknowledged_backup_change_sequence_number_impl(
+            &canister_data_ref_cell.borrow(),
+            &caller_principal_id,
+            &canister_owner_principal_id,
+            backup_epoch,
+        )
+    })
+}
+
+fn get_last_acknowledged_backup_change_sequence_number_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: &Principal,
+    canister_owner_principal_id: &Principal,
+    backup_epoch: u64,
+) -> Option<u64> {
+    canister_data
+        .user_backup_store
+        .get_manifest(canister_owner_principal_id)
+        .filter(|manifest| manifest.user_canister_id == *caller_principal_id)
+        .filter(|manifest| manifest.last_acknowledged_backup_epoch == Some(backup_epoch))
+        .and_then(|manifest| manifest.last_acknowledged_change_sequence_number)
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::backup_manifest::UserBackupManifest;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_last_acknowledged_backup_change_sequence_number_impl() {
+        let mut canister_data = CanisterData::default();
+        let mut manifest = UserBackupManifest::new(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        manifest.last_acknowledged_change_sequence_number = Some(10);
+        manifest.last_acknowledged_backup_epoch = Some(1);
+        canister_data.user_backup_store.insert_manifest(manifest);
+
+        assert_eq!(
+            get_last_acknowledged_backup_change_sequence_number_impl(
+                &canister_data,
+                &get_mock_user_alice_canister_id(),
+                &get_mock_user_alice_principal_id(),
+                1,
+            ),
+            Some(10)
+        );
+        assert_eq!(
+            get_last_acknowledged_backup_change_sequence_number_impl(
+                &canister_data,
+                &get_mock_user_alice_canister_id(),
+                &get_mock_user_alice_principal_id(),
+                2,
+            ),
+            None
+        );
+        assert_eq!(
+            get_last_acknowledged_backup_change_sequence_number_impl(
+                &canister_data,
+                &get_mock_user_bob_canister_id(),
+                &get_mock_user_alice_principal_id(),
+                1,
+            ),
+            None
+        );
+    }
+}
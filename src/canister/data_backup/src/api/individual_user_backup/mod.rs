@@ -1,7 +1,14 @@
+pub mod acknowledge_backup_change_sequence_number;
+pub mod get_last_acknowledged_backup_change_sequence_number;
+pub mod purge_backups_of_deleted_user;
 pub mod receive_all_token_transactions_from_individual_user_canister;
 pub mod receive_all_user_posts_from_individual_user_canister;
+pub mod receive_backup_section_digests_from_individual_user_canister;
+pub mod receive_canister_data_section_from_individual_user_canister;
 pub mod receive_current_token_balance_from_individual_user_canister;
+pub mod receive_deleted_token_transaction_ids_from_individual_user_canister;
 pub mod receive_principals_i_follow_from_individual_user_canister;
 pub mod receive_principals_that_follow_me_from_individual_user_canister;
 pub mod receive_profile_details_from_individual_user_canister;
 pub mod restore_backed_up_data_to_individual_users_canister;
+pub mod restore_backed_up_data_to_reprovisioned_individual_user_canister;
@@ -0,0 +1,138 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Deletes the backup of a user whose account is being deleted, along with their copies in
+/// snapshots and their exported and imported archives
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn purge_backups_of_deleted_user(user_principal_id: Principal) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        purge_backups_of_deleted_user_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            user_principal_id,
+        )
+    })
+}
+
+fn purge_backups_of_deleted_user_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+) -> Result<(), String> {
+    let user_index_canister_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if caller_principal_id != *user_index_canister_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.purge_user_data(&user_principal_id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::{
+        canister_specific::data_backup::types::{
+            all_user_data::{AllUserData, UserOwnedCanisterData},
+            backup_snapshot::BackupSnapshotKind,
+        },
+        common::types::storable_principal::StorablePrincipal,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_purge_backups_of_deleted_user_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+        for (user_principal_id, user_canister_id) in [
+            (alice, get_mock_user_alice_canister_id()),
+            (bob, get_mock_user_bob_canister_id()),
+        ] {
+            canister_data
+                .user_backup_store
+                .insert_all_user_data(&AllUserData {
+                    user_principal_id,
+                    user_canister_id,
+                    canister_data: UserOwnedCanisterData::default(),
+                });
+        }
+
+        let snapshot_id = canister_data
+            .start_backup_snapshot(BackupSnapshotKind::Daily, None, SystemTime::now())
+            .unwrap();
+        while canister_data.has_pending_backup_snapshot_work() {
+            canister_data.copy_users_into_backup_snapshot_batch(10);
+        }
+        canister_data
+            .export_user_data(&alice, SystemTime::now())
+            .unwrap();
+        canister_data
+            .legacy_user_principal_id_to_all_user_data_map
+            .insert(
+                StorablePrincipal(alice),
+                canister_data.get_all_user_data(&alice).unwrap().unwrap(),
+            );
+
+        assert_eq!(
+            purge_backups_of_deleted_user_impl(&mut canister_data, alice, alice),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            purge_backups_of_deleted_user_impl(
+                &mut canister_data,
+                get_mock_canister_id_user_index(),
+                alice
+            ),
+            Ok(())
+        );
+
+        assert!(canister_data.get_all_user_data(&alice).unwrap().is_none());
+        assert!(canister_data
+            .user_backup_store
+            .get_manifest(&alice)
+            .is_none());
+        assert!(canister_data
+            .get_backup_snapshot_all_user_data(snapshot_id, &alice)
+            .is_err());
+        assert_eq!(
+            canister_data.heap_data.backup_snapshots[&snapshot_id].user_count,
+            1
+        );
+        assert!(canister_data.heap_data.user_data_exports.is_empty());
+        assert!(canister_data
+            .heap_data
+            .exported_user_data_archive_digests
+            .is_empty());
+
+        assert!(canister_data.get_all_user_data(&bob).unwrap().is_some());
+        assert!(canister_data
+            .get_backup_snapshot_all_user_data(snapshot_id, &bob)
+            .is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 use candid::Principal;
-use shared_utils::common::types::{
-    storable_principal::StorablePrincipal, utility_token::token_event::TokenEvent,
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::types::utility_token::token_event::TokenEvent,
 };
 
 use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
@@ -10,7 +11,7 @@ use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
 fn receive_all_token_transactions_from_individual_user_canister(
     all_token_transactions_from_individual_user_canister_chunk: Vec<(u64, TokenEvent)>,
     canister_owner_principal_id: Principal,
-) {
+) -> Result<(), String> {
     // * Get the caller principal ID.
     let caller_principal_id = ic_cdk::caller();
 
@@ -20,8 +21,8 @@ fn receive_all_token_transactions_from_individual_user_canister(
             all_token_transactions_from_individual_user_canister_chunk,
             &caller_principal_id,
             &canister_owner_principal_id,
-        );
-    });
+        )
+    })
 }
 
 fn receive_all_token_transactions_from_individual_user_canister_impl(
@@ -29,39 +30,23 @@ fn receive_all_token_transactions_from_individual_user_canister_impl(
     all_token_transactions_from_individual_user_canister_chunk: Vec<(u64, TokenEvent)>,
     caller_principal_id: &Principal,
     canister_owner_principal_id: &Principal,
-) {
-    let does_the_current_call_makers_record_exist = canister_data
-        .user_principal_id_to_all_user_data_map
-        .contains_key(&StorablePrincipal(*canister_owner_principal_id));
-
-    if !does_the_current_call_makers_record_exist {
-        return;
+) -> Result<(), String> {
+    if canister_data
+        .get_manifest_of_backup_sent_by_user_canister(
+            canister_owner_principal_id,
+            caller_principal_id,
+        )
+        .is_none()
+    {
+        return Err("Unauthorized".to_string());
     }
 
-    let mut existing_entry = canister_data
-        .user_principal_id_to_all_user_data_map
-        .get(&StorablePrincipal(*canister_owner_principal_id))
-        .unwrap();
-
-    if existing_entry.user_canister_id != *caller_principal_id {
-        return;
-    }
-
-    all_token_transactions_from_individual_user_canister_chunk
-        .iter()
-        .for_each(|token_transaction| {
-            // upsert the post details in the user's record.
-            existing_entry
-                .canister_data
-                .token_data
-                .utility_token_transaction_history
-                .insert(token_transaction.0, token_transaction.1.clone());
-        });
-
-    canister_data.user_principal_id_to_all_user_data_map.insert(
-        StorablePrincipal(*canister_owner_principal_id),
-        existing_entry,
-    );
+    // upsert the token transactions in the user's record.
+    canister_data.user_backup_store.write_section_items(
+        canister_owner_principal_id,
+        BackupSection::TokenData,
+        all_token_transactions_from_individual_user_canister_chunk,
+    )
 }
 
 #[cfg(test)]
@@ -114,37 +99,38 @@ mod test {
             all_token_transactions_from_individual_user_canister_chunk.clone(),
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap_err();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_none());
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
                 user_principal_id: get_mock_user_alice_principal_id(),
                 user_canister_id: get_mock_user_bob_canister_id(),
                 canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+            });
 
         receive_all_token_transactions_from_individual_user_canister_impl(
             &mut canister_data,
             all_token_transactions_from_individual_user_canister_chunk.clone(),
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap_err();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .token_data
@@ -153,30 +139,30 @@ mod test {
             0
         );
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
                 user_principal_id: get_mock_user_alice_principal_id(),
                 user_canister_id: get_mock_user_alice_canister_id(),
                 canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+            });
 
         receive_all_token_transactions_from_individual_user_canister_impl(
             &mut canister_data,
             all_token_transactions_from_individual_user_canister_chunk,
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .token_data
@@ -1,7 +1,6 @@
 use candid::Principal;
-use shared_utils::{
-    canister_specific::individual_user_template::types::post::Post,
-    common::types::storable_principal::StorablePrincipal,
+use shared_utils::canister_specific::{
+    data_backup::types::backup_manifest::BackupSection, individual_user_template::types::post::Post,
 };
 
 use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
@@ -11,7 +10,7 @@ use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
 fn receive_all_user_posts_from_individual_user_canister(
     all_user_posts_from_individual_user_canister_vec: Vec<Post>,
     canister_owner_principal_id: Principal,
-) {
+) -> Result<(), String> {
     // * Get the caller principal ID.
     let caller_principal_id = ic_cdk::caller();
 
@@ -21,8 +20,8 @@ fn receive_all_user_posts_from_individual_user_canister(
             all_user_posts_from_individual_user_canister_vec,
             &caller_principal_id,
             &canister_owner_principal_id,
-        );
-    });
+        )
+    })
 }
 
 fn receive_all_user_posts_from_individual_user_canister_impl(
@@ -30,38 +29,25 @@ fn receive_all_user_posts_from_individual_user_canister_impl(
     all_user_posts_from_individual_user_canister: Vec<Post>,
     caller_principal_id: &Principal,
     canister_owner_principal_id: &Principal,
-) {
-    let does_the_current_call_makers_record_exist = canister_data
-        .user_principal_id_to_all_user_data_map
-        .contains_key(&StorablePrincipal(*canister_owner_principal_id));
-
-    if !does_the_current_call_makers_record_exist {
-        return;
-    }
-
-    let mut existing_entry = canister_data
-        .user_principal_id_to_all_user_data_map
-        .get(&StorablePrincipal(*canister_owner_principal_id))
-        .unwrap();
-
-    if existing_entry.user_canister_id != *caller_principal_id {
-        return;
+) -> Result<(), String> {
+    if canister_data
+        .get_manifest_of_backup_sent_by_user_canister(
+            canister_owner_principal_id,
+            caller_principal_id,
+        )
+        .is_none()
+    {
+        return Err("Unauthorized".to_string());
     }
 
-    all_user_posts_from_individual_user_canister
-        .iter()
-        .for_each(|post| {
-            // upsert the post details in the user's record.
-            existing_entry
-                .canister_data
-                .all_created_posts
-                .insert(post.id, post.clone());
-        });
-
-    canister_data.user_principal_id_to_all_user_data_map.insert(
-        StorablePrincipal(*canister_owner_principal_id),
-        existing_entry,
-    );
+    // upsert the post details in the user's record.
+    canister_data.user_backup_store.write_section_items(
+        canister_owner_principal_id,
+        BackupSection::Posts,
+        all_user_posts_from_individual_user_canister
+            .into_iter()
+            .map(|post| (post.id, post)),
+    )
 }
 
 #[cfg(test)]
@@ -135,37 +121,38 @@ mod test {
             all_user_posts_from_individual_user_canister.clone(),
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap_err();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_none());
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
                 user_principal_id: get_mock_user_alice_principal_id(),
                 user_canister_id: get_mock_user_bob_canister_id(),
                 canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+            });
 
         receive_all_user_posts_from_individual_user_canister_impl(
             &mut canister_data,
             all_user_posts_from_individual_user_canister.clone(),
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap_err();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .all_created_posts
@@ -173,30 +160,30 @@ mod test {
             0
         );
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
                 user_principal_id: get_mock_user_alice_principal_id(),
                 user_canister_id: get_mock_user_alice_canister_id(),
                 canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+            });
 
         receive_all_user_posts_from_individual_user_canister_impl(
             &mut canister_data,
             all_user_posts_from_individual_user_canister,
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .all_created_posts
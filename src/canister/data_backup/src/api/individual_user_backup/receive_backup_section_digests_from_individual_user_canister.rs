@@ -0,0 +1,101 @@
+use candid::Principal;
+use shared_utils::canister_specific::data_backup::types::backup_section_digests::BackupSectionDigests;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Stores the digests a user's canister computed over the data it backed up, which the backup
+/// verification job checks the stored data against
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn receive_backup_section_digests_from_individual_user_canister(
+    section_digests: BackupSectionDigests,
+    canister_owner_principal_id: Principal,
+) {
+    // * Get the caller principal ID.
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        receive_backup_section_digests_from_individual_user_canister_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            section_digests,
+            &caller_principal_id,
+            &canister_owner_principal_id,
+        );
+    });
+}
+
+fn receive_backup_section_digests_from_individual_user_canister_impl(
+    canister_data: &mut CanisterData,
+    section_digests: BackupSectionDigests,
+    caller_principal_id: &Principal,
+    canister_owner_principal_id: &Principal,
+) {
+    let Some(mut manifest) = canister_data.get_manifest_of_backup_sent_by_user_canister(
+        canister_owner_principal_id,
+        caller_principal_id,
+    ) else {
+        return;
+    };
+
+    manifest.section_digests = Some(section_digests);
+    canister_data.user_backup_store.insert_manifest(manifest);
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use shared_utils::canister_specific::data_backup::types::backup_manifest::{
+        BackupSection, UserBackupManifest,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_receive_backup_section_digests_from_individual_user_canister_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .user_backup_store
+            .insert_manifest(UserBackupManifest::new(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ));
+        let section_digests = BackupSectionDigests {
+            digests: BTreeMap::from([(BackupSection::Posts, vec![1; 32])]),
+        };
+
+        receive_backup_section_digests_from_individual_user_canister_impl(
+            &mut canister_data,
+            section_digests.clone(),
+            &get_mock_user_bob_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        );
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .section_digests,
+            None
+        );
+
+        receive_backup_section_digests_from_individual_user_canister_impl(
+            &mut canister_data,
+            section_digests.clone(),
+            &get_mock_user_alice_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        );
+        assert_eq!(
+            canister_data
+                .user_backup_store
+                .get_manifest(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .section_digests,
+            Some(section_digests)
+        );
+    }
+}
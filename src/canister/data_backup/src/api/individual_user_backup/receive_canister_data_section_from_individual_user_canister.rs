@@ -0,0 +1,205 @@
+use candid::Principal;
+use shared_utils::canister_specific::data_backup::types::individual_user_canister_data_section::IndividualUserCanisterDataSection;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn receive_canister_data_section_from_individual_user_canister(
+    canister_data_section: IndividualUserCanisterDataSection,
+    canister_owner_principal_id: Principal,
+) -> Result<(), String> {
+    // * Get the caller principal ID.
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        receive_canister_data_section_from_individual_user_canister_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            canister_data_section,
+            &caller_principal_id,
+            &canister_owner_principal_id,
+        )
+    })
+}
+
+fn receive_canister_data_section_from_individual_user_canister_impl(
+    canister_data: &mut CanisterData,
+    canister_data_section: IndividualUserCanisterDataSection,
+    caller_principal_id: &Principal,
+    canister_owner_principal_id: &Principal,
+) -> Result<(), String> {
+    if canister_data
+        .get_manifest_of_backup_sent_by_user_canister(
+            canister_owner_principal_id,
+            caller_principal_id,
+        )
+        .is_none()
+    {
+        return Err("Unauthorized".to_string());
+    }
+
+    let section = canister_data_section.get_backup_section();
+    let user_backup_store = &mut canister_data.user_backup_store;
+
+    match canister_data_section {
+        IndividualUserCanisterDataSection::HotOrNotBetsPlaced(bets_placed_chunk) => {
+            // upsert the bets in the user's record.
+            user_backup_store.write_section_items(
+                canister_owner_principal_id,
+                section,
+                bets_placed_chunk,
+            )
+        }
+        IndividualUserCanisterDataSection::FollowData(follow_data) => {
+            user_backup_store.write_section(canister_owner_principal_id, section, &follow_data)
+        }
+        IndividualUserCanisterDataSection::Configuration(configuration) => {
+            user_backup_store.write_section(canister_owner_principal_id, section, &configuration)
+        }
+        IndividualUserCanisterDataSection::KnownPrincipalIds(known_principal_ids) => {
+            user_backup_store.write_section(
+                canister_owner_principal_id,
+                section,
+                &known_principal_ids,
+            )
+        }
+        IndividualUserCanisterDataSection::VersionDetails(version_details) => {
+            user_backup_store.write_section(canister_owner_principal_id, section, &version_details)
+        }
+        IndividualUserCanisterDataSection::PostsIndexSortedByHomeFeedScore(post_score_index)
+        | IndividualUserCanisterDataSection::PostsIndexSortedByHotOrNotFeedScore(
+            post_score_index,
+        ) => {
+            user_backup_store.write_section(canister_owner_principal_id, section, &post_score_index)
+        }
+        IndividualUserCanisterDataSection::PostScoreSyncQueue(post_score_sync_queue) => {
+            user_backup_store.write_section(
+                canister_owner_principal_id,
+                section,
+                &post_score_sync_queue,
+            )
+        }
+        IndividualUserCanisterDataSection::LastAccessTime(last_access_time) => {
+            user_backup_store.write_section(canister_owner_principal_id, section, &last_access_time)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use shared_utils::canister_specific::{
+        data_backup::types::{
+            all_user_data::{AllUserData, UserOwnedCanisterData},
+            backup_manifest::CURRENT_BACKUP_FORMAT_VERSION,
+        },
+        individual_user_template::types::follow::{FollowData, FollowEntryDetail},
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_placed_bet_detail, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_receive_canister_data_section_from_individual_user_canister_impl() {
+        let mut canister_data = CanisterData::default();
+        let last_access_time = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(100));
+
+        receive_canister_data_section_from_individual_user_canister_impl(
+            &mut canister_data,
+            IndividualUserCanisterDataSection::LastAccessTime(last_access_time),
+            &get_mock_user_alice_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        )
+        .unwrap_err();
+        assert!(canister_data
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
+            .is_none());
+
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+
+        // * only the user's own canister can send their data
+        receive_canister_data_section_from_individual_user_canister_impl(
+            &mut canister_data,
+            IndividualUserCanisterDataSection::LastAccessTime(last_access_time),
+            &get_mock_user_bob_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        )
+        .unwrap_err();
+        let all_user_data = canister_data
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
+            .unwrap();
+        assert_eq!(all_user_data.canister_data.last_access_time, None);
+        assert_eq!(all_user_data.canister_data.backup_format_version, Some(1));
+
+        let mut follow_data = FollowData::default();
+        follow_data.following.add(FollowEntryDetail {
+            principal_id: get_mock_user_bob_principal_id(),
+            canister_id: get_mock_user_bob_canister_id(),
+        });
+
+        for canister_data_section in [
+            IndividualUserCanisterDataSection::LastAccessTime(last_access_time),
+            IndividualUserCanisterDataSection::FollowData(follow_data),
+            IndividualUserCanisterDataSection::HotOrNotBetsPlaced(vec![
+                get_mock_placed_bet_detail(0),
+                get_mock_placed_bet_detail(1),
+            ]),
+            IndividualUserCanisterDataSection::HotOrNotBetsPlaced(vec![
+                get_mock_placed_bet_detail(1),
+                get_mock_placed_bet_detail(2),
+            ]),
+        ] {
+            receive_canister_data_section_from_individual_user_canister_impl(
+                &mut canister_data,
+                canister_data_section,
+                &get_mock_user_alice_canister_id(),
+                &get_mock_user_alice_principal_id(),
+            )
+            .unwrap();
+        }
+
+        let all_user_data = canister_data
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            all_user_data.canister_data.backup_format_version,
+            Some(CURRENT_BACKUP_FORMAT_VERSION)
+        );
+        assert_eq!(
+            all_user_data.canister_data.last_access_time,
+            Some(last_access_time)
+        );
+        assert!(all_user_data
+            .canister_data
+            .follow_data
+            .unwrap()
+            .following
+            .contains(&FollowEntryDetail {
+                principal_id: get_mock_user_bob_principal_id(),
+                canister_id: get_mock_user_bob_canister_id(),
+            }));
+        assert_eq!(
+            all_user_data
+                .canister_data
+                .all_hot_or_not_bets_placed
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(all_user_data.canister_data.configuration.is_none());
+    }
+}
@@ -1,5 +1,8 @@
 use candid::Principal;
-use shared_utils::common::types::storable_principal::StorablePrincipal;
+use shared_utils::canister_specific::{
+    data_backup::types::backup_manifest::BackupSection,
+    individual_user_template::types::token::TokenBalance,
+};
 
 use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
 
@@ -8,7 +11,7 @@ use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
 fn receive_current_token_balance_from_individual_user_canister(
     utility_token_balance: u64,
     canister_owner_principal_id: Principal,
-) {
+) -> Result<(), String> {
     // * Get the caller principal ID.
     let caller_principal_id = ic_cdk::caller();
 
@@ -18,8 +21,8 @@ fn receive_current_token_balance_from_individual_user_canister(
             utility_token_balance,
             &caller_principal_id,
             &canister_owner_principal_id,
-        );
-    });
+        )
+    })
 }
 
 fn receive_current_token_balance_from_individual_user_canister_impl(
@@ -27,33 +30,30 @@ fn receive_current_token_balance_from_individual_user_canister_impl(
     utility_token_balance: u64,
     caller_principal_id: &Principal,
     canister_owner_principal_id: &Principal,
-) {
-    let does_the_current_call_makers_record_exist = canister_data
-        .user_principal_id_to_all_user_data_map
-        .contains_key(&StorablePrincipal(*canister_owner_principal_id));
-
-    if !does_the_current_call_makers_record_exist {
-        return;
+) -> Result<(), String> {
+    if canister_data
+        .get_manifest_of_backup_sent_by_user_canister(
+            canister_owner_principal_id,
+            caller_principal_id,
+        )
+        .is_none()
+    {
+        return Err("Unauthorized".to_string());
     }
 
-    let mut existing_entry = canister_data
-        .user_principal_id_to_all_user_data_map
-        .get(&StorablePrincipal(*canister_owner_principal_id))
-        .unwrap();
+    // * the transaction history is stored as items of the section, so this is only the balance
+    let mut token_data: TokenBalance = canister_data
+        .user_backup_store
+        .read_section(canister_owner_principal_id, BackupSection::TokenData)?
+        .unwrap_or_default();
 
-    if existing_entry.user_canister_id != *caller_principal_id {
-        return;
-    }
+    token_data.utility_token_balance = utility_token_balance;
 
-    existing_entry
-        .canister_data
-        .token_data
-        .utility_token_balance = utility_token_balance;
-
-    canister_data.user_principal_id_to_all_user_data_map.insert(
-        StorablePrincipal(*canister_owner_principal_id),
-        existing_entry,
-    );
+    canister_data.user_backup_store.write_section(
+        canister_owner_principal_id,
+        BackupSection::TokenData,
+        &token_data,
+    )
 }
 
 #[cfg(test)]
@@ -77,37 +77,38 @@ mod test {
             1500,
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap_err();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_none());
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
                 user_principal_id: get_mock_user_alice_principal_id(),
                 user_canister_id: get_mock_user_bob_canister_id(),
                 canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+            });
 
         receive_current_token_balance_from_individual_user_canister_impl(
             &mut canister_data,
             1500,
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap_err();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .token_data
@@ -115,30 +116,30 @@ mod test {
             0
         );
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
                 user_principal_id: get_mock_user_alice_principal_id(),
                 user_canister_id: get_mock_user_alice_canister_id(),
                 canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+            });
 
         receive_current_token_balance_from_individual_user_canister_impl(
             &mut canister_data,
             1500,
             &get_mock_user_alice_canister_id(),
             &get_mock_user_alice_principal_id(),
-        );
+        )
+        .unwrap();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .token_data
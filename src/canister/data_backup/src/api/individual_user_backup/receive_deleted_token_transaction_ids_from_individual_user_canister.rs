@@ -0,0 +1,123 @@
+use candid::Principal;
+use shared_utils::canister_specific::data_backup::types::backup_manifest::BackupSection;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Removes the token transactions the user's canister trimmed from its transaction history
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn receive_deleted_token_transaction_ids_from_individual_user_canister(
+    deleted_token_transaction_ids_chunk: Vec<u64>,
+    canister_owner_principal_id: Principal,
+) -> Result<(), String> {
+    // * Get the caller principal ID.
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        receive_deleted_token_transaction_ids_from_individual_user_canister_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            deleted_token_transaction_ids_chunk,
+            &caller_principal_id,
+            &canister_owner_principal_id,
+        )
+    })
+}
+
+fn receive_deleted_token_transaction_ids_from_individual_user_canister_impl(
+    canister_data: &mut CanisterData,
+    deleted_token_transaction_ids_chunk: Vec<u64>,
+    caller_principal_id: &Principal,
+    canister_owner_principal_id: &Principal,
+) -> Result<(), String> {
+    if canister_data
+        .get_manifest_of_backup_sent_by_user_canister(
+            canister_owner_principal_id,
+            caller_principal_id,
+        )
+        .is_none()
+    {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.user_backup_store.remove_section_items(
+        canister_owner_principal_id,
+        BackupSection::TokenData,
+        deleted_token_transaction_ids_chunk,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::{
+        canister_specific::data_backup::types::all_user_data::{
+            AllUserData, UserOwnedCanisterData,
+        },
+        common::types::utility_token::token_event::TokenEvent,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_receive_deleted_token_transaction_ids_from_individual_user_canister_impl() {
+        let mut canister_data = CanisterData::default();
+
+        receive_deleted_token_transaction_ids_from_individual_user_canister_impl(
+            &mut canister_data,
+            vec![0],
+            &get_mock_user_alice_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        )
+        .unwrap_err();
+
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+        canister_data
+            .user_backup_store
+            .write_section_items(
+                &get_mock_user_alice_principal_id(),
+                BackupSection::TokenData,
+                (0_u64..3).map(|token_transaction_id| (token_transaction_id, TokenEvent::Burn)),
+            )
+            .unwrap();
+
+        // * only the user's own canister can remove their data
+        receive_deleted_token_transaction_ids_from_individual_user_canister_impl(
+            &mut canister_data,
+            vec![0],
+            &get_mock_user_bob_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        )
+        .unwrap_err();
+
+        receive_deleted_token_transaction_ids_from_individual_user_canister_impl(
+            &mut canister_data,
+            vec![0, 1],
+            &get_mock_user_alice_canister_id(),
+            &get_mock_user_alice_principal_id(),
+        )
+        .unwrap();
+
+        let utility_token_transaction_history = canister_data
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
+            .unwrap()
+            .canister_data
+            .token_data
+            .utility_token_transaction_history;
+        assert_eq!(
+            utility_token_transaction_history
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+}
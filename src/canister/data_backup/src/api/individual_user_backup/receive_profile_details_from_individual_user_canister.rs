@@ -1,10 +1,7 @@
 use candid::Principal;
-use shared_utils::{
-    canister_specific::{
-        data_backup::types::all_user_data::{AllUserData, UserOwnedCanisterData},
-        individual_user_template::types::profile::UserProfile,
-    },
-    common::types::storable_principal::StorablePrincipal,
+use shared_utils::canister_specific::{
+    data_backup::types::backup_manifest::{BackupSection, UserBackupManifest},
+    individual_user_template::types::profile::UserProfile,
 };
 
 use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
@@ -15,7 +12,7 @@ fn receive_profile_details_from_individual_user_canister(
     profile_data: UserProfile,
     canister_owner_principal_id: Principal,
     canister_id: Principal,
-) {
+) -> Result<(), String> {
     // * Get the caller principal ID.
     let caller_principal_id = ic_cdk::caller();
 
@@ -26,8 +23,8 @@ fn receive_profile_details_from_individual_user_canister(
             &profile_data,
             &canister_owner_principal_id,
             &canister_id,
-        );
-    });
+        )
+    })
 }
 
 fn receive_profile_details_from_individual_user_canister_impl(
@@ -36,41 +33,41 @@ fn receive_profile_details_from_individual_user_canister_impl(
     profile_data: &UserProfile,
     canister_owner_principal_id: &Principal,
     canister_id: &Principal,
-) {
+) -> Result<(), String> {
     let is_caller_modifying_their_own_canister = *caller_principal_id == *canister_id;
     if !is_caller_modifying_their_own_canister {
-        return;
+        return Err("Unauthorized".to_string());
     }
 
-    let mut entry_to_insert = if canister_data
-        .user_principal_id_to_all_user_data_map
-        .contains_key(&StorablePrincipal(*canister_owner_principal_id))
+    canister_data.migrate_legacy_user_data(canister_owner_principal_id);
+
+    if !canister_data
+        .user_backup_store
+        .contains_user(canister_owner_principal_id)
     {
         canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(*canister_owner_principal_id))
-            .unwrap()
-    } else {
-        AllUserData {
-            user_principal_id: *canister_owner_principal_id,
-            user_canister_id: *canister_id,
-            canister_data: UserOwnedCanisterData::default(),
-        }
-    };
-
-    entry_to_insert.canister_data.profile = profile_data.clone();
+            .user_backup_store
+            .insert_manifest(UserBackupManifest::new(
+                *canister_owner_principal_id,
+                *canister_id,
+            ));
+    }
 
-    canister_data.user_principal_id_to_all_user_data_map.insert(
-        StorablePrincipal(*canister_owner_principal_id),
-        entry_to_insert,
-    );
+    canister_data.user_backup_store.write_section(
+        canister_owner_principal_id,
+        BackupSection::Profile,
+        profile_data,
+    )
 }
 
 #[cfg(test)]
 mod test {
-    use shared_utils::canister_specific::{
-        data_backup::types::all_user_data::{AllUserData, UserOwnedCanisterData},
-        individual_user_template::types::profile::UserProfileGlobalStats,
+    use shared_utils::{
+        canister_specific::{
+            data_backup::types::all_user_data::{AllUserData, UserOwnedCanisterData},
+            individual_user_template::types::profile::UserProfileGlobalStats,
+        },
+        common::types::storable_principal::StorablePrincipal,
     };
     use test_utils::setup::test_constants::{
         get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
@@ -104,10 +101,11 @@ mod test {
             &profile_data,
             &get_mock_user_alice_principal_id(),
             &get_mock_user_alice_canister_id(),
-        );
+        )
+        .unwrap_err();
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_none());
 
         receive_profile_details_from_individual_user_canister_impl(
@@ -116,16 +114,17 @@ mod test {
             &profile_data,
             &get_mock_user_alice_principal_id(),
             &get_mock_user_alice_canister_id(),
-        );
+        )
+        .unwrap();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -134,8 +133,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -144,8 +143,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -155,8 +154,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -166,8 +165,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -177,8 +176,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -187,14 +186,16 @@ mod test {
             5
         );
 
-        canister_data.user_principal_id_to_all_user_data_map.insert(
-            StorablePrincipal(get_mock_user_alice_principal_id()),
-            AllUserData {
-                user_principal_id: get_mock_user_alice_principal_id(),
-                user_canister_id: get_mock_user_bob_canister_id(),
-                canister_data: UserOwnedCanisterData::default(),
-            },
-        );
+        canister_data
+            .legacy_user_principal_id_to_all_user_data_map
+            .insert(
+                StorablePrincipal(get_mock_user_alice_principal_id()),
+                AllUserData {
+                    user_principal_id: get_mock_user_alice_principal_id(),
+                    user_canister_id: get_mock_user_bob_canister_id(),
+                    canister_data: UserOwnedCanisterData::default(),
+                },
+            );
 
         receive_profile_details_from_individual_user_canister_impl(
             &mut canister_data,
@@ -202,16 +203,17 @@ mod test {
             &profile_data,
             &get_mock_user_alice_principal_id(),
             &get_mock_user_alice_canister_id(),
-        );
+        )
+        .unwrap();
 
         assert!(canister_data
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
             .is_some());
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -220,8 +222,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
@@ -230,8 +232,8 @@ mod test {
         );
         assert_eq!(
             canister_data
-                .user_principal_id_to_all_user_data_map
-                .get(&StorablePrincipal(get_mock_user_alice_principal_id()))
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
                 .unwrap()
                 .canister_data
                 .profile
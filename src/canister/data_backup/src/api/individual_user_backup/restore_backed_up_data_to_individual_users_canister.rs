@@ -1,8 +1,11 @@
 use candid::Principal;
 use ic_cdk::api::call;
 use shared_utils::{
-    canister_specific::data_backup::types::all_user_data::AllUserData,
-    common::types::{known_principal::KnownPrincipalType, storable_principal::StorablePrincipal},
+    canister_specific::data_backup::types::{
+        all_user_data::{AllUserData, UserOwnedCanisterData},
+        individual_user_canister_data_section::IndividualUserCanisterDataSection,
+    },
+    common::types::known_principal::KnownPrincipalType,
 };
 
 use crate::CANISTER_DATA;
@@ -28,43 +31,131 @@ async fn restore_backed_up_data_to_individual_users_canister(
     }
 
     let users_data = CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell
-            .borrow()
-            .user_principal_id_to_all_user_data_map
-            .get(&StorablePrincipal(user_principal_id))
-    });
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        // * the restored canister tracks changes from the start, so its next backup run sends
+        // * everything
+        canister_data
+            .user_backup_store
+            .reset_acknowledged_change_sequence_number(&user_principal_id);
 
-    if users_data.is_none() {
-        return "No user data found".to_string();
-    }
+        canister_data.get_all_user_data(&user_principal_id)
+    });
 
-    let users_data = users_data.unwrap();
+    let users_data = match users_data {
+        Ok(Some(users_data)) => users_data,
+        Ok(None) => return "No user data found".to_string(),
+        Err(e) => return e,
+    };
 
-    send_posts(&users_data).await;
-    send_utility_token_balance(&users_data).await;
-    send_utility_token_history(&users_data).await;
-    send_principals_i_follow(&users_data).await;
-    send_principals_that_follow_me(&users_data).await;
-    send_profile_data(&users_data).await;
+    if let Err(e) = send_all_backed_up_data_to_users_canister(&users_data).await {
+        return e;
+    }
 
     "Success".to_string()
 }
 
+/// Stops at the first call that fails. Every call can safely be made again, so the restore can
+/// be retried from the start.
+pub(crate) async fn send_all_backed_up_data_to_users_canister(
+    users_data: &AllUserData,
+) -> Result<(), String> {
+    send_posts(users_data).await?;
+    send_utility_token_balance(users_data).await?;
+    send_utility_token_history(users_data).await?;
+    send_principals_i_follow(users_data).await?;
+    send_principals_that_follow_me(users_data).await?;
+    send_profile_data(users_data).await?;
+    send_canister_data_sections(users_data).await
+}
+
 const CHUNK_SIZE: usize = 10;
 
-async fn send_profile_data(users_data: &AllUserData) {
+async fn send_canister_data_sections(users_data: &AllUserData) -> Result<(), String> {
+    let canister_id_to_send_to = users_data.user_canister_id;
+
+    for canister_data_section in get_canister_data_sections_to_restore(&users_data.canister_data) {
+        call::call::<_, ()>(
+            canister_id_to_send_to,
+            "receive_canister_data_section_from_data_backup_canister",
+            (canister_data_section,),
+        )
+        .await
+        .map_err(|e| format!("Failed to call the receive_canister_data_section_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
+    }
+
+    Ok(())
+}
+
+/// The sections a backup holds beyond the ones with their own restore endpoints. Backups taken
+/// before a section was added do not hold it, and the canister keeps what it has for it.
+/// `known_principal_ids` and `version_details` describe the installation rather than the user,
+/// so the canister keeps the ones it was installed with.
+fn get_canister_data_sections_to_restore(
+    canister_data: &UserOwnedCanisterData,
+) -> Vec<IndividualUserCanisterDataSection> {
+    let mut canister_data_sections = vec![];
+
+    if let Some(all_hot_or_not_bets_placed) = &canister_data.all_hot_or_not_bets_placed {
+        let all_hot_or_not_bets_placed_vec = all_hot_or_not_bets_placed
+            .iter()
+            .map(|(bet_key, placed_bet_detail)| (*bet_key, placed_bet_detail.clone()))
+            .collect::<Vec<_>>();
+
+        canister_data_sections.extend(
+            all_hot_or_not_bets_placed_vec
+                .chunks(CHUNK_SIZE)
+                .map(|chunk| IndividualUserCanisterDataSection::HotOrNotBetsPlaced(chunk.to_vec())),
+        );
+    }
+
+    canister_data_sections.extend(
+        [
+            canister_data
+                .follow_data
+                .clone()
+                .map(IndividualUserCanisterDataSection::FollowData),
+            canister_data
+                .configuration
+                .clone()
+                .map(IndividualUserCanisterDataSection::Configuration),
+            canister_data
+                .posts_index_sorted_by_home_feed_score
+                .clone()
+                .map(IndividualUserCanisterDataSection::PostsIndexSortedByHomeFeedScore),
+            canister_data
+                .posts_index_sorted_by_hot_or_not_feed_score
+                .clone()
+                .map(IndividualUserCanisterDataSection::PostsIndexSortedByHotOrNotFeedScore),
+            canister_data
+                .post_score_sync_queue
+                .clone()
+                .map(IndividualUserCanisterDataSection::PostScoreSyncQueue),
+            canister_data
+                .last_access_time
+                .map(IndividualUserCanisterDataSection::LastAccessTime),
+        ]
+        .into_iter()
+        .flatten(),
+    );
+
+    canister_data_sections
+}
+
+async fn send_profile_data(users_data: &AllUserData) -> Result<(), String> {
     let canister_id_to_send_to = users_data.user_canister_id;
 
-    let _: () = call::call(
+    call::call::<_, ()>(
         canister_id_to_send_to,
         "receive_my_profile_from_data_backup_canister",
         (users_data.canister_data.profile.clone(),),
     )
     .await
-    .expect("Failed to call the receive_my_profile_from_data_backup_canister method on the individual user's canister");
+    .map_err(|e| format!("Failed to call the receive_my_profile_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
+
+    Ok(())
 }
 
-async fn send_principals_that_follow_me(users_data: &AllUserData) {
+async fn send_principals_that_follow_me(users_data: &AllUserData) -> Result<(), String> {
     let canister_id_to_send_to = users_data.user_canister_id;
 
     let principals_that_follow_me_vec = users_data
@@ -79,17 +170,19 @@ async fn send_principals_that_follow_me(users_data: &AllUserData) {
         .collect::<Vec<_>>();
 
     for chunk in principals_that_follow_me_vec_chunks {
-        let _: () = call::call(
+        call::call::<_, ()>(
             canister_id_to_send_to,
             "receive_principals_that_follow_me_from_data_backup_canister",
             (chunk.to_vec(),),
         )
         .await
-        .expect("Failed to call the receive_principals_that_follow_me_from_data_backup_canister method on the individual user's canister");
+        .map_err(|e| format!("Failed to call the receive_principals_that_follow_me_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
     }
+
+    Ok(())
 }
 
-async fn send_principals_i_follow(users_data: &AllUserData) {
+async fn send_principals_i_follow(users_data: &AllUserData) -> Result<(), String> {
     let canister_id_to_send_to = users_data.user_canister_id;
 
     let principals_i_follow_vec = users_data
@@ -104,17 +197,19 @@ async fn send_principals_i_follow(users_data: &AllUserData) {
         .collect::<Vec<_>>();
 
     for chunk in principals_i_follow_vec_chunks {
-        let _: () = call::call(
+        call::call::<_, ()>(
             canister_id_to_send_to,
             "receive_principals_i_follow_from_data_backup_canister",
             (chunk.to_vec(),),
         )
         .await
-        .expect("Failed to call the receive_principals_i_follow_from_data_backup_canister method on the individual user's canister");
+        .map_err(|e| format!("Failed to call the receive_principals_i_follow_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
     }
+
+    Ok(())
 }
 
-async fn send_utility_token_history(users_data: &AllUserData) {
+async fn send_utility_token_history(users_data: &AllUserData) -> Result<(), String> {
     let canister_id_to_send_to = users_data.user_canister_id;
 
     let all_utility_token_transactions_vec = users_data
@@ -130,29 +225,33 @@ async fn send_utility_token_history(users_data: &AllUserData) {
         .collect::<Vec<_>>();
 
     for chunk in all_utility_token_transactions_chunks {
-        let _: () = call::call(
+        call::call::<_, ()>(
             canister_id_to_send_to,
             "receive_my_utility_token_transaction_history_from_data_backup_canister",
             (chunk.to_vec(),),
         )
         .await
-        .expect("Failed to call the receive_my_utility_token_transaction_history_from_data_backup_canister method on the individual user's canister");
+        .map_err(|e| format!("Failed to call the receive_my_utility_token_transaction_history_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
     }
+
+    Ok(())
 }
 
-async fn send_utility_token_balance(users_data: &AllUserData) {
+async fn send_utility_token_balance(users_data: &AllUserData) -> Result<(), String> {
     let canister_id_to_send_to = users_data.user_canister_id;
 
-    let _: () = call::call(
+    call::call::<_, ()>(
         canister_id_to_send_to,
         "receive_my_utility_token_balance_from_data_backup_canister",
         (users_data.canister_data.token_data.utility_token_balance,),
     )
     .await
-    .expect("Failed to call the receive_my_utility_token_balance_from_data_backup_canister method on the individual user's canister");
+    .map_err(|e| format!("Failed to call the receive_my_utility_token_balance_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
+
+    Ok(())
 }
 
-async fn send_posts(users_data: &AllUserData) {
+async fn send_posts(users_data: &AllUserData) -> Result<(), String> {
     let canister_id_to_send_to = users_data.user_canister_id;
 
     let all_created_posts_vec = users_data
@@ -165,12 +264,63 @@ async fn send_posts(users_data: &AllUserData) {
     let all_created_posts_chunks = all_created_posts_vec.chunks(CHUNK_SIZE).collect::<Vec<_>>();
 
     for chunk in all_created_posts_chunks {
-        let _: () = call::call(
+        call::call::<_, ()>(
             canister_id_to_send_to,
             "receive_my_created_posts_from_data_backup_canister",
             (chunk.to_vec(),),
         )
         .await
-        .expect("Failed to call the receive_my_created_posts_from_data_backup_canister method on the individual user's canister");
+        .map_err(|e| format!("Failed to call the receive_my_created_posts_from_data_backup_canister method on the individual user's canister: {}", e.1))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::{
+        canister_specific::individual_user_template::types::configuration::IndividualUserConfiguration,
+        common::types::version_details::VersionDetails,
+    };
+    use test_utils::setup::test_constants::get_mock_placed_bet_detail;
+
+    use super::*;
+
+    #[test]
+    fn test_get_canister_data_sections_to_restore() {
+        // * backups taken before versioning only restore through the dedicated endpoints
+        assert!(
+            get_canister_data_sections_to_restore(&UserOwnedCanisterData::default()).is_empty()
+        );
+
+        let all_hot_or_not_bets_placed = (0..CHUNK_SIZE as u64 + 1)
+            .map(get_mock_placed_bet_detail)
+            .collect();
+        let canister_data = UserOwnedCanisterData {
+            all_hot_or_not_bets_placed: Some(all_hot_or_not_bets_placed),
+            configuration: Some(IndividualUserConfiguration::default()),
+            version_details: Some(VersionDetails::default()),
+            last_access_time: Some(None),
+            ..Default::default()
+        };
+
+        let canister_data_sections = get_canister_data_sections_to_restore(&canister_data);
+        assert_eq!(canister_data_sections.len(), 4);
+        assert!(matches!(
+            &canister_data_sections[0],
+            IndividualUserCanisterDataSection::HotOrNotBetsPlaced(chunk) if chunk.len() == CHUNK_SIZE
+        ));
+        assert!(matches!(
+            &canister_data_sections[1],
+            IndividualUserCanisterDataSection::HotOrNotBetsPlaced(chunk) if chunk.len() == 1
+        ));
+        assert!(matches!(
+            canister_data_sections[2],
+            IndividualUserCanisterDataSection::Configuration(_)
+        ));
+        assert!(matches!(
+            canister_data_sections[3],
+            IndividualUserCanisterDataSection::LastAccessTime(None)
+        ));
     }
 }
@@ -0,0 +1,131 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::all_user_data::AllUserData,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+use super::restore_backed_up_data_to_individual_users_canister::send_all_backed_up_data_to_users_canister;
+
+/// Restores a user's backed up data to the new canister they got after their previous canister
+/// was reclaimed. Further backups are accepted from the new canister only.
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+async fn restore_backed_up_data_to_reprovisioned_individual_user_canister(
+    user_principal_id: Principal,
+    new_user_canister_id: Principal,
+) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    let users_data = CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_user_canister_id_of_backed_up_data_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            user_principal_id,
+            new_user_canister_id,
+        )
+    })?;
+
+    send_all_backed_up_data_to_users_canister(&users_data).await
+}
+
+fn update_user_canister_id_of_backed_up_data_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+    new_user_canister_id: Principal,
+) -> Result<AllUserData, String> {
+    let user_index_canister_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if caller_principal_id != *user_index_canister_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.migrate_legacy_user_data(&user_principal_id);
+
+    let mut manifest = canister_data
+        .user_backup_store
+        .get_manifest(&user_principal_id)
+        .ok_or("No user data found")?;
+
+    manifest.user_canister_id = new_user_canister_id;
+    // * the new canister tracks changes from the start, so its first backup run sends everything
+    manifest.last_acknowledged_change_sequence_number = None;
+
+    canister_data.user_backup_store.insert_manifest(manifest);
+
+    canister_data
+        .get_all_user_data(&user_principal_id)?
+        .ok_or("No user data found".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::all_user_data::UserOwnedCanisterData;
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_update_user_canister_id_of_backed_up_data_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        let result = update_user_canister_id_of_backed_up_data_impl(
+            &mut canister_data,
+            get_mock_canister_id_user_index(),
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        assert_eq!(result.err(), Some("No user data found".to_string()));
+
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+
+        let result = update_user_canister_id_of_backed_up_data_impl(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        assert_eq!(result.err(), Some("Unauthorized".to_string()));
+
+        let result = update_user_canister_id_of_backed_up_data_impl(
+            &mut canister_data,
+            get_mock_canister_id_user_index(),
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        assert_eq!(
+            result.unwrap().user_canister_id,
+            get_mock_user_bob_canister_id()
+        );
+        assert_eq!(
+            canister_data
+                .get_all_user_data(&get_mock_user_alice_principal_id())
+                .unwrap()
+                .unwrap()
+                .user_canister_id,
+            get_mock_user_bob_canister_id()
+        );
+    }
+}
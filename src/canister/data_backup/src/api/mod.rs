@@ -1,6 +1,9 @@
 pub mod access_control;
+pub mod backup_snapshot;
 pub mod backup_statistics;
 pub mod canister_lifecycle;
 pub mod individual_user_backup;
+pub mod restore_run;
+pub mod user_data_archive;
 pub mod user_index_backup;
 pub mod well_known_principal;
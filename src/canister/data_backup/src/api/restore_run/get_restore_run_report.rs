@@ -0,0 +1,77 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::restore_run::RestoreRunReport,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// The restore run in progress, or else the last one
+///
+/// # Access Control
+/// Only the global super admin can call this.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_restore_run_report() -> Result<Option<RestoreRunReport>, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_restore_run_report_impl(&canister_data_ref_cell.borrow(), caller_principal_id)
+    })
+}
+
+fn get_restore_run_report_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+) -> Result<Option<RestoreRunReport>, String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(canister_data.heap_data.restore_run.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::restore_run::RestoreRunScope;
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_restore_run_report_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        assert_eq!(
+            get_restore_run_report_impl(&canister_data, get_mock_user_alice_principal_id()),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            get_restore_run_report_impl(&canister_data, get_global_super_admin_principal_id()),
+            Ok(None)
+        );
+
+        canister_data
+            .start_restore_run(RestoreRunScope::AllUsers, 10, SystemTime::now())
+            .unwrap();
+        assert!(
+            get_restore_run_report_impl(&canister_data, get_global_super_admin_principal_id())
+                .unwrap()
+                .is_some()
+        );
+    }
+}
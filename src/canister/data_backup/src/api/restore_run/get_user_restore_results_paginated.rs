@@ -0,0 +1,155 @@
+use std::ops::Bound;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::restore_run::UserRestoreResult,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+const MAX_USER_RESTORE_RESULTS_PER_PAGE: u64 = 100;
+
+/// The outcome of each user restored by the latest restore run, in order of principal, starting
+/// after `start_after`. With `failed_only`, only the users whose restore failed, so that they can
+/// be restored again with a run of the selected users.
+///
+/// # Access Control
+/// Only the global super admin can call this.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_restore_results_paginated(
+    start_after: Option<Principal>,
+    limit: u64,
+    failed_only: bool,
+) -> Result<Vec<UserRestoreResult>, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_restore_results_paginated_impl(
+            &canister_data_ref_cell.borrow(),
+            caller_principal_id,
+            start_after,
+            limit,
+            failed_only,
+        )
+    })
+}
+
+fn get_user_restore_results_paginated_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    start_after: Option<Principal>,
+    limit: u64,
+    failed_only: bool,
+) -> Result<Vec<UserRestoreResult>, String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    let start_bound = match start_after {
+        Some(start_after) => Bound::Excluded(start_after),
+        None => Bound::Unbounded,
+    };
+
+    Ok(canister_data
+        .heap_data
+        .user_restore_results
+        .range((start_bound, Bound::Unbounded))
+        .map(|(_, user_restore_result)| user_restore_result)
+        .filter(|user_restore_result| !failed_only || user_restore_result.error.is_some())
+        .take(limit.min(MAX_USER_RESTORE_RESULTS_PER_PAGE) as usize)
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_restore_results_paginated_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let now = SystemTime::now();
+
+        assert_eq!(
+            get_user_restore_results_paginated_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                None,
+                10,
+                false
+            ),
+            Err("Unauthorized".to_string())
+        );
+
+        for (user_principal_id, error) in [
+            (get_mock_user_alice_principal_id(), None),
+            (
+                get_mock_user_bob_principal_id(),
+                Some("No user data found".to_string()),
+            ),
+        ] {
+            canister_data.heap_data.user_restore_results.insert(
+                user_principal_id,
+                UserRestoreResult {
+                    user_principal_id,
+                    user_canister_id: None,
+                    finished_at: now,
+                    error,
+                },
+            );
+        }
+
+        let all_results = get_user_restore_results_paginated_impl(
+            &canister_data,
+            get_global_super_admin_principal_id(),
+            None,
+            10,
+            false,
+        )
+        .unwrap();
+        assert_eq!(all_results.len(), 2);
+
+        let next_page = get_user_restore_results_paginated_impl(
+            &canister_data,
+            get_global_super_admin_principal_id(),
+            Some(all_results[0].user_principal_id),
+            10,
+            false,
+        )
+        .unwrap();
+        assert_eq!(next_page, all_results[1..].to_vec());
+
+        let failed_results = get_user_restore_results_paginated_impl(
+            &canister_data,
+            get_global_super_admin_principal_id(),
+            None,
+            10,
+            true,
+        )
+        .unwrap();
+        assert_eq!(failed_results.len(), 1);
+        assert_eq!(
+            failed_results[0].user_principal_id,
+            get_mock_user_bob_principal_id()
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod get_restore_run_report;
+pub mod get_user_restore_results_paginated;
+pub mod start_restore_run;
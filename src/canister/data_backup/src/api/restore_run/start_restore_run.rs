@@ -0,0 +1,140 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::restore_run::RestoreRunScope,
+    common::{types::known_principal::KnownPrincipalType, utils::system_time},
+};
+
+use crate::{
+    data::memory_layout::CanisterData, util::restore_run_job::run_restore_run_job, CANISTER_DATA,
+};
+
+const DEFAULT_MAX_CONCURRENT_RESTORES: u64 = 10;
+const MAX_CONCURRENT_RESTORES: u64 = 50;
+
+/// Restores the backups of the users in `scope` to their canisters, and records their canisters
+/// and usernames in the user index, restoring `max_concurrent_restores` users at a time. The
+/// progress is reported in `get_restore_run_report` and the outcome of each user in
+/// `get_user_restore_results_paginated`.
+///
+/// # Access Control
+/// Only the global super admin can start a restore run.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn start_restore_run(
+    scope: RestoreRunScope,
+    max_concurrent_restores: Option<u64>,
+) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        start_restore_run_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            scope,
+            max_concurrent_restores,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })?;
+
+    run_restore_run_job();
+
+    Ok(())
+}
+
+fn start_restore_run_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    scope: RestoreRunScope,
+    max_concurrent_restores: Option<u64>,
+    current_time: SystemTime,
+) -> Result<(), String> {
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    if !canister_data
+        .heap_data
+        .known_principal_ids
+        .contains_key(&KnownPrincipalType::CanisterIdUserIndex)
+    {
+        return Err("User index canister not found in internal records".to_string());
+    }
+
+    let max_concurrent_restores = max_concurrent_restores
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_RESTORES)
+        .clamp(1, MAX_CONCURRENT_RESTORES);
+
+    canister_data.start_restore_run(scope, max_concurrent_restores, current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_user_index,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_start_restore_run_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let now = SystemTime::now();
+
+        assert_eq!(
+            start_restore_run_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                RestoreRunScope::AllUsers,
+                None,
+                now
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            start_restore_run_impl(
+                &mut canister_data,
+                get_global_super_admin_principal_id(),
+                RestoreRunScope::AllUsers,
+                None,
+                now
+            ),
+            Err("User index canister not found in internal records".to_string())
+        );
+
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+        assert_eq!(
+            start_restore_run_impl(
+                &mut canister_data,
+                get_global_super_admin_principal_id(),
+                RestoreRunScope::AllUsers,
+                Some(1000),
+                now
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            canister_data
+                .heap_data
+                .restore_run
+                .unwrap()
+                .max_concurrent_restores,
+            MAX_CONCURRENT_RESTORES
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::data_backup::types::{
+        all_user_data::AllUserData, backup_key_counts::BackupKeyCounts,
+        user_data_archive::UserDataArchiveManifest,
+    },
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{
+    api::individual_user_backup::restore_backed_up_data_to_individual_users_canister::send_all_backed_up_data_to_users_canister,
+    data::memory_layout::CanisterData, CANISTER_DATA,
+};
+
+use super::check_caller_can_import_user_data;
+
+/// Restores the uploaded archive into the canister the user has now, which must not hold any
+/// data yet. Once the canister holds it, the archive replaces the user's backup, so that later
+/// restores and backup runs start from it.
+///
+/// # Access Control
+/// Only the user the archive belongs to, or the global super admin, can import it.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+async fn complete_backup_archive_import(user_principal_id: Principal) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    let (mut users_data, manifest, user_index_canister_id) =
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            get_backup_archive_import_to_complete_impl(
+                &canister_data_ref_cell.borrow(),
+                caller_principal_id,
+                user_principal_id,
+            )
+        })?;
+
+    let (user_canister_id,): (Option<Principal>,) = call::call(
+        user_index_canister_id,
+        "get_user_canister_id_from_user_principal_id",
+        (user_principal_id,),
+    )
+    .await
+    .map_err(|e| e.1)?;
+    users_data.user_canister_id = user_canister_id.ok_or("The user has no canister")?;
+
+    let (key_counts,): (BackupKeyCounts,) =
+        call::call(users_data.user_canister_id, "get_backup_key_counts", ())
+            .await
+            .map_err(|e| e.1)?;
+    if key_counts != BackupKeyCounts::default() {
+        return Err("The user's canister already holds data".to_string());
+    }
+
+    send_all_backed_up_data_to_users_canister(&users_data).await?;
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        commit_backup_archive_import_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            &manifest,
+            &users_data,
+        )
+    });
+
+    Ok(())
+}
+
+/// The decoded archive, its manifest and the user index to ask for the user's canister
+fn get_backup_archive_import_to_complete_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+) -> Result<(AllUserData, UserDataArchiveManifest, Principal), String> {
+    check_caller_can_import_user_data(canister_data, caller_principal_id, user_principal_id)?;
+
+    let user_index_canister_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    // * fail before any call if the archive is incomplete or corrupt
+    let users_data = canister_data.get_user_data_import(&user_principal_id)?;
+    let manifest = canister_data
+        .get_user_data_import_manifest(&user_principal_id)
+        .cloned()
+        .ok_or("No import in progress")?;
+
+    Ok((users_data, manifest, *user_index_canister_id))
+}
+
+/// Replaces the user's backup with the archive the user's canister now holds, and ends the
+/// import unless it was restarted meanwhile
+fn commit_backup_archive_import_impl(
+    canister_data: &mut CanisterData,
+    manifest: &UserDataArchiveManifest,
+    users_data: &AllUserData,
+) {
+    let user_principal_id = users_data.user_principal_id;
+
+    canister_data.migrate_legacy_user_data(&user_principal_id);
+    canister_data
+        .user_backup_store
+        .insert_all_user_data(users_data);
+    // * the restored canister tracks changes from the start, so its next backup run sends
+    // * everything
+    canister_data
+        .user_backup_store
+        .reset_acknowledged_change_sequence_number(&user_principal_id);
+
+    if canister_data.get_user_data_import_manifest(&user_principal_id) == Some(manifest) {
+        canister_data.discard_user_data_import(&user_principal_id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::all_user_data::UserOwnedCanisterData;
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_complete_backup_archive_import() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData {
+                    principals_i_follow: [get_mock_user_bob_principal_id()].into(),
+                    ..Default::default()
+                },
+            });
+        let manifest = canister_data
+            .export_user_data(&get_mock_user_alice_principal_id(), SystemTime::UNIX_EPOCH)
+            .unwrap();
+        let chunk = canister_data
+            .get_user_data_export_chunk(&get_mock_user_alice_principal_id(), 0)
+            .unwrap();
+
+        canister_data
+            .start_user_data_import(manifest.clone(), SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(
+            get_backup_archive_import_to_complete_impl(
+                &canister_data,
+                get_mock_user_bob_principal_id(),
+                get_mock_user_alice_principal_id(),
+            )
+            .unwrap_err(),
+            "Global super admin not found in internal records"
+        );
+        assert_eq!(
+            get_backup_archive_import_to_complete_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+            )
+            .unwrap_err(),
+            "Chunk 0 was not uploaded"
+        );
+
+        canister_data
+            .upload_user_data_import_chunk(&get_mock_user_alice_principal_id(), 0, chunk)
+            .unwrap();
+        let (mut users_data, manifest_to_complete, user_index_canister_id) =
+            get_backup_archive_import_to_complete_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+            )
+            .unwrap();
+        assert_eq!(manifest_to_complete, manifest);
+        assert_eq!(user_index_canister_id, get_mock_canister_id_user_index());
+
+        users_data.user_canister_id = get_mock_user_bob_canister_id();
+        commit_backup_archive_import_impl(&mut canister_data, &manifest, &users_data);
+        assert!(canister_data.heap_data.user_data_import.is_none());
+
+        let manifest_after_import = canister_data
+            .user_backup_store
+            .get_manifest(&get_mock_user_alice_principal_id())
+            .unwrap();
+        assert_eq!(
+            manifest_after_import.user_canister_id,
+            get_mock_user_bob_canister_id()
+        );
+        assert_eq!(
+            manifest_after_import.last_acknowledged_change_sequence_number,
+            None
+        );
+        assert!(canister_data
+            .get_all_user_data(&get_mock_user_alice_principal_id())
+            .unwrap()
+            .unwrap()
+            .canister_data
+            .principals_i_follow
+            .contains(&get_mock_user_bob_principal_id()));
+
+        // * an import restarted while the canister was being restored is kept
+        let restarted_manifest = UserDataArchiveManifest {
+            exported_at: SystemTime::now(),
+            ..manifest.clone()
+        };
+        canister_data
+            .start_user_data_import(restarted_manifest.clone(), SystemTime::UNIX_EPOCH)
+            .unwrap();
+        commit_backup_archive_import_impl(&mut canister_data, &manifest, &users_data);
+        assert_eq!(
+            canister_data.get_user_data_import_manifest(&get_mock_user_alice_principal_id()),
+            Some(&restarted_manifest)
+        );
+    }
+}
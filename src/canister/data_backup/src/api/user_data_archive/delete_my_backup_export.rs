@@ -0,0 +1,81 @@
+use candid::Principal;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Deletes the archive last exported by `export_my_backup`, once it has been downloaded
+///
+/// # Access Control
+/// Users can only delete their own export.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn delete_my_backup_export() -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        delete_my_backup_export_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+        )
+    })
+}
+
+fn delete_my_backup_export_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+) -> Result<(), String> {
+    if caller_principal_id == Principal::anonymous() {
+        return Err("Unauthorized".to_string());
+    }
+
+    if !canister_data
+        .heap_data
+        .user_data_exports
+        .contains_key(&caller_principal_id)
+    {
+        return Err("No export found".to_string());
+    }
+
+    canister_data.delete_user_data_export(&caller_principal_id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::all_user_data::{
+        AllUserData, UserOwnedCanisterData,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_delete_my_backup_export_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+
+        assert_eq!(
+            delete_my_backup_export_impl(&mut canister_data, get_mock_user_alice_principal_id()),
+            Err("No export found".to_string())
+        );
+
+        canister_data
+            .export_user_data(&get_mock_user_alice_principal_id(), SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(
+            delete_my_backup_export_impl(&mut canister_data, get_mock_user_alice_principal_id()),
+            Ok(())
+        );
+        assert!(canister_data.heap_data.user_data_exports.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::user_data_archive::UserDataArchiveManifest,
+    common::utils::system_time,
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Exports the caller's backup as a portable archive, replacing their previous export. The
+/// archive is downloaded chunk by chunk with `get_my_backup_export_chunk`.
+///
+/// # Access Control
+/// Users can only export their own backup.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn export_my_backup() -> Result<UserDataArchiveManifest, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        export_my_backup_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn export_my_backup_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    current_time: SystemTime,
+) -> Result<UserDataArchiveManifest, String> {
+    if caller_principal_id == Principal::anonymous() {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.export_user_data(&caller_principal_id, current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::all_user_data::{
+        AllUserData, UserOwnedCanisterData,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_export_my_backup_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+
+        assert_eq!(
+            export_my_backup_impl(
+                &mut canister_data,
+                Principal::anonymous(),
+                SystemTime::UNIX_EPOCH
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            export_my_backup_impl(
+                &mut canister_data,
+                get_mock_user_bob_principal_id(),
+                SystemTime::UNIX_EPOCH
+            ),
+            Err("No user data found".to_string())
+        );
+
+        let manifest = export_my_backup_impl(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            SystemTime::UNIX_EPOCH,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.user_principal_id,
+            get_mock_user_alice_principal_id()
+        );
+        assert_eq!(
+            manifest.source_user_canister_id,
+            get_mock_user_alice_canister_id()
+        );
+        assert_eq!(
+            canister_data
+                .heap_data
+                .user_data_exports
+                .get(&get_mock_user_alice_principal_id()),
+            Some(&manifest)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use candid::Principal;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// A chunk of the archive last exported by `export_my_backup`
+///
+/// # Access Control
+/// Users can only download their own export.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_my_backup_export_chunk(chunk_index: u32) -> Result<Vec<u8>, String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_my_backup_export_chunk_impl(
+            &canister_data_ref_cell.borrow(),
+            caller_principal_id,
+            chunk_index,
+        )
+    })
+}
+
+fn get_my_backup_export_chunk_impl(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    chunk_index: u32,
+) -> Result<Vec<u8>, String> {
+    if caller_principal_id == Principal::anonymous() {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.get_user_data_export_chunk(&caller_principal_id, chunk_index)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::all_user_data::{
+        AllUserData, UserOwnedCanisterData,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_my_backup_export_chunk_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+        let manifest = canister_data
+            .export_user_data(&get_mock_user_alice_principal_id(), SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        assert_eq!(
+            get_my_backup_export_chunk_impl(&canister_data, get_mock_user_bob_principal_id(), 0),
+            Err("No export found".to_string())
+        );
+
+        let chunk =
+            get_my_backup_export_chunk_impl(&canister_data, get_mock_user_alice_principal_id(), 0)
+                .unwrap();
+        assert_eq!(chunk.len() as u64, manifest.size_in_bytes);
+        assert!(manifest.decode_archive(&chunk).is_ok());
+    }
+}
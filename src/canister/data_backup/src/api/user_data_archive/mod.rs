@@ -0,0 +1,34 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::data::memory_layout::CanisterData;
+
+pub mod complete_backup_archive_import;
+pub mod delete_my_backup_export;
+pub mod export_my_backup;
+pub mod get_my_backup_export_chunk;
+pub mod start_backup_archive_import;
+pub mod upload_backup_archive_import_chunk;
+
+/// Archives are imported by the user they belong to, or by the global super admin on their behalf
+fn check_caller_can_import_user_data(
+    canister_data: &CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+) -> Result<(), String> {
+    if caller_principal_id != Principal::anonymous() && caller_principal_id == user_principal_id {
+        return Ok(());
+    }
+
+    let global_super_admin_principal_id = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Global super admin not found in internal records")?;
+
+    if caller_principal_id != *global_super_admin_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(())
+}
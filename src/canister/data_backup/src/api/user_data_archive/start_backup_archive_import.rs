@@ -0,0 +1,204 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::user_data_archive::UserDataArchiveManifest,
+    common::{
+        types::{known_principal::KnownPrincipalType, storable_principal::StorablePrincipal},
+        utils::system_time,
+    },
+};
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+/// Starts importing the archive described by `manifest`. Its chunks are then uploaded with
+/// `upload_backup_archive_import_chunk` and the import is finished by
+/// `complete_backup_archive_import`. One archive is imported at a time. Any earlier import of the
+/// user that was not completed is discarded.
+///
+/// # Access Control
+/// Users with a backup can import one of the latest archives this canister exported for them.
+/// The global super admin can import any archive on behalf of a user.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn start_backup_archive_import(manifest: UserDataArchiveManifest) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        start_backup_archive_import_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            manifest,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn start_backup_archive_import_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    manifest: UserDataArchiveManifest,
+    current_time: SystemTime,
+) -> Result<(), String> {
+    let is_caller_global_super_admin = canister_data
+        .heap_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        == Some(&caller_principal_id);
+
+    if !is_caller_global_super_admin {
+        if caller_principal_id == Principal::anonymous()
+            || caller_principal_id != manifest.user_principal_id
+        {
+            return Err("Unauthorized".to_string());
+        }
+
+        let has_backup = canister_data
+            .user_backup_store
+            .contains_user(&caller_principal_id)
+            || canister_data
+                .legacy_user_principal_id_to_all_user_data_map
+                .contains_key(&StorablePrincipal(caller_principal_id));
+        if !has_backup {
+            return Err("No user data found".to_string());
+        }
+
+        if !canister_data.is_exported_user_data_archive(&manifest) {
+            return Err("The archive was not exported by this canister".to_string());
+        }
+    }
+
+    canister_data.start_user_data_import(manifest, current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use shared_utils::canister_specific::data_backup::types::all_user_data::{
+        AllUserData, UserOwnedCanisterData,
+    };
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn get_manifest(user_principal_id: Principal) -> UserDataArchiveManifest {
+        UserDataArchiveManifest {
+            format_version: 1,
+            user_principal_id,
+            source_user_canister_id: get_mock_user_alice_canister_id(),
+            exported_at: SystemTime::UNIX_EPOCH,
+            chunk_count: 1,
+            size_in_bytes: 10,
+            sha256: vec![],
+        }
+    }
+
+    #[test]
+    fn test_start_backup_archive_import_impl() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        canister_data.heap_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let alice_manifest = get_manifest(get_mock_user_alice_principal_id());
+
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_mock_user_bob_principal_id(),
+                alice_manifest.clone(),
+                now
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                alice_manifest.clone(),
+                now
+            ),
+            Err("No user data found".to_string())
+        );
+
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                alice_manifest.clone(),
+                now
+            ),
+            Err("The archive was not exported by this canister".to_string())
+        );
+
+        let exported_manifest = canister_data
+            .export_user_data(&get_mock_user_alice_principal_id(), now)
+            .unwrap();
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                UserDataArchiveManifest {
+                    format_version: 0,
+                    ..exported_manifest.clone()
+                },
+                now
+            ),
+            Err("Unsupported archive format version 0".to_string())
+        );
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                exported_manifest.clone(),
+                now
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            canister_data.get_user_data_import_manifest(&get_mock_user_alice_principal_id()),
+            Some(&exported_manifest)
+        );
+
+        // * the super admin can import any archive, one at a time
+        let bob_manifest = UserDataArchiveManifest {
+            source_user_canister_id: get_mock_user_bob_canister_id(),
+            ..get_manifest(get_mock_user_bob_principal_id())
+        };
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_global_super_admin_principal_id(),
+                bob_manifest.clone(),
+                now
+            ),
+            Err("Another import is in progress".to_string())
+        );
+        assert_eq!(
+            start_backup_archive_import_impl(
+                &mut canister_data,
+                get_global_super_admin_principal_id(),
+                bob_manifest.clone(),
+                now + Duration::from_secs(60 * 60)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            canister_data.get_user_data_import_manifest(&get_mock_user_alice_principal_id()),
+            None
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use candid::Principal;
+
+use crate::{data::memory_layout::CanisterData, CANISTER_DATA};
+
+use super::check_caller_can_import_user_data;
+
+/// Uploads a chunk of the archive whose import was started by `start_backup_archive_import`.
+/// Uploading a chunk again replaces it.
+///
+/// # Access Control
+/// Only the user the archive belongs to, or the global super admin, can upload it.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn upload_backup_archive_import_chunk(
+    user_principal_id: Principal,
+    chunk_index: u32,
+    chunk: Vec<u8>,
+) -> Result<(), String> {
+    let caller_principal_id = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        upload_backup_archive_import_chunk_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            caller_principal_id,
+            user_principal_id,
+            chunk_index,
+            chunk,
+        )
+    })
+}
+
+fn upload_backup_archive_import_chunk_impl(
+    canister_data: &mut CanisterData,
+    caller_principal_id: Principal,
+    user_principal_id: Principal,
+    chunk_index: u32,
+    chunk: Vec<u8>,
+) -> Result<(), String> {
+    check_caller_can_import_user_data(canister_data, caller_principal_id, user_principal_id)?;
+
+    canister_data.upload_user_data_import_chunk(&user_principal_id, chunk_index, chunk)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::data_backup::types::user_data_archive::UserDataArchiveManifest;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_upload_backup_archive_import_chunk_impl() {
+        let mut canister_data = CanisterData::default();
+
+        assert_eq!(
+            upload_backup_archive_import_chunk_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+                0,
+                vec![1],
+            ),
+            Err("No import in progress".to_string())
+        );
+
+        canister_data
+            .start_user_data_import(
+                UserDataArchiveManifest {
+                    format_version: 1,
+                    user_principal_id: get_mock_user_alice_principal_id(),
+                    source_user_canister_id: get_mock_user_alice_canister_id(),
+                    exported_at: SystemTime::UNIX_EPOCH,
+                    chunk_count: 1,
+                    size_in_bytes: 1,
+                    sha256: vec![],
+                },
+                SystemTime::UNIX_EPOCH,
+            )
+            .unwrap();
+
+        assert_eq!(
+            upload_backup_archive_import_chunk_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+                1,
+                vec![1],
+            ),
+            Err("Chunk index out of range".to_string())
+        );
+        assert_eq!(
+            upload_backup_archive_import_chunk_impl(
+                &mut canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_principal_id(),
+                0,
+                vec![1],
+            ),
+            Ok(())
+        );
+    }
+}
@@ -1,4 +1,10 @@
-use shared_utils::common::types::known_principal::KnownPrincipalType;
+use shared_utils::{
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::profile::UserProfile,
+    },
+    common::types::known_principal::KnownPrincipalType,
+};
 
 use crate::CANISTER_DATA;
 
@@ -28,27 +34,59 @@ fn send_restore_data_back_to_user_index_canister() {
             .get(&KnownPrincipalType::CanisterIdUserIndex)
             .unwrap();
 
-        canister_data_ref_cell
-            .borrow()
-            .user_principal_id_to_all_user_data_map
+        let canister_data = canister_data_ref_cell.borrow();
+
+        let users_in_backup_store = canister_data
+            .user_backup_store
+            .user_principal_id_to_manifest_map
+            .iter()
+            .map(|(storable_user_principal_id, manifest)| {
+                // * an unreadable profile only loses the user name, the user is still restored
+                let unique_user_name = canister_data
+                    .user_backup_store
+                    .read_section::<UserProfile>(
+                        &storable_user_principal_id.0,
+                        BackupSection::Profile,
+                    )
+                    .ok()
+                    .flatten()
+                    .and_then(|profile| profile.unique_user_name);
+
+                (
+                    storable_user_principal_id.0,
+                    manifest.user_canister_id,
+                    unique_user_name,
+                )
+            });
+        let users_in_legacy_map = canister_data
+            .legacy_user_principal_id_to_all_user_data_map
             .iter()
-            .for_each(
+            .map(
                 |(storable_user_principal_id, corresponding_all_user_data)| {
-                    ic_cdk::notify(
-                        user_index_canister_id,
-                        "receive_data_from_backup_canister_and_restore_data_to_heap",
-                        (
-                            storable_user_principal_id.0,
-                            corresponding_all_user_data.user_canister_id,
-                            corresponding_all_user_data
-                                .canister_data
-                                .profile
-                                .unique_user_name
-                                .unwrap_or("".to_string()),
-                        ),
+                    (
+                        storable_user_principal_id.0,
+                        corresponding_all_user_data.user_canister_id,
+                        corresponding_all_user_data
+                            .canister_data
+                            .profile
+                            .unique_user_name,
                     )
-                    .unwrap_or_default();
                 },
             );
+
+        users_in_backup_store.chain(users_in_legacy_map).for_each(
+            |(user_principal_id, user_canister_id, unique_user_name)| {
+                ic_cdk::notify(
+                    user_index_canister_id,
+                    "receive_data_from_backup_canister_and_restore_data_to_heap",
+                    (
+                        user_principal_id,
+                        user_canister_id,
+                        unique_user_name.unwrap_or("".to_string()),
+                    ),
+                )
+                .unwrap_or_default();
+            },
+        );
     });
 }
@@ -0,0 +1,264 @@
+use std::{ops::Bound, time::SystemTime};
+
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::StableBTreeMap;
+use serde::Serialize;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_statistics::BackupCoverageSummary,
+    common::types::storable_principal::StorablePrincipal,
+};
+
+use super::memory_layout::{self, CanisterData, Memory};
+
+/// Users of the user index found without a backup. There can be as many as there are users, so
+/// they are kept in stable memory. The scan in progress fills one set while the other holds what
+/// the last completed scan found.
+pub struct UsersWithoutBackupStore {
+    sets: [StableBTreeMap<StorablePrincipal, (), Memory>; 2],
+}
+
+impl Default for UsersWithoutBackupStore {
+    fn default() -> Self {
+        Self {
+            sets: [0, 1].map(|set_index| {
+                StableBTreeMap::init(memory_layout::get_users_without_backup_set_memory(
+                    set_index,
+                ))
+            }),
+        }
+    }
+}
+
+impl UsersWithoutBackupStore {
+    fn clear_set(&mut self, set_index: usize) {
+        self.sets[set_index] = StableBTreeMap::new(
+            memory_layout::get_users_without_backup_set_memory(set_index),
+        );
+    }
+
+    pub fn remove_user(&mut self, user_principal_id: &Principal) {
+        self.sets.iter_mut().for_each(|set| {
+            set.remove(&StorablePrincipal(*user_principal_id));
+        });
+    }
+}
+
+/// A scan that walks every backup, then every user of the user index, to compute a
+/// `BackupCoverageSummary` and find the users that have no backup
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct BackupCoverageScan {
+    pub summary: BackupCoverageSummary,
+    /// The last user whose backup was scanned
+    pub manifest_cursor: Option<Principal>,
+    pub are_backups_scanned: bool,
+    /// The last user of the user index that was checked
+    pub user_index_cursor: Option<Principal>,
+}
+
+impl CanisterData {
+    pub fn start_backup_coverage_scan(&mut self, current_time: SystemTime) -> Result<(), String> {
+        if self.heap_data.backup_coverage_scan_in_progress.is_some() {
+            return Err("Backup coverage scan is already in progress".to_string());
+        }
+
+        self.heap_data.backup_coverage_scan_in_progress = Some(BackupCoverageScan {
+            summary: BackupCoverageSummary::new(current_time),
+            manifest_cursor: None,
+            are_backups_scanned: false,
+            user_index_cursor: None,
+        });
+        self.users_without_backup_store
+            .clear_set(self.get_users_without_backup_scan_set_index());
+
+        Ok(())
+    }
+
+    /// Adds the next `batch_size` backups to the summary of the scan in progress
+    pub fn scan_backups_for_coverage_batch(&mut self, batch_size: usize) {
+        let Some(scan) = self.heap_data.backup_coverage_scan_in_progress.as_mut() else {
+            return;
+        };
+
+        let start_bound = match scan.manifest_cursor {
+            Some(user_principal_id) => Bound::Excluded(StorablePrincipal(user_principal_id)),
+            None => Bound::Unbounded,
+        };
+
+        let mut scanned_count = 0;
+        for (user_principal_id, manifest) in self
+            .user_backup_store
+            .user_principal_id_to_manifest_map
+            .range((start_bound, Bound::Unbounded))
+            .take(batch_size)
+        {
+            scan.summary.record_manifest(&manifest);
+            scan.manifest_cursor = Some(user_principal_id.0);
+            scanned_count += 1;
+        }
+
+        if scanned_count < batch_size {
+            scan.are_backups_scanned = true;
+        }
+    }
+
+    /// Records which users of a page of the user index have no backup
+    pub fn record_user_index_page_for_backup_coverage(&mut self, user_principal_ids: &[Principal]) {
+        let users_without_backup: Vec<Principal> = user_principal_ids
+            .iter()
+            .filter(|user_principal_id| {
+                !self.user_backup_store.contains_user(user_principal_id)
+                    && !self
+                        .legacy_user_principal_id_to_all_user_data_map
+                        .contains_key(&StorablePrincipal(**user_principal_id))
+            })
+            .copied()
+            .collect();
+
+        let Some(scan) = self.heap_data.backup_coverage_scan_in_progress.as_mut() else {
+            return;
+        };
+
+        scan.summary.users_without_backup_count += users_without_backup.len() as u64;
+        if let Some(last_user_principal_id) = user_principal_ids.last() {
+            scan.user_index_cursor = Some(*last_user_principal_id);
+        }
+
+        let scan_set_index = self.get_users_without_backup_scan_set_index();
+        users_without_backup
+            .into_iter()
+            .for_each(|user_principal_id| {
+                self.users_without_backup_store.sets[scan_set_index]
+                    .insert(StorablePrincipal(user_principal_id), ());
+            });
+    }
+
+    pub fn complete_backup_coverage_scan(&mut self, current_time: SystemTime) {
+        let Some(mut scan) = self.heap_data.backup_coverage_scan_in_progress.take() else {
+            return;
+        };
+
+        scan.summary.completed_at = Some(current_time);
+        self.heap_data.last_completed_backup_coverage = Some(scan.summary);
+        self.heap_data.users_without_backup_set_index =
+            self.get_users_without_backup_scan_set_index();
+    }
+
+    /// The set the scan in progress fills, the one that does not hold the last completed scan
+    fn get_users_without_backup_scan_set_index(&self) -> usize {
+        1 - self.heap_data.users_without_backup_set_index
+    }
+
+    /// Users the last completed scan found without a backup, in order, starting after
+    /// `start_after`
+    pub fn get_user_principal_ids_without_backup(
+        &self,
+        start_after: Option<Principal>,
+        limit: usize,
+    ) -> Vec<Principal> {
+        let start_bound = match start_after {
+            Some(start_after) => Bound::Excluded(StorablePrincipal(start_after)),
+            None => Bound::Unbounded,
+        };
+
+        self.users_without_backup_store.sets[self.heap_data.users_without_backup_set_index]
+            .range((start_bound, Bound::Unbounded))
+            .take(limit)
+            .map(|(user_principal_id, _)| user_principal_id.0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::all_user_data::{
+        AllUserData, UserOwnedCanisterData,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_backup_coverage_scan() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: get_mock_user_alice_canister_id(),
+                canister_data: UserOwnedCanisterData::default(),
+            });
+
+        canister_data.start_backup_coverage_scan(now).unwrap();
+        assert_eq!(
+            canister_data.start_backup_coverage_scan(now),
+            Err("Backup coverage scan is already in progress".to_string())
+        );
+
+        canister_data.scan_backups_for_coverage_batch(1);
+        assert!(
+            !canister_data
+                .heap_data
+                .backup_coverage_scan_in_progress
+                .as_ref()
+                .unwrap()
+                .are_backups_scanned
+        );
+        canister_data.scan_backups_for_coverage_batch(1);
+        assert!(
+            canister_data
+                .heap_data
+                .backup_coverage_scan_in_progress
+                .as_ref()
+                .unwrap()
+                .are_backups_scanned
+        );
+
+        canister_data.record_user_index_page_for_backup_coverage(&[
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+        ]);
+        canister_data
+            .record_user_index_page_for_backup_coverage(&[get_mock_user_charlie_principal_id()]);
+        canister_data.complete_backup_coverage_scan(now);
+
+        let summary = canister_data
+            .heap_data
+            .last_completed_backup_coverage
+            .clone()
+            .unwrap();
+        assert_eq!(summary.completed_at, Some(now));
+        assert_eq!(summary.users_without_backup_count, 2);
+        assert!(summary.total_backup_size_in_bytes > 0);
+        let mut user_principal_ids_without_backup = vec![
+            get_mock_user_bob_principal_id(),
+            get_mock_user_charlie_principal_id(),
+        ];
+        user_principal_ids_without_backup.sort();
+        assert_eq!(
+            canister_data.get_user_principal_ids_without_backup(None, 10),
+            user_principal_ids_without_backup
+        );
+        assert!(canister_data
+            .heap_data
+            .backup_coverage_scan_in_progress
+            .is_none());
+
+        // * the next scan keeps the result of the last one until it completes
+        canister_data.start_backup_coverage_scan(now).unwrap();
+        canister_data
+            .record_user_index_page_for_backup_coverage(&[get_mock_user_bob_principal_id()]);
+        assert_eq!(
+            canister_data.get_user_principal_ids_without_backup(None, 10),
+            user_principal_ids_without_backup
+        );
+        canister_data.complete_backup_coverage_scan(now);
+        assert_eq!(
+            canister_data.get_user_principal_ids_without_backup(None, 10),
+            vec![get_mock_user_bob_principal_id()]
+        );
+    }
+}
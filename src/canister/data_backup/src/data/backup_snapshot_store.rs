@@ -0,0 +1,536 @@
+use std::{ops::Bound, time::SystemTime};
+
+use candid::{Decode, Encode, Principal};
+use ic_stable_structures::StableBTreeMap;
+use shared_utils::{
+    canister_specific::data_backup::types::{
+        all_user_data::AllUserData,
+        backup_chunk::{BackupChunk, BACKUP_CHUNK_SIZE_IN_BYTES},
+        backup_snapshot::{
+            BackupSnapshot, BackupSnapshotChunkKey, BackupSnapshotKind, BackupSnapshotStatus,
+            BackupSnapshotUserEntry, BackupSnapshotUserKey,
+        },
+    },
+    common::types::{storable_principal::StorablePrincipal, version_details::VersionDetails},
+};
+
+use super::memory_layout::{self, CanisterData, Memory};
+
+/// Copies of users' backups taken at a point in time, kept apart from the live backups so that
+/// a bad backup does not overwrite them. Each copy is the candid encoded `AllUserData` of the
+/// user, split into chunks.
+pub struct BackupSnapshotStore {
+    snapshot_user_key_to_entry_map:
+        StableBTreeMap<BackupSnapshotUserKey, BackupSnapshotUserEntry, Memory>,
+    snapshot_chunk_key_to_chunk_map: StableBTreeMap<BackupSnapshotChunkKey, BackupChunk, Memory>,
+}
+
+impl Default for BackupSnapshotStore {
+    fn default() -> Self {
+        Self {
+            snapshot_user_key_to_entry_map: StableBTreeMap::init(
+                memory_layout::get_backup_snapshot_user_entry_map_memory(),
+            ),
+            snapshot_chunk_key_to_chunk_map: StableBTreeMap::init(
+                memory_layout::get_backup_snapshot_chunk_map_memory(),
+            ),
+        }
+    }
+}
+
+impl BackupSnapshotStore {
+    pub fn insert_all_user_data(&mut self, snapshot_id: u64, all_user_data: &AllUserData) {
+        self.remove_user(snapshot_id, &all_user_data.user_principal_id);
+
+        let all_user_data_bytes = Encode!(all_user_data).unwrap();
+        let chunks: Vec<&[u8]> = all_user_data_bytes
+            .chunks(BACKUP_CHUNK_SIZE_IN_BYTES)
+            .collect();
+
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            self.snapshot_chunk_key_to_chunk_map.insert(
+                BackupSnapshotChunkKey {
+                    snapshot_id,
+                    user_principal_id: all_user_data.user_principal_id,
+                    chunk_index: chunk_index as u32,
+                },
+                BackupChunk(chunk.to_vec()),
+            );
+        }
+        self.snapshot_user_key_to_entry_map.insert(
+            BackupSnapshotUserKey {
+                snapshot_id,
+                user_principal_id: all_user_data.user_principal_id,
+            },
+            BackupSnapshotUserEntry {
+                user_canister_id: all_user_data.user_canister_id,
+                chunk_count: chunks.len() as u32,
+                size_in_bytes: all_user_data_bytes.len() as u64,
+            },
+        );
+    }
+
+    pub fn get_all_user_data(
+        &self,
+        snapshot_id: u64,
+        user_principal_id: &Principal,
+    ) -> Option<AllUserData> {
+        let entry = self
+            .snapshot_user_key_to_entry_map
+            .get(&BackupSnapshotUserKey {
+                snapshot_id,
+                user_principal_id: *user_principal_id,
+            })?;
+
+        let mut all_user_data_bytes = Vec::with_capacity(entry.size_in_bytes as usize);
+        for chunk_index in 0..entry.chunk_count {
+            let chunk = self
+                .snapshot_chunk_key_to_chunk_map
+                .get(&BackupSnapshotChunkKey {
+                    snapshot_id,
+                    user_principal_id: *user_principal_id,
+                    chunk_index,
+                })?;
+            all_user_data_bytes.extend(chunk.0);
+        }
+
+        Decode!(&all_user_data_bytes, AllUserData).ok()
+    }
+
+    fn remove_user(&mut self, snapshot_id: u64, user_principal_id: &Principal) {
+        let Some(entry) = self
+            .snapshot_user_key_to_entry_map
+            .remove(&BackupSnapshotUserKey {
+                snapshot_id,
+                user_principal_id: *user_principal_id,
+            })
+        else {
+            return;
+        };
+
+        for chunk_index in 0..entry.chunk_count {
+            self.snapshot_chunk_key_to_chunk_map
+                .remove(&BackupSnapshotChunkKey {
+                    snapshot_id,
+                    user_principal_id: *user_principal_id,
+                    chunk_index,
+                });
+        }
+    }
+
+    /// Removes up to `batch_size` users from a snapshot. Returns the number of users removed.
+    pub fn remove_snapshot_batch(&mut self, snapshot_id: u64, batch_size: usize) -> usize {
+        let user_principal_ids: Vec<Principal> = self
+            .snapshot_user_key_to_entry_map
+            .range(
+                BackupSnapshotUserKey {
+                    snapshot_id,
+                    user_principal_id: Principal::management_canister(),
+                }..,
+            )
+            .take_while(|(user_key, _)| user_key.snapshot_id == snapshot_id)
+            .take(batch_size)
+            .map(|(user_key, _)| user_key.user_principal_id)
+            .collect();
+
+        user_principal_ids
+            .iter()
+            .for_each(|user_principal_id| self.remove_user(snapshot_id, user_principal_id));
+
+        user_principal_ids.len()
+    }
+}
+
+impl CanisterData {
+    fn get_backup_snapshot_in_progress_id(&self) -> Option<u64> {
+        self.heap_data
+            .backup_snapshots
+            .values()
+            .find(|snapshot| snapshot.status == BackupSnapshotStatus::InProgress)
+            .map(|snapshot| snapshot.snapshot_id)
+    }
+
+    /// Registers a snapshot whose users are then copied by `copy_users_into_backup_snapshot_batch`.
+    /// One snapshot is taken at a time. A pre-upgrade snapshot cancels a daily snapshot in
+    /// progress, as the upgrade run does not wait for it.
+    pub fn start_backup_snapshot(
+        &mut self,
+        kind: BackupSnapshotKind,
+        source_wasm_version: Option<VersionDetails>,
+        current_time: SystemTime,
+    ) -> Result<u64, String> {
+        if let Some(snapshot_id) = self.get_backup_snapshot_in_progress_id() {
+            let snapshot_in_progress_kind = self.heap_data.backup_snapshots[&snapshot_id].kind;
+            if kind != BackupSnapshotKind::PreUpgrade
+                || snapshot_in_progress_kind != BackupSnapshotKind::Daily
+            {
+                return Err(format!("Snapshot {} is still in progress", snapshot_id));
+            }
+
+            self.heap_data.backup_snapshots.remove(&snapshot_id);
+            self.heap_data
+                .backup_snapshot_ids_pending_deletion
+                .push(snapshot_id);
+        }
+
+        // * ids of snapshots whose data is still being deleted are not reused
+        let snapshot_id = self
+            .heap_data
+            .backup_snapshots
+            .keys()
+            .chain(self.heap_data.backup_snapshot_ids_pending_deletion.iter())
+            .max()
+            .map_or(0, |last_snapshot_id| last_snapshot_id + 1);
+        self.heap_data.backup_snapshots.insert(
+            snapshot_id,
+            BackupSnapshot {
+                snapshot_id,
+                kind,
+                taken_at: current_time,
+                source_wasm_version,
+                status: BackupSnapshotStatus::InProgress,
+                user_count: 0,
+                unreadable_user_count: 0,
+            },
+        );
+        self.heap_data.backup_snapshot_copy_cursor = None;
+
+        Ok(snapshot_id)
+    }
+
+    /// Copies the next `batch_size` users into the snapshot in progress, and completes it once
+    /// every user is copied. Users are copied one by one, so a backup written while the snapshot
+    /// is in progress may or may not be part of it.
+    pub fn copy_users_into_backup_snapshot_batch(&mut self, batch_size: usize) {
+        let Some(snapshot_id) = self.get_backup_snapshot_in_progress_id() else {
+            return;
+        };
+
+        // * the snapshot only reads the chunked store
+        if !self
+            .legacy_user_principal_id_to_all_user_data_map
+            .is_empty()
+        {
+            self.migrate_legacy_user_data_batch(batch_size);
+            return;
+        }
+
+        let start_bound = match self.heap_data.backup_snapshot_copy_cursor {
+            Some(user_principal_id) => Bound::Excluded(StorablePrincipal(user_principal_id)),
+            None => Bound::Unbounded,
+        };
+        let user_principal_ids: Vec<Principal> = self
+            .user_backup_store
+            .user_principal_id_to_manifest_map
+            .range((start_bound, Bound::Unbounded))
+            .take(batch_size)
+            .map(|(user_principal_id, _)| user_principal_id.0)
+            .collect();
+
+        let mut copied_user_count = 0;
+        let mut unreadable_user_count = 0;
+        user_principal_ids.iter().for_each(|user_principal_id| {
+            match self.user_backup_store.get_all_user_data(user_principal_id) {
+                Ok(Some(all_user_data)) => {
+                    self.backup_snapshot_store
+                        .insert_all_user_data(snapshot_id, &all_user_data);
+                    copied_user_count += 1;
+                }
+                Ok(None) => {}
+                Err(_) => unreadable_user_count += 1,
+            }
+        });
+
+        let snapshot = self
+            .heap_data
+            .backup_snapshots
+            .get_mut(&snapshot_id)
+            .unwrap();
+        snapshot.user_count += copied_user_count;
+        snapshot.unreadable_user_count += unreadable_user_count;
+
+        if user_principal_ids.len() < batch_size {
+            snapshot.status = BackupSnapshotStatus::Complete;
+            self.heap_data.backup_snapshot_copy_cursor = None;
+            self.apply_backup_snapshot_retention_policy();
+        } else {
+            self.heap_data.backup_snapshot_copy_cursor = user_principal_ids.last().copied();
+        }
+    }
+
+    /// Drops the snapshots the retention policy does not keep. Their data is deleted by
+    /// `delete_pending_backup_snapshots_batch`.
+    pub fn apply_backup_snapshot_retention_policy(&mut self) {
+        let snapshot_ids_to_delete = self
+            .heap_data
+            .backup_snapshot_retention_policy
+            .get_snapshot_ids_to_delete(&self.heap_data.backup_snapshots);
+
+        snapshot_ids_to_delete.into_iter().for_each(|snapshot_id| {
+            self.heap_data.backup_snapshots.remove(&snapshot_id);
+            self.heap_data
+                .backup_snapshot_ids_pending_deletion
+                .push(snapshot_id);
+        });
+    }
+
+    /// Deletes up to `batch_size` users of the first snapshot pending deletion
+    pub fn delete_pending_backup_snapshots_batch(&mut self, batch_size: usize) {
+        let Some(&snapshot_id) = self.heap_data.backup_snapshot_ids_pending_deletion.first() else {
+            return;
+        };
+
+        let removed_user_count = self
+            .backup_snapshot_store
+            .remove_snapshot_batch(snapshot_id, batch_size);
+
+        if removed_user_count < batch_size {
+            self.heap_data
+                .backup_snapshot_ids_pending_deletion
+                .remove(0);
+        }
+    }
+
+    pub fn has_pending_backup_snapshot_work(&self) -> bool {
+        self.get_backup_snapshot_in_progress_id().is_some()
+            || !self
+                .heap_data
+                .backup_snapshot_ids_pending_deletion
+                .is_empty()
+    }
+
+    /// Removes a user from every snapshot, when their account is deleted
+    pub fn remove_user_from_backup_snapshots(&mut self, user_principal_id: &Principal) {
+        for snapshot in self.heap_data.backup_snapshots.values_mut() {
+            let user_key = BackupSnapshotUserKey {
+                snapshot_id: snapshot.snapshot_id,
+                user_principal_id: *user_principal_id,
+            };
+            if !self
+                .backup_snapshot_store
+                .snapshot_user_key_to_entry_map
+                .contains_key(&user_key)
+            {
+                continue;
+            }
+
+            self.backup_snapshot_store
+                .remove_user(snapshot.snapshot_id, user_principal_id);
+            snapshot.user_count = snapshot.user_count.saturating_sub(1);
+        }
+    }
+
+    /// The backed up data of a user as of a complete snapshot
+    pub fn get_backup_snapshot_all_user_data(
+        &self,
+        snapshot_id: u64,
+        user_principal_id: &Principal,
+    ) -> Result<AllUserData, String> {
+        match self.heap_data.backup_snapshots.get(&snapshot_id) {
+            Some(snapshot) if snapshot.status == BackupSnapshotStatus::Complete => {}
+            Some(_) => return Err("Snapshot is still in progress".to_string()),
+            None => return Err("Snapshot not found".to_string()),
+        }
+
+        self.backup_snapshot_store
+            .get_all_user_data(snapshot_id, user_principal_id)
+            .ok_or("No user data found in snapshot".to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use shared_utils::canister_specific::data_backup::types::{
+        all_user_data::UserOwnedCanisterData, backup_manifest::BackupSection,
+        backup_snapshot::BackupSnapshotRetentionPolicy,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn get_all_user_data(user_principal_id: Principal, user_canister_id: Principal) -> AllUserData {
+        AllUserData {
+            user_principal_id,
+            user_canister_id,
+            canister_data: UserOwnedCanisterData::default(),
+        }
+    }
+
+    fn take_backup_snapshot(canister_data: &mut CanisterData, kind: BackupSnapshotKind) -> u64 {
+        let snapshot_id = canister_data
+            .start_backup_snapshot(kind, None, SystemTime::now())
+            .unwrap();
+        while canister_data.get_backup_snapshot_in_progress_id().is_some() {
+            canister_data.copy_users_into_backup_snapshot_batch(1);
+        }
+
+        snapshot_id
+    }
+
+    #[test]
+    fn test_backup_snapshot_keeps_data_as_of_when_it_was_taken() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&get_all_user_data(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ));
+        canister_data
+            .legacy_user_principal_id_to_all_user_data_map
+            .insert(
+                StorablePrincipal(get_mock_user_bob_principal_id()),
+                get_all_user_data(
+                    get_mock_user_bob_principal_id(),
+                    get_mock_user_bob_canister_id(),
+                ),
+            );
+
+        let snapshot_id = canister_data
+            .start_backup_snapshot(BackupSnapshotKind::Daily, None, SystemTime::now())
+            .unwrap();
+        assert!(canister_data
+            .start_backup_snapshot(BackupSnapshotKind::Daily, None, SystemTime::now())
+            .is_err());
+        assert_eq!(
+            canister_data
+                .get_backup_snapshot_all_user_data(snapshot_id, &get_mock_user_alice_principal_id())
+                .unwrap_err(),
+            "Snapshot is still in progress"
+        );
+
+        while canister_data.has_pending_backup_snapshot_work() {
+            canister_data.copy_users_into_backup_snapshot_batch(1);
+        }
+        assert_eq!(
+            canister_data.heap_data.backup_snapshots[&snapshot_id].user_count,
+            2
+        );
+
+        // * a later backup does not change the snapshot
+        canister_data
+            .user_backup_store
+            .write_section(
+                &get_mock_user_alice_principal_id(),
+                BackupSection::PrincipalsIFollow,
+                &BTreeSet::from([get_mock_user_bob_principal_id()]),
+            )
+            .unwrap();
+
+        let snapshot_all_user_data = canister_data
+            .get_backup_snapshot_all_user_data(snapshot_id, &get_mock_user_alice_principal_id())
+            .unwrap();
+        assert!(snapshot_all_user_data
+            .canister_data
+            .principals_i_follow
+            .is_empty());
+        assert_eq!(
+            canister_data
+                .get_backup_snapshot_all_user_data(snapshot_id, &get_mock_user_bob_principal_id())
+                .unwrap()
+                .user_canister_id,
+            get_mock_user_bob_canister_id()
+        );
+    }
+
+    #[test]
+    fn test_backup_snapshot_retention_policy_deletes_old_snapshots() {
+        let mut canister_data = CanisterData::default();
+        canister_data.heap_data.backup_snapshot_retention_policy = BackupSnapshotRetentionPolicy {
+            daily_snapshots_to_keep: 1,
+            pre_upgrade_snapshots_to_keep: 1,
+        };
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&get_all_user_data(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ));
+
+        let first_daily_snapshot_id =
+            take_backup_snapshot(&mut canister_data, BackupSnapshotKind::Daily);
+        let pre_upgrade_snapshot_id =
+            take_backup_snapshot(&mut canister_data, BackupSnapshotKind::PreUpgrade);
+        let second_daily_snapshot_id =
+            take_backup_snapshot(&mut canister_data, BackupSnapshotKind::Daily);
+
+        assert_eq!(
+            canister_data.heap_data.backup_snapshot_ids_pending_deletion,
+            vec![first_daily_snapshot_id]
+        );
+        assert!(canister_data
+            .get_backup_snapshot_all_user_data(
+                first_daily_snapshot_id,
+                &get_mock_user_alice_principal_id()
+            )
+            .is_err());
+
+        while canister_data.has_pending_backup_snapshot_work() {
+            canister_data.delete_pending_backup_snapshots_batch(1);
+        }
+        assert!(canister_data
+            .backup_snapshot_store
+            .get_all_user_data(first_daily_snapshot_id, &get_mock_user_alice_principal_id())
+            .is_none());
+        assert!(canister_data
+            .get_backup_snapshot_all_user_data(
+                pre_upgrade_snapshot_id,
+                &get_mock_user_alice_principal_id()
+            )
+            .is_ok());
+        assert!(canister_data
+            .get_backup_snapshot_all_user_data(
+                second_daily_snapshot_id,
+                &get_mock_user_alice_principal_id()
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_pre_upgrade_backup_snapshot_cancels_daily_snapshot_in_progress() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&get_all_user_data(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ));
+
+        let daily_snapshot_id = canister_data
+            .start_backup_snapshot(BackupSnapshotKind::Daily, None, SystemTime::now())
+            .unwrap();
+        let pre_upgrade_snapshot_id = canister_data
+            .start_backup_snapshot(BackupSnapshotKind::PreUpgrade, None, SystemTime::now())
+            .unwrap();
+        assert_ne!(daily_snapshot_id, pre_upgrade_snapshot_id);
+        assert!(!canister_data
+            .heap_data
+            .backup_snapshots
+            .contains_key(&daily_snapshot_id));
+        assert_eq!(
+            canister_data.heap_data.backup_snapshot_ids_pending_deletion,
+            vec![daily_snapshot_id]
+        );
+
+        // * a pre-upgrade snapshot in progress is not cancelled
+        assert!(canister_data
+            .start_backup_snapshot(BackupSnapshotKind::PreUpgrade, None, SystemTime::now())
+            .is_err());
+
+        while canister_data.has_pending_backup_snapshot_work() {
+            canister_data.copy_users_into_backup_snapshot_batch(1);
+            canister_data.delete_pending_backup_snapshots_batch(1);
+        }
+        assert!(canister_data
+            .get_backup_snapshot_all_user_data(
+                pre_upgrade_snapshot_id,
+                &get_mock_user_alice_principal_id()
+            )
+            .is_ok());
+    }
+}
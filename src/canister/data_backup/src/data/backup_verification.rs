@@ -0,0 +1,353 @@
+use std::{ops::Bound, time::SystemTime};
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::{
+        backup_manifest::UserBackupError,
+        backup_section_digests::BackupSectionDigests,
+        backup_statistics::{
+            BackupDigestMismatch, BackupDigestMismatchKind, BackupVerificationReport,
+        },
+    },
+    common::types::storable_principal::StorablePrincipal,
+};
+
+use super::memory_layout::CanisterData;
+
+/// A user's backup, to be checked against the digests of their live canister
+pub struct BackupVerificationTask {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+}
+
+/// Compares the digests recomputed from the stored data with the digests the canister sent with
+/// its last backup run, and with the digests of the live canister
+fn get_backup_digest_mismatches(
+    task: &BackupVerificationTask,
+    stored_data_digests: &BackupSectionDigests,
+    source_digests: Option<&BackupSectionDigests>,
+    live_canister_digests: &BackupSectionDigests,
+) -> Vec<BackupDigestMismatch> {
+    let source_sections = source_digests
+        .map(|source_digests| stored_data_digests.get_mismatched_sections(source_digests))
+        .unwrap_or_default();
+    let live_canister_sections = stored_data_digests.get_mismatched_sections(live_canister_digests);
+
+    [
+        (
+            BackupDigestMismatchKind::StoredDataDiffersFromSource,
+            source_sections,
+        ),
+        (
+            BackupDigestMismatchKind::LiveCanisterDiffersFromBackup,
+            live_canister_sections,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, sections)| !sections.is_empty())
+    .map(|(kind, sections)| BackupDigestMismatch {
+        user_principal_id: task.user_principal_id,
+        user_canister_id: task.user_canister_id,
+        kind,
+        sections,
+    })
+    .collect()
+}
+
+impl CanisterData {
+    pub fn start_backup_verification(&mut self, current_time: SystemTime) -> Result<(), String> {
+        if self.heap_data.backup_verification_in_progress.is_some() {
+            return Err("Backup verification is already in progress".to_string());
+        }
+
+        self.heap_data.backup_verification_in_progress =
+            Some(BackupVerificationReport::new(current_time));
+        self.heap_data.backup_verification_cursor = None;
+
+        Ok(())
+    }
+
+    /// The next `batch_size` users after the cursor of the verification in progress. Backups
+    /// still in the legacy map are not verified.
+    pub fn get_next_backup_verification_batch(
+        &self,
+        batch_size: usize,
+    ) -> Vec<BackupVerificationTask> {
+        if self.heap_data.backup_verification_in_progress.is_none() {
+            return vec![];
+        }
+
+        let start_bound = match self.heap_data.backup_verification_cursor {
+            Some(user_principal_id) => Bound::Excluded(StorablePrincipal(user_principal_id)),
+            None => Bound::Unbounded,
+        };
+
+        self.user_backup_store
+            .user_principal_id_to_manifest_map
+            .range((start_bound, Bound::Unbounded))
+            .take(batch_size)
+            .map(|(user_principal_id, manifest)| BackupVerificationTask {
+                user_principal_id: user_principal_id.0,
+                user_canister_id: manifest.user_canister_id,
+            })
+            .collect()
+    }
+
+    /// Records the outcome of verifying a user, given the digests of their live canister or the
+    /// error asking for them. The stored data is read now, after the call, so that a backup run
+    /// that completed meanwhile is compared as a whole. Failures and stored data that differs
+    /// from its source are also recorded as the user's last error.
+    pub fn record_backup_verification_result(
+        &mut self,
+        task: &BackupVerificationTask,
+        live_canister_digests: Result<BackupSectionDigests, String>,
+        current_time: SystemTime,
+    ) {
+        if self.heap_data.backup_verification_in_progress.is_none() {
+            return;
+        }
+
+        let Some(manifest) = self.user_backup_store.get_manifest(&task.user_principal_id) else {
+            // * the user was removed while their canister was being asked
+            return;
+        };
+        let result = live_canister_digests
+            .map_err(|e| format!("Backup verification could not reach the canister: {}", e))
+            .and_then(|live_canister_digests| {
+                let all_user_data = self
+                    .user_backup_store
+                    .get_all_user_data(&task.user_principal_id)
+                    .map_err(|e| format!("Backup verification could not read the backup: {}", e))?
+                    .ok_or("Backup verification could not read the backup: No user data found")?;
+
+                Ok(get_backup_digest_mismatches(
+                    task,
+                    &BackupSectionDigests::from(&all_user_data.canister_data),
+                    manifest.section_digests.as_ref(),
+                    &live_canister_digests,
+                ))
+            });
+
+        let error_message = match &result {
+            Ok(mismatches) => mismatches
+                .iter()
+                .find(|mismatch| {
+                    mismatch.kind == BackupDigestMismatchKind::StoredDataDiffersFromSource
+                })
+                .map(|mismatch| {
+                    format!(
+                        "Backup verification found stored sections that differ from the canister: {:?}",
+                        mismatch.sections
+                    )
+                }),
+            Err(e) => Some(e.clone()),
+        };
+
+        let report = self
+            .heap_data
+            .backup_verification_in_progress
+            .as_mut()
+            .unwrap();
+        match result {
+            Ok(mismatches) => report.record_verified_user(mismatches),
+            Err(_) => report.failed_user_count += 1,
+        }
+
+        if let Some(error_message) = error_message {
+            self.user_backup_store.set_last_error(
+                &task.user_principal_id,
+                UserBackupError::new(&error_message, current_time),
+            );
+        }
+    }
+
+    pub fn advance_backup_verification_cursor(
+        &mut self,
+        last_verified_user_principal_id: Principal,
+    ) {
+        self.heap_data.backup_verification_cursor = Some(last_verified_user_principal_id);
+    }
+
+    pub fn complete_backup_verification(&mut self, current_time: SystemTime) {
+        let Some(mut report) = self.heap_data.backup_verification_in_progress.take() else {
+            return;
+        };
+
+        report.completed_at = Some(current_time);
+        self.heap_data.last_completed_backup_verification = Some(report);
+        self.heap_data.backup_verification_cursor = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::{
+        data_backup::types::{
+            all_user_data::{AllUserData, UserOwnedCanisterData},
+            backup_manifest::BackupSection,
+        },
+        individual_user_template::types::token::TokenBalance,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_canister_id, get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    fn insert_user(
+        canister_data: &mut CanisterData,
+        user_principal_id: Principal,
+        canister_id: Principal,
+    ) {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id,
+                user_canister_id: canister_id,
+                canister_data: UserOwnedCanisterData::default(),
+            });
+    }
+
+    #[test]
+    fn test_get_backup_digest_mismatches() {
+        let stored_data_digests = BackupSectionDigests::from(&UserOwnedCanisterData::default());
+        let changed_digests = BackupSectionDigests::from(&UserOwnedCanisterData {
+            token_data: TokenBalance {
+                utility_token_balance: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let task = BackupVerificationTask {
+            user_principal_id: get_mock_user_alice_principal_id(),
+            user_canister_id: get_mock_user_alice_canister_id(),
+        };
+
+        assert_eq!(
+            get_backup_digest_mismatches(
+                &task,
+                &stored_data_digests,
+                Some(&changed_digests),
+                &changed_digests
+            ),
+            vec![
+                BackupDigestMismatch {
+                    user_principal_id: get_mock_user_alice_principal_id(),
+                    user_canister_id: get_mock_user_alice_canister_id(),
+                    kind: BackupDigestMismatchKind::StoredDataDiffersFromSource,
+                    sections: vec![BackupSection::TokenData],
+                },
+                BackupDigestMismatch {
+                    user_principal_id: get_mock_user_alice_principal_id(),
+                    user_canister_id: get_mock_user_alice_canister_id(),
+                    kind: BackupDigestMismatchKind::LiveCanisterDiffersFromBackup,
+                    sections: vec![BackupSection::TokenData],
+                },
+            ]
+        );
+        assert!(get_backup_digest_mismatches(
+            &task,
+            &stored_data_digests,
+            None,
+            &stored_data_digests
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_backup_verification_run() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        insert_user(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        insert_user(
+            &mut canister_data,
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        insert_user(
+            &mut canister_data,
+            get_mock_user_charlie_principal_id(),
+            get_mock_user_charlie_canister_id(),
+        );
+        // * the profile of charlie's backup no longer decodes
+        canister_data
+            .user_backup_store
+            .write_section(
+                &get_mock_user_charlie_principal_id(),
+                BackupSection::Profile,
+                &"not a profile".to_string(),
+            )
+            .unwrap();
+        let stored_data_digests = BackupSectionDigests::from(&UserOwnedCanisterData::default());
+
+        assert!(canister_data
+            .get_next_backup_verification_batch(10)
+            .is_empty());
+
+        canister_data.start_backup_verification(now).unwrap();
+        assert_eq!(
+            canister_data.start_backup_verification(now),
+            Err("Backup verification is already in progress".to_string())
+        );
+
+        let batch = canister_data.get_next_backup_verification_batch(10);
+        assert_eq!(batch.len(), 3);
+        for task in &batch {
+            let live_canister_digests =
+                if task.user_principal_id == get_mock_user_bob_principal_id() {
+                    Err("Out of cycles".to_string())
+                } else {
+                    Ok(stored_data_digests.clone())
+                };
+            canister_data.record_backup_verification_result(task, live_canister_digests, now);
+        }
+        canister_data.advance_backup_verification_cursor(batch[2].user_principal_id);
+
+        let get_last_error_message = |user_principal_id: Principal| {
+            canister_data
+                .user_backup_store
+                .get_manifest(&user_principal_id)
+                .unwrap()
+                .last_error
+                .map(|last_error| last_error.message)
+        };
+        assert_eq!(
+            get_last_error_message(get_mock_user_alice_principal_id()),
+            None
+        );
+        assert_eq!(
+            get_last_error_message(get_mock_user_bob_principal_id()),
+            Some("Backup verification could not reach the canister: Out of cycles".to_string())
+        );
+        assert!(get_last_error_message(get_mock_user_charlie_principal_id())
+            .unwrap()
+            .starts_with("Backup verification could not read the backup"));
+
+        assert!(canister_data
+            .get_next_backup_verification_batch(10)
+            .is_empty());
+        canister_data.complete_backup_verification(now);
+
+        assert!(canister_data
+            .heap_data
+            .backup_verification_in_progress
+            .is_none());
+        assert_eq!(
+            canister_data.heap_data.last_completed_backup_verification,
+            Some(BackupVerificationReport {
+                started_at: now,
+                completed_at: Some(now),
+                verified_user_count: 1,
+                mismatched_user_count: 0,
+                failed_user_count: 2,
+                mismatches: vec![],
+            })
+        );
+    }
+}
@@ -1,13 +1,65 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
 use shared_utils::{
-    access_control::UserAccessRole, common::types::known_principal::KnownPrincipalMap,
+    access_control::UserAccessRole,
+    canister_specific::data_backup::types::{
+        backup_snapshot::{BackupSnapshot, BackupSnapshotRetentionPolicy},
+        backup_statistics::{BackupCoverageSummary, BackupVerificationReport},
+        restore_run::{RestoreRunReport, UserRestoreResult},
+        user_data_archive::UserDataArchiveManifest,
+    },
+    common::types::known_principal::KnownPrincipalMap,
 };
 
+use super::{backup_coverage::BackupCoverageScan, user_data_archive_store::UserDataImport};
+
 #[derive(Default, CandidType, Deserialize, Serialize)]
 pub struct HeapData {
     pub known_principal_ids: KnownPrincipalMap,
     pub access_control_list: HashMap<Principal, Vec<UserAccessRole>>,
+    #[serde(default)]
+    pub backup_snapshots: BTreeMap<u64, BackupSnapshot>,
+    #[serde(default)]
+    pub backup_snapshot_retention_policy: BackupSnapshotRetentionPolicy,
+    /// The last user copied into the snapshot in progress
+    #[serde(default)]
+    pub backup_snapshot_copy_cursor: Option<Principal>,
+    /// Snapshots dropped by the retention policy whose data is still being deleted
+    #[serde(default)]
+    pub backup_snapshot_ids_pending_deletion: Vec<u64>,
+    #[serde(default)]
+    pub backup_verification_in_progress: Option<BackupVerificationReport>,
+    /// The last user verified by the verification in progress
+    #[serde(default)]
+    pub backup_verification_cursor: Option<Principal>,
+    #[serde(default)]
+    pub last_completed_backup_verification: Option<BackupVerificationReport>,
+    #[serde(default)]
+    pub backup_coverage_scan_in_progress: Option<BackupCoverageScan>,
+    #[serde(default)]
+    pub last_completed_backup_coverage: Option<BackupCoverageSummary>,
+    /// Which set of `UsersWithoutBackupStore` holds the users the last completed coverage scan
+    /// found without a backup
+    #[serde(default)]
+    pub users_without_backup_set_index: usize,
+    /// The latest export of each user that exported their backup
+    #[serde(default)]
+    pub user_data_exports: BTreeMap<Principal, UserDataArchiveManifest>,
+    /// Digests of the latest archives exported for each user, the only ones users can import
+    #[serde(default)]
+    pub exported_user_data_archive_digests: BTreeMap<Principal, Vec<Vec<u8>>>,
+    /// The archive being uploaded to be imported. One is imported at a time.
+    #[serde(default)]
+    pub user_data_import: Option<UserDataImport>,
+    /// The restore run in progress, or else the last one
+    #[serde(default)]
+    pub restore_run: Option<RestoreRunReport>,
+    /// The last user restored by the restore run in progress
+    #[serde(default)]
+    pub restore_run_cursor: Option<Principal>,
+    /// The outcome of each user restored by the latest restore run
+    #[serde(default)]
+    pub user_restore_results: BTreeMap<Principal, UserRestoreResult>,
 }
@@ -1,17 +1,24 @@
 use std::cell::RefCell;
 
-use candid::Deserialize;
+use candid::{Deserialize, Principal};
 use ic_stable_structures::{
     memory_manager::{MemoryId, MemoryManager, VirtualMemory},
     DefaultMemoryImpl, StableBTreeMap,
 };
 use serde::Serialize;
 use shared_utils::{
-    canister_specific::data_backup::types::all_user_data::AllUserData,
+    canister_specific::data_backup::types::{
+        all_user_data::AllUserData,
+        backup_manifest::{BackupSection, UserBackupManifest},
+    },
     common::types::storable_principal::StorablePrincipal,
 };
 
-use super::heap_data::HeapData;
+use super::{
+    backup_coverage::UsersWithoutBackupStore, backup_snapshot_store::BackupSnapshotStore,
+    heap_data::HeapData, user_backup_store::UserBackupStore,
+    user_data_archive_store::UserDataArchiveStore,
+};
 
 thread_local! {
   static MEMORY_MANANGER: RefCell<MemoryManager<DefaultMemoryImpl>> = RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
@@ -22,20 +29,115 @@ pub type Memory = VirtualMemory<DefaultMemoryImpl>;
 #[derive(Deserialize, Serialize)]
 pub struct CanisterData {
     pub heap_data: HeapData,
+    /// Backups taken before they were split into chunks. Entries move to `user_backup_store` when
+    /// they are next written, or in batches after an upgrade.
     #[serde(skip, default = "init_user_principal_id_to_all_user_data_map")]
-    pub user_principal_id_to_all_user_data_map:
+    pub legacy_user_principal_id_to_all_user_data_map:
         StableBTreeMap<StorablePrincipal, AllUserData, Memory>,
+    #[serde(skip)]
+    pub user_backup_store: UserBackupStore,
+    #[serde(skip)]
+    pub backup_snapshot_store: BackupSnapshotStore,
+    #[serde(skip)]
+    pub user_data_archive_store: UserDataArchiveStore,
+    #[serde(skip)]
+    pub users_without_backup_store: UsersWithoutBackupStore,
 }
 
 impl Default for CanisterData {
     fn default() -> Self {
         Self {
             heap_data: HeapData::default(),
-            user_principal_id_to_all_user_data_map: init_user_principal_id_to_all_user_data_map(),
+            legacy_user_principal_id_to_all_user_data_map:
+                init_user_principal_id_to_all_user_data_map(),
+            user_backup_store: UserBackupStore::default(),
+            backup_snapshot_store: BackupSnapshotStore::default(),
+            user_data_archive_store: UserDataArchiveStore::default(),
+            users_without_backup_store: UsersWithoutBackupStore::default(),
         }
     }
 }
 
+impl CanisterData {
+    /// The backed up data of a user, reassembled from its chunks. `None` when the user is not
+    /// backed up.
+    pub fn get_all_user_data(
+        &self,
+        user_principal_id: &Principal,
+    ) -> Result<Option<AllUserData>, String> {
+        Ok(self
+            .user_backup_store
+            .get_all_user_data(user_principal_id)?
+            .or_else(|| {
+                self.legacy_user_principal_id_to_all_user_data_map
+                    .get(&StorablePrincipal(*user_principal_id))
+            }))
+    }
+
+    pub fn get_user_count(&self) -> u64 {
+        self.user_backup_store.get_user_count()
+            + self.legacy_user_principal_id_to_all_user_data_map.len()
+    }
+
+    /// Moves a user's backup out of the legacy map into the chunked store
+    pub fn migrate_legacy_user_data(&mut self, user_principal_id: &Principal) {
+        if let Some(all_user_data) = self
+            .legacy_user_principal_id_to_all_user_data_map
+            .remove(&StorablePrincipal(*user_principal_id))
+        {
+            self.user_backup_store.insert_all_user_data(&all_user_data);
+        }
+    }
+
+    /// Deletes everything kept for a user whose account is deleted: their backup, their copies in
+    /// snapshots, and their exports and imports
+    pub fn purge_user_data(&mut self, user_principal_id: &Principal) {
+        self.legacy_user_principal_id_to_all_user_data_map
+            .remove(&StorablePrincipal(*user_principal_id));
+        self.user_backup_store.remove_user(user_principal_id);
+        self.remove_user_from_backup_snapshots(user_principal_id);
+        self.delete_user_data_export(user_principal_id);
+        self.discard_user_data_import(user_principal_id);
+        self.heap_data
+            .exported_user_data_archive_digests
+            .remove(user_principal_id);
+        self.users_without_backup_store
+            .remove_user(user_principal_id);
+        self.heap_data
+            .user_restore_results
+            .remove(user_principal_id);
+    }
+
+    /// Moves up to `batch_size` users out of the legacy map. Returns the number of users moved.
+    pub fn migrate_legacy_user_data_batch(&mut self, batch_size: usize) -> usize {
+        let user_principal_ids: Vec<Principal> = self
+            .legacy_user_principal_id_to_all_user_data_map
+            .iter()
+            .take(batch_size)
+            .map(|(user_principal_id, _)| user_principal_id.0)
+            .collect();
+
+        user_principal_ids
+            .iter()
+            .for_each(|user_principal_id| self.migrate_legacy_user_data(user_principal_id));
+
+        user_principal_ids.len()
+    }
+
+    /// The manifest of a user whose backup is sent by `caller_principal_id`, the user's canister
+    pub fn get_manifest_of_backup_sent_by_user_canister(
+        &mut self,
+        user_principal_id: &Principal,
+        caller_principal_id: &Principal,
+    ) -> Option<UserBackupManifest> {
+        self.migrate_legacy_user_data(user_principal_id);
+
+        self.user_backup_store
+            .get_manifest(user_principal_id)
+            .filter(|manifest| manifest.user_canister_id == *caller_principal_id)
+    }
+}
+
 // * Heap data memory.
 const HEAP_DATA_MEMORY_ID: MemoryId = MemoryId::new(0);
 pub fn get_heap_data_memory() -> Memory {
@@ -55,6 +157,101 @@ pub fn get_user_principal_id_to_all_user_data_map_memory() -> Memory {
             .get(USER_PRINCIPAL_ID_TO_ALL_USER_DATA_MAP_MEMORY_ID)
     })
 }
+
+// * User backup manifest map memory.
+const USER_BACKUP_MANIFEST_MAP_MEMORY_ID: MemoryId = MemoryId::new(2);
+pub fn get_user_backup_manifest_map_memory() -> Memory {
+    MEMORY_MANANGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(USER_BACKUP_MANIFEST_MAP_MEMORY_ID)
+    })
+}
+
+// * Backup section chunk map memories, one per section.
+pub fn get_backup_section_chunk_map_memory(section: BackupSection) -> Memory {
+    let memory_id = match section {
+        BackupSection::Profile => MemoryId::new(3),
+        BackupSection::Posts => MemoryId::new(4),
+        BackupSection::TokenData => MemoryId::new(5),
+        BackupSection::PrincipalsIFollow => MemoryId::new(6),
+        BackupSection::PrincipalsThatFollowMe => MemoryId::new(7),
+        BackupSection::HotOrNotBetsPlaced => MemoryId::new(8),
+        BackupSection::FollowData => MemoryId::new(9),
+        BackupSection::Configuration => MemoryId::new(10),
+        BackupSection::KnownPrincipalIds => MemoryId::new(11),
+        BackupSection::VersionDetails => MemoryId::new(12),
+        BackupSection::PostsIndexSortedByHomeFeedScore => MemoryId::new(13),
+        BackupSection::PostsIndexSortedByHotOrNotFeedScore => MemoryId::new(14),
+        BackupSection::PostScoreSyncQueue => MemoryId::new(15),
+        BackupSection::LastAccessTime => MemoryId::new(16),
+    };
+
+    MEMORY_MANANGER
+        .with(|memory_manager_ref_cell| memory_manager_ref_cell.borrow_mut().get(memory_id))
+}
+
+// * Backup snapshot user entry map memory.
+const BACKUP_SNAPSHOT_USER_ENTRY_MAP_MEMORY_ID: MemoryId = MemoryId::new(17);
+pub fn get_backup_snapshot_user_entry_map_memory() -> Memory {
+    MEMORY_MANANGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(BACKUP_SNAPSHOT_USER_ENTRY_MAP_MEMORY_ID)
+    })
+}
+
+// * Backup snapshot chunk map memory.
+const BACKUP_SNAPSHOT_CHUNK_MAP_MEMORY_ID: MemoryId = MemoryId::new(18);
+pub fn get_backup_snapshot_chunk_map_memory() -> Memory {
+    MEMORY_MANANGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(BACKUP_SNAPSHOT_CHUNK_MAP_MEMORY_ID)
+    })
+}
+
+// * User data export chunk map memory.
+const USER_DATA_EXPORT_CHUNK_MAP_MEMORY_ID: MemoryId = MemoryId::new(19);
+pub fn get_user_data_export_chunk_map_memory() -> Memory {
+    MEMORY_MANANGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(USER_DATA_EXPORT_CHUNK_MAP_MEMORY_ID)
+    })
+}
+
+// * User data import chunk map memory.
+const USER_DATA_IMPORT_CHUNK_MAP_MEMORY_ID: MemoryId = MemoryId::new(20);
+pub fn get_user_data_import_chunk_map_memory() -> Memory {
+    MEMORY_MANANGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(USER_DATA_IMPORT_CHUNK_MAP_MEMORY_ID)
+    })
+}
+
+// * Backup section item chunk map memory.
+const BACKUP_SECTION_ITEM_CHUNK_MAP_MEMORY_ID: MemoryId = MemoryId::new(21);
+pub fn get_backup_section_item_chunk_map_memory() -> Memory {
+    MEMORY_MANANGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(BACKUP_SECTION_ITEM_CHUNK_MAP_MEMORY_ID)
+    })
+}
+
+// * Users without backup set memories, one per set.
+pub fn get_users_without_backup_set_memory(set_index: usize) -> Memory {
+    let memory_id = match set_index {
+        0 => MemoryId::new(22),
+        _ => MemoryId::new(23),
+    };
+
+    MEMORY_MANANGER
+        .with(|memory_manager_ref_cell| memory_manager_ref_cell.borrow_mut().get(memory_id))
+}
+
 fn init_user_principal_id_to_all_user_data_map(
 ) -> StableBTreeMap<StorablePrincipal, AllUserData, Memory> {
     StableBTreeMap::init(get_user_principal_id_to_all_user_data_map_memory())
@@ -1,2 +1,8 @@
+pub mod backup_coverage;
+pub mod backup_snapshot_store;
+pub mod backup_verification;
 pub mod heap_data;
 pub mod memory_layout;
+pub mod restore_run;
+pub mod user_backup_store;
+pub mod user_data_archive_store;
@@ -0,0 +1,364 @@
+use std::{ops::Bound, time::SystemTime};
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::{
+        all_user_data::AllUserData,
+        restore_run::{RestoreRunReport, RestoreRunScope, UserRestoreResult},
+    },
+    common::types::storable_principal::StorablePrincipal,
+};
+
+use super::memory_layout::CanisterData;
+
+/// Fails when the canister in the backup no longer belongs to the user: the user index maps the
+/// user to another canister or, when it has no canister for them, the canister's profile names
+/// someone else
+pub fn check_restore_target(
+    users_data: &AllUserData,
+    user_index_user_canister_id: Option<Principal>,
+    canister_profile_owner: Option<Principal>,
+) -> Result<(), String> {
+    match user_index_user_canister_id {
+        Some(user_canister_id) if user_canister_id == users_data.user_canister_id => Ok(()),
+        Some(user_canister_id) => Err(format!(
+            "The user index maps the user to canister {} instead of {}",
+            user_canister_id, users_data.user_canister_id
+        )),
+        None if canister_profile_owner == Some(users_data.user_principal_id) => Ok(()),
+        None => Err(format!(
+            "Canister {} no longer belongs to the user",
+            users_data.user_canister_id
+        )),
+    }
+}
+
+impl CanisterData {
+    pub fn is_restore_run_in_progress(&self) -> bool {
+        self.heap_data
+            .restore_run
+            .as_ref()
+            .is_some_and(|report| report.completed_at.is_none())
+    }
+
+    /// Starts restoring the users in `scope`, replacing the report and results of the previous
+    /// run
+    pub fn start_restore_run(
+        &mut self,
+        scope: RestoreRunScope,
+        max_concurrent_restores: u64,
+        current_time: SystemTime,
+    ) -> Result<(), String> {
+        if self.is_restore_run_in_progress() {
+            return Err("A restore run is already in progress".to_string());
+        }
+
+        if scope == RestoreRunScope::SelectedUsers(Default::default()) {
+            return Err("No users selected".to_string());
+        }
+
+        self.heap_data.restore_run = Some(RestoreRunReport::new(
+            scope,
+            max_concurrent_restores,
+            current_time,
+        ));
+        self.heap_data.restore_run_cursor = None;
+        self.heap_data.user_restore_results.clear();
+
+        Ok(())
+    }
+
+    /// The next `batch_size` users after the cursor of the run in progress. A run of all users
+    /// restores the ones in the backup store, which legacy backups are migrated into after an
+    /// upgrade.
+    pub fn get_next_restore_run_batch(&self, batch_size: usize) -> Vec<Principal> {
+        let Some(report) = self
+            .heap_data
+            .restore_run
+            .as_ref()
+            .filter(|report| report.completed_at.is_none())
+        else {
+            return vec![];
+        };
+
+        let cursor = self.heap_data.restore_run_cursor;
+        match &report.scope {
+            RestoreRunScope::AllUsers => {
+                let start_bound = match cursor {
+                    Some(user_principal_id) => {
+                        Bound::Excluded(StorablePrincipal(user_principal_id))
+                    }
+                    None => Bound::Unbounded,
+                };
+
+                self.user_backup_store
+                    .user_principal_id_to_manifest_map
+                    .range((start_bound, Bound::Unbounded))
+                    .take(batch_size)
+                    .map(|(user_principal_id, _)| user_principal_id.0)
+                    .collect()
+            }
+            RestoreRunScope::SelectedUsers(user_principal_ids) => {
+                let start_bound = match cursor {
+                    Some(user_principal_id) => Bound::Excluded(user_principal_id),
+                    None => Bound::Unbounded,
+                };
+
+                user_principal_ids
+                    .range((start_bound, Bound::Unbounded))
+                    .take(batch_size)
+                    .copied()
+                    .collect()
+            }
+        }
+    }
+
+    /// The data to send to the user's canister. The restored canister tracks changes from the
+    /// start, so its next backup run sends everything.
+    pub fn prepare_user_restore(
+        &mut self,
+        user_principal_id: &Principal,
+    ) -> Result<AllUserData, String> {
+        self.migrate_legacy_user_data(user_principal_id);
+        self.user_backup_store
+            .reset_acknowledged_change_sequence_number(user_principal_id);
+
+        self.get_all_user_data(user_principal_id)?
+            .ok_or("No user data found".to_string())
+    }
+
+    pub fn record_user_restore_result(
+        &mut self,
+        user_principal_id: Principal,
+        user_canister_id: Option<Principal>,
+        result: Result<(), String>,
+        current_time: SystemTime,
+    ) {
+        let Some(report) = self.heap_data.restore_run.as_mut() else {
+            return;
+        };
+
+        match result {
+            Ok(()) => report.restored_user_count += 1,
+            Err(_) => report.failed_user_count += 1,
+        }
+
+        self.heap_data.user_restore_results.insert(
+            user_principal_id,
+            UserRestoreResult {
+                user_principal_id,
+                user_canister_id,
+                finished_at: current_time,
+                error: result.err(),
+            },
+        );
+    }
+
+    pub fn advance_restore_run_cursor(&mut self, last_restored_user_principal_id: Principal) {
+        self.heap_data.restore_run_cursor = Some(last_restored_user_principal_id);
+    }
+
+    pub fn complete_restore_run(&mut self, current_time: SystemTime) {
+        if let Some(report) = self.heap_data.restore_run.as_mut() {
+            report.completed_at.get_or_insert(current_time);
+        }
+        self.heap_data.restore_run_cursor = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::all_user_data::UserOwnedCanisterData;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    fn insert_user(
+        canister_data: &mut CanisterData,
+        user_principal_id: Principal,
+        canister_id: Principal,
+    ) {
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&AllUserData {
+                user_principal_id,
+                user_canister_id: canister_id,
+                canister_data: UserOwnedCanisterData::default(),
+            });
+    }
+
+    #[test]
+    fn test_restore_run_of_all_users() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        insert_user(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        insert_user(
+            &mut canister_data,
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+
+        assert!(canister_data.get_next_restore_run_batch(10).is_empty());
+
+        canister_data
+            .start_restore_run(RestoreRunScope::AllUsers, 5, now)
+            .unwrap();
+        assert_eq!(
+            canister_data.start_restore_run(RestoreRunScope::AllUsers, 5, now),
+            Err("A restore run is already in progress".to_string())
+        );
+
+        let batch = canister_data.get_next_restore_run_batch(1);
+        assert_eq!(batch.len(), 1);
+        let all_user_data = canister_data.prepare_user_restore(&batch[0]).unwrap();
+        canister_data.record_user_restore_result(
+            batch[0],
+            Some(all_user_data.user_canister_id),
+            Ok(()),
+            now,
+        );
+        canister_data.advance_restore_run_cursor(batch[0]);
+
+        let batch = canister_data.get_next_restore_run_batch(10);
+        assert_eq!(batch.len(), 1);
+        let all_user_data = canister_data.prepare_user_restore(&batch[0]).unwrap();
+        canister_data.record_user_restore_result(
+            batch[0],
+            Some(all_user_data.user_canister_id),
+            Err("Out of cycles".to_string()),
+            now,
+        );
+        canister_data.advance_restore_run_cursor(batch[0]);
+
+        assert!(canister_data.get_next_restore_run_batch(10).is_empty());
+        canister_data.complete_restore_run(now);
+
+        assert!(!canister_data.is_restore_run_in_progress());
+        assert_eq!(
+            canister_data.heap_data.restore_run,
+            Some(RestoreRunReport {
+                scope: RestoreRunScope::AllUsers,
+                max_concurrent_restores: 5,
+                started_at: now,
+                completed_at: Some(now),
+                restored_user_count: 1,
+                failed_user_count: 1,
+            })
+        );
+        assert_eq!(
+            canister_data.heap_data.user_restore_results[&batch[0]].error,
+            Some("Out of cycles".to_string())
+        );
+    }
+
+    #[test]
+    fn test_restore_run_of_selected_users() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        insert_user(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        insert_user(
+            &mut canister_data,
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        canister_data.heap_data.user_restore_results.insert(
+            get_mock_user_alice_principal_id(),
+            UserRestoreResult {
+                user_principal_id: get_mock_user_alice_principal_id(),
+                user_canister_id: None,
+                finished_at: now,
+                error: None,
+            },
+        );
+
+        assert_eq!(
+            canister_data.start_restore_run(
+                RestoreRunScope::SelectedUsers(Default::default()),
+                5,
+                now
+            ),
+            Err("No users selected".to_string())
+        );
+
+        canister_data
+            .start_restore_run(
+                RestoreRunScope::SelectedUsers(
+                    [
+                        get_mock_user_bob_principal_id(),
+                        get_mock_user_charlie_principal_id(),
+                    ]
+                    .into(),
+                ),
+                5,
+                now,
+            )
+            .unwrap();
+        assert!(canister_data.heap_data.user_restore_results.is_empty());
+
+        let mut batch = canister_data.get_next_restore_run_batch(10);
+        batch.sort();
+        let mut expected_batch = vec![
+            get_mock_user_bob_principal_id(),
+            get_mock_user_charlie_principal_id(),
+        ];
+        expected_batch.sort();
+        assert_eq!(batch, expected_batch);
+
+        assert_eq!(
+            canister_data
+                .prepare_user_restore(&get_mock_user_charlie_principal_id())
+                .unwrap_err(),
+            "No user data found"
+        );
+
+        canister_data.advance_restore_run_cursor(*batch.last().unwrap());
+        assert!(canister_data.get_next_restore_run_batch(10).is_empty());
+    }
+
+    #[test]
+    fn test_check_restore_target() {
+        let users_data = AllUserData {
+            user_principal_id: get_mock_user_alice_principal_id(),
+            user_canister_id: get_mock_user_alice_canister_id(),
+            canister_data: UserOwnedCanisterData::default(),
+        };
+
+        assert_eq!(
+            check_restore_target(&users_data, Some(get_mock_user_alice_canister_id()), None),
+            Ok(())
+        );
+        assert_eq!(
+            check_restore_target(&users_data, Some(get_mock_user_bob_canister_id()), None),
+            Err(format!(
+                "The user index maps the user to canister {} instead of {}",
+                get_mock_user_bob_canister_id(),
+                get_mock_user_alice_canister_id()
+            ))
+        );
+
+        // * the user index lost track of the user, so the canister's profile decides
+        assert_eq!(
+            check_restore_target(&users_data, None, Some(get_mock_user_alice_principal_id())),
+            Ok(())
+        );
+        assert_eq!(
+            check_restore_target(&users_data, None, Some(get_mock_user_bob_principal_id())),
+            Err(format!(
+                "Canister {} no longer belongs to the user",
+                get_mock_user_alice_canister_id()
+            ))
+        );
+    }
+}
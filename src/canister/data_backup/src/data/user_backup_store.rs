@@ -0,0 +1,891 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use candid::{CandidType, Decode, Encode, Principal};
+use ic_stable_structures::StableBTreeMap;
+use serde::de::DeserializeOwned;
+use shared_utils::canister_specific::{
+    data_backup::types::{
+        all_user_data::{AllUserData, UserOwnedCanisterData},
+        backup_chunk::{
+            BackupChunk, UserBackupChunkKey, UserBackupItemChunkKey, BACKUP_CHUNK_SIZE_IN_BYTES,
+            MAX_BACKUP_ITEM_KEY_SIZE_IN_BYTES,
+        },
+        backup_manifest::{BackupSection, UserBackupError, UserBackupManifest},
+    },
+    individual_user_template::types::token::TokenBalance,
+};
+use shared_utils::common::types::storable_principal::StorablePrincipal;
+
+use super::memory_layout::{self, Memory};
+
+type BackupChunkMap = StableBTreeMap<UserBackupChunkKey, BackupChunk, Memory>;
+type BackupItemChunkMap = StableBTreeMap<UserBackupItemChunkKey, BackupChunk, Memory>;
+
+/// Backups of individual users, with each section split into chunks stored in a stable map of
+/// its own. A manifest per user lists the chunks, so that the sections can be reassembled.
+/// Sections holding collections store each item on its own instead, so that batches received
+/// for them only write the items they hold.
+pub struct UserBackupStore {
+    pub user_principal_id_to_manifest_map:
+        StableBTreeMap<StorablePrincipal, UserBackupManifest, Memory>,
+    section_to_chunk_map: BTreeMap<BackupSection, BackupChunkMap>,
+    item_chunk_map: BackupItemChunkMap,
+}
+
+impl Default for UserBackupStore {
+    fn default() -> Self {
+        Self {
+            user_principal_id_to_manifest_map: StableBTreeMap::init(
+                memory_layout::get_user_backup_manifest_map_memory(),
+            ),
+            section_to_chunk_map: BackupSection::ALL
+                .into_iter()
+                .map(|section| {
+                    (
+                        section,
+                        StableBTreeMap::init(memory_layout::get_backup_section_chunk_map_memory(
+                            section,
+                        )),
+                    )
+                })
+                .collect(),
+            item_chunk_map: StableBTreeMap::init(
+                memory_layout::get_backup_section_item_chunk_map_memory(),
+            ),
+        }
+    }
+}
+
+impl UserBackupStore {
+    fn get_chunk_map(&self, section: BackupSection) -> &BackupChunkMap {
+        // * every section gets a chunk map in `default`
+        self.section_to_chunk_map.get(&section).unwrap()
+    }
+
+    fn get_chunk_map_mut(&mut self, section: BackupSection) -> &mut BackupChunkMap {
+        self.section_to_chunk_map.get_mut(&section).unwrap()
+    }
+
+    pub fn get_manifest(&self, user_principal_id: &Principal) -> Option<UserBackupManifest> {
+        self.user_principal_id_to_manifest_map
+            .get(&StorablePrincipal(*user_principal_id))
+    }
+
+    pub fn insert_manifest(&mut self, manifest: UserBackupManifest) {
+        self.user_principal_id_to_manifest_map
+            .insert(StorablePrincipal(manifest.user_principal_id), manifest);
+    }
+
+    pub fn reset_acknowledged_change_sequence_number(&mut self, user_principal_id: &Principal) {
+        if let Some(mut manifest) = self.get_manifest(user_principal_id) {
+            manifest.last_acknowledged_change_sequence_number = None;
+            self.insert_manifest(manifest);
+        }
+    }
+
+    pub fn set_last_error(&mut self, user_principal_id: &Principal, last_error: UserBackupError) {
+        if let Some(mut manifest) = self.get_manifest(user_principal_id) {
+            manifest.last_error = Some(last_error);
+            self.insert_manifest(manifest);
+        }
+    }
+
+    pub fn contains_user(&self, user_principal_id: &Principal) -> bool {
+        self.user_principal_id_to_manifest_map
+            .contains_key(&StorablePrincipal(*user_principal_id))
+    }
+
+    pub fn get_user_count(&self) -> u64 {
+        self.user_principal_id_to_manifest_map.len()
+    }
+
+    /// Reassembles the value of a section from its chunks. `None` when the user or section is
+    /// not backed up.
+    pub fn read_section<T: CandidType + DeserializeOwned>(
+        &self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+    ) -> Result<Option<T>, String> {
+        let Some(section_manifest) = self
+            .get_manifest(user_principal_id)
+            .and_then(|manifest| manifest.sections.get(&section).cloned())
+        else {
+            return Ok(None);
+        };
+        if section_manifest.chunk_count == 0 {
+            return Ok(None);
+        }
+        let chunk_map = self.get_chunk_map(section);
+
+        let mut section_bytes = Vec::with_capacity(section_manifest.size_in_bytes as usize);
+        for chunk_index in 0..section_manifest.chunk_count {
+            let chunk = chunk_map
+                .get(&UserBackupChunkKey {
+                    user_principal_id: *user_principal_id,
+                    chunk_index,
+                })
+                .ok_or(format!(
+                    "Chunk {} of section {:?} is missing",
+                    chunk_index, section
+                ))?;
+            section_bytes.extend(chunk.0);
+        }
+
+        Decode!(&section_bytes, T)
+            .map(Some)
+            .map_err(|e| format!("Failed to decode section {:?}: {}", section, e))
+    }
+
+    /// Replaces the value of a section of a user that has a manifest. Items of the section are
+    /// kept.
+    pub fn write_section<T: CandidType>(
+        &mut self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+        value: &T,
+    ) -> Result<(), String> {
+        let mut manifest = self
+            .get_manifest(user_principal_id)
+            .ok_or("No user data found")?;
+        let section_bytes = Encode!(value).map_err(|e| e.to_string())?;
+        let chunks: Vec<&[u8]> = section_bytes.chunks(BACKUP_CHUNK_SIZE_IN_BYTES).collect();
+        let mut section_manifest = manifest.sections.remove(&section).unwrap_or_default();
+
+        let chunk_map = self.get_chunk_map_mut(section);
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            chunk_map.insert(
+                UserBackupChunkKey {
+                    user_principal_id: *user_principal_id,
+                    chunk_index: chunk_index as u32,
+                },
+                BackupChunk(chunk.to_vec()),
+            );
+        }
+        for chunk_index in chunks.len() as u32..section_manifest.chunk_count {
+            chunk_map.remove(&UserBackupChunkKey {
+                user_principal_id: *user_principal_id,
+                chunk_index,
+            });
+        }
+
+        section_manifest.chunk_count = chunks.len() as u32;
+        section_manifest.size_in_bytes = section_bytes.len() as u64;
+        manifest.sections.insert(section, section_manifest);
+        self.insert_manifest(manifest);
+
+        Ok(())
+    }
+
+    fn get_item_chunk_key(
+        user_principal_id: &Principal,
+        section: BackupSection,
+        item_key: Vec<u8>,
+        chunk_index: u32,
+    ) -> UserBackupItemChunkKey {
+        UserBackupItemChunkKey {
+            user_principal_id: *user_principal_id,
+            section,
+            item_key,
+            chunk_index,
+        }
+    }
+
+    /// The items of a section, in key order, with each item's chunks joined
+    fn read_item_bytes(
+        &self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+    ) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut items: Vec<(Vec<u8>, Vec<u8>)> = vec![];
+
+        self.item_chunk_map
+            .range(Self::get_item_chunk_key(user_principal_id, section, vec![], 0)..)
+            .take_while(|(key, _)| {
+                key.user_principal_id == *user_principal_id && key.section == section
+            })
+            .for_each(|(key, chunk)| match items.last_mut() {
+                Some((item_key, item_bytes)) if *item_key == key.item_key => {
+                    item_bytes.extend(chunk.0)
+                }
+                _ => items.push((key.item_key, chunk.0)),
+            });
+
+        items
+    }
+
+    /// The items of a section. `None` when the user or section is not backed up.
+    pub fn read_section_items<K, V>(
+        &self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+    ) -> Result<Option<BTreeMap<K, V>>, String>
+    where
+        K: CandidType + DeserializeOwned + Ord,
+        V: CandidType + DeserializeOwned,
+    {
+        let Some(section_manifest) = self
+            .get_manifest(user_principal_id)
+            .and_then(|manifest| manifest.sections.get(&section).cloned())
+        else {
+            return Ok(None);
+        };
+
+        let items = self
+            .read_item_bytes(user_principal_id, section)
+            .into_iter()
+            .map(|(item_key, item_bytes)| {
+                let key = Decode!(&item_key, K).map_err(|e| e.to_string())?;
+                let value = Decode!(&item_bytes, V).map_err(|e| e.to_string())?;
+                Ok((key, value))
+            })
+            .collect::<Result<BTreeMap<K, V>, String>>()
+            .map_err(|e| format!("Failed to decode an item of section {:?}: {}", section, e))?;
+
+        if items.len() as u64 != section_manifest.item_count {
+            return Err(format!(
+                "Section {:?} holds {} items instead of {}",
+                section,
+                items.len(),
+                section_manifest.item_count
+            ));
+        }
+
+        Ok(Some(items))
+    }
+
+    /// The items of a section holding a set
+    pub fn read_section_item_set<K>(
+        &self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+    ) -> Result<Option<BTreeSet<K>>, String>
+    where
+        K: CandidType + DeserializeOwned + Ord,
+    {
+        self.read_section_items::<K, ()>(user_principal_id, section)
+            .map(|items| items.map(|items| items.into_keys().collect()))
+    }
+
+    /// Upserts items into a section of a user that has a manifest. Only the chunks of the given
+    /// items are written.
+    pub fn write_section_items<K: CandidType, V: CandidType>(
+        &mut self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+        items: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<(), String> {
+        let mut manifest = self
+            .get_manifest(user_principal_id)
+            .ok_or("No user data found")?;
+        // * encode everything first, so that a failure does not leave the section half written
+        let encoded_items = items
+            .into_iter()
+            .map(|(key, value)| {
+                let item_key = Encode!(&key).map_err(|e| e.to_string())?;
+                if item_key.len() > MAX_BACKUP_ITEM_KEY_SIZE_IN_BYTES {
+                    return Err(format!(
+                        "Item key of section {:?} is longer than {} bytes",
+                        section, MAX_BACKUP_ITEM_KEY_SIZE_IN_BYTES
+                    ));
+                }
+                let item_bytes = Encode!(&value).map_err(|e| e.to_string())?;
+                Ok((item_key, item_bytes))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let mut section_manifest = manifest.sections.remove(&section).unwrap_or_default();
+
+        for (item_key, item_bytes) in encoded_items {
+            let (previous_chunk_count, previous_size_in_bytes) =
+                self.remove_item_chunks(user_principal_id, section, &item_key);
+            if previous_chunk_count == 0 {
+                section_manifest.item_count += 1;
+            }
+            section_manifest.item_size_in_bytes = section_manifest
+                .item_size_in_bytes
+                .saturating_sub(previous_size_in_bytes)
+                + item_bytes.len() as u64;
+
+            for (chunk_index, chunk) in item_bytes.chunks(BACKUP_CHUNK_SIZE_IN_BYTES).enumerate() {
+                self.item_chunk_map.insert(
+                    Self::get_item_chunk_key(
+                        user_principal_id,
+                        section,
+                        item_key.clone(),
+                        chunk_index as u32,
+                    ),
+                    BackupChunk(chunk.to_vec()),
+                );
+            }
+        }
+
+        manifest.sections.insert(section, section_manifest);
+        self.insert_manifest(manifest);
+
+        Ok(())
+    }
+
+    /// Removes items from a section of a user that has a manifest. Keys that are not backed up
+    /// are skipped.
+    pub fn remove_section_items<K: CandidType>(
+        &mut self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<(), String> {
+        let mut manifest = self
+            .get_manifest(user_principal_id)
+            .ok_or("No user data found")?;
+        let item_keys = keys
+            .into_iter()
+            .map(|key| Encode!(&key).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, String>>()?;
+        let mut section_manifest = manifest.sections.remove(&section).unwrap_or_default();
+
+        for item_key in item_keys {
+            let (removed_chunk_count, removed_size_in_bytes) =
+                self.remove_item_chunks(user_principal_id, section, &item_key);
+            if removed_chunk_count > 0 {
+                section_manifest.item_count = section_manifest.item_count.saturating_sub(1);
+            }
+            section_manifest.item_size_in_bytes = section_manifest
+                .item_size_in_bytes
+                .saturating_sub(removed_size_in_bytes);
+        }
+
+        manifest.sections.insert(section, section_manifest);
+        self.insert_manifest(manifest);
+
+        Ok(())
+    }
+
+    /// Removes the chunks of an item. Returns the number of chunks removed and their size.
+    fn remove_item_chunks(
+        &mut self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+        item_key: &[u8],
+    ) -> (u32, u64) {
+        let item_chunks: Vec<(UserBackupItemChunkKey, u64)> = self
+            .item_chunk_map
+            .range(Self::get_item_chunk_key(user_principal_id, section, item_key.to_vec(), 0)..)
+            .take_while(|(key, _)| {
+                key.user_principal_id == *user_principal_id
+                    && key.section == section
+                    && key.item_key == item_key
+            })
+            .map(|(key, chunk)| (key, chunk.0.len() as u64))
+            .collect();
+
+        let size_in_bytes = item_chunks.iter().map(|(_, size)| size).sum();
+        item_chunks.iter().for_each(|(key, _)| {
+            self.item_chunk_map.remove(key);
+        });
+
+        (item_chunks.len() as u32, size_in_bytes)
+    }
+
+    /// The token data, with the transaction history stored as items of the section
+    pub fn read_token_data(
+        &self,
+        user_principal_id: &Principal,
+    ) -> Result<Option<TokenBalance>, String> {
+        let Some(utility_token_transaction_history) =
+            self.read_section_items(user_principal_id, BackupSection::TokenData)?
+        else {
+            return Ok(None);
+        };
+        let mut token_data: TokenBalance = self
+            .read_section(user_principal_id, BackupSection::TokenData)?
+            .unwrap_or_default();
+        token_data.utility_token_transaction_history = utility_token_transaction_history;
+
+        Ok(Some(token_data))
+    }
+
+    /// Replaces the token data, storing the transaction history as items of the section
+    fn write_token_data(
+        &mut self,
+        user_principal_id: &Principal,
+        token_data: &TokenBalance,
+    ) -> Result<(), String> {
+        self.remove_section(user_principal_id, BackupSection::TokenData);
+        self.write_section(
+            user_principal_id,
+            BackupSection::TokenData,
+            &TokenBalance {
+                utility_token_transaction_history: BTreeMap::new(),
+                ..token_data.clone()
+            },
+        )?;
+        self.write_section_items(
+            user_principal_id,
+            BackupSection::TokenData,
+            token_data.utility_token_transaction_history.clone(),
+        )
+    }
+
+    /// Reassembles the backup of a user. `None` when the user is not backed up.
+    pub fn get_all_user_data(
+        &self,
+        user_principal_id: &Principal,
+    ) -> Result<Option<AllUserData>, String> {
+        let Some(manifest) = self.get_manifest(user_principal_id) else {
+            return Ok(None);
+        };
+
+        Ok(Some(AllUserData {
+            user_principal_id: manifest.user_principal_id,
+            user_canister_id: manifest.user_canister_id,
+            canister_data: UserOwnedCanisterData {
+                all_created_posts: self
+                    .read_section_items(user_principal_id, BackupSection::Posts)?
+                    .unwrap_or_default(),
+                principals_i_follow: self
+                    .read_section_item_set(user_principal_id, BackupSection::PrincipalsIFollow)?
+                    .unwrap_or_default(),
+                principals_that_follow_me: self
+                    .read_section_item_set(
+                        user_principal_id,
+                        BackupSection::PrincipalsThatFollowMe,
+                    )?
+                    .unwrap_or_default(),
+                profile: self
+                    .read_section(user_principal_id, BackupSection::Profile)?
+                    .unwrap_or_default(),
+                token_data: self.read_token_data(user_principal_id)?.unwrap_or_default(),
+                backup_format_version: Some(manifest.get_format_version()),
+                all_hot_or_not_bets_placed: self
+                    .read_section_items(user_principal_id, BackupSection::HotOrNotBetsPlaced)?,
+                follow_data: self.read_section(user_principal_id, BackupSection::FollowData)?,
+                configuration: self
+                    .read_section(user_principal_id, BackupSection::Configuration)?,
+                known_principal_ids: self
+                    .read_section(user_principal_id, BackupSection::KnownPrincipalIds)?,
+                version_details: self
+                    .read_section(user_principal_id, BackupSection::VersionDetails)?,
+                posts_index_sorted_by_home_feed_score: self.read_section(
+                    user_principal_id,
+                    BackupSection::PostsIndexSortedByHomeFeedScore,
+                )?,
+                posts_index_sorted_by_hot_or_not_feed_score: self.read_section(
+                    user_principal_id,
+                    BackupSection::PostsIndexSortedByHotOrNotFeedScore,
+                )?,
+                post_score_sync_queue: self
+                    .read_section(user_principal_id, BackupSection::PostScoreSyncQueue)?,
+                last_access_time: self
+                    .read_section(user_principal_id, BackupSection::LastAccessTime)?,
+            },
+        }))
+    }
+
+    /// Stores all sections of `all_user_data`, replacing what was backed up for the user
+    pub fn insert_all_user_data(&mut self, all_user_data: &AllUserData) {
+        let user_principal_id = all_user_data.user_principal_id;
+        let mut manifest = self.get_manifest(&user_principal_id).unwrap_or_else(|| {
+            UserBackupManifest::new(user_principal_id, all_user_data.user_canister_id)
+        });
+        manifest.user_canister_id = all_user_data.user_canister_id;
+        self.insert_manifest(manifest);
+
+        let canister_data = &all_user_data.canister_data;
+        // * sections encode infallibly and item keys are short, so the writes cannot fail once
+        // * the manifest exists
+        self.write_section(
+            &user_principal_id,
+            BackupSection::Profile,
+            &canister_data.profile,
+        )
+        .unwrap();
+        self.replace_section_items(
+            &user_principal_id,
+            BackupSection::Posts,
+            &Some(canister_data.all_created_posts.clone()),
+        );
+        self.write_token_data(&user_principal_id, &canister_data.token_data)
+            .unwrap();
+        self.replace_section_items(
+            &user_principal_id,
+            BackupSection::PrincipalsIFollow,
+            &Some(
+                canister_data
+                    .principals_i_follow
+                    .iter()
+                    .map(|principal| (*principal, ()))
+                    .collect(),
+            ),
+        );
+        self.replace_section_items(
+            &user_principal_id,
+            BackupSection::PrincipalsThatFollowMe,
+            &Some(
+                canister_data
+                    .principals_that_follow_me
+                    .iter()
+                    .map(|principal| (*principal, ()))
+                    .collect(),
+            ),
+        );
+
+        self.replace_section_items(
+            &user_principal_id,
+            BackupSection::HotOrNotBetsPlaced,
+            &canister_data.all_hot_or_not_bets_placed,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::FollowData,
+            &canister_data.follow_data,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::Configuration,
+            &canister_data.configuration,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::KnownPrincipalIds,
+            &canister_data.known_principal_ids,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::VersionDetails,
+            &canister_data.version_details,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::PostsIndexSortedByHomeFeedScore,
+            &canister_data.posts_index_sorted_by_home_feed_score,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::PostsIndexSortedByHotOrNotFeedScore,
+            &canister_data.posts_index_sorted_by_hot_or_not_feed_score,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::PostScoreSyncQueue,
+            &canister_data.post_score_sync_queue,
+        );
+        self.replace_section(
+            &user_principal_id,
+            BackupSection::LastAccessTime,
+            &canister_data.last_access_time,
+        );
+    }
+
+    /// Writes the section if there is a value for it and removes it otherwise
+    fn replace_section<T: CandidType>(
+        &mut self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+        value: &Option<T>,
+    ) {
+        match value {
+            Some(value) => self
+                .write_section(user_principal_id, section, value)
+                .unwrap(),
+            None => self.remove_section(user_principal_id, section),
+        }
+    }
+
+    /// Replaces the items of the section if there are any and removes the section otherwise
+    fn replace_section_items<K: CandidType + Clone, V: CandidType + Clone>(
+        &mut self,
+        user_principal_id: &Principal,
+        section: BackupSection,
+        items: &Option<BTreeMap<K, V>>,
+    ) {
+        self.remove_section(user_principal_id, section);
+        if let Some(items) = items {
+            self.write_section_items(user_principal_id, section, items.clone())
+                .unwrap();
+        }
+    }
+
+    /// Removes a user's backup, with all of its sections and chunks
+    pub fn remove_user(&mut self, user_principal_id: &Principal) {
+        let Some(manifest) = self.get_manifest(user_principal_id) else {
+            return;
+        };
+
+        manifest
+            .sections
+            .keys()
+            .for_each(|section| self.remove_section(user_principal_id, *section));
+        self.user_principal_id_to_manifest_map
+            .remove(&StorablePrincipal(*user_principal_id));
+    }
+
+    /// Removes a section with its chunks and items from a user's backup
+    pub fn remove_section(&mut self, user_principal_id: &Principal, section: BackupSection) {
+        let Some(mut manifest) = self.get_manifest(user_principal_id) else {
+            return;
+        };
+        let Some(section_manifest) = manifest.sections.remove(&section) else {
+            return;
+        };
+
+        let chunk_map = self.get_chunk_map_mut(section);
+        for chunk_index in 0..section_manifest.chunk_count {
+            chunk_map.remove(&UserBackupChunkKey {
+                user_principal_id: *user_principal_id,
+                chunk_index,
+            });
+        }
+
+        let item_chunk_keys: Vec<UserBackupItemChunkKey> = self
+            .item_chunk_map
+            .range(Self::get_item_chunk_key(user_principal_id, section, vec![], 0)..)
+            .take_while(|(key, _)| {
+                key.user_principal_id == *user_principal_id && key.section == section
+            })
+            .map(|(key, _)| key)
+            .collect();
+        item_chunk_keys.iter().for_each(|key| {
+            self.item_chunk_map.remove(key);
+        });
+
+        self.insert_manifest(manifest);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+    use crate::data::memory_layout::CanisterData;
+
+    fn get_principals(principal_count: u32) -> BTreeSet<Principal> {
+        (0..principal_count)
+            .map(|index| Principal::self_authenticating(index.to_le_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_write_section_larger_than_a_chunk() {
+        let mut store = CanisterData::default().user_backup_store;
+        let alice = get_mock_user_alice_principal_id();
+        let section = BackupSection::PrincipalsThatFollowMe;
+
+        assert!(store
+            .write_section(&alice, section, &get_principals(1))
+            .is_err());
+
+        store.insert_manifest(UserBackupManifest::new(
+            alice,
+            get_mock_user_alice_canister_id(),
+        ));
+
+        // * well beyond the 100 kB a single entry could hold before
+        let principals = get_principals(5_000);
+        store.write_section(&alice, section, &principals).unwrap();
+        let section_manifest = store
+            .get_manifest(&alice)
+            .unwrap()
+            .sections
+            .get(&section)
+            .cloned()
+            .unwrap();
+        assert!(section_manifest.size_in_bytes > 100_000);
+        assert_eq!(
+            section_manifest.chunk_count as u64,
+            section_manifest
+                .size_in_bytes
+                .div_ceil(BACKUP_CHUNK_SIZE_IN_BYTES as u64)
+        );
+        assert_eq!(store.read_section(&alice, section), Ok(Some(principals)));
+
+        // * chunks left over from a larger section are removed
+        let principals = get_principals(2);
+        store.write_section(&alice, section, &principals).unwrap();
+        assert_eq!(store.get_chunk_map(section).len(), 1);
+        assert_eq!(store.read_section(&alice, section), Ok(Some(principals)));
+    }
+
+    #[test]
+    fn test_write_section_items() {
+        let mut store = CanisterData::default().user_backup_store;
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+        let section = BackupSection::Posts;
+
+        assert!(store
+            .write_section_items(&alice, section, [(0_u64, "post 0".to_string())])
+            .is_err());
+        assert_eq!(
+            store.read_section_items::<u64, String>(&alice, section),
+            Ok(None)
+        );
+
+        for (user_principal_id, user_canister_id) in [
+            (alice, get_mock_user_alice_canister_id()),
+            (bob, get_mock_user_bob_canister_id()),
+        ] {
+            store.insert_manifest(UserBackupManifest::new(user_principal_id, user_canister_id));
+        }
+        store
+            .write_section_items(&bob, section, [(0_u64, "bob post 0".to_string())])
+            .unwrap();
+
+        // * an item larger than a chunk
+        let long_post = "🥫".repeat(BACKUP_CHUNK_SIZE_IN_BYTES);
+        store
+            .write_section_items(
+                &alice,
+                section,
+                [(0_u64, "post 0".to_string()), (1, long_post.clone())],
+            )
+            .unwrap();
+        // * upserts only touch the items in the batch
+        store
+            .write_section_items(
+                &alice,
+                section,
+                [(1_u64, "post 1".to_string()), (2, "post 2".to_string())],
+            )
+            .unwrap();
+
+        assert_eq!(
+            store.read_section_items(&alice, section),
+            Ok(Some(BTreeMap::from([
+                (0_u64, "post 0".to_string()),
+                (1, "post 1".to_string()),
+                (2, "post 2".to_string()),
+            ])))
+        );
+        let section_manifest = store.get_manifest(&alice).unwrap().sections[&section].clone();
+        assert_eq!(section_manifest.item_count, 3);
+        assert_eq!(
+            section_manifest.item_size_in_bytes,
+            ["post 0", "post 1", "post 2"]
+                .iter()
+                .map(|post| Encode!(&post.to_string()).unwrap().len() as u64)
+                .sum::<u64>()
+        );
+        assert_eq!(
+            store.read_section_items(&bob, section),
+            Ok(Some(BTreeMap::from([(0_u64, "bob post 0".to_string())])))
+        );
+
+        // * removing a key that is not backed up changes nothing
+        store
+            .remove_section_items(&alice, section, [0_u64, 3])
+            .unwrap();
+        assert_eq!(
+            store.read_section_items(&alice, section),
+            Ok(Some(BTreeMap::from([
+                (1_u64, "post 1".to_string()),
+                (2, "post 2".to_string()),
+            ])))
+        );
+        let section_manifest = store.get_manifest(&alice).unwrap().sections[&section].clone();
+        assert_eq!(section_manifest.item_count, 2);
+        assert_eq!(
+            section_manifest.item_size_in_bytes,
+            ["post 1", "post 2"]
+                .iter()
+                .map(|post| Encode!(&post.to_string()).unwrap().len() as u64)
+                .sum::<u64>()
+        );
+
+        // * a missing item is reported instead of being left out
+        store.item_chunk_map.remove(&UserBackupItemChunkKey {
+            user_principal_id: alice,
+            section,
+            item_key: Encode!(&2_u64).unwrap(),
+            chunk_index: 0,
+        });
+        assert!(store
+            .read_section_items::<u64, String>(&alice, section)
+            .is_err());
+
+        store.remove_section(&alice, section);
+        assert_eq!(
+            store.read_section_items::<u64, String>(&alice, section),
+            Ok(None)
+        );
+        assert_eq!(store.item_chunk_map.len(), 1);
+    }
+
+    #[test]
+    fn test_read_section_reports_missing_chunks() {
+        let mut store = CanisterData::default().user_backup_store;
+        let alice = get_mock_user_alice_principal_id();
+        let section = BackupSection::FollowData;
+        store.insert_manifest(UserBackupManifest::new(
+            alice,
+            get_mock_user_alice_canister_id(),
+        ));
+
+        store
+            .write_section(&alice, section, &get_principals(3))
+            .unwrap();
+        store
+            .get_chunk_map_mut(section)
+            .remove(&UserBackupChunkKey {
+                user_principal_id: alice,
+                chunk_index: 0,
+            });
+
+        assert!(store
+            .read_section::<BTreeSet<Principal>>(&alice, section)
+            .is_err());
+        assert!(store.get_all_user_data(&alice).is_err());
+    }
+
+    #[test]
+    fn test_migrate_legacy_user_data_batch() {
+        let mut canister_data = CanisterData::default();
+
+        for (user_principal_id, user_canister_id) in [
+            (
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ),
+            (
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+            ),
+        ] {
+            let mut all_user_data = AllUserData {
+                user_principal_id,
+                user_canister_id,
+                canister_data: UserOwnedCanisterData::default(),
+            };
+            all_user_data.canister_data.principals_i_follow = get_principals(3);
+            canister_data
+                .legacy_user_principal_id_to_all_user_data_map
+                .insert(StorablePrincipal(user_principal_id), all_user_data);
+        }
+        assert_eq!(canister_data.get_user_count(), 2);
+
+        assert_eq!(canister_data.migrate_legacy_user_data_batch(1), 1);
+        assert_eq!(canister_data.user_backup_store.get_user_count(), 1);
+        assert_eq!(canister_data.get_user_count(), 2);
+        assert_eq!(canister_data.migrate_legacy_user_data_batch(5), 1);
+        assert_eq!(canister_data.migrate_legacy_user_data_batch(5), 0);
+        assert!(canister_data
+            .legacy_user_principal_id_to_all_user_data_map
+            .is_empty());
+
+        let all_user_data = canister_data
+            .get_all_user_data(&get_mock_user_bob_principal_id())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            all_user_data.user_canister_id,
+            get_mock_user_bob_canister_id()
+        );
+        assert_eq!(
+            all_user_data.canister_data.principals_i_follow,
+            get_principals(3)
+        );
+    }
+}
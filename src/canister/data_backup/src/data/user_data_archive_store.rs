@@ -0,0 +1,380 @@
+use std::time::{Duration, SystemTime};
+
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::StableBTreeMap;
+use serde::Serialize;
+use shared_utils::canister_specific::data_backup::types::{
+    all_user_data::AllUserData,
+    backup_chunk::{BackupChunk, UserBackupChunkKey},
+    user_data_archive::{
+        encode_user_data_archive, UserDataArchiveManifest, USER_DATA_ARCHIVE_CHUNK_SIZE_IN_BYTES,
+    },
+};
+
+use super::memory_layout::{self, CanisterData, Memory};
+
+type ArchiveChunkMap = StableBTreeMap<UserBackupChunkKey, BackupChunk, Memory>;
+
+/// The number of latest exports of a user whose archives the user can import
+const MAX_EXPORTED_ARCHIVE_DIGESTS_PER_USER: usize = 5;
+
+/// An import that was started longer ago than this is abandoned, and another one can start
+const ABANDONED_USER_DATA_IMPORT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserDataImport {
+    pub manifest: UserDataArchiveManifest,
+    pub started_at: SystemTime,
+}
+
+/// The chunks of archives exported for users to download, and of archives users upload to be
+/// imported. Their manifests are kept in the heap data.
+pub struct UserDataArchiveStore {
+    export_chunk_map: ArchiveChunkMap,
+    import_chunk_map: ArchiveChunkMap,
+}
+
+impl Default for UserDataArchiveStore {
+    fn default() -> Self {
+        Self {
+            export_chunk_map: StableBTreeMap::init(
+                memory_layout::get_user_data_export_chunk_map_memory(),
+            ),
+            import_chunk_map: StableBTreeMap::init(
+                memory_layout::get_user_data_import_chunk_map_memory(),
+            ),
+        }
+    }
+}
+
+fn remove_user_chunks(chunk_map: &mut ArchiveChunkMap, user_principal_id: &Principal) {
+    let chunk_keys: Vec<UserBackupChunkKey> = chunk_map
+        .range(
+            UserBackupChunkKey {
+                user_principal_id: *user_principal_id,
+                chunk_index: 0,
+            }..=UserBackupChunkKey {
+                user_principal_id: *user_principal_id,
+                chunk_index: u32::MAX,
+            },
+        )
+        .map(|(chunk_key, _)| chunk_key)
+        .collect();
+
+    chunk_keys.iter().for_each(|chunk_key| {
+        chunk_map.remove(chunk_key);
+    });
+}
+
+impl CanisterData {
+    /// Encodes the user's backup into an archive that replaces their previous export
+    pub fn export_user_data(
+        &mut self,
+        user_principal_id: &Principal,
+        current_time: SystemTime,
+    ) -> Result<UserDataArchiveManifest, String> {
+        let all_user_data = self
+            .get_all_user_data(user_principal_id)?
+            .ok_or("No user data found")?;
+        let (manifest, archive_bytes) = encode_user_data_archive(&all_user_data, current_time)?;
+
+        let export_chunk_map = &mut self.user_data_archive_store.export_chunk_map;
+        remove_user_chunks(export_chunk_map, user_principal_id);
+        for (chunk_index, chunk) in archive_bytes
+            .chunks(USER_DATA_ARCHIVE_CHUNK_SIZE_IN_BYTES)
+            .enumerate()
+        {
+            export_chunk_map.insert(
+                UserBackupChunkKey {
+                    user_principal_id: *user_principal_id,
+                    chunk_index: chunk_index as u32,
+                },
+                BackupChunk(chunk.to_vec()),
+            );
+        }
+
+        let exported_archive_digests = self
+            .heap_data
+            .exported_user_data_archive_digests
+            .entry(*user_principal_id)
+            .or_default();
+        exported_archive_digests.push(manifest.sha256.clone());
+        if exported_archive_digests.len() > MAX_EXPORTED_ARCHIVE_DIGESTS_PER_USER {
+            exported_archive_digests.remove(0);
+        }
+        self.heap_data
+            .user_data_exports
+            .insert(*user_principal_id, manifest.clone());
+
+        Ok(manifest)
+    }
+
+    pub fn get_user_data_export_chunk(
+        &self,
+        user_principal_id: &Principal,
+        chunk_index: u32,
+    ) -> Result<Vec<u8>, String> {
+        if !self
+            .heap_data
+            .user_data_exports
+            .contains_key(user_principal_id)
+        {
+            return Err("No export found".to_string());
+        }
+
+        self.user_data_archive_store
+            .export_chunk_map
+            .get(&UserBackupChunkKey {
+                user_principal_id: *user_principal_id,
+                chunk_index,
+            })
+            .map(|chunk| chunk.0)
+            .ok_or("Chunk not found".to_string())
+    }
+
+    pub fn delete_user_data_export(&mut self, user_principal_id: &Principal) {
+        self.heap_data.user_data_exports.remove(user_principal_id);
+        remove_user_chunks(
+            &mut self.user_data_archive_store.export_chunk_map,
+            user_principal_id,
+        );
+    }
+
+    /// Whether the archive is one of the latest ones exported for its user
+    pub fn is_exported_user_data_archive(&self, manifest: &UserDataArchiveManifest) -> bool {
+        self.heap_data
+            .exported_user_data_archive_digests
+            .get(&manifest.user_principal_id)
+            .is_some_and(|exported_archive_digests| {
+                exported_archive_digests.contains(&manifest.sha256)
+            })
+    }
+
+    /// Prepares for the chunks of the archive described by `manifest` to be uploaded. One archive
+    /// is imported at a time, so an import of another user must be completed or abandoned first.
+    /// An import of the same user that was not completed is discarded.
+    pub fn start_user_data_import(
+        &mut self,
+        manifest: UserDataArchiveManifest,
+        current_time: SystemTime,
+    ) -> Result<(), String> {
+        manifest.validate()?;
+
+        if let Some(user_data_import) = &self.heap_data.user_data_import {
+            let is_abandoned = current_time
+                .duration_since(user_data_import.started_at)
+                .is_ok_and(|elapsed| elapsed >= ABANDONED_USER_DATA_IMPORT_TIMEOUT);
+            if user_data_import.manifest.user_principal_id != manifest.user_principal_id
+                && !is_abandoned
+            {
+                return Err("Another import is in progress".to_string());
+            }
+
+            let user_principal_id = user_data_import.manifest.user_principal_id;
+            self.discard_user_data_import(&user_principal_id);
+        }
+
+        self.heap_data.user_data_import = Some(UserDataImport {
+            manifest,
+            started_at: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// The manifest of the import in progress, when it belongs to the user
+    pub fn get_user_data_import_manifest(
+        &self,
+        user_principal_id: &Principal,
+    ) -> Option<&UserDataArchiveManifest> {
+        self.heap_data
+            .user_data_import
+            .as_ref()
+            .map(|user_data_import| &user_data_import.manifest)
+            .filter(|manifest| manifest.user_principal_id == *user_principal_id)
+    }
+
+    pub fn upload_user_data_import_chunk(
+        &mut self,
+        user_principal_id: &Principal,
+        chunk_index: u32,
+        chunk: Vec<u8>,
+    ) -> Result<(), String> {
+        let manifest = self
+            .get_user_data_import_manifest(user_principal_id)
+            .ok_or("No import in progress")?;
+
+        if chunk_index >= manifest.chunk_count {
+            return Err("Chunk index out of range".to_string());
+        }
+
+        if chunk.len() > USER_DATA_ARCHIVE_CHUNK_SIZE_IN_BYTES {
+            return Err("Chunk is too large".to_string());
+        }
+
+        self.user_data_archive_store.import_chunk_map.insert(
+            UserBackupChunkKey {
+                user_principal_id: *user_principal_id,
+                chunk_index,
+            },
+            BackupChunk(chunk),
+        );
+
+        Ok(())
+    }
+
+    /// Reassembles and decodes the uploaded archive, after checking it against its manifest
+    pub fn get_user_data_import(
+        &self,
+        user_principal_id: &Principal,
+    ) -> Result<AllUserData, String> {
+        let manifest = self
+            .get_user_data_import_manifest(user_principal_id)
+            .ok_or("No import in progress")?;
+
+        let mut archive_bytes = Vec::with_capacity(manifest.size_in_bytes as usize);
+        for chunk_index in 0..manifest.chunk_count {
+            let chunk = self
+                .user_data_archive_store
+                .import_chunk_map
+                .get(&UserBackupChunkKey {
+                    user_principal_id: *user_principal_id,
+                    chunk_index,
+                })
+                .ok_or(format!("Chunk {} was not uploaded", chunk_index))?;
+            archive_bytes.extend(chunk.0);
+        }
+
+        manifest.decode_archive(&archive_bytes)
+    }
+
+    pub fn discard_user_data_import(&mut self, user_principal_id: &Principal) {
+        if self
+            .get_user_data_import_manifest(user_principal_id)
+            .is_some()
+        {
+            self.heap_data.user_data_import = None;
+        }
+        remove_user_chunks(
+            &mut self.user_data_archive_store.import_chunk_map,
+            user_principal_id,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::{
+        data_backup::types::all_user_data::UserOwnedCanisterData,
+        individual_user_template::types::post::Post,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    fn get_all_user_data() -> AllUserData {
+        let mut canister_data = UserOwnedCanisterData::default();
+        // * large enough to span several chunks
+        for post_id in 0..200 {
+            canister_data.all_created_posts.insert(
+                post_id,
+                Post {
+                    id: post_id,
+                    description: "a".repeat(500),
+                    hashtags: vec![],
+                    video_uid: "video_uid".to_string(),
+                    status: Default::default(),
+                    created_at: SystemTime::UNIX_EPOCH,
+                    likes: Default::default(),
+                    share_count: 0,
+                    view_stats: Default::default(),
+                    home_feed_score: Default::default(),
+                    creator_consent_for_inclusion_in_hot_or_not: false,
+                    hot_or_not_details: None,
+                    is_nsfw: false,
+                },
+            );
+        }
+
+        AllUserData {
+            user_principal_id: get_mock_user_alice_principal_id(),
+            user_canister_id: get_mock_user_alice_canister_id(),
+            canister_data,
+        }
+    }
+
+    #[test]
+    fn test_export_and_import_user_data() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        let user_principal_id = get_mock_user_alice_principal_id();
+
+        assert_eq!(
+            canister_data
+                .export_user_data(&user_principal_id, now)
+                .unwrap_err(),
+            "No user data found"
+        );
+
+        canister_data
+            .user_backup_store
+            .insert_all_user_data(&get_all_user_data());
+        let manifest = canister_data
+            .export_user_data(&user_principal_id, now)
+            .unwrap();
+        assert!(manifest.chunk_count > 1);
+
+        let archive_chunks: Vec<Vec<u8>> = (0..manifest.chunk_count)
+            .map(|chunk_index| {
+                canister_data
+                    .get_user_data_export_chunk(&user_principal_id, chunk_index)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            canister_data.get_user_data_export_chunk(&user_principal_id, manifest.chunk_count),
+            Err("Chunk not found".to_string())
+        );
+
+        canister_data
+            .start_user_data_import(manifest.clone(), now)
+            .unwrap();
+        for (chunk_index, chunk) in archive_chunks.iter().enumerate().skip(1) {
+            canister_data
+                .upload_user_data_import_chunk(
+                    &user_principal_id,
+                    chunk_index as u32,
+                    chunk.clone(),
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            canister_data
+                .get_user_data_import(&user_principal_id)
+                .unwrap_err(),
+            "Chunk 0 was not uploaded"
+        );
+
+        canister_data
+            .upload_user_data_import_chunk(&user_principal_id, 0, archive_chunks[0].clone())
+            .unwrap();
+        let all_user_data = canister_data
+            .get_user_data_import(&user_principal_id)
+            .unwrap();
+        assert_eq!(all_user_data.canister_data.all_created_posts.len(), 200);
+
+        canister_data.discard_user_data_import(&user_principal_id);
+        canister_data.delete_user_data_export(&user_principal_id);
+        assert!(canister_data.heap_data.user_data_import.is_none());
+        assert_eq!(
+            canister_data.get_user_data_export_chunk(&user_principal_id, 0),
+            Err("No export found".to_string())
+        );
+        assert!(canister_data
+            .user_data_archive_store
+            .export_chunk_map
+            .is_empty());
+    }
+}
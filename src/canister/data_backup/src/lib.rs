@@ -6,18 +6,29 @@ use shared_utils::{
     access_control::UserAccessRole,
     canister_specific::{
         data_backup::types::{
-            all_user_data::AllUserData, args::DataBackupInitArgs,
-            backup_statistics::BackupStatistics,
+            all_user_data::AllUserData,
+            args::DataBackupInitArgs,
+            backup_key_counts::BackupKeyCounts,
+            backup_section_digests::BackupSectionDigests,
+            backup_snapshot::{BackupSnapshot, BackupSnapshotKind, BackupSnapshotRetentionPolicy},
+            backup_statistics::{BackupStatistics, UserBackupStatus},
+            individual_user_canister_data_section::IndividualUserCanisterDataSection,
+            restore_run::{RestoreRunReport, RestoreRunScope, UserRestoreResult},
+            user_data_archive::UserDataArchiveManifest,
         },
         individual_user_template::types::{post::Post, profile::UserProfile},
     },
-    common::types::{known_principal::KnownPrincipalType, utility_token::token_event::TokenEvent},
+    common::types::{
+        known_principal::KnownPrincipalType, utility_token::token_event::TokenEvent,
+        version_details::VersionDetails,
+    },
 };
 
 mod api;
 mod data;
 #[cfg(test)]
 mod test;
+mod util;
 
 thread_local! {
     pub static CANISTER_DATA: RefCell<CanisterData> = RefCell::new(CanisterData::default());
@@ -0,0 +1,128 @@
+use std::{cell::Cell, time::Duration};
+
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::common::{types::known_principal::KnownPrincipalType, utils::system_time};
+
+use crate::CANISTER_DATA;
+
+const DAILY_BACKUP_COVERAGE_SCAN_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const BACKUP_COVERAGE_SCAN_BATCH_SIZE: usize = 500;
+const USER_INDEX_PAGE_SIZE: u64 = 1000;
+
+thread_local! {
+    static IS_BACKUP_COVERAGE_SCAN_SCHEDULED: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enqueue_timer_for_daily_backup_coverage_scan() {
+    ic_cdk_timers::set_timer_interval(DAILY_BACKUP_COVERAGE_SCAN_INTERVAL, || {
+        let result = CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .start_backup_coverage_scan(system_time::get_current_system_time_from_ic())
+        });
+
+        match result {
+            Ok(()) => run_backup_coverage_scan_in_batches(),
+            Err(e) => ic_cdk::print(format!("Failed to start backup coverage scan: {}", e)),
+        }
+    });
+}
+
+/// Scans a batch of backups, or a page of the user index once every backup is scanned, per timer
+/// tick, so that no single message runs out of instructions
+pub fn run_backup_coverage_scan_in_batches() {
+    if IS_BACKUP_COVERAGE_SCAN_SCHEDULED.with(|is_scheduled| is_scheduled.replace(true)) {
+        return;
+    }
+
+    schedule_next_backup_coverage_scan_batch();
+}
+
+fn schedule_next_backup_coverage_scan_batch() {
+    ic_cdk_timers::set_timer(Duration::from_secs(1), || {
+        ic_cdk::spawn(async {
+            if scan_next_backup_coverage_batch().await {
+                schedule_next_backup_coverage_scan_batch();
+            } else {
+                IS_BACKUP_COVERAGE_SCAN_SCHEDULED.with(|is_scheduled| is_scheduled.set(false));
+            }
+        })
+    });
+}
+
+/// Returns whether the scan has more to do
+async fn scan_next_backup_coverage_batch() -> bool {
+    let (scan_state, user_index_canister_id) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
+        (
+            canister_data
+                .heap_data
+                .backup_coverage_scan_in_progress
+                .as_ref()
+                .map(|scan| (scan.are_backups_scanned, scan.user_index_cursor)),
+            canister_data
+                .heap_data
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdUserIndex)
+                .copied(),
+        )
+    });
+
+    let Some((are_backups_scanned, user_index_cursor)) = scan_state else {
+        return false;
+    };
+
+    if !are_backups_scanned {
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .scan_backups_for_coverage_batch(BACKUP_COVERAGE_SCAN_BATCH_SIZE)
+        });
+        return true;
+    }
+
+    let page = match user_index_canister_id {
+        Some(user_index_canister_id) => {
+            get_user_principal_ids_page(user_index_canister_id, user_index_cursor).await
+        }
+        None => Err("User index canister not found in internal records".to_string()),
+    };
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        match page {
+            Ok(user_principal_ids) => {
+                canister_data.record_user_index_page_for_backup_coverage(&user_principal_ids);
+                if (user_principal_ids.len() as u64) < USER_INDEX_PAGE_SIZE {
+                    canister_data.complete_backup_coverage_scan(
+                        system_time::get_current_system_time_from_ic(),
+                    );
+                    return false;
+                }
+                true
+            }
+            Err(e) => {
+                ic_cdk::print(format!("Backup coverage scan failed: {}", e));
+                canister_data.heap_data.backup_coverage_scan_in_progress = None;
+                false
+            }
+        }
+    })
+}
+
+async fn get_user_principal_ids_page(
+    user_index_canister_id: Principal,
+    start_after: Option<Principal>,
+) -> Result<Vec<Principal>, String> {
+    let (response,): (Result<Vec<Principal>, String>,) = call::call(
+        user_index_canister_id,
+        "get_user_principal_ids_paginated",
+        (start_after, USER_INDEX_PAGE_SIZE),
+    )
+    .await
+    .map_err(|e| e.1)?;
+
+    response
+}
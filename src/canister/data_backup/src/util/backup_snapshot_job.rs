@@ -0,0 +1,92 @@
+use std::{cell::Cell, time::Duration};
+
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_snapshot::BackupSnapshotKind,
+    common::{
+        types::{known_principal::KnownPrincipalType, version_details::VersionDetails},
+        utils::system_time,
+    },
+};
+
+use crate::CANISTER_DATA;
+
+const DAILY_BACKUP_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const BACKUP_SNAPSHOT_JOB_BATCH_SIZE: usize = 10;
+
+thread_local! {
+    static IS_BACKUP_SNAPSHOT_JOB_SCHEDULED: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enqueue_timer_for_daily_backup_snapshots() {
+    ic_cdk_timers::set_timer_interval(DAILY_BACKUP_SNAPSHOT_INTERVAL, || {
+        ic_cdk::spawn(take_daily_backup_snapshot())
+    });
+}
+
+async fn take_daily_backup_snapshot() {
+    let source_wasm_version = get_individual_user_template_wasm_version().await;
+
+    let result = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell.borrow_mut().start_backup_snapshot(
+            BackupSnapshotKind::Daily,
+            source_wasm_version,
+            system_time::get_current_system_time_from_ic(),
+        )
+    });
+
+    match result {
+        Ok(_) => run_backup_snapshot_job_in_batches(),
+        Err(e) => ic_cdk::print(format!("Failed to take daily backup snapshot: {}", e)),
+    }
+}
+
+/// The version the individual user canisters run, when the user index knows a single one
+async fn get_individual_user_template_wasm_version() -> Option<VersionDetails> {
+    let user_index_canister_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .heap_data
+            .known_principal_ids
+            .get(&KnownPrincipalType::CanisterIdUserIndex)
+            .copied()
+    })?;
+
+    let (version_details,): (Option<VersionDetails>,) = call::call(
+        user_index_canister_id,
+        "get_index_details_individual_user_template_version",
+        (),
+    )
+    .await
+    .ok()?;
+
+    version_details
+}
+
+/// Copies users into the snapshot in progress and deletes snapshots dropped by the retention
+/// policy, a batch per timer tick, so that no single message runs out of instructions
+pub fn run_backup_snapshot_job_in_batches() {
+    if IS_BACKUP_SNAPSHOT_JOB_SCHEDULED.with(|is_scheduled| is_scheduled.replace(true)) {
+        return;
+    }
+
+    schedule_next_backup_snapshot_job_batch();
+}
+
+fn schedule_next_backup_snapshot_job_batch() {
+    ic_cdk_timers::set_timer(Duration::from_secs(1), || {
+        let has_pending_work = CANISTER_DATA.with(|canister_data_ref_cell| {
+            let mut canister_data = canister_data_ref_cell.borrow_mut();
+            canister_data.copy_users_into_backup_snapshot_batch(BACKUP_SNAPSHOT_JOB_BATCH_SIZE);
+            canister_data.delete_pending_backup_snapshots_batch(BACKUP_SNAPSHOT_JOB_BATCH_SIZE);
+
+            canister_data.has_pending_backup_snapshot_work()
+        });
+
+        if has_pending_work {
+            schedule_next_backup_snapshot_job_batch();
+        } else {
+            IS_BACKUP_SNAPSHOT_JOB_SCHEDULED.with(|is_scheduled| is_scheduled.set(false));
+        }
+    });
+}
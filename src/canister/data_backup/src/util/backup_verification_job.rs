@@ -0,0 +1,118 @@
+use std::{cell::Cell, time::Duration};
+
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_section_digests::BackupSectionDigests,
+    common::utils::{
+        system_time,
+        task::{run_task_concurrently, InProgressGuard},
+    },
+};
+
+use crate::{data::backup_verification::BackupVerificationTask, CANISTER_DATA};
+
+const DAILY_BACKUP_VERIFICATION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const BACKUP_VERIFICATION_BATCH_SIZE: usize = 20;
+const MAX_CONCURRENT_BACKUP_VERIFICATIONS: usize = 10;
+
+thread_local! {
+    static IS_BACKUP_VERIFICATION_JOB_RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enqueue_timer_for_daily_backup_verification() {
+    ic_cdk_timers::set_timer_interval(DAILY_BACKUP_VERIFICATION_INTERVAL, || {
+        let result = CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .start_backup_verification(system_time::get_current_system_time_from_ic())
+        });
+
+        match result {
+            Ok(()) => run_backup_verification_job(),
+            Err(e) => ic_cdk::print(format!("Failed to start backup verification: {}", e)),
+        }
+    });
+}
+
+/// Verifies the backups of the verification in progress a batch at a time, recording the
+/// outcome and moving the cursor after each batch so that an upgrade can resume it
+pub fn run_backup_verification_job() {
+    let Some(in_progress_guard) = InProgressGuard::acquire(&IS_BACKUP_VERIFICATION_JOB_RUNNING)
+    else {
+        return;
+    };
+
+    ic_cdk::spawn(async move {
+        let _in_progress_guard = in_progress_guard;
+        verify_backups_in_batches().await;
+    });
+}
+
+async fn verify_backups_in_batches() {
+    loop {
+        let batch = CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow()
+                .get_next_backup_verification_batch(BACKUP_VERIFICATION_BATCH_SIZE)
+        });
+
+        let Some(last_user_principal_id) = batch.last().map(|task| task.user_principal_id) else {
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow_mut()
+                    .complete_backup_verification(system_time::get_current_system_time_from_ic())
+            });
+            return;
+        };
+
+        let result_callback = |(task, live_canister_digests): (
+            BackupVerificationTask,
+            Result<BackupSectionDigests, String>,
+        )| {
+            if let Err(e) = &live_canister_digests {
+                ic_cdk::print(format!(
+                    "Failed to get backup section digests of canister {}: {}",
+                    task.user_canister_id.to_text(),
+                    e
+                ));
+            }
+
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow_mut()
+                    .record_backup_verification_result(
+                        &task,
+                        live_canister_digests,
+                        system_time::get_current_system_time_from_ic(),
+                    )
+            });
+        };
+
+        run_task_concurrently(
+            batch.into_iter().map(get_live_canister_digests),
+            MAX_CONCURRENT_BACKUP_VERIFICATIONS,
+            result_callback,
+            || false,
+        )
+        .await;
+
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .advance_backup_verification_cursor(last_user_principal_id)
+        });
+    }
+}
+
+async fn get_live_canister_digests(
+    task: BackupVerificationTask,
+) -> (BackupVerificationTask, Result<BackupSectionDigests, String>) {
+    let response: call::CallResult<(BackupSectionDigests,)> =
+        call::call(task.user_canister_id, "get_backup_section_digests", ()).await;
+
+    let live_canister_digests = response
+        .map(|(live_canister_digests,)| live_canister_digests)
+        .map_err(|e| e.1);
+
+    (task, live_canister_digests)
+}
@@ -0,0 +1,4 @@
+pub mod backup_coverage_job;
+pub mod backup_snapshot_job;
+pub mod backup_verification_job;
+pub mod restore_run_job;
@@ -0,0 +1,191 @@
+use std::cell::Cell;
+
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::{
+        data_backup::types::all_user_data::AllUserData,
+        individual_user_template::types::profile::UserProfileDetailsForFrontend,
+    },
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{
+            system_time,
+            task::{run_task_concurrently, InProgressGuard},
+        },
+    },
+};
+
+use crate::{
+    api::individual_user_backup::restore_backed_up_data_to_individual_users_canister::send_all_backed_up_data_to_users_canister,
+    data::restore_run::check_restore_target, CANISTER_DATA,
+};
+
+const RESTORE_RUN_BATCH_SIZE: usize = 50;
+
+thread_local! {
+    static IS_RESTORE_RUN_JOB_RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Restores the users of the run in progress a batch at a time, recording the outcome of each
+/// user and moving the cursor after each batch so that an upgrade can resume it
+pub fn run_restore_run_job() {
+    let Some(in_progress_guard) = InProgressGuard::acquire(&IS_RESTORE_RUN_JOB_RUNNING) else {
+        return;
+    };
+
+    ic_cdk::spawn(async move {
+        let _in_progress_guard = in_progress_guard;
+        restore_users_in_batches().await;
+    });
+}
+
+async fn restore_users_in_batches() {
+    loop {
+        let (batch, max_concurrent_restores) = CANISTER_DATA.with(|canister_data_ref_cell| {
+            let canister_data = canister_data_ref_cell.borrow();
+            (
+                canister_data.get_next_restore_run_batch(RESTORE_RUN_BATCH_SIZE),
+                canister_data
+                    .heap_data
+                    .restore_run
+                    .as_ref()
+                    .map_or(1, |report| report.max_concurrent_restores),
+            )
+        });
+
+        let Some(last_user_principal_id) = batch.last().copied() else {
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow_mut()
+                    .complete_restore_run(system_time::get_current_system_time_from_ic())
+            });
+            return;
+        };
+
+        let result_callback = |(user_principal_id, user_canister_id, result): (
+            Principal,
+            Option<Principal>,
+            Result<(), String>,
+        )| {
+            if let Err(e) = &result {
+                ic_cdk::print(format!(
+                    "Failed to restore user {}: {}",
+                    user_principal_id.to_text(),
+                    e
+                ));
+            }
+
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow_mut()
+                    .record_user_restore_result(
+                        user_principal_id,
+                        user_canister_id,
+                        result,
+                        system_time::get_current_system_time_from_ic(),
+                    )
+            });
+        };
+
+        run_task_concurrently(
+            batch.into_iter().map(restore_user),
+            max_concurrent_restores as usize,
+            result_callback,
+            || false,
+        )
+        .await;
+
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .advance_restore_run_cursor(last_user_principal_id)
+        });
+    }
+}
+
+/// Sends the user's backed up data to their canister, once it is clear the canister is still
+/// theirs, then records the canister and username of the user in the user index
+async fn restore_user(
+    user_principal_id: Principal,
+) -> (Principal, Option<Principal>, Result<(), String>) {
+    let users_data = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .prepare_user_restore(&user_principal_id)
+    });
+
+    let users_data = match users_data {
+        Ok(users_data) => users_data,
+        Err(e) => return (user_principal_id, None, Err(e)),
+    };
+
+    let user_canister_id = users_data.user_canister_id;
+    let result = restore_user_data(&users_data).await;
+
+    (user_principal_id, Some(user_canister_id), result)
+}
+
+async fn restore_user_data(users_data: &AllUserData) -> Result<(), String> {
+    let user_index_canister_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .heap_data
+            .known_principal_ids
+            .get(&KnownPrincipalType::CanisterIdUserIndex)
+            .copied()
+            .ok_or("User index canister not found in internal records")
+    })?;
+
+    // * the canister may have been recycled for another user since the backup was taken
+    let (user_index_user_canister_id,): (Option<Principal>,) = call::call(
+        user_index_canister_id,
+        "get_user_canister_id_from_user_principal_id",
+        (users_data.user_principal_id,),
+    )
+    .await
+    .map_err(|e| {
+        format!(
+            "Failed to ask the user index for the user's canister: {}",
+            e.1
+        )
+    })?;
+    let canister_profile_owner = match user_index_user_canister_id {
+        Some(_) => None,
+        None => {
+            let (profile,): (UserProfileDetailsForFrontend,) =
+                call::call(users_data.user_canister_id, "get_profile_details", ())
+                    .await
+                    .map_err(|e| {
+                        format!("Failed to read the profile of the user's canister: {}", e.1)
+                    })?;
+            Some(profile.principal_id)
+        }
+    };
+    check_restore_target(
+        users_data,
+        user_index_user_canister_id,
+        canister_profile_owner,
+    )?;
+
+    send_all_backed_up_data_to_users_canister(users_data).await?;
+
+    call::call::<_, ()>(
+        user_index_canister_id,
+        "receive_data_from_backup_canister_and_restore_data_to_heap",
+        (
+            users_data.user_principal_id,
+            users_data.user_canister_id,
+            users_data
+                .canister_data
+                .profile
+                .unique_user_name
+                .clone()
+                .unwrap_or_default(),
+        ),
+    )
+    .await
+    .map_err(|e| format!("Failed to update the user index: {}", e.1))?;
+
+    Ok(())
+}
@@ -1,8 +1,20 @@
-use candid::Principal;
-use ic_cdk::api::call;
-use shared_utils::common::types::known_principal::KnownPrincipalType;
+use std::collections::BTreeMap;
 
-use crate::CANISTER_DATA;
+use candid::{utils::ArgumentEncoder, Principal};
+use ic_cdk::api::call::{self, CallResult};
+use shared_utils::{
+    canister_specific::data_backup::types::{
+        backup_manifest::BackupSection,
+        backup_section_digests::{
+            get_follow_data_digest, get_posts_digest, get_profile_digest, get_token_data_digest,
+            BackupSectionDigests,
+        },
+        individual_user_canister_data_section::IndividualUserCanisterDataSection,
+    },
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
 
 #[ic_cdk::update]
 #[candid::candid_method(update)]
@@ -56,6 +68,15 @@ async fn backup_data_to_backup_canister(
         return;
     }
 
+    // * the account is being deleted, and its backups are about to be purged
+    if CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .is_frozen_for_account_deletion
+    }) {
+        return;
+    }
+
     let data_backup_canister_id = CANISTER_DATA.with(|canister_data_ref_cell| {
         *canister_data_ref_cell
             .borrow()
@@ -69,96 +90,397 @@ async fn backup_data_to_backup_canister(
         canister_id
     ));
 
-    send_profile_data(
+    let backup_epoch = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .backup_change_tracker
+            .get_epoch()
+    });
+
+    // * a reinstalled canister is in a new epoch, so it gets no acknowledgement and sends everything
+    let since = get_last_acknowledged_change_sequence_number(
+        &data_backup_canister_id,
+        &canister_owner_principal_id,
+        backup_epoch,
+    )
+    .await;
+
+    let change_sequence_number = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        if let Some(since) = since {
+            canister_data
+                .backup_change_tracker
+                .forget_deletions_acknowledged_by_backup(since);
+        }
+
+        canister_data
+            .backup_change_tracker
+            .get_last_change_sequence_number()
+    });
+
+    // * each digest is computed in the message that reads its section, so that it matches the data
+    // * sent
+    let profile_digest = send_profile_data(
         &data_backup_canister_id,
         &canister_owner_principal_id,
         &canister_id,
+        since,
+    )
+    .await;
+    let posts_digest = send_created_posts(
+        &data_backup_canister_id,
+        &canister_owner_principal_id,
+        since,
+    )
+    .await;
+    let token_data_digest = send_token_data(
+        &data_backup_canister_id,
+        &canister_owner_principal_id,
+        since,
     )
     .await;
-    send_all_created_posts(&data_backup_canister_id, &canister_owner_principal_id).await;
-    send_all_token_data(&data_backup_canister_id, &canister_owner_principal_id).await;
     send_all_follower_following_data(&data_backup_canister_id, &canister_owner_principal_id).await;
+    let follow_data_digest = send_canister_data_sections(
+        &data_backup_canister_id,
+        &canister_owner_principal_id,
+        since,
+    )
+    .await;
+
+    let section_digests = BackupSectionDigests {
+        digests: BTreeMap::from([
+            (BackupSection::Profile, profile_digest),
+            (BackupSection::Posts, posts_digest),
+            (BackupSection::TokenData, token_data_digest),
+            (BackupSection::FollowData, follow_data_digest),
+        ]),
+    };
+    send_backup_section_digests(
+        &data_backup_canister_id,
+        &canister_owner_principal_id,
+        section_digests,
+    )
+    .await;
+
+    send_to_data_backup_canister(
+        &data_backup_canister_id,
+        "acknowledge_backup_change_sequence_number",
+        (
+            canister_owner_principal_id,
+            backup_epoch,
+            change_sequence_number,
+        ),
+    )
+    .await;
 }
 
 const CHUNK_SIZE: usize = 10;
+const DELETED_ITEMS_CHUNK_SIZE: usize = 1000;
 
-async fn send_profile_data(
+/// Whether the section changed after `since`. Everything counts as changed when `since` is `None`.
+fn has_section_changed_since(
+    canister_data: &CanisterData,
+    section: BackupSection,
+    since: Option<u64>,
+) -> bool {
+    match since {
+        Some(since) => canister_data
+            .backup_change_tracker
+            .has_section_changed_since(section, since),
+        None => true,
+    }
+}
+
+/// Calls a data_backup endpoint that stores part of the backup. Traps when it fails, so that the
+/// backup run is not acknowledged and the next run sends the data again.
+async fn send_to_data_backup_canister<T: ArgumentEncoder>(
+    data_backup_canister_id: &Principal,
+    method: &str,
+    args: T,
+) {
+    call::call::<_, (Result<(), String>,)>(*data_backup_canister_id, method, args)
+        .await
+        .map_err(|e| e.1)
+        .and_then(|(result,)| result)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Failed to call the {} method on the data_backup canister: {}",
+                method, e
+            )
+        });
+}
+
+/// Failing to send the digests only leaves the backup unverifiable, so it does not fail the run
+async fn send_backup_section_digests(
     data_backup_canister_id: &Principal,
     canister_owner_principal_id: &Principal,
-    canister_id: &Principal,
+    section_digests: BackupSectionDigests,
 ) {
-    let profile_data = CANISTER_DATA
-        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().profile.clone());
+    let response: CallResult<()> = call::call(
+        *data_backup_canister_id,
+        "receive_backup_section_digests_from_individual_user_canister",
+        (section_digests, *canister_owner_principal_id),
+    )
+    .await;
 
-    if profile_data.display_name.is_none() && profile_data.profile_picture_url.is_none() {
-        return;
+    if let Err(e) = response {
+        ic_cdk::print(format!("Failed to send backup section digests: {}", e.1));
     }
+}
+
+/// `None` when data_backup has no complete backup of this epoch to build on
+async fn get_last_acknowledged_change_sequence_number(
+    data_backup_canister_id: &Principal,
+    canister_owner_principal_id: &Principal,
+    backup_epoch: u64,
+) -> Option<u64> {
+    let response: CallResult<(Option<u64>,)> = call::call(
+        *data_backup_canister_id,
+        "get_last_acknowledged_backup_change_sequence_number",
+        (*canister_owner_principal_id, backup_epoch),
+    )
+    .await;
+
+    response.ok().and_then(|(acknowledged,)| acknowledged)
+}
 
-    let _: () = call::call(
-            *data_backup_canister_id,
-            "receive_profile_details_from_individual_user_canister",
-            (profile_data, *canister_owner_principal_id, *canister_id),
+async fn send_canister_data_sections(
+    data_backup_canister_id: &Principal,
+    canister_owner_principal_id: &Principal,
+    since: Option<u64>,
+) -> Vec<u8> {
+    let (canister_data_sections, follow_data_digest) =
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            let canister_data = canister_data_ref_cell.borrow();
+
+            (
+                get_canister_data_sections_to_back_up(&canister_data, since),
+                get_follow_data_digest(&canister_data.follow_data),
+            )
+        });
+
+    for canister_data_section in canister_data_sections {
+        send_to_data_backup_canister(
+            data_backup_canister_id,
+            "receive_canister_data_section_from_individual_user_canister",
+            (canister_data_section, *canister_owner_principal_id),
         )
-        .await
-        .expect("Failed to call the receive_profile_details_from_individual_user_canister method on the data_backup canister");
+        .await;
+    }
+
+    follow_data_digest
+}
+
+/// The state not covered by the dedicated backup endpoints that changed after `since`, with the
+/// bets placed split into chunks
+fn get_canister_data_sections_to_back_up(
+    canister_data: &CanisterData,
+    since: Option<u64>,
+) -> Vec<IndividualUserCanisterDataSection> {
+    let changed_hot_or_not_bets_placed = match since {
+        Some(since) => canister_data
+            .backup_change_tracker
+            .get_hot_or_not_bets_placed_changed_since(since),
+        None => canister_data
+            .all_hot_or_not_bets_placed
+            .keys()
+            .copied()
+            .collect(),
+    }
+    .into_iter()
+    .filter_map(|bet_key| {
+        canister_data
+            .all_hot_or_not_bets_placed
+            .get(&bet_key)
+            .map(|placed_bet_detail| (bet_key, placed_bet_detail.clone()))
+    })
+    .collect::<Vec<_>>();
+
+    let canister_data_sections = [
+        IndividualUserCanisterDataSection::FollowData(canister_data.follow_data.clone()),
+        IndividualUserCanisterDataSection::Configuration(canister_data.configuration.clone()),
+        IndividualUserCanisterDataSection::KnownPrincipalIds(
+            canister_data.known_principal_ids.clone(),
+        ),
+        IndividualUserCanisterDataSection::VersionDetails(canister_data.version_details.clone()),
+        IndividualUserCanisterDataSection::PostsIndexSortedByHomeFeedScore(
+            canister_data.posts_index_sorted_by_home_feed_score.clone(),
+        ),
+        IndividualUserCanisterDataSection::PostsIndexSortedByHotOrNotFeedScore(
+            canister_data
+                .posts_index_sorted_by_hot_or_not_feed_score
+                .clone(),
+        ),
+        IndividualUserCanisterDataSection::PostScoreSyncQueue(
+            canister_data.post_score_sync_queue.clone(),
+        ),
+        IndividualUserCanisterDataSection::LastAccessTime(canister_data.last_access_time),
+    ]
+    .into_iter()
+    .filter(|canister_data_section| {
+        has_section_changed_since(
+            canister_data,
+            canister_data_section.get_backup_section(),
+            since,
+        )
+    });
+
+    changed_hot_or_not_bets_placed
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| IndividualUserCanisterDataSection::HotOrNotBetsPlaced(chunk.to_vec()))
+        .chain(canister_data_sections)
+        .collect()
 }
 
-async fn send_all_created_posts(
+async fn send_profile_data(
     data_backup_canister_id: &Principal,
     canister_owner_principal_id: &Principal,
-) {
-    let all_created_posts = CANISTER_DATA
-        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().all_created_posts.clone());
+    canister_id: &Principal,
+    since: Option<u64>,
+) -> Vec<u8> {
+    let (profile_data, profile_digest) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
+
+        (
+            has_section_changed_since(&canister_data, BackupSection::Profile, since)
+                .then(|| canister_data.profile.clone()),
+            get_profile_digest(&canister_data.profile),
+        )
+    });
+
+    let Some(profile_data) = profile_data else {
+        return profile_digest;
+    };
+
+    send_to_data_backup_canister(
+        data_backup_canister_id,
+        "receive_profile_details_from_individual_user_canister",
+        (profile_data, *canister_owner_principal_id, *canister_id),
+    )
+    .await;
+
+    profile_digest
+}
+
+async fn send_created_posts(
+    data_backup_canister_id: &Principal,
+    canister_owner_principal_id: &Principal,
+    since: Option<u64>,
+) -> Vec<u8> {
+    let (changed_posts_vec, posts_digest) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
 
-    let all_created_posts_vec = all_created_posts.values().cloned().collect::<Vec<_>>();
+        let changed_posts_vec = match since {
+            Some(since) => canister_data
+                .backup_change_tracker
+                .get_posts_changed_since(since),
+            None => canister_data.all_created_posts.keys().copied().collect(),
+        }
+        .into_iter()
+        .filter_map(|post_id| canister_data.all_created_posts.get(&post_id).cloned())
+        .collect::<Vec<_>>();
+
+        (
+            changed_posts_vec,
+            get_posts_digest(&canister_data.all_created_posts),
+        )
+    });
 
-    let all_created_posts_chunks = all_created_posts_vec.chunks(CHUNK_SIZE).collect::<Vec<_>>();
+    let changed_posts_chunks = changed_posts_vec.chunks(CHUNK_SIZE).collect::<Vec<_>>();
 
-    for chunk in all_created_posts_chunks {
-        let _: () = call::call(
-            *data_backup_canister_id,
+    for chunk in changed_posts_chunks {
+        send_to_data_backup_canister(
+            data_backup_canister_id,
             "receive_all_user_posts_from_individual_user_canister",
             (chunk.to_vec(), *canister_owner_principal_id),
         )
-        .await
-        .expect("Failed to call the receive_all_user_posts_from_individual_user_canister method on the data_backup canister");
+        .await;
     }
+
+    posts_digest
 }
 
-async fn send_all_token_data(
+async fn send_token_data(
     data_backup_canister_id: &Principal,
     canister_owner_principal_id: &Principal,
-) {
-    let token_data = CANISTER_DATA
-        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().my_token_balance.clone());
+    since: Option<u64>,
+) -> Vec<u8> {
+    let (
+        utility_token_balance,
+        changed_token_transactions,
+        deleted_token_transaction_ids,
+        token_data_digest,
+    ) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
+        let backup_change_tracker = &canister_data.backup_change_tracker;
+        let token_data = &canister_data.my_token_balance;
 
-    let _: () = call::call(
-            *data_backup_canister_id,
-            "receive_current_token_balance_from_individual_user_canister",
-            (token_data.utility_token_balance, *canister_owner_principal_id),
+        let (changed_token_transaction_ids, deleted_token_transaction_ids) = match since {
+            Some(since) => (
+                backup_change_tracker.get_token_events_changed_since(since),
+                backup_change_tracker.get_token_events_deleted_since(since),
+            ),
+            None => (
+                token_data
+                    .utility_token_transaction_history
+                    .keys()
+                    .copied()
+                    .collect(),
+                vec![],
+            ),
+        };
+
+        (
+            has_section_changed_since(&canister_data, BackupSection::TokenData, since)
+                .then_some(token_data.utility_token_balance),
+            changed_token_transaction_ids
+                .into_iter()
+                .filter_map(|token_transaction_id| {
+                    token_data
+                        .utility_token_transaction_history
+                        .get(&token_transaction_id)
+                        .map(|token_event| (token_transaction_id, token_event.clone()))
+                })
+                .collect::<Vec<_>>(),
+            deleted_token_transaction_ids,
+            get_token_data_digest(token_data),
         )
-        .await
-        .expect("Failed to call the receive_current_token_balance_from_individual_user_canister method on the data_backup canister");
+    });
 
-    let all_token_transactions = token_data
-        .utility_token_transaction_history
-        .iter()
-        .map(|(token_transaction_id, token_event)| (*token_transaction_id, (*token_event).clone()))
-        .collect::<Vec<_>>();
+    if let Some(utility_token_balance) = utility_token_balance {
+        send_to_data_backup_canister(
+            data_backup_canister_id,
+            "receive_current_token_balance_from_individual_user_canister",
+            (utility_token_balance, *canister_owner_principal_id),
+        )
+        .await;
+    }
 
-    let all_token_transactions_chunks = all_token_transactions
+    let changed_token_transactions_chunks = changed_token_transactions
         .chunks(CHUNK_SIZE)
         .collect::<Vec<_>>();
 
-    for chunk in all_token_transactions_chunks {
-        let _: () = call::call(
-            *data_backup_canister_id,
+    for chunk in changed_token_transactions_chunks {
+        send_to_data_backup_canister(
+            data_backup_canister_id,
             "receive_all_token_transactions_from_individual_user_canister",
             (chunk.to_vec(), *canister_owner_principal_id),
         )
-        .await
-        .expect("Failed to call the receive_all_token_transactions_from_individual_user_canister method on the data_backup canister");
+        .await;
+    }
+
+    // * events trimmed from the transaction history
+    for chunk in deleted_token_transaction_ids.chunks(DELETED_ITEMS_CHUNK_SIZE) {
+        send_to_data_backup_canister(
+            data_backup_canister_id,
+            "receive_deleted_token_transaction_ids_from_individual_user_canister",
+            (chunk.to_vec(), *canister_owner_principal_id),
+        )
+        .await;
     }
+
+    token_data_digest
 }
 
 async fn send_all_follower_following_data(
@@ -218,3 +540,51 @@ async fn send_all_follower_following_data(
     //     .expect("Failed to call the receive_principals_that_follow_me_from_individual_user_canister method on the data_backup canister");
     // }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use test_utils::setup::test_constants::get_mock_placed_bet_detail;
+
+    use super::*;
+
+    #[test]
+    fn test_get_canister_data_sections_to_back_up() {
+        let mut canister_data = CanisterData::default();
+        assert_eq!(
+            get_canister_data_sections_to_back_up(&canister_data, None).len(),
+            8
+        );
+
+        let since = Some(
+            canister_data
+                .backup_change_tracker
+                .get_last_change_sequence_number(),
+        );
+        assert!(get_canister_data_sections_to_back_up(&canister_data, since).is_empty());
+
+        for post_id in 0..CHUNK_SIZE as u64 + 1 {
+            let (bet_key, placed_bet_detail) = get_mock_placed_bet_detail(post_id);
+            canister_data
+                .all_hot_or_not_bets_placed
+                .insert(bet_key, placed_bet_detail);
+            canister_data
+                .backup_change_tracker
+                .record_hot_or_not_bet_placed_change(bet_key);
+        }
+        canister_data.set_last_access_time(Some(SystemTime::UNIX_EPOCH));
+
+        // * only the bets and the last access time changed
+        let canister_data_sections = get_canister_data_sections_to_back_up(&canister_data, since);
+        assert_eq!(canister_data_sections.len(), 3);
+        assert!(matches!(
+            &canister_data_sections[1],
+            IndividualUserCanisterDataSection::HotOrNotBetsPlaced(chunk) if chunk.len() == 1
+        ));
+        assert!(matches!(
+            canister_data_sections[2],
+            IndividualUserCanisterDataSection::LastAccessTime(Some(_))
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Empties the collections a restore then fills item by item, so that the restore replaces them
+/// instead of adding to them. The other sections are replaced as a whole when they are restored.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn clear_backed_up_sections_from_data_backup_canister(sections: Vec<BackupSection>) {
+    let caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        clear_backed_up_sections_from_data_backup_canister_impl(
+            caller,
+            sections,
+            &mut canister_data_ref_cell.borrow_mut(),
+        );
+    });
+}
+
+fn clear_backed_up_sections_from_data_backup_canister_impl(
+    caller: Principal,
+    sections: Vec<BackupSection>,
+    canister_data: &mut CanisterData,
+) {
+    let data_backup_canister_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdDataBackup);
+
+    if data_backup_canister_id != Some(&caller) {
+        return;
+    }
+
+    for section in sections {
+        match section {
+            BackupSection::Posts => canister_data.all_created_posts.clear(),
+            BackupSection::TokenData => canister_data
+                .my_token_balance
+                .utility_token_transaction_history
+                .clear(),
+            BackupSection::PrincipalsIFollow => canister_data.principals_i_follow.clear(),
+            BackupSection::PrincipalsThatFollowMe => {
+                canister_data.principals_that_follow_me.clear()
+            }
+            BackupSection::HotOrNotBetsPlaced => canister_data.all_hot_or_not_bets_placed.clear(),
+            _ => continue,
+        }
+
+        canister_data
+            .backup_change_tracker
+            .record_section_change(section);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_data_backup, get_mock_placed_bet_detail,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_clear_backed_up_sections_from_data_backup_canister_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdDataBackup,
+            get_mock_canister_id_data_backup(),
+        );
+        canister_data
+            .principals_that_follow_me
+            .insert(get_mock_user_alice_principal_id());
+        canister_data
+            .principals_i_follow
+            .insert(get_mock_user_alice_principal_id());
+        let (bet_key, placed_bet_detail) = get_mock_placed_bet_detail(0);
+        canister_data
+            .all_hot_or_not_bets_placed
+            .insert(bet_key, placed_bet_detail);
+
+        clear_backed_up_sections_from_data_backup_canister_impl(
+            get_mock_user_alice_principal_id(),
+            vec![BackupSection::PrincipalsThatFollowMe],
+            &mut canister_data,
+        );
+        assert_eq!(canister_data.principals_that_follow_me.len(), 1);
+
+        clear_backed_up_sections_from_data_backup_canister_impl(
+            get_mock_canister_id_data_backup(),
+            vec![
+                BackupSection::PrincipalsThatFollowMe,
+                BackupSection::PrincipalsIFollow,
+            ],
+            &mut canister_data,
+        );
+        assert!(canister_data.principals_that_follow_me.is_empty());
+        assert!(canister_data.principals_i_follow.is_empty());
+        assert_eq!(canister_data.all_hot_or_not_bets_placed.len(), 1);
+        assert!(canister_data
+            .backup_change_tracker
+            .has_section_changed_since(BackupSection::PrincipalsIFollow, 0));
+    }
+}
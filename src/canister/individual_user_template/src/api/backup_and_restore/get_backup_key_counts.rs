@@ -0,0 +1,60 @@
+use shared_utils::canister_specific::data_backup::types::backup_key_counts::BackupKeyCounts;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Compared against the counts of the backup in data_backup before and after upgrades
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_backup_key_counts() -> BackupKeyCounts {
+    CANISTER_DATA
+        .with(|canister_data_ref_cell| get_backup_key_counts_impl(&canister_data_ref_cell.borrow()))
+}
+
+fn get_backup_key_counts_impl(canister_data: &CanisterData) -> BackupKeyCounts {
+    BackupKeyCounts {
+        post_count: canister_data.all_created_posts.len() as u64,
+        token_event_count: canister_data
+            .my_token_balance
+            .utility_token_transaction_history
+            .len() as u64,
+        hot_or_not_bet_count: canister_data.all_hot_or_not_bets_placed.len() as u64,
+        following_count: canister_data.follow_data.following.sorted_index.len() as u64,
+        follower_count: canister_data.follow_data.follower.sorted_index.len() as u64,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::common::types::utility_token::token_event::{MintEvent, TokenEvent};
+    use test_utils::setup::test_constants::get_mock_user_alice_principal_id;
+
+    use super::*;
+
+    #[test]
+    fn test_get_backup_key_counts_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .my_token_balance
+            .utility_token_transaction_history
+            .insert(
+                0,
+                TokenEvent::Mint {
+                    amount: 1000,
+                    details: MintEvent::NewUserSignup {
+                        new_user_principal_id: get_mock_user_alice_principal_id(),
+                    },
+                    timestamp: SystemTime::now(),
+                },
+            );
+
+        assert_eq!(
+            get_backup_key_counts_impl(&canister_data),
+            BackupKeyCounts {
+                token_event_count: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use shared_utils::canister_specific::data_backup::types::backup_section_digests::BackupSectionDigests;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Compared by data_backup against the digests of the stored backup to verify it
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_backup_section_digests() -> BackupSectionDigests {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_backup_section_digests_impl(&canister_data_ref_cell.borrow())
+    })
+}
+
+fn get_backup_section_digests_impl(canister_data: &CanisterData) -> BackupSectionDigests {
+    BackupSectionDigests::new(
+        &canister_data.profile,
+        &canister_data.all_created_posts,
+        &canister_data.my_token_balance,
+        &canister_data.follow_data,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::data_backup::types::all_user_data::UserOwnedCanisterData;
+
+    use super::*;
+
+    #[test]
+    fn test_get_backup_section_digests_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.my_token_balance.utility_token_balance = 1000;
+        canister_data.my_token_balance.lifetime_earnings = 1000;
+
+        let mut backed_up_data = UserOwnedCanisterData::default();
+        backed_up_data.token_data.utility_token_balance = 1000;
+
+        assert_eq!(
+            get_backup_section_digests_impl(&canister_data),
+            BackupSectionDigests::from(&backed_up_data)
+        );
+    }
+}
@@ -1,4 +1,8 @@
 pub mod backup_data_to_backup_canister;
+pub mod clear_backed_up_sections_from_data_backup_canister;
+pub mod get_backup_key_counts;
+pub mod get_backup_section_digests;
+pub mod receive_canister_data_section_from_data_backup_canister;
 pub mod receive_my_created_posts_from_data_backup_canister;
 pub mod receive_my_profile_from_data_backup_canister;
 pub mod receive_my_utility_token_balance_from_data_backup_canister;
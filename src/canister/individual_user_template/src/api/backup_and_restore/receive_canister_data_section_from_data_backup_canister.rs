@@ -0,0 +1,169 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::individual_user_canister_data_section::IndividualUserCanisterDataSection,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn receive_canister_data_section_from_data_backup_canister(
+    canister_data_section: IndividualUserCanisterDataSection,
+) {
+    let caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        receive_canister_data_section_from_data_backup_canister_impl(
+            caller,
+            canister_data_section,
+            &mut canister_data_ref_cell.borrow_mut(),
+        );
+    });
+}
+
+fn receive_canister_data_section_from_data_backup_canister_impl(
+    caller: Principal,
+    canister_data_section: IndividualUserCanisterDataSection,
+    canister_data: &mut CanisterData,
+) {
+    let data_backup_canister_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdDataBackup);
+
+    if data_backup_canister_id != Some(&caller) {
+        return;
+    }
+
+    let section = canister_data_section.get_backup_section();
+
+    match canister_data_section {
+        IndividualUserCanisterDataSection::HotOrNotBetsPlaced(bets_placed_chunk) => {
+            for (bet_key, placed_bet_detail) in bets_placed_chunk {
+                canister_data
+                    .all_hot_or_not_bets_placed
+                    .insert(bet_key, placed_bet_detail);
+                canister_data
+                    .backup_change_tracker
+                    .record_hot_or_not_bet_placed_change(bet_key);
+            }
+            return;
+        }
+        IndividualUserCanisterDataSection::FollowData(follow_data) => {
+            canister_data.follow_data = follow_data;
+        }
+        IndividualUserCanisterDataSection::Configuration(configuration) => {
+            canister_data.configuration = configuration;
+        }
+        IndividualUserCanisterDataSection::PostsIndexSortedByHomeFeedScore(post_score_index) => {
+            canister_data.posts_index_sorted_by_home_feed_score = post_score_index;
+        }
+        IndividualUserCanisterDataSection::PostsIndexSortedByHotOrNotFeedScore(
+            post_score_index,
+        ) => {
+            canister_data.posts_index_sorted_by_hot_or_not_feed_score = post_score_index;
+        }
+        IndividualUserCanisterDataSection::PostScoreSyncQueue(mut post_score_sync_queue) => {
+            // * no flush is scheduled in this canister yet, the next score update schedules one
+            post_score_sync_queue.is_flush_scheduled = false;
+            canister_data.post_score_sync_queue = post_score_sync_queue;
+        }
+        IndividualUserCanisterDataSection::LastAccessTime(last_access_time) => {
+            canister_data.last_access_time = last_access_time;
+        }
+        // * these describe the installation the canister got, not the user
+        IndividualUserCanisterDataSection::KnownPrincipalIds(_)
+        | IndividualUserCanisterDataSection::VersionDetails(_) => return,
+    }
+
+    canister_data
+        .backup_change_tracker
+        .record_section_change(section);
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::{
+        canister_specific::individual_user_template::types::{
+            configuration::IndividualUserConfiguration,
+            follow::{FollowData, FollowEntryDetail},
+        },
+        common::types::known_principal::KnownPrincipalMap,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_data_backup, get_mock_canister_id_user_index,
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_receive_canister_data_section_from_data_backup_canister_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdDataBackup,
+            get_mock_canister_id_data_backup(),
+        );
+        let configuration = IndividualUserConfiguration {
+            url_to_send_canister_metrics_to: Some("https://metrics.example".to_string()),
+        };
+
+        receive_canister_data_section_from_data_backup_canister_impl(
+            get_mock_canister_id_user_index(),
+            IndividualUserCanisterDataSection::Configuration(configuration.clone()),
+            &mut canister_data,
+        );
+        assert_eq!(
+            canister_data.configuration.url_to_send_canister_metrics_to,
+            None
+        );
+
+        receive_canister_data_section_from_data_backup_canister_impl(
+            get_mock_canister_id_data_backup(),
+            IndividualUserCanisterDataSection::Configuration(configuration),
+            &mut canister_data,
+        );
+        assert_eq!(
+            canister_data.configuration.url_to_send_canister_metrics_to,
+            Some("https://metrics.example".to_string())
+        );
+
+        receive_canister_data_section_from_data_backup_canister_impl(
+            get_mock_canister_id_data_backup(),
+            IndividualUserCanisterDataSection::LastAccessTime(Some(SystemTime::UNIX_EPOCH)),
+            &mut canister_data,
+        );
+        assert_eq!(canister_data.last_access_time, Some(SystemTime::UNIX_EPOCH));
+
+        // * the canister keeps the known principals it was installed with
+        receive_canister_data_section_from_data_backup_canister_impl(
+            get_mock_canister_id_data_backup(),
+            IndividualUserCanisterDataSection::KnownPrincipalIds(KnownPrincipalMap::default()),
+            &mut canister_data,
+        );
+        assert_eq!(
+            canister_data
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdDataBackup),
+            Some(&get_mock_canister_id_data_backup())
+        );
+
+        let follow_entry_detail = FollowEntryDetail {
+            principal_id: get_mock_user_alice_principal_id(),
+            canister_id: get_mock_user_alice_canister_id(),
+        };
+        let mut follow_data = FollowData::default();
+        follow_data.follower.add(follow_entry_detail.clone());
+        receive_canister_data_section_from_data_backup_canister_impl(
+            get_mock_canister_id_data_backup(),
+            IndividualUserCanisterDataSection::FollowData(follow_data),
+            &mut canister_data,
+        );
+        assert!(canister_data
+            .follow_data
+            .follower
+            .contains(&follow_entry_detail));
+    }
+}
@@ -26,6 +26,9 @@ fn receive_my_created_posts_from_data_backup_canister(all_posts_chunk_vec: Vec<P
         let mut canister_data = canister_data_ref_cell.borrow_mut();
 
         for post in all_posts_chunk_vec {
+            canister_data
+                .backup_change_tracker
+                .record_post_change(post.id);
             canister_data.all_created_posts.insert(post.id, post);
         }
     });
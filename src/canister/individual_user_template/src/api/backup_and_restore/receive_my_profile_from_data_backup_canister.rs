@@ -1,5 +1,8 @@
 use shared_utils::{
-    canister_specific::individual_user_template::types::profile::UserProfile,
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::profile::UserProfile,
+    },
     common::types::known_principal::KnownPrincipalType,
 };
 
@@ -23,6 +26,10 @@ fn receive_my_profile_from_data_backup_canister(profile: UserProfile) {
     }
 
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell.borrow_mut().profile = profile;
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.profile = profile;
+        canister_data
+            .backup_change_tracker
+            .record_section_change(BackupSection::Profile);
     });
 }
@@ -1,4 +1,7 @@
-use shared_utils::common::types::known_principal::KnownPrincipalType;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::types::known_principal::KnownPrincipalType,
+};
 
 use crate::CANISTER_DATA;
 
@@ -20,9 +23,10 @@ fn receive_my_utility_token_balance_from_data_backup_canister(token_balance: u64
     }
 
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell
-            .borrow_mut()
-            .my_token_balance
-            .utility_token_balance = token_balance;
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.my_token_balance.utility_token_balance = token_balance;
+        canister_data
+            .backup_change_tracker
+            .record_section_change(BackupSection::TokenData);
     });
 }
@@ -31,6 +31,9 @@ fn receive_my_utility_token_transaction_history_from_data_backup_canister(
                 .my_token_balance
                 .utility_token_transaction_history
                 .insert(id, token_event);
+            canister_data
+                .backup_change_tracker
+                .record_token_event_change(id);
         }
     });
 }
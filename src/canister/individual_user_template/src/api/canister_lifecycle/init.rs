@@ -1,7 +1,13 @@
 use crate::{data_model::CanisterData, CANISTER_DATA};
 use shared_utils::{
-    canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs,
-    common::timer::send_metrics::enqueue_timer_for_calling_metrics_rest_api,
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::arg::IndividualUserTemplateInitArgs,
+    },
+    common::{
+        timer::send_metrics::enqueue_timer_for_calling_metrics_rest_api,
+        utils::system_time,
+    },
 };
 
 #[ic_cdk::init]
@@ -10,6 +16,13 @@ fn init(init_args: IndividualUserTemplateInitArgs) {
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let mut data = canister_data_ref_cell.borrow_mut();
         init_impl(init_args, &mut data);
+        let last_access_time = data
+            .profile
+            .principal_id
+            .map(|_| system_time::get_current_system_time_from_ic());
+        data.set_last_access_time(last_access_time);
+        data.backup_change_tracker
+            .start_epoch_if_unset(ic_cdk::api::time());
     });
 
     send_canister_metrics();
@@ -31,6 +44,15 @@ fn init_impl(init_args: IndividualUserTemplateInitArgs, data: &mut CanisterData)
 
     data.version_details.version_number = init_args.upgrade_version_number.unwrap_or_default();
     data.version_details.version = init_args.version;
+
+    [
+        BackupSection::KnownPrincipalIds,
+        BackupSection::Profile,
+        BackupSection::Configuration,
+        BackupSection::VersionDetails,
+    ]
+    .into_iter()
+    .for_each(|section| data.backup_change_tracker.record_section_change(section));
 }
 
 pub fn send_canister_metrics() {
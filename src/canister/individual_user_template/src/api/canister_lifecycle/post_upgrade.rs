@@ -4,11 +4,18 @@ use ic_stable_structures::Memory;
 
 use crate::data_model::memory;
 
-use shared_utils::canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs;
+use shared_utils::{
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::arg::IndividualUserTemplateInitArgs,
+    },
+    common::utils::system_time,
+};
 
 use crate::{
     api::{
         hot_or_not_bet::reenqueue_timers_for_pending_bet_outcomes::reenqueue_timers_for_pending_bet_outcomes,
+        post::update_scores_and_share_with_post_cache_if_difference_beyond_threshold::schedule_post_score_sync_queue_flush,
         well_known_principal::update_locally_stored_well_known_principals,
     },
     CANISTER_DATA,
@@ -19,8 +26,11 @@ use crate::{
 fn post_upgrade() {
     restore_data_from_stable_memory();
     save_upgrade_args_to_memory();
+    start_tracking_last_access_time();
+    start_backup_epoch();
     refetch_well_known_principals();
     reenqueue_timers_for_pending_bet_outcomes();
+    schedule_post_score_sync_queue_flush();
 }
 
 fn restore_data_from_stable_memory() {
@@ -47,10 +57,16 @@ fn save_upgrade_args_to_memory() {
 
         if let Some(known_principal_ids) = upgrade_args.known_principal_ids {
             canister_data_ref_cell.known_principal_ids = known_principal_ids;
+            canister_data_ref_cell
+                .backup_change_tracker
+                .record_section_change(BackupSection::KnownPrincipalIds);
         }
 
         if let Some(profile_owner) = upgrade_args.profile_owner {
             canister_data_ref_cell.profile.principal_id = Some(profile_owner);
+            canister_data_ref_cell
+                .backup_change_tracker
+                .record_section_change(BackupSection::Profile);
         }
 
         if let Some(upgrade_version_number) = upgrade_args.upgrade_version_number {
@@ -58,16 +74,43 @@ fn save_upgrade_args_to_memory() {
         }
 
         canister_data_ref_cell.borrow_mut().version_details.version = upgrade_args.version;
+        canister_data_ref_cell
+            .backup_change_tracker
+            .record_section_change(BackupSection::VersionDetails);
 
         if let Some(url_to_send_canister_metrics_to) = upgrade_args.url_to_send_canister_metrics_to
         {
             canister_data_ref_cell
                 .configuration
                 .url_to_send_canister_metrics_to = Some(url_to_send_canister_metrics_to);
+            canister_data_ref_cell
+                .backup_change_tracker
+                .record_section_change(BackupSection::Configuration);
         }
     });
 }
 
+/// Canisters from before the last access time was tracked start counting from this upgrade
+fn start_tracking_last_access_time() {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        if canister_data.profile.principal_id.is_some() && canister_data.last_access_time.is_none() {
+            canister_data.set_last_access_time(Some(system_time::get_current_system_time_from_ic()));
+        }
+    });
+}
+
+/// Canisters installed before backup epochs existed start theirs on this upgrade
+fn start_backup_epoch() {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .backup_change_tracker
+            .start_epoch_if_unset(ic_cdk::api::time());
+    });
+}
+
 const DELAY_FOR_REFETCHING_WELL_KNOWN_PRINCIPALS: Duration = Duration::from_secs(1);
 fn refetch_well_known_principals() {
     ic_cdk_timers::set_timer(DELAY_FOR_REFETCHING_WELL_KNOWN_PRINCIPALS, || {
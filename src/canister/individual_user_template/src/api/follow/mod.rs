@@ -1,5 +1,7 @@
 pub mod do_i_follow_this_user;
 pub mod get_principals_that_follow_this_profile_paginated;
 pub mod get_principals_this_profile_follows_paginated;
+pub mod remove_deleted_profile_from_follow_lists;
+pub mod remove_this_profile_from_follow_lists_of_others;
 pub mod update_profiles_i_follow_toggle_list_with_specified_profile;
 pub mod update_profiles_that_follow_me_toggle_list_with_specified_profile;
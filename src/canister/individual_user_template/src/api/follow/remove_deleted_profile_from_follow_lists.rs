@@ -0,0 +1,93 @@
+use candid::Principal;
+use shared_utils::canister_specific::{
+    data_backup::types::backup_manifest::BackupSection,
+    individual_user_template::types::follow::FollowEntryDetail,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Removes a profile whose account is being deleted from the followers and followees of this
+/// profile
+///
+/// # Access Control
+/// Only the canister of the profile being deleted can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn remove_deleted_profile_from_follow_lists(
+    deleted_profile: FollowEntryDetail,
+) -> Result<(), String> {
+    let calling_canister_principal = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        remove_deleted_profile_from_follow_lists_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            calling_canister_principal,
+            &deleted_profile,
+        )
+    })
+}
+
+fn remove_deleted_profile_from_follow_lists_impl(
+    canister_data: &mut CanisterData,
+    calling_canister_principal: Principal,
+    deleted_profile: &FollowEntryDetail,
+) -> Result<(), String> {
+    if calling_canister_principal != deleted_profile.canister_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.follow_data.follower.remove(deleted_profile);
+    canister_data.follow_data.following.remove(deleted_profile);
+    canister_data
+        .backup_change_tracker
+        .record_section_change(BackupSection::FollowData);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_remove_deleted_profile_from_follow_lists_impl() {
+        let mut canister_data = CanisterData::default();
+        let alice = FollowEntryDetail {
+            principal_id: get_mock_user_alice_principal_id(),
+            canister_id: get_mock_user_alice_canister_id(),
+        };
+        let bob = FollowEntryDetail {
+            principal_id: get_mock_user_bob_principal_id(),
+            canister_id: get_mock_user_bob_canister_id(),
+        };
+        canister_data.follow_data.follower.add(alice.clone());
+        canister_data.follow_data.following.add(alice.clone());
+        canister_data.follow_data.following.add(bob.clone());
+
+        assert_eq!(
+            remove_deleted_profile_from_follow_lists_impl(
+                &mut canister_data,
+                get_mock_user_bob_canister_id(),
+                &alice,
+            ),
+            Err("Unauthorized".to_string())
+        );
+
+        assert_eq!(
+            remove_deleted_profile_from_follow_lists_impl(
+                &mut canister_data,
+                get_mock_user_alice_canister_id(),
+                &alice,
+            ),
+            Ok(())
+        );
+        assert!(canister_data.follow_data.follower.is_empty());
+        assert!(!canister_data.follow_data.following.contains(&alice));
+        assert!(canister_data.follow_data.following.contains(&bob));
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::follow::FollowEntryDetail,
+    },
+    common::{types::known_principal::KnownPrincipalType, utils::task::run_task_concurrently},
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+const MAX_CONCURRENT_FOLLOW_LIST_UPDATES: usize = 10;
+
+/// Removes this profile from the follow lists of its followers and followees, as part of deleting
+/// its user's account. Profiles whose list was updated are dropped from this profile's lists, so
+/// that a retry only updates the remaining ones.
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+async fn remove_this_profile_from_follow_lists_of_others() -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    let (this_profile, profiles_to_update) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_profiles_to_remove_this_profile_from_impl(
+            &canister_data_ref_cell.borrow(),
+            api_caller,
+            ic_cdk::id(),
+        )
+    })?;
+
+    let update_futures = profiles_to_update.into_iter().map(|profile| {
+        let this_profile = this_profile.clone();
+        async move {
+            let update_result: Result<(Result<(), String>,), _> = call::call(
+                profile.canister_id,
+                "remove_deleted_profile_from_follow_lists",
+                (this_profile,),
+            )
+            .await;
+            (profile, update_result)
+        }
+    });
+
+    let mut failed_update_count = 0;
+    let result_callback = |(profile, update_result): (FollowEntryDetail, _)| match update_result {
+        Ok((Ok(()),)) => CANISTER_DATA.with(|canister_data_ref_cell| {
+            let mut canister_data = canister_data_ref_cell.borrow_mut();
+            canister_data.follow_data.follower.remove(&profile);
+            canister_data.follow_data.following.remove(&profile);
+            canister_data
+                .backup_change_tracker
+                .record_section_change(BackupSection::FollowData);
+        }),
+        Ok((Err(_),)) | Err(_) => failed_update_count += 1,
+    };
+
+    run_task_concurrently(
+        update_futures,
+        MAX_CONCURRENT_FOLLOW_LIST_UPDATES,
+        result_callback,
+        || false,
+    )
+    .await;
+
+    if failed_update_count > 0 {
+        return Err(format!(
+            "Failed to update the follow lists of {} profiles",
+            failed_update_count
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_profiles_to_remove_this_profile_from_impl(
+    canister_data: &CanisterData,
+    api_caller: Principal,
+    this_canister_id: Principal,
+) -> Result<(FollowEntryDetail, Vec<FollowEntryDetail>), String> {
+    let user_index_canister_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if api_caller != *user_index_canister_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    let this_profile = FollowEntryDetail {
+        principal_id: canister_data
+            .profile
+            .principal_id
+            .ok_or("This canister has no owner")?,
+        canister_id: this_canister_id,
+    };
+
+    let profiles_to_update: HashSet<FollowEntryDetail> = canister_data
+        .follow_data
+        .follower
+        .sorted_index
+        .values()
+        .chain(canister_data.follow_data.following.sorted_index.values())
+        .cloned()
+        .collect();
+
+    Ok((this_profile, profiles_to_update.into_iter().collect()))
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_canister_id,
+        get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_profiles_to_remove_this_profile_from_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        assert_eq!(
+            get_profiles_to_remove_this_profile_from_impl(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            get_profiles_to_remove_this_profile_from_impl(
+                &canister_data,
+                get_mock_canister_id_user_index(),
+                get_mock_user_alice_canister_id(),
+            ),
+            Err("This canister has no owner".to_string())
+        );
+
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+        let bob = FollowEntryDetail {
+            principal_id: get_mock_user_bob_principal_id(),
+            canister_id: get_mock_user_bob_canister_id(),
+        };
+        let charlie = FollowEntryDetail {
+            principal_id: get_mock_user_charlie_principal_id(),
+            canister_id: get_mock_user_charlie_canister_id(),
+        };
+        canister_data.follow_data.follower.add(bob.clone());
+        canister_data.follow_data.following.add(bob.clone());
+        canister_data.follow_data.following.add(charlie.clone());
+
+        let (this_profile, profiles_to_update) = get_profiles_to_remove_this_profile_from_impl(
+            &canister_data,
+            get_mock_canister_id_user_index(),
+            get_mock_user_alice_canister_id(),
+        )
+        .unwrap();
+        assert_eq!(
+            this_profile,
+            FollowEntryDetail {
+                principal_id: get_mock_user_alice_principal_id(),
+                canister_id: get_mock_user_alice_canister_id(),
+            }
+        );
+        assert_eq!(
+            profiles_to_update.into_iter().collect::<HashSet<_>>(),
+            HashSet::from([bob, charlie])
+        );
+    }
+}
@@ -1,8 +1,14 @@
 use crate::{data_model::CanisterData, CANISTER_DATA};
 
 use candid::Principal;
-use shared_utils::canister_specific::individual_user_template::types::{
-    arg::FolloweeArg, error::FollowAnotherUserProfileError, follow::FollowEntryDetail,
+use shared_utils::{
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::{
+            arg::FolloweeArg, error::FollowAnotherUserProfileError, follow::FollowEntryDetail,
+        },
+    },
+    common::utils::system_time,
 };
 
 use super::update_profiles_that_follow_me_toggle_list_with_specified_profile::FollowerArg;
@@ -49,8 +55,11 @@ async fn update_profiles_i_follow_toggle_list_with_specified_profile(
     };
 
     CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.set_last_access_time(Some(system_time::get_current_system_time_from_ic()));
+
         add_or_remove_followee_depending_on_follow_status(
-            &mut canister_data_ref_cell.borrow_mut(),
+            &mut canister_data,
             &follow_status,
             &followee_entry_detail,
         )
@@ -72,6 +81,10 @@ fn validate_incoming_request(
         return Err(FollowAnotherUserProfileError::Unauthorized);
     }
 
+    if canister_data.is_frozen() {
+        return Err(FollowAnotherUserProfileError::Unauthorized);
+    }
+
     if canister_data.follow_data.following.len() as u64 > MAX_USERS_IN_FOLLOWER_FOLLOWING_LIST {
         return Err(FollowAnotherUserProfileError::UsersICanFollowListIsFull);
     }
@@ -91,6 +104,9 @@ fn add_or_remove_followee_depending_on_follow_status(
     } else {
         following.remove(followee_entry_detail);
     }
+    canister_data
+        .backup_change_tracker
+        .record_section_change(BackupSection::FollowData);
 
     Ok(())
 }
@@ -122,6 +138,12 @@ mod test {
 
         current_caller = get_mock_user_alice_principal_id();
 
+        canister_data.is_frozen_for_reclamation = true;
+        let result = validate_incoming_request(&canister_data, &current_caller, &my_principal_id);
+
+        assert_eq!(result, Err(FollowAnotherUserProfileError::Unauthorized));
+        canister_data.is_frozen_for_reclamation = false;
+
         (0..MAX_USERS_IN_FOLLOWER_FOLLOWING_LIST).for_each(|id: u64| {
             let follow_entry_detail = FollowEntryDetail {
                 principal_id: Principal::self_authenticating(id.to_ne_bytes()),
@@ -1,6 +1,9 @@
 use candid::{CandidType, Deserialize, Principal};
-use shared_utils::canister_specific::individual_user_template::types::{
-    error::FollowAnotherUserProfileError, follow::FollowEntryDetail,
+use shared_utils::canister_specific::{
+    data_backup::types::backup_manifest::BackupSection,
+    individual_user_template::types::{
+        error::FollowAnotherUserProfileError, follow::FollowEntryDetail,
+    },
 };
 
 use crate::{data_model::CanisterData, CANISTER_DATA};
@@ -42,6 +45,10 @@ fn update_profiles_that_follow_me_toggle_list_with_specified_profile_impl(
         return Err(FollowAnotherUserProfileError::Unauthorized);
     }
 
+    if canister_data.is_frozen() {
+        return Err(FollowAnotherUserProfileError::Unauthorized);
+    }
+
     if canister_data.follow_data.follower.len() as u64 > MAX_USERS_IN_FOLLOWER_FOLLOWING_LIST {
         return Err(FollowAnotherUserProfileError::UserITriedToFollowHasTheirFollowersListFull);
     }
@@ -53,13 +60,18 @@ fn update_profiles_that_follow_me_toggle_list_with_specified_profile_impl(
 
     let follower = &mut canister_data.follow_data.follower;
 
-    if follower.contains(&follow_entry_detail) {
+    let follow_status = if follower.contains(&follow_entry_detail) {
         follower.remove(&follow_entry_detail);
-        Ok(false)
+        false
     } else {
         follower.add(follow_entry_detail);
-        Ok(true)
-    }
+        true
+    };
+    canister_data
+        .backup_change_tracker
+        .record_section_change(BackupSection::FollowData);
+
+    Ok(follow_status)
 }
 
 #[cfg(test)]
@@ -90,6 +102,17 @@ mod test {
         assert_eq!(result, Err(FollowAnotherUserProfileError::Unauthorized));
 
         calling_canister_principal = get_mock_user_alice_canister_id();
+
+        canister_data.is_frozen_for_account_deletion = true;
+        let result = update_profiles_that_follow_me_toggle_list_with_specified_profile_impl(
+            &mut canister_data,
+            &calling_canister_principal,
+            &arg,
+        );
+
+        assert_eq!(result, Err(FollowAnotherUserProfileError::Unauthorized));
+        canister_data.is_frozen_for_account_deletion = false;
+
         (0..MAX_USERS_IN_FOLLOWER_FOLLOWING_LIST).for_each(|id: u64| {
             let follow_entry_detail = FollowEntryDetail {
                 principal_id: Principal::self_authenticating(id.to_ne_bytes()),
@@ -58,9 +58,9 @@ async fn bet_on_currently_viewing_post(
         } => {
             CANISTER_DATA.with(|canister_data_ref_cell| {
                 let canister_data = &mut canister_data_ref_cell.borrow_mut();
+                canister_data.set_last_access_time(Some(current_time));
 
-                let my_token_balance = &mut canister_data.my_token_balance;
-                my_token_balance.handle_token_event(TokenEvent::Stake {
+                canister_data.handle_token_event(TokenEvent::Stake {
                     amount: place_bet_arg.bet_amount,
                     details: StakeEvent::BetOnHotOrNotPost {
                         post_canister_id: place_bet_arg.post_canister_id,
@@ -85,6 +85,12 @@ async fn bet_on_currently_viewing_post(
                         outcome_received: BetOutcomeForBetMaker::default(),
                     },
                 );
+                canister_data
+                    .backup_change_tracker
+                    .record_hot_or_not_bet_placed_change((
+                        place_bet_arg.post_canister_id,
+                        place_bet_arg.post_id,
+                    ));
             });
         }
     }
@@ -110,6 +116,10 @@ fn validate_incoming_bet(
         return Err(BetOnCurrentlyViewingPostError::Unauthorized);
     }
 
+    if canister_data.is_frozen() {
+        return Err(BetOnCurrentlyViewingPostError::BettingClosed);
+    }
+
     let utlility_token_balance = canister_data.my_token_balance.get_utility_token_balance();
 
     if utlility_token_balance < place_bet_arg.bet_amount {
@@ -201,6 +211,21 @@ mod test {
 
         assert_eq!(result, Ok(()));
 
+        canister_data.is_frozen_for_account_deletion = true;
+        let result = validate_incoming_bet(
+            &canister_data,
+            &get_mock_user_alice_principal_id(),
+            &PlaceBetArg {
+                post_canister_id: get_mock_user_alice_canister_id(),
+                post_id: 0,
+                bet_amount: 100,
+                bet_direction: BetDirection::Hot,
+            },
+        );
+
+        assert_eq!(result, Err(BetOnCurrentlyViewingPostError::BettingClosed));
+        canister_data.is_frozen_for_account_deletion = false;
+
         canister_data.all_hot_or_not_bets_placed.insert(
             (get_mock_user_alice_canister_id(), 0),
             PlacedBetDetail {
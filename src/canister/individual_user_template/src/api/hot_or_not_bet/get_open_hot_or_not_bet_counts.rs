@@ -0,0 +1,158 @@
+use std::time::{Duration, SystemTime};
+
+use shared_utils::{
+    canister_specific::individual_user_template::types::hot_or_not::{
+        BetOutcomeForBetMaker, OpenHotOrNotBetCounts, RoomBetPossibleOutcomes,
+        TOTAL_DURATION_OF_ALL_SLOTS_IN_SECONDS,
+    },
+    common::utils::system_time,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Used by the user index to hold off deleting the account of this canister's user until their
+/// bets are settled
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_open_hot_or_not_bet_counts() -> OpenHotOrNotBetCounts {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_open_hot_or_not_bet_counts_impl(
+            &canister_data_ref_cell.borrow(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn get_open_hot_or_not_bet_counts_impl(
+    canister_data: &CanisterData,
+    current_time: SystemTime,
+) -> OpenHotOrNotBetCounts {
+    let betting_duration = Duration::from_secs(TOTAL_DURATION_OF_ALL_SLOTS_IN_SECONDS);
+    let is_within_betting_duration = |started_at: SystemTime| {
+        !current_time
+            .duration_since(started_at)
+            .is_ok_and(|elapsed| elapsed >= betting_duration)
+    };
+
+    // * outcomes arrive by the end of the post's betting duration, so a bet placed before that
+    // * whose outcome is still missing is not waited for
+    let bets_awaiting_result_count = canister_data
+        .all_hot_or_not_bets_placed
+        .values()
+        .filter(|placed_bet_detail| {
+            placed_bet_detail.outcome_received == BetOutcomeForBetMaker::AwaitingResult
+                && is_within_betting_duration(placed_bet_detail.bet_placed_at)
+        })
+        .count() as u64;
+
+    let posts_with_open_bets_count = canister_data
+        .all_created_posts
+        .values()
+        .filter(|post| {
+            let Some(hot_or_not_details) = &post.hot_or_not_details else {
+                return false;
+            };
+
+            is_within_betting_duration(post.created_at)
+                || hot_or_not_details
+                    .slot_history
+                    .values()
+                    .flat_map(|slot_details| slot_details.room_details.values())
+                    .any(|room_details| {
+                        room_details.bet_outcome == RoomBetPossibleOutcomes::BetOngoing
+                    })
+        })
+        .count() as u64;
+
+    OpenHotOrNotBetCounts {
+        bets_awaiting_result_count,
+        posts_with_open_bets_count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::individual_user_template::types::{
+        hot_or_not::{BetDirection, PlacedBetDetail, RoomDetails, SlotDetails},
+        post::{Post, PostDetailsFromFrontend},
+    };
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_get_open_hot_or_not_bet_counts_impl() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        let betting_duration = Duration::from_secs(TOTAL_DURATION_OF_ALL_SLOTS_IN_SECONDS);
+
+        assert!(get_open_hot_or_not_bet_counts_impl(&canister_data, now).is_settled());
+
+        let post_details = PostDetailsFromFrontend {
+            is_nsfw: false,
+            description: "This is a new post".to_string(),
+            hashtags: vec![],
+            video_uid: "abcd1234".to_string(),
+            creator_consent_for_inclusion_in_hot_or_not: true,
+        };
+        // * betting still open
+        canister_data
+            .all_created_posts
+            .insert(0, Post::new(0, &post_details, &now));
+        // * betting closed with a room not tabulated yet
+        let mut post = Post::new(1, &post_details, &(now - betting_duration));
+        post.hot_or_not_details
+            .as_mut()
+            .unwrap()
+            .slot_history
+            .insert(
+                48,
+                SlotDetails {
+                    room_details: [(1, RoomDetails::default())].into(),
+                },
+            );
+        canister_data.all_created_posts.insert(1, post);
+        // * betting closed and settled
+        canister_data
+            .all_created_posts
+            .insert(2, Post::new(2, &post_details, &(now - betting_duration)));
+
+        let placed_bet_detail = PlacedBetDetail {
+            canister_id: get_mock_user_alice_canister_id(),
+            post_id: 0,
+            slot_id: 1,
+            room_id: 1,
+            amount_bet: 10,
+            bet_direction: BetDirection::Hot,
+            bet_placed_at: now,
+            outcome_received: BetOutcomeForBetMaker::AwaitingResult,
+        };
+        canister_data.all_hot_or_not_bets_placed.insert(
+            (get_mock_user_alice_canister_id(), 0),
+            placed_bet_detail.clone(),
+        );
+        canister_data.all_hot_or_not_bets_placed.insert(
+            (get_mock_user_alice_canister_id(), 1),
+            PlacedBetDetail {
+                outcome_received: BetOutcomeForBetMaker::Lost,
+                ..placed_bet_detail.clone()
+            },
+        );
+        // * overdue outcome
+        canister_data.all_hot_or_not_bets_placed.insert(
+            (get_mock_user_alice_canister_id(), 2),
+            PlacedBetDetail {
+                bet_placed_at: now - betting_duration,
+                ..placed_bet_detail
+            },
+        );
+
+        assert_eq!(
+            get_open_hot_or_not_bet_counts_impl(&canister_data, now),
+            OpenHotOrNotBetCounts {
+                bets_awaiting_result_count: 1,
+                posts_with_open_bets_count: 2,
+            }
+        );
+    }
+}
@@ -3,10 +3,13 @@ use std::time::SystemTime;
 use candid::Principal;
 use ic_cdk::api::management_canister::provisional::CanisterId;
 use shared_utils::{
-    canister_specific::individual_user_template::types::{
-        arg::PlaceBetArg,
-        error::BetOnCurrentlyViewingPostError,
-        hot_or_not::{BetDirection, BettingStatus},
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::{
+            arg::PlaceBetArg,
+            error::BetOnCurrentlyViewingPostError,
+            hot_or_not::{BetDirection, BettingStatus},
+        },
     },
     common::utils::system_time,
 };
@@ -60,15 +63,24 @@ fn receive_bet_from_bet_makers_canister_impl(
         ..
     } = place_bet_arg;
 
+    if canister_data.is_frozen() {
+        return Err(BetOnCurrentlyViewingPostError::BettingClosed);
+    }
+
     let post = canister_data.all_created_posts.get_mut(&post_id).unwrap();
 
-    post.place_hot_or_not_bet(
+    let betting_status = post.place_hot_or_not_bet(
         bet_maker_principal_id,
         bet_maker_canister_id,
         bet_amount,
         &bet_direction,
         current_time,
-    )
+    );
+    canister_data
+        .backup_change_tracker
+        .record_post_change(post_id);
+
+    betting_status
 }
 
 fn update_profile_stats_with_bet_placed(
@@ -83,6 +95,9 @@ fn update_profile_stats_with_bet_placed(
             canister_data.profile.profile_stats.not_bets_received += 1;
         }
     }
+    canister_data
+        .backup_change_tracker
+        .record_section_change(BackupSection::Profile);
 }
 
 #[cfg(test)]
@@ -140,5 +155,20 @@ mod test {
                 has_this_user_participated_in_this_post: Some(true)
             })
         );
+        canister_data.is_frozen_for_account_deletion = true;
+        let result = receive_bet_from_bet_makers_canister_impl(
+            &mut canister_data,
+            &get_mock_user_alice_principal_id(),
+            &get_mock_user_alice_canister_id(),
+            PlaceBetArg {
+                post_canister_id: get_mock_user_alice_canister_id(),
+                post_id: 0,
+                bet_amount: 100,
+                bet_direction: BetDirection::Hot,
+            },
+            &SystemTime::now(),
+        );
+
+        assert_eq!(result, Err(BetOnCurrentlyViewingPostError::BettingClosed));
     }
 }
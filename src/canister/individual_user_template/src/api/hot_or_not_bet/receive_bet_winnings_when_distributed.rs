@@ -53,9 +53,11 @@ fn receive_bet_winnings_when_distributed(post_id: PostId, outcome: BetOutcomeFor
             .get(&(post_creator_canister_id, post_id))
             .cloned()
             .unwrap();
+        canister_data
+            .backup_change_tracker
+            .record_hot_or_not_bet_placed_change((post_creator_canister_id, post_id));
 
-        let my_token_balance = &mut canister_data.my_token_balance;
-        my_token_balance.handle_token_event(TokenEvent::HotOrNotOutcomePayout {
+        canister_data.handle_token_event(TokenEvent::HotOrNotOutcomePayout {
             amount: match outcome {
                 BetOutcomeForBetMaker::Draw(amount) => amount,
                 BetOutcomeForBetMaker::Won(amount) => amount,
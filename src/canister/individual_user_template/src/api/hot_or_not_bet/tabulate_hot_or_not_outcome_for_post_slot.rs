@@ -1,6 +1,7 @@
 use candid::Principal;
 use shared_utils::{
     canister_specific::individual_user_template::types::{
+        backup_change_tracker::get_token_event_id_bounds,
         hot_or_not::{BetDirection, BetOutcomeForBetMaker, BetPayout, RoomBetPossibleOutcomes},
         post::Post,
     },
@@ -19,6 +20,7 @@ pub fn tabulate_hot_or_not_outcome_for_post_slot(
 
     let post_to_tabulate_results_for = canister_data.all_created_posts.get_mut(&post_id).unwrap();
     let token_balance = &mut canister_data.my_token_balance;
+    let token_event_ids_before = get_token_event_id_bounds(token_balance);
 
     post_to_tabulate_results_for.tabulate_hot_or_not_outcome_for_slot(
         &this_canister_id,
@@ -28,6 +30,11 @@ pub fn tabulate_hot_or_not_outcome_for_post_slot(
     );
 
     inform_participants_of_outcome(post_to_tabulate_results_for, &slot_id);
+
+    let backup_change_tracker = &mut canister_data.backup_change_tracker;
+    backup_change_tracker.record_post_change(post_id);
+    backup_change_tracker
+        .record_token_balance_change(token_event_ids_before, &canister_data.my_token_balance);
 }
 
 fn inform_participants_of_outcome(post: &Post, slot_id: &u8) {
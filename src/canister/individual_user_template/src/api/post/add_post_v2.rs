@@ -36,11 +36,9 @@ fn add_post_v2(post_details: PostDetailsFromFrontend) -> Result<u64, String> {
         )
     });
 
-    let post_id = response.clone().unwrap();
+    let post_id = response?;
 
-    if response.is_ok() {
-        update_scores_and_share_with_post_cache_if_difference_beyond_threshold(&post_id);
-    }
+    update_scores_and_share_with_post_cache_if_difference_beyond_threshold(&post_id);
 
     if post_details.creator_consent_for_inclusion_in_hot_or_not {
         // * schedule hot_or_not outcome tabulation for the 48 hours after the post is created
@@ -68,14 +66,58 @@ fn add_post_to_memory(
     post_details: &PostDetailsFromFrontend,
     current_system_time: &SystemTime,
 ) -> Result<u64, String> {
+    if canister_data.is_frozen() {
+        return Err("This canister is frozen".to_string());
+    }
+
     let new_post = Post::new(
         canister_data.all_created_posts.len() as u64,
         post_details,
         current_system_time,
     );
     let new_post_id = new_post.id;
+    canister_data.set_last_access_time(Some(*current_system_time));
     canister_data
         .all_created_posts
         .insert(new_post.id, new_post);
+    canister_data
+        .backup_change_tracker
+        .record_post_change(new_post_id);
     Ok(new_post_id)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_add_post_to_memory_rejects_posts_while_frozen_for_account_deletion() {
+        let mut canister_data = CanisterData::default();
+        let post_details = PostDetailsFromFrontend {
+            is_nsfw: false,
+            description: "This is a new post".to_string(),
+            hashtags: vec![],
+            video_uid: "abcd1234".to_string(),
+            creator_consent_for_inclusion_in_hot_or_not: true,
+        };
+
+        assert_eq!(
+            add_post_to_memory(&mut canister_data, &post_details, &SystemTime::now()),
+            Ok(0)
+        );
+
+        canister_data.is_frozen_for_account_deletion = true;
+        assert_eq!(
+            add_post_to_memory(&mut canister_data, &post_details, &SystemTime::now()),
+            Err("This canister is frozen".to_string())
+        );
+
+        canister_data.is_frozen_for_account_deletion = false;
+        canister_data.is_frozen_for_reclamation = true;
+        assert_eq!(
+            add_post_to_memory(&mut canister_data, &post_details, &SystemTime::now()),
+            Err("This canister is frozen".to_string())
+        );
+        assert_eq!(canister_data.all_created_posts.len(), 1);
+    }
+}
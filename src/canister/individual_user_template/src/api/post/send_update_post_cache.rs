@@ -111,6 +111,9 @@ pub fn update_local_cache_get_items(
     }
 
     all_posts.insert(post_id, post_to_synchronise);
+    canister_data
+        .backup_change_tracker
+        .record_post_change(post_id);
 
     (home_feed_index_score_item, hot_or_not_index_score_item)
 }
@@ -17,10 +17,9 @@ fn update_post_add_view_details(id: u64, details: PostViewDetailsFromFrontend) {
 
         post_to_update.add_view_details(&details);
 
-        canister_data_ref_cell
-            .borrow_mut()
-            .all_created_posts
-            .insert(id, post_to_update);
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.all_created_posts.insert(id, post_to_update);
+        canister_data.backup_change_tracker.record_post_change(id);
     });
 
     update_scores_and_share_with_post_cache_if_difference_beyond_threshold(&id);
@@ -34,10 +34,9 @@ fn update_post_as_ready_to_view(id: u64) {
 
         post_to_update.update_status(PostStatus::ReadyToView);
 
-        canister_data_ref_cell
-            .borrow_mut()
-            .all_created_posts
-            .insert(id, post_to_update);
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.all_created_posts.insert(id, post_to_update);
+        canister_data.backup_change_tracker.record_post_change(id);
     });
 
     send_update_post_cache(&id);
@@ -15,10 +15,9 @@ fn update_post_increment_share_count(id: u64) -> u64 {
 
         let updated_share_count = post_to_update.increment_share_count();
 
-        canister_data_ref_cell
-            .borrow_mut()
-            .all_created_posts
-            .insert(id, post_to_update);
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.all_created_posts.insert(id, post_to_update);
+        canister_data.backup_change_tracker.record_post_change(id);
 
         updated_share_count
     });
@@ -17,10 +17,9 @@ fn update_post_toggle_like_status_by_caller(id: u64) -> bool {
 
         let updated_like_status = post_to_update.toggle_like_status(&caller_id);
 
-        canister_data_ref_cell
-            .borrow_mut()
-            .all_created_posts
-            .insert(id, post_to_update);
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.all_created_posts.insert(id, post_to_update);
+        canister_data.backup_change_tracker.record_post_change(id);
 
         updated_like_status
     });
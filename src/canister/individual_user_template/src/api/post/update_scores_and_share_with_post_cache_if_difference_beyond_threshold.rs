@@ -1,8 +1,13 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use candid::Principal;
-use ic_cdk::{api::call, update};
+use ic_cdk::api::call::{self, CallResult};
 use shared_utils::{
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::post_score_sync::PostScoreSyncQueue,
+        post_cache::types::score_sync::PostScoresReceivedAck,
+    },
     common::{
         types::{
             known_principal::KnownPrincipalType,
@@ -18,6 +23,9 @@ use shared_utils::{
 
 use crate::{data_model::CanisterData, CANISTER_DATA};
 
+const POST_SCORE_SYNC_QUEUE_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+const MAX_POST_SCORE_SYNC_QUEUE_FLUSH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 #[ic_cdk::update]
 #[candid::candid_method(update)]
 fn check_and_update_scores_and_share_with_post_cache_if_difference_beyond_threshold(
@@ -32,45 +40,152 @@ pub fn update_scores_and_share_with_post_cache_if_difference_beyond_threshold(po
     let current_time = system_time::get_current_system_time_from_ic();
     let canisters_own_principal_id = ic_cdk::id();
 
-    let (home_feed_index_score_item, hot_or_not_index_score_item): (
-        Option<PostScoreIndexItemV1>,
-        Option<PostScoreIndexItemV1>,
-    ) = CANISTER_DATA.with(|canister_data_ref_cell| {
-        update_home_feed_and_hot_or_not_feed_score_and_get_post_index_item_to_send(
-            &mut canister_data_ref_cell.borrow_mut(),
-            *post_id,
-            current_time,
-            canisters_own_principal_id,
-        )
+    let is_batch_full = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        let (home_feed_index_score_item, hot_or_not_index_score_item) =
+            update_home_feed_and_hot_or_not_feed_score_and_get_post_index_item_to_send(
+                &mut canister_data,
+                *post_id,
+                current_time,
+                canisters_own_principal_id,
+            );
+
+        canister_data
+            .post_score_sync_queue
+            .enqueue(home_feed_index_score_item, hot_or_not_index_score_item);
+        canister_data
+            .backup_change_tracker
+            .record_section_change(BackupSection::PostScoreSyncQueue);
+
+        canister_data.post_score_sync_queue.is_batch_full()
     });
 
-    if home_feed_index_score_item.is_none() && hot_or_not_index_score_item.is_none() {
-        return;
+    if is_batch_full && !is_post_score_sync_queue_flush_backing_off() {
+        ic_cdk::spawn(flush_post_score_sync_queue_to_post_cache());
+    } else {
+        schedule_post_score_sync_queue_flush();
+    }
+}
+
+fn is_post_score_sync_queue_flush_backing_off() -> bool {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .post_score_sync_queue
+            .consecutive_failed_flush_count
+            > 0
+    })
+}
+
+/// Enqueues a timer to flush the queued scores unless one is already pending. The timer backs
+/// off while flushes keep failing.
+pub fn schedule_post_score_sync_queue_flush() {
+    let flush_delay = update_post_score_sync_queue(|post_score_sync_queue| {
+        if post_score_sync_queue.is_empty() || post_score_sync_queue.is_flush_scheduled {
+            return None;
+        }
+
+        post_score_sync_queue.is_flush_scheduled = true;
+        Some(post_score_sync_queue.get_flush_delay(
+            POST_SCORE_SYNC_QUEUE_FLUSH_INTERVAL,
+            MAX_POST_SCORE_SYNC_QUEUE_FLUSH_INTERVAL,
+        ))
+    });
+
+    if let Some(flush_delay) = flush_delay {
+        ic_cdk_timers::set_timer(flush_delay, || {
+            update_post_score_sync_queue(|post_score_sync_queue| {
+                post_score_sync_queue.is_flush_scheduled = false;
+            });
+
+            ic_cdk::spawn(flush_post_score_sync_queue_to_post_cache());
+        });
     }
+}
 
-    let post_cache_canister_principal_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+async fn flush_post_score_sync_queue_to_post_cache() {
+    let Some(post_cache_canister_principal_id) = CANISTER_DATA.with(|canister_data_ref_cell| {
         canister_data_ref_cell
             .borrow()
             .known_principal_ids
             .get(&KnownPrincipalType::CanisterIdPostCache)
             .cloned()
-            .unwrap()
-    });
+    }) else {
+        return;
+    };
+
+    let (home_feed_items, hot_or_not_feed_items) =
+        update_post_score_sync_queue(|post_score_sync_queue| post_score_sync_queue.take_batch());
+
+    let mut did_flush_fail = false;
 
-    if home_feed_index_score_item.is_some() {
-        let _ = call::notify(
+    if !home_feed_items.is_empty()
+        && !send_post_scores_to_post_cache(
             post_cache_canister_principal_id,
             "receive_top_home_feed_posts_from_publishing_canister",
-            (vec![home_feed_index_score_item.unwrap()],),
-        );
+            &home_feed_items,
+        )
+        .await
+    {
+        did_flush_fail = true;
+        update_post_score_sync_queue(|post_score_sync_queue| {
+            post_score_sync_queue.requeue_home_feed(home_feed_items)
+        });
     }
 
-    if hot_or_not_index_score_item.is_some() {
-        let _ = call::notify(
+    if !hot_or_not_feed_items.is_empty()
+        && !send_post_scores_to_post_cache(
             post_cache_canister_principal_id,
             "receive_top_hot_or_not_feed_posts_from_publishing_canister",
-            (vec![hot_or_not_index_score_item.unwrap()],),
-        );
+            &hot_or_not_feed_items,
+        )
+        .await
+    {
+        did_flush_fail = true;
+        update_post_score_sync_queue(|post_score_sync_queue| {
+            post_score_sync_queue.requeue_hot_or_not_feed(hot_or_not_feed_items)
+        });
+    }
+
+    update_post_score_sync_queue(|post_score_sync_queue| {
+        post_score_sync_queue.record_flush_result(!did_flush_fail)
+    });
+
+    // * picks up items that did not fit in this batch as well as failed ones
+    schedule_post_score_sync_queue_flush();
+}
+
+/// Runs `update` on the queue and records the change for the next backup run
+fn update_post_score_sync_queue<T>(update: impl FnOnce(&mut PostScoreSyncQueue) -> T) -> T {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        let result = update(&mut canister_data.post_score_sync_queue);
+        canister_data
+            .backup_change_tracker
+            .record_section_change(BackupSection::PostScoreSyncQueue);
+        result
+    })
+}
+
+/// Returns true if the post cache acknowledged every item in the batch.
+async fn send_post_scores_to_post_cache(
+    post_cache_canister_principal_id: Principal,
+    method: &str,
+    items: &[PostScoreIndexItemV1],
+) -> bool {
+    let response: CallResult<(PostScoresReceivedAck,)> =
+        call::call(post_cache_canister_principal_id, method, (items,)).await;
+
+    match response {
+        Ok((ack,)) => ack.number_of_posts_received == items.len() as u64,
+        Err((rejection_code, err)) => {
+            ic_cdk::print(format!(
+                "Error: {} failed with rejection code: {:?}, error: {}",
+                method, rejection_code, err
+            ));
+            false
+        }
     }
 }
 
@@ -154,6 +269,9 @@ fn update_home_feed_and_hot_or_not_feed_score_and_get_post_index_item_to_send(
     }
 
     all_posts.insert(post_id, post_to_synchronise);
+    canister_data
+        .backup_change_tracker
+        .record_post_change(post_id);
 
     (home_feed_index_score_item, hot_or_not_index_score_item)
 }
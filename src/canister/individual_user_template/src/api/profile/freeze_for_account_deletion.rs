@@ -0,0 +1,70 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Stops the canister from taking new posts and bets and from sending backups, as the first step
+/// of deleting its user's account, so that nothing new comes in while the deletion runs.
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn freeze_for_account_deletion() -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        freeze_for_account_deletion_impl(api_caller, &mut canister_data_ref_cell.borrow_mut())
+    })
+}
+
+fn freeze_for_account_deletion_impl(
+    caller: Principal,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let user_index_canister_principal_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if caller != *user_index_canister_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.is_frozen_for_account_deletion = true;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_freeze_for_account_deletion_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        assert_eq!(
+            freeze_for_account_deletion_impl(
+                get_mock_user_alice_principal_id(),
+                &mut canister_data
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert!(!canister_data.is_frozen_for_account_deletion);
+
+        assert_eq!(
+            freeze_for_account_deletion_impl(get_mock_canister_id_user_index(), &mut canister_data),
+            Ok(())
+        );
+        assert!(canister_data.is_frozen_for_account_deletion);
+    }
+}
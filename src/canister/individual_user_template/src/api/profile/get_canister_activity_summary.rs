@@ -0,0 +1,80 @@
+use shared_utils::canister_specific::individual_user_template::types::activity::CanisterActivitySummary;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Used by the user index to find canisters of users that have been inactive for long
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_canister_activity_summary() -> CanisterActivitySummary {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_canister_activity_summary_impl(&canister_data_ref_cell.borrow())
+    })
+}
+
+fn get_canister_activity_summary_impl(canister_data: &CanisterData) -> CanisterActivitySummary {
+    CanisterActivitySummary {
+        last_access_time: canister_data.last_access_time,
+        created_posts_count: canister_data.all_created_posts.len() as u64,
+        followers_count: canister_data.follow_data.follower.len() as u64,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use shared_utils::canister_specific::individual_user_template::types::{
+        follow::FollowEntryDetail,
+        post::{Post, PostDetailsFromFrontend},
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_canister_activity_summary_impl() {
+        let mut canister_data = CanisterData::default();
+
+        assert_eq!(
+            get_canister_activity_summary_impl(&canister_data),
+            CanisterActivitySummary {
+                last_access_time: None,
+                created_posts_count: 0,
+                followers_count: 0,
+            }
+        );
+
+        let now = SystemTime::now();
+        canister_data.last_access_time = Some(now);
+        canister_data.all_created_posts.insert(
+            0,
+            Post::new(
+                0,
+                &PostDetailsFromFrontend {
+                    is_nsfw: false,
+                    description: "This is a new post".to_string(),
+                    hashtags: vec![],
+                    video_uid: "abcd1234".to_string(),
+                    creator_consent_for_inclusion_in_hot_or_not: false,
+                },
+                &now,
+            ),
+        );
+
+        canister_data.follow_data.follower.add(FollowEntryDetail {
+            principal_id: get_mock_user_bob_principal_id(),
+            canister_id: get_mock_user_bob_canister_id(),
+        });
+
+        assert_eq!(
+            get_canister_activity_summary_impl(&canister_data),
+            CanisterActivitySummary {
+                last_access_time: Some(now),
+                created_posts_count: 1,
+                followers_count: 1,
+            }
+        );
+    }
+}
@@ -1,3 +1,8 @@
+pub mod freeze_for_account_deletion;
+pub mod get_canister_activity_summary;
 pub mod get_profile_details;
+pub mod set_frozen_for_reclamation;
 pub mod update_profile_display_details;
+pub mod update_profile_owner;
 pub mod update_profile_set_unique_username_once;
+pub mod update_profile_unique_user_name_from_user_index;
@@ -0,0 +1,91 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Stops the canister from taking new posts, bets, follows and profile edits while the user index
+/// takes its final backup and reclaims it. Backups still go through. The user index lifts the
+/// freeze again if the reclamation does not go ahead.
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_frozen_for_reclamation(is_frozen: bool) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_frozen_for_reclamation_impl(
+            api_caller,
+            is_frozen,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn set_frozen_for_reclamation_impl(
+    caller: Principal,
+    is_frozen: bool,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let user_index_canister_principal_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if caller != *user_index_canister_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.is_frozen_for_reclamation = is_frozen;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_frozen_for_reclamation_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        assert_eq!(
+            set_frozen_for_reclamation_impl(
+                get_mock_user_alice_principal_id(),
+                true,
+                &mut canister_data
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert!(!canister_data.is_frozen());
+
+        assert_eq!(
+            set_frozen_for_reclamation_impl(
+                get_mock_canister_id_user_index(),
+                true,
+                &mut canister_data
+            ),
+            Ok(())
+        );
+        assert!(canister_data.is_frozen());
+
+        assert_eq!(
+            set_frozen_for_reclamation_impl(
+                get_mock_canister_id_user_index(),
+                false,
+                &mut canister_data
+            ),
+            Ok(())
+        );
+        assert!(!canister_data.is_frozen());
+    }
+}
@@ -1,7 +1,13 @@
 use crate::CANISTER_DATA;
 use candid::CandidType;
-use shared_utils::canister_specific::individual_user_template::types::profile::{
-    UserProfileDetailsForFrontend, UserProfileUpdateDetailsFromFrontend,
+use shared_utils::{
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::profile::{
+            UserProfileDetailsForFrontend, UserProfileUpdateDetailsFromFrontend,
+        },
+    },
+    common::utils::system_time,
 };
 
 #[derive(CandidType)]
@@ -26,11 +32,21 @@ fn update_profile_display_details(
         return Err(UpdateProfileDetailsError::NotAuthorized);
     }
 
+    if CANISTER_DATA.with(|canister_data_ref_cell| canister_data_ref_cell.borrow().is_frozen()) {
+        return Err(UpdateProfileDetailsError::NotAuthorized);
+    }
+
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        let profile = &mut canister_data_ref_cell.borrow_mut().profile;
+        let canister_data = &mut canister_data_ref_cell.borrow_mut();
+        canister_data.set_last_access_time(Some(system_time::get_current_system_time_from_ic()));
+
+        let profile = &mut canister_data.profile;
 
         profile.display_name = user_profile_details.display_name;
         profile.profile_picture_url = user_profile_details.profile_picture_url;
+        canister_data
+            .backup_change_tracker
+            .record_section_change(BackupSection::Profile);
     });
 
     Ok(CANISTER_DATA.with(|canister_data_ref_cell| {
@@ -0,0 +1,109 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::{types::known_principal::KnownPrincipalType, utils::system_time},
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Sets the owner of a canister that was provisioned ahead of signup, when the user index hands
+/// it out to a new user.
+///
+/// # Access Control
+/// Only the user index canister can set the owner, and only once.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn update_profile_owner(profile_owner: Principal) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_profile_owner_impl(
+            api_caller,
+            profile_owner,
+            &mut canister_data_ref_cell.borrow_mut(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn update_profile_owner_impl(
+    caller: Principal,
+    profile_owner: Principal,
+    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+) -> Result<(), String> {
+    let user_index_canister_principal_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if caller != *user_index_canister_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    if canister_data.profile.principal_id.is_some() {
+        return Err("Profile owner is already set".to_string());
+    }
+
+    canister_data.profile.principal_id = Some(profile_owner);
+    canister_data
+        .backup_change_tracker
+        .record_section_change(BackupSection::Profile);
+    canister_data.set_last_access_time(Some(current_time));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_update_profile_owner_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        let result = update_profile_owner_impl(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_principal_id(),
+            &mut canister_data,
+            SystemTime::now(),
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+        assert_eq!(canister_data.profile.principal_id, None);
+
+        let result = update_profile_owner_impl(
+            get_mock_canister_id_user_index(),
+            get_mock_user_alice_principal_id(),
+            &mut canister_data,
+            SystemTime::now(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data.profile.principal_id,
+            Some(get_mock_user_alice_principal_id())
+        );
+
+        let result = update_profile_owner_impl(
+            get_mock_canister_id_user_index(),
+            get_mock_user_bob_principal_id(),
+            &mut canister_data,
+            SystemTime::now(),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            canister_data.profile.principal_id,
+            Some(get_mock_user_alice_principal_id())
+        );
+    }
+}
@@ -1,13 +1,23 @@
 use crate::CANISTER_DATA;
 use ic_cdk::api::call;
 use shared_utils::{
-    common::types::known_principal::KnownPrincipalType,
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{system_time, user_name::validate_and_normalize_user_name},
+    },
     types::canister_specific::{
         individual_user_template::error_types::UpdateProfileSetUniqueUsernameError,
         user_index::error_types::SetUniqueUsernameError,
     },
 };
 
+/// Despite its name, this also changes an already set username. The user index enforces a
+/// cooldown between changes and keeps the replaced username reserved for a while.
+///
+/// The username is validated and stored lowercased. The user index checks it against the
+/// default and the admin managed blocklists, as only it knows the latter.
+///
 /// # Access Control
 /// Only the user whose profile details are stored in this canister can update their details.
 #[ic_cdk::update]
@@ -25,6 +35,13 @@ async fn update_profile_set_unique_username_once(
         return Err(UpdateProfileSetUniqueUsernameError::NotAuthorized);
     }
 
+    if CANISTER_DATA.with(|canister_data_ref_cell| canister_data_ref_cell.borrow().is_frozen()) {
+        return Err(UpdateProfileSetUniqueUsernameError::NotAuthorized);
+    }
+
+    let new_unique_username = validate_and_normalize_user_name(&new_unique_username)
+        .map_err(map_set_unique_username_error)?;
+
     let user_index_canister_principal_id = CANISTER_DATA.with(|canister_data_ref_cell| {
         canister_data_ref_cell
             .borrow()
@@ -46,20 +63,47 @@ async fn update_profile_set_unique_username_once(
     match response {
         Ok(()) => {
             CANISTER_DATA.with(|canister_data_ref_cell| {
-                let mut profile = canister_data_ref_cell.borrow_mut().profile.clone();
-                profile.unique_user_name = Some(new_unique_username);
-                canister_data_ref_cell.borrow_mut().profile = profile;
+                let mut canister_data = canister_data_ref_cell.borrow_mut();
+                canister_data.profile.unique_user_name = Some(new_unique_username);
+                canister_data
+                    .backup_change_tracker
+                    .record_section_change(BackupSection::Profile);
+                canister_data
+                    .set_last_access_time(Some(system_time::get_current_system_time_from_ic()));
             });
             Ok(())
         }
-        Err(SetUniqueUsernameError::UsernameAlreadyTaken) => {
-            Err(UpdateProfileSetUniqueUsernameError::UsernameAlreadyTaken)
+        Err(error) => Err(map_set_unique_username_error(error)),
+    }
+}
+
+fn map_set_unique_username_error(
+    error: SetUniqueUsernameError,
+) -> UpdateProfileSetUniqueUsernameError {
+    match error {
+        SetUniqueUsernameError::UsernameAlreadyTaken => {
+            UpdateProfileSetUniqueUsernameError::UsernameAlreadyTaken
+        }
+        SetUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId => {
+            UpdateProfileSetUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId
+        }
+        SetUniqueUsernameError::UserCanisterEntryDoesNotExist => {
+            UpdateProfileSetUniqueUsernameError::UserCanisterEntryDoesNotExist
+        }
+        SetUniqueUsernameError::UsernameChangeOnCooldown => {
+            UpdateProfileSetUniqueUsernameError::UsernameChangeOnCooldown
+        }
+        SetUniqueUsernameError::UsernameTooShort => {
+            UpdateProfileSetUniqueUsernameError::UsernameTooShort
+        }
+        SetUniqueUsernameError::UsernameTooLong => {
+            UpdateProfileSetUniqueUsernameError::UsernameTooLong
         }
-        Err(SetUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId) => {
-            Err(UpdateProfileSetUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId)
+        SetUniqueUsernameError::UsernameHasInvalidCharacters => {
+            UpdateProfileSetUniqueUsernameError::UsernameHasInvalidCharacters
         }
-        Err(SetUniqueUsernameError::UserCanisterEntryDoesNotExist) => {
-            Err(UpdateProfileSetUniqueUsernameError::UserCanisterEntryDoesNotExist)
+        SetUniqueUsernameError::UsernameIsBlocked => {
+            UpdateProfileSetUniqueUsernameError::UsernameIsBlocked
         }
     }
 }
@@ -0,0 +1,96 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Brings the username in the profile in line with the user index, when the user index changes
+/// it without the user asking, e.g. when it case folds existing usernames. `None` means the user
+/// index released the username and the user has to pick a new one.
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn update_profile_unique_user_name_from_user_index(
+    unique_user_name: Option<String>,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_profile_unique_user_name_from_user_index_impl(
+            api_caller,
+            unique_user_name,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn update_profile_unique_user_name_from_user_index_impl(
+    caller: Principal,
+    unique_user_name: Option<String>,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let user_index_canister_principal_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if caller != *user_index_canister_principal_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.profile.unique_user_name = unique_user_name;
+    canister_data
+        .backup_change_tracker
+        .record_section_change(BackupSection::Profile);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_update_profile_unique_user_name_from_user_index_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+        canister_data.profile.unique_user_name = Some("Alice".to_string());
+
+        let result = update_profile_unique_user_name_from_user_index_impl(
+            get_mock_user_alice_principal_id(),
+            Some("alice".to_string()),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = update_profile_unique_user_name_from_user_index_impl(
+            get_mock_canister_id_user_index(),
+            Some("alice".to_string()),
+            &mut canister_data,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            canister_data.profile.unique_user_name,
+            Some("alice".to_string())
+        );
+
+        let result = update_profile_unique_user_name_from_user_index_impl(
+            get_mock_canister_id_user_index(),
+            None,
+            &mut canister_data,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(canister_data.profile.unique_user_name, None);
+    }
+}
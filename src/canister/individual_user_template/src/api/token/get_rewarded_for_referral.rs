@@ -29,7 +29,7 @@ fn get_rewarded_for_referral(referrer: Principal, referree: Principal) {
     let current_time = system_time::get_current_system_time_from_ic();
 
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        let my_token_balance = &mut canister_data_ref_cell.borrow_mut().my_token_balance;
+        let mut canister_data_ref = canister_data_ref_cell.borrow_mut();
 
         let referral_reward_amount =
             TokenEvent::get_token_amount_for_token_event(&TokenEvent::Mint {
@@ -41,7 +41,7 @@ fn get_rewarded_for_referral(referrer: Principal, referree: Principal) {
                 timestamp: current_time,
             });
 
-        my_token_balance.handle_token_event(TokenEvent::Mint {
+        canister_data_ref.handle_token_event(TokenEvent::Mint {
             amount: referral_reward_amount,
             details: MintEvent::Referral {
                 referrer_user_principal_id: referrer,
@@ -30,7 +30,6 @@ fn get_rewarded_for_signing_up() {
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let mut canister_data_ref = canister_data_ref_cell.borrow_mut();
         let my_principal_id = canister_data_ref.profile.principal_id.unwrap();
-        let my_token_balance = &mut canister_data_ref.my_token_balance;
 
         let signup_reward_amount =
             TokenEvent::get_token_amount_for_token_event(&TokenEvent::Mint {
@@ -41,7 +40,7 @@ fn get_rewarded_for_signing_up() {
                 timestamp: current_time,
             });
 
-        my_token_balance.handle_token_event(TokenEvent::Mint {
+        canister_data_ref.handle_token_event(TokenEvent::Mint {
             amount: signup_reward_amount,
             details: MintEvent::NewUserSignup {
                 new_user_principal_id: my_principal_id,
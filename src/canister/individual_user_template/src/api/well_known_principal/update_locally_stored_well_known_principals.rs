@@ -1,6 +1,9 @@
 use candid::Principal;
 use ic_cdk::api::call;
-use shared_utils::common::types::known_principal::KnownPrincipalType;
+use shared_utils::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::types::known_principal::KnownPrincipalType,
+};
 
 use crate::CANISTER_DATA;
 
@@ -29,5 +32,8 @@ pub async fn update_locally_stored_well_known_principals() {
         canister_data.known_principal_ids = well_known_principals
             .into_iter()
             .collect::<std::collections::HashMap<_, _>>();
+        canister_data
+            .backup_change_tracker
+            .record_section_change(BackupSection::KnownPrincipalIds);
     });
 }
@@ -1,16 +1,29 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::SystemTime,
+};
 
 use candid::{Deserialize, Principal};
 use ic_cdk::api::management_canister::provisional::CanisterId;
 use serde::Serialize;
 use shared_utils::{
-    canister_specific::individual_user_template::types::{
-        configuration::IndividualUserConfiguration, follow::FollowData,
-        hot_or_not::PlacedBetDetail, post::Post, profile::UserProfile, token::TokenBalance,
+    canister_specific::{
+        data_backup::types::backup_manifest::BackupSection,
+        individual_user_template::types::{
+            backup_change_tracker::{get_token_event_id_bounds, BackupChangeTracker},
+            configuration::IndividualUserConfiguration,
+            follow::FollowData,
+            hot_or_not::PlacedBetDetail,
+            post::Post,
+            post_score_sync::PostScoreSyncQueue,
+            profile::UserProfile,
+            token::TokenBalance,
+        },
     },
     common::types::{
         app_primitive_type::PostId, known_principal::KnownPrincipalMap,
-        top_posts::post_score_index::PostScoreIndex, version_details::VersionDetails,
+        top_posts::post_score_index::PostScoreIndex, utility_token::token_event::TokenEvent,
+        version_details::VersionDetails,
     },
 };
 
@@ -31,4 +44,41 @@ pub struct CanisterData {
     pub principals_that_follow_me: BTreeSet<Principal>,
     pub profile: UserProfile,
     pub version_details: VersionDetails,
+    #[serde(default)]
+    pub post_score_sync_queue: PostScoreSyncQueue,
+    /// Last time the owner posted, bet, followed or updated their profile
+    #[serde(default)]
+    pub last_access_time: Option<SystemTime>,
+    #[serde(default)]
+    pub backup_change_tracker: BackupChangeTracker,
+    /// Set once the deletion of the owner's account starts. The canister then takes no new posts
+    /// or bets and sends no more backups, until it is reinstalled.
+    #[serde(default)]
+    pub is_frozen_for_account_deletion: bool,
+    /// Set while the user index reclaims the canister, so that nothing is written after the final
+    /// backup. Unlike the deletion freeze, it still lets that backup through.
+    #[serde(default)]
+    pub is_frozen_for_reclamation: bool,
+}
+
+impl CanisterData {
+    /// Whether the canister takes no new posts, bets, follows or profile edits
+    pub fn is_frozen(&self) -> bool {
+        self.is_frozen_for_account_deletion || self.is_frozen_for_reclamation
+    }
+
+    /// Sets the last access time and records the change for the next backup run
+    pub fn set_last_access_time(&mut self, last_access_time: Option<SystemTime>) {
+        self.last_access_time = last_access_time;
+        self.backup_change_tracker
+            .record_section_change(BackupSection::LastAccessTime);
+    }
+
+    /// Applies the token event to the balance and records the change for the next backup run
+    pub fn handle_token_event(&mut self, token_event: TokenEvent) {
+        let token_event_ids_before = get_token_event_id_bounds(&self.my_token_balance);
+        self.my_token_balance.handle_token_event(token_event);
+        self.backup_change_tracker
+            .record_token_balance_change(token_event_ids_before, &self.my_token_balance);
+    }
 }
@@ -8,19 +8,29 @@ use candid::{export_service, Principal};
 use data_model::CanisterData;
 use ic_cdk::api::management_canister::provisional::CanisterId;
 use shared_utils::{
-    canister_specific::individual_user_template::types::{
-        arg::{FolloweeArg, IndividualUserTemplateInitArgs, PlaceBetArg},
-        error::{
-            BetOnCurrentlyViewingPostError, FollowAnotherUserProfileError,
-            GetPostsOfUserProfileError,
+    canister_specific::{
+        data_backup::types::{
+            backup_key_counts::BackupKeyCounts, backup_manifest::BackupSection,
+            backup_section_digests::BackupSectionDigests,
+            individual_user_canister_data_section::IndividualUserCanisterDataSection,
         },
-        follow::{FollowEntryDetail, FollowEntryId},
-        hot_or_not::{BetOutcomeForBetMaker, BettingStatus, PlacedBetDetail},
-        post::{
-            Post, PostDetailsForFrontend, PostDetailsFromFrontend, PostViewDetailsFromFrontend,
-        },
-        profile::{
-            UserProfile, UserProfileDetailsForFrontend, UserProfileUpdateDetailsFromFrontend,
+        individual_user_template::types::{
+            activity::CanisterActivitySummary,
+            arg::{FolloweeArg, IndividualUserTemplateInitArgs, PlaceBetArg},
+            error::{
+                BetOnCurrentlyViewingPostError, FollowAnotherUserProfileError,
+                GetPostsOfUserProfileError,
+            },
+            follow::{FollowEntryDetail, FollowEntryId},
+            hot_or_not::{
+                BetOutcomeForBetMaker, BettingStatus, OpenHotOrNotBetCounts, PlacedBetDetail,
+            },
+            post::{
+                Post, PostDetailsForFrontend, PostDetailsFromFrontend, PostViewDetailsFromFrontend,
+            },
+            profile::{
+                UserProfile, UserProfileDetailsForFrontend, UserProfileUpdateDetailsFromFrontend,
+            },
         },
     },
     common::types::{
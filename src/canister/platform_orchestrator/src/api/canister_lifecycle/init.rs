@@ -0,0 +1,63 @@
+use shared_utils::canister_specific::platform_orchestrator::types::args::PlatformOrchestratorInitArgs;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+#[ic_cdk::init]
+#[candid::candid_method(init)]
+fn init(init_args: PlatformOrchestratorInitArgs) {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut data = canister_data_ref_cell.borrow_mut();
+        init_impl(init_args, &mut data);
+    });
+}
+
+fn init_impl(init_args: PlatformOrchestratorInitArgs, data: &mut CanisterData) {
+    init_args
+        .known_principal_ids
+        .unwrap_or_default()
+        .iter()
+        .for_each(|(principal_belongs_to, principal_id)| {
+            data.known_principal_ids
+                .insert(*principal_belongs_to, *principal_id);
+        });
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::common::types::known_principal::{KnownPrincipalMap, KnownPrincipalType};
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_configuration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_init_impl() {
+        let mut known_principal_ids = KnownPrincipalMap::new();
+        known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdConfiguration,
+            get_mock_canister_id_configuration(),
+        );
+        let init_args = PlatformOrchestratorInitArgs {
+            known_principal_ids: Some(known_principal_ids),
+        };
+        let mut data = CanisterData::default();
+
+        init_impl(init_args, &mut data);
+
+        assert_eq!(
+            data.known_principal_ids
+                .get(&KnownPrincipalType::UserIdGlobalSuperAdmin),
+            Some(&get_global_super_admin_principal_id())
+        );
+        assert_eq!(
+            data.known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdConfiguration),
+            Some(&get_mock_canister_id_configuration())
+        );
+    }
+}
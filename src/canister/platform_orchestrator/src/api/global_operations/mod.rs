@@ -0,0 +1,63 @@
+pub mod start_upgrades_for_all_user_index_canisters;
+pub mod update_well_known_principal_on_all_user_index_canisters;
+
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::data_model::CanisterData;
+
+const MAX_CONCURRENT_USER_INDEX_CALLS: usize = 10;
+
+/// The registered user_index canisters, if the caller may run global operations on them
+fn get_user_index_canister_ids_for_global_operation(
+    caller: Principal,
+    canister_data: &CanisterData,
+) -> Result<Vec<Principal>, String> {
+    let super_admin = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(canister_data.user_index_canisters.keys().copied().collect())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_user_index,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_index_canister_ids_for_global_operation() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data
+            .user_index_canisters
+            .insert(get_mock_canister_id_user_index(), Default::default());
+
+        assert_eq!(
+            get_user_index_canister_ids_for_global_operation(
+                get_mock_user_alice_principal_id(),
+                &canister_data
+            ),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            get_user_index_canister_ids_for_global_operation(
+                get_global_super_admin_principal_id(),
+                &canister_data
+            ),
+            Ok(vec![get_mock_canister_id_user_index()])
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::common::utils::task::run_task_concurrently;
+
+use crate::CANISTER_DATA;
+
+use super::{get_user_index_canister_ids_for_global_operation, MAX_CONCURRENT_USER_INDEX_CALLS};
+
+/// Starts the upgrade of the individual user canisters on every registered user_index. Returns
+/// the outcome per user_index.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+async fn start_upgrades_for_all_user_index_canisters(
+) -> Result<Vec<(Principal, Result<(), String>)>, String> {
+    let api_caller = ic_cdk::caller();
+    let user_index_canister_ids = CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_index_canister_ids_for_global_operation(
+            api_caller,
+            &canister_data_ref_cell.borrow(),
+        )
+    })?;
+
+    let start_upgrade_futures =
+        user_index_canister_ids
+            .into_iter()
+            .map(|user_index_canister_id| async move {
+                let result = call::call::<_, (String,)>(
+                    user_index_canister_id,
+                    "start_upgrades_for_individual_canisters",
+                    (),
+                )
+                .await
+                .map_err(|e| e.1)
+                .and_then(|(response,)| match response.as_str() {
+                    "Success" => Ok(()),
+                    _ => Err(response),
+                });
+
+                (user_index_canister_id, result)
+            });
+
+    let mut results = vec![];
+    run_task_concurrently(
+        start_upgrade_futures,
+        MAX_CONCURRENT_USER_INDEX_CALLS,
+        |result| results.push(result),
+        || false,
+    )
+    .await;
+
+    Ok(results)
+}
@@ -0,0 +1,68 @@
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::common::{
+    types::known_principal::KnownPrincipalType, utils::task::run_task_concurrently,
+};
+
+use crate::CANISTER_DATA;
+
+use super::{get_user_index_canister_ids_for_global_operation, MAX_CONCURRENT_USER_INDEX_CALLS};
+
+/// Sets a well-known principal here and on every registered user_index. Returns the outcome per
+/// user_index. The super admin and platform orchestrator principals can only be changed by the
+/// super admin on each user_index directly.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+async fn update_well_known_principal_on_all_user_index_canisters(
+    principal_type: KnownPrincipalType,
+    principal_value: Principal,
+) -> Result<Vec<(Principal, Result<(), String>)>, String> {
+    let api_caller = ic_cdk::caller();
+    let user_index_canister_ids = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        let user_index_canister_ids =
+            get_user_index_canister_ids_for_global_operation(api_caller, &canister_data)?;
+        if matches!(
+            principal_type,
+            KnownPrincipalType::UserIdGlobalSuperAdmin
+                | KnownPrincipalType::CanisterIdPlatformOrchestrator
+        ) {
+            return Err(
+                "The super admin and platform orchestrator can't be changed through the orchestrator"
+                    .to_string(),
+            );
+        }
+        canister_data
+            .known_principal_ids
+            .insert(principal_type, principal_value);
+
+        Ok::<_, String>(user_index_canister_ids)
+    })?;
+
+    let update_futures =
+        user_index_canister_ids
+            .into_iter()
+            .map(|user_index_canister_id| async move {
+                let result = call::call::<_, (Result<(), String>,)>(
+                    user_index_canister_id,
+                    "update_well_known_principal",
+                    (principal_type, principal_value),
+                )
+                .await
+                .map_err(|e| e.1)
+                .and_then(|(response,)| response);
+
+                (user_index_canister_id, result)
+            });
+
+    let mut results = vec![];
+    run_task_concurrently(
+        update_futures,
+        MAX_CONCURRENT_USER_INDEX_CALLS,
+        |result| results.push(result),
+        || false,
+    )
+    .await;
+
+    Ok(results)
+}
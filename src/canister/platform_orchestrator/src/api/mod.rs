@@ -0,0 +1,3 @@
+pub mod canister_lifecycle;
+pub mod global_operations;
+pub mod user_index_registry;
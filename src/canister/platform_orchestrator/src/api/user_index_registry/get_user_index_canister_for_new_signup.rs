@@ -0,0 +1,14 @@
+use candid::Principal;
+
+use crate::CANISTER_DATA;
+
+/// The user_index new users should sign up on. `None` when no subnet has capacity left.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_index_canister_for_new_signup() -> Option<Principal> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .get_user_index_canister_for_new_signup()
+    })
+}
@@ -0,0 +1,16 @@
+use candid::Principal;
+
+use crate::CANISTER_DATA;
+
+/// The user_index that owns `user_principal_id`
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_index_canister_id_of_user(user_principal_id: Principal) -> Option<Principal> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .user_principal_id_to_user_index_canister_id_map
+            .get(&user_principal_id)
+            .copied()
+    })
+}
@@ -0,0 +1,18 @@
+use candid::Principal;
+
+use crate::{data_model::UserIndexRegistration, CANISTER_DATA};
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_index_canisters() -> Vec<(Principal, UserIndexRegistration)> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .user_index_canisters
+            .iter()
+            .map(|(user_index_canister_id, registration)| {
+                (*user_index_canister_id, registration.clone())
+            })
+            .collect()
+    })
+}
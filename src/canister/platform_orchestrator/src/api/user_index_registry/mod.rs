@@ -0,0 +1,8 @@
+pub mod get_user_index_canister_for_new_signup;
+pub mod get_user_index_canister_id_of_user;
+pub mod get_user_index_canisters;
+pub mod register_user_index_canister;
+pub mod report_deleted_user_of_user_index;
+pub mod report_new_user_of_user_index;
+pub mod set_user_index_signups_open;
+pub mod sync_users_of_user_index_canisters;
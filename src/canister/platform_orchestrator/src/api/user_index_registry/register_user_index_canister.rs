@@ -0,0 +1,120 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{
+    data_model::{CanisterData, UserIndexRegistration},
+    CANISTER_DATA,
+};
+
+/// Adds a user_index to the federation, or updates the subnet and capacity of one already
+/// registered. New user_index canisters take signups right away.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn register_user_index_canister(
+    user_index_canister_id: Principal,
+    subnet_id: Principal,
+    user_capacity: u64,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        register_user_index_canister_impl(
+            api_caller,
+            user_index_canister_id,
+            subnet_id,
+            user_capacity,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn register_user_index_canister_impl(
+    caller: Principal,
+    user_index_canister_id: Principal,
+    subnet_id: Principal,
+    user_capacity: u64,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    let registration = canister_data
+        .user_index_canisters
+        .entry(user_index_canister_id)
+        .or_insert_with(|| UserIndexRegistration {
+            signups_open: true,
+            ..Default::default()
+        });
+    registration.subnet_id = subnet_id;
+    registration.user_capacity = user_capacity;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_user_index,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_register_user_index_canister_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let subnet_id = Principal::from_slice(&[1]);
+
+        let result = register_user_index_canister_impl(
+            get_mock_user_alice_principal_id(),
+            get_mock_canister_id_user_index(),
+            subnet_id,
+            100,
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = register_user_index_canister_impl(
+            get_global_super_admin_principal_id(),
+            get_mock_canister_id_user_index(),
+            subnet_id,
+            100,
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+
+        // * registering again keeps the user count
+        canister_data
+            .user_index_canisters
+            .get_mut(&get_mock_canister_id_user_index())
+            .unwrap()
+            .user_count = 10;
+        let result = register_user_index_canister_impl(
+            get_global_super_admin_principal_id(),
+            get_mock_canister_id_user_index(),
+            subnet_id,
+            200,
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data.user_index_canisters[&get_mock_canister_id_user_index()],
+            UserIndexRegistration {
+                subnet_id,
+                user_capacity: 200,
+                user_count: 10,
+                signups_open: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use candid::Principal;
+
+use crate::CANISTER_DATA;
+
+/// Called by a registered user_index before it provisions a canister for a new user. Fails if
+/// another user_index owns the user.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn report_new_user_of_user_index(user_principal_id: Principal) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .record_user_of_user_index(api_caller, user_principal_id)
+    })
+}
@@ -0,0 +1,93 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// A user_index with signups closed is no longer handed new users, whatever its capacity
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_user_index_signups_open(
+    user_index_canister_id: Principal,
+    signups_open: bool,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_user_index_signups_open_impl(
+            api_caller,
+            user_index_canister_id,
+            signups_open,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn set_user_index_signups_open_impl(
+    caller: Principal,
+    user_index_canister_id: Principal,
+    signups_open: bool,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data
+        .user_index_canisters
+        .get_mut(&user_index_canister_id)
+        .ok_or("User index canister not registered")?
+        .signups_open = signups_open;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_user_index,
+    };
+
+    use crate::data_model::UserIndexRegistration;
+
+    use super::*;
+
+    #[test]
+    fn test_set_user_index_signups_open_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result = set_user_index_signups_open_impl(
+            get_global_super_admin_principal_id(),
+            get_mock_canister_id_user_index(),
+            false,
+            &mut canister_data,
+        );
+        assert!(result.is_err());
+
+        canister_data.user_index_canisters.insert(
+            get_mock_canister_id_user_index(),
+            UserIndexRegistration {
+                signups_open: true,
+                ..Default::default()
+            },
+        );
+        let result = set_user_index_signups_open_impl(
+            get_global_super_admin_principal_id(),
+            get_mock_canister_id_user_index(),
+            false,
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert!(
+            !canister_data.user_index_canisters[&get_mock_canister_id_user_index()].signups_open
+        );
+    }
+}
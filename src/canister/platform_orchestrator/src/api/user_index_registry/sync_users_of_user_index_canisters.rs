@@ -0,0 +1,176 @@
+use std::cell::Cell;
+
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::common::{
+    types::known_principal::KnownPrincipalType, utils::task::InProgressGuard,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+const USER_PRINCIPAL_IDS_PAGE_SIZE: u64 = 1000;
+
+thread_local! {
+    static IS_USER_SYNC_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Pages through the users of every registered user_index, so that users indexed before the
+/// user_index was registered, or whose report got lost, can be looked up
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn sync_users_of_user_index_canisters() -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        sync_users_of_user_index_canisters_impl(api_caller, &canister_data_ref_cell.borrow())
+    })?;
+
+    let Some(user_sync_in_progress_guard) = InProgressGuard::acquire(&IS_USER_SYNC_IN_PROGRESS)
+    else {
+        return Err("A user sync is already in progress".to_string());
+    };
+
+    ic_cdk::spawn(async move {
+        let _guard = user_sync_in_progress_guard;
+        sync_users().await;
+    });
+
+    Ok(())
+}
+
+fn sync_users_of_user_index_canisters_impl(
+    caller: Principal,
+    canister_data: &CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(())
+}
+
+async fn sync_users() {
+    let user_index_canister_ids: Vec<Principal> = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .user_index_canisters
+            .keys()
+            .copied()
+            .collect()
+    });
+
+    for user_index_canister_id in user_index_canister_ids {
+        if let Err(e) = sync_users_of_user_index_canister(user_index_canister_id).await {
+            ic_cdk::print(format!(
+                "Failed to sync users of user index canister {}: {}",
+                user_index_canister_id.to_text(),
+                e
+            ));
+        }
+    }
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        recount_users_of_user_index_canisters(&mut canister_data_ref_cell.borrow_mut());
+    });
+}
+
+async fn sync_users_of_user_index_canister(
+    user_index_canister_id: Principal,
+) -> Result<(), String> {
+    let mut start_after: Option<Principal> = None;
+
+    loop {
+        let (user_principal_ids,): (Result<Vec<Principal>, String>,) = call::call(
+            user_index_canister_id,
+            "get_user_principal_ids_paginated",
+            (start_after, USER_PRINCIPAL_IDS_PAGE_SIZE),
+        )
+        .await
+        .map_err(|e| e.1)?;
+        let user_principal_ids = user_principal_ids?;
+
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            let mut canister_data = canister_data_ref_cell.borrow_mut();
+            for user_principal_id in user_principal_ids.iter() {
+                if let Err(e) = canister_data
+                    .record_user_of_user_index(user_index_canister_id, *user_principal_id)
+                {
+                    ic_cdk::print(format!("User {}: {}", user_principal_id.to_text(), e));
+                }
+            }
+        });
+
+        if (user_principal_ids.len() as u64) < USER_PRINCIPAL_IDS_PAGE_SIZE {
+            return Ok(());
+        }
+        start_after = user_principal_ids.last().copied();
+    }
+}
+
+/// The user counts are rebuilt from the users recorded, so that counts off from lost reports are
+/// corrected
+fn recount_users_of_user_index_canisters(canister_data: &mut CanisterData) {
+    canister_data
+        .user_index_canisters
+        .values_mut()
+        .for_each(|registration| registration.user_count = 0);
+
+    for user_index_canister_id in canister_data
+        .user_principal_id_to_user_index_canister_id_map
+        .values()
+    {
+        if let Some(registration) = canister_data
+            .user_index_canisters
+            .get_mut(user_index_canister_id)
+        {
+            registration.user_count += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_model::UserIndexRegistration;
+
+    use super::*;
+
+    #[test]
+    fn test_recount_users_of_user_index_canisters() {
+        let mut canister_data = CanisterData::default();
+        canister_data.user_index_canisters.insert(
+            Principal::from_slice(&[1]),
+            UserIndexRegistration {
+                user_count: 5,
+                ..Default::default()
+            },
+        );
+        canister_data.user_index_canisters.insert(
+            Principal::from_slice(&[2]),
+            UserIndexRegistration::default(),
+        );
+        for i in 0..3 {
+            canister_data
+                .user_principal_id_to_user_index_canister_id_map
+                .insert(
+                    Principal::self_authenticating([i]),
+                    Principal::from_slice(&[2]),
+                );
+        }
+
+        recount_users_of_user_index_canisters(&mut canister_data);
+
+        assert_eq!(
+            canister_data.user_index_canisters[&Principal::from_slice(&[1])].user_count,
+            0
+        );
+        assert_eq!(
+            canister_data.user_index_canisters[&Principal::from_slice(&[2])].user_count,
+            3
+        );
+    }
+}
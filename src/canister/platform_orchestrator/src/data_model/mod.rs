@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+use shared_utils::common::types::known_principal::KnownPrincipalMap;
+
+#[derive(Default, CandidType, Serialize, Deserialize)]
+pub struct CanisterData {
+    pub known_principal_ids: KnownPrincipalMap,
+    pub user_index_canisters: BTreeMap<Principal, UserIndexRegistration>,
+    pub user_principal_id_to_user_index_canister_id_map: BTreeMap<Principal, Principal>,
+}
+
+/// A user_index canister and the subnet it runs on
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserIndexRegistration {
+    pub subnet_id: Principal,
+    /// Number of users the user_index takes before new signups go elsewhere
+    pub user_capacity: u64,
+    pub user_count: u64,
+    pub signups_open: bool,
+}
+
+impl Default for UserIndexRegistration {
+    fn default() -> Self {
+        Self {
+            subnet_id: Principal::anonymous(),
+            user_capacity: 0,
+            user_count: 0,
+            signups_open: false,
+        }
+    }
+}
+
+impl UserIndexRegistration {
+    pub fn get_remaining_capacity(&self) -> u64 {
+        self.user_capacity.saturating_sub(self.user_count)
+    }
+}
+
+impl CanisterData {
+    /// The user_index with signups open that has the most room left
+    pub fn get_user_index_canister_for_new_signup(&self) -> Option<Principal> {
+        self.user_index_canisters
+            .iter()
+            .filter(|(_, registration)| {
+                registration.signups_open && registration.get_remaining_capacity() > 0
+            })
+            .max_by_key(|(_, registration)| registration.get_remaining_capacity())
+            .map(|(user_index_canister_id, _)| *user_index_canister_id)
+    }
+
+    /// Records that `user_principal_id` is indexed on `user_index_canister_id`. A user already
+    /// owned by another user_index keeps its owner.
+    pub fn record_user_of_user_index(
+        &mut self,
+        user_index_canister_id: Principal,
+        user_principal_id: Principal,
+    ) -> Result<(), String> {
+        let registration = self
+            .user_index_canisters
+            .get_mut(&user_index_canister_id)
+            .ok_or("User index canister not registered")?;
+
+        match self
+            .user_principal_id_to_user_index_canister_id_map
+            .get(&user_principal_id)
+        {
+            Some(owner) if *owner == user_index_canister_id => Ok(()),
+            Some(owner) => Err(format!(
+                "User already owned by user index canister {}",
+                owner.to_text()
+            )),
+            None => {
+                self.user_principal_id_to_user_index_canister_id_map
+                    .insert(user_principal_id, user_index_canister_id);
+                registration.user_count += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Forgets `user_principal_id`, whose account was deleted on `user_index_canister_id`. Only the
+    /// user_index that owns the user can forget them.
+    pub fn forget_user_of_user_index(
+        &mut self,
+        user_index_canister_id: Principal,
+        user_principal_id: Principal,
+    ) -> Result<(), String> {
+        let registration = self
+            .user_index_canisters
+            .get_mut(&user_index_canister_id)
+            .ok_or("User index canister not registered")?;
+
+        match self
+            .user_principal_id_to_user_index_canister_id_map
+            .get(&user_principal_id)
+        {
+            Some(owner) if *owner == user_index_canister_id => {
+                self.user_principal_id_to_user_index_canister_id_map
+                    .remove(&user_principal_id);
+                registration.user_count = registration.user_count.saturating_sub(1);
+                Ok(())
+            }
+            Some(owner) => Err(format!(
+                "User owned by user index canister {}",
+                owner.to_text()
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_registration(user_capacity: u64, user_count: u64) -> UserIndexRegistration {
+        UserIndexRegistration {
+            subnet_id: Principal::anonymous(),
+            user_capacity,
+            user_count,
+            signups_open: true,
+        }
+    }
+
+    #[test]
+    fn test_get_user_index_canister_for_new_signup() {
+        let mut canister_data = CanisterData::default();
+        assert_eq!(canister_data.get_user_index_canister_for_new_signup(), None);
+
+        canister_data
+            .user_index_canisters
+            .insert(Principal::from_slice(&[1]), get_registration(100, 90));
+        canister_data
+            .user_index_canisters
+            .insert(Principal::from_slice(&[2]), get_registration(100, 50));
+        canister_data
+            .user_index_canisters
+            .insert(Principal::from_slice(&[3]), get_registration(100, 100));
+        assert_eq!(
+            canister_data.get_user_index_canister_for_new_signup(),
+            Some(Principal::from_slice(&[2]))
+        );
+
+        canister_data
+            .user_index_canisters
+            .get_mut(&Principal::from_slice(&[2]))
+            .unwrap()
+            .signups_open = false;
+        assert_eq!(
+            canister_data.get_user_index_canister_for_new_signup(),
+            Some(Principal::from_slice(&[1]))
+        );
+    }
+
+    #[test]
+    fn test_record_user_of_user_index() {
+        let mut canister_data = CanisterData::default();
+        let user_index_canister_id = Principal::from_slice(&[1]);
+        let user_principal_id = Principal::self_authenticating([1]);
+
+        assert!(canister_data
+            .record_user_of_user_index(user_index_canister_id, user_principal_id)
+            .is_err());
+
+        canister_data
+            .user_index_canisters
+            .insert(user_index_canister_id, get_registration(100, 0));
+        canister_data
+            .user_index_canisters
+            .insert(Principal::from_slice(&[2]), get_registration(100, 0));
+
+        assert!(canister_data
+            .record_user_of_user_index(user_index_canister_id, user_principal_id)
+            .is_ok());
+        // * reporting the same user again is not counted twice
+        assert!(canister_data
+            .record_user_of_user_index(user_index_canister_id, user_principal_id)
+            .is_ok());
+        assert!(canister_data
+            .record_user_of_user_index(Principal::from_slice(&[2]), user_principal_id)
+            .is_err());
+
+        assert_eq!(
+            canister_data.user_index_canisters[&user_index_canister_id].user_count,
+            1
+        );
+        assert_eq!(
+            canister_data.user_principal_id_to_user_index_canister_id_map[&user_principal_id],
+            user_index_canister_id
+        );
+    }
+
+    #[test]
+    fn test_forget_user_of_user_index() {
+        let mut canister_data = CanisterData::default();
+        let user_index_canister_id = Principal::from_slice(&[1]);
+        let user_principal_id = Principal::self_authenticating([1]);
+        canister_data
+            .user_index_canisters
+            .insert(user_index_canister_id, get_registration(100, 0));
+        canister_data
+            .user_index_canisters
+            .insert(Principal::from_slice(&[2]), get_registration(100, 0));
+        canister_data
+            .record_user_of_user_index(user_index_canister_id, user_principal_id)
+            .unwrap();
+
+        assert!(canister_data
+            .forget_user_of_user_index(Principal::from_slice(&[2]), user_principal_id)
+            .is_err());
+        assert!(canister_data
+            .forget_user_of_user_index(user_index_canister_id, user_principal_id)
+            .is_ok());
+        // * forgetting the user again is a no-op
+        assert!(canister_data
+            .forget_user_of_user_index(user_index_canister_id, user_principal_id)
+            .is_ok());
+
+        assert_eq!(
+            canister_data.user_index_canisters[&user_index_canister_id].user_count,
+            0
+        );
+        assert!(canister_data
+            .user_principal_id_to_user_index_canister_id_map
+            .is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+use std::cell::RefCell;
+
+use candid::{export_service, Principal};
+use data_model::{CanisterData, UserIndexRegistration};
+use shared_utils::{
+    canister_specific::platform_orchestrator::types::args::PlatformOrchestratorInitArgs,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+mod api;
+mod data_model;
+#[cfg(test)]
+mod test;
+
+thread_local! {
+    static CANISTER_DATA: RefCell<CanisterData> = RefCell::default();
+}
+
+#[ic_cdk::query(name = "__get_candid_interface_tmp_hack")]
+fn export_candid() -> String {
+    export_service!();
+    __export_service()
+}
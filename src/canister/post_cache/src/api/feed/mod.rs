@@ -1,2 +1,3 @@
 pub mod remove_all_feed_entries;
+pub mod remove_feed_entries_of_deleted_user_canister;
 pub mod trigger_update_indexes;
@@ -0,0 +1,207 @@
+use candid::Principal;
+use shared_utils::common::types::{
+    known_principal::KnownPrincipalType, top_posts::post_score_index_item::PostScoreIndexItem,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Removes the posts published by a canister from every feed index and snapshot, when the
+/// account of its user is deleted. Returns the number of posts removed.
+///
+/// # Access Control
+/// Only the user index canister can call this.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn remove_feed_entries_of_deleted_user_canister(
+    publisher_canister_id: Principal,
+) -> Result<u64, String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        remove_feed_entries_of_deleted_user_canister_impl(
+            &mut canister_data_ref_cell.borrow_mut(),
+            api_caller,
+            publisher_canister_id,
+        )
+    })
+}
+
+fn remove_feed_entries_of_deleted_user_canister_impl(
+    canister_data: &mut CanisterData,
+    api_caller: Principal,
+    publisher_canister_id: Principal,
+) -> Result<u64, String> {
+    let user_index_canister_id = canister_data
+        .known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdUserIndex)
+        .ok_or("User index canister not found in internal records")?;
+
+    if api_caller != *user_index_canister_id {
+        return Err("Unauthorized".to_string());
+    }
+
+    let mut removed_post_ids: Vec<u64> = vec![];
+
+    let home_feed_items: Vec<_> = canister_data
+        .posts_index_sorted_by_home_feed_score_v1
+        .item_presence_index
+        .values()
+        .filter(|item| item.publisher_canister_id == publisher_canister_id)
+        .cloned()
+        .collect();
+    home_feed_items.iter().for_each(|item| {
+        canister_data
+            .posts_index_sorted_by_home_feed_score_v1
+            .remove(item);
+        removed_post_ids.push(item.post_id);
+    });
+
+    let hot_or_not_feed_items: Vec<_> = canister_data
+        .posts_index_sorted_by_hot_or_not_feed_score_v1
+        .item_presence_index
+        .values()
+        .filter(|item| item.publisher_canister_id == publisher_canister_id)
+        .cloned()
+        .collect();
+    hot_or_not_feed_items.iter().for_each(|item| {
+        canister_data
+            .posts_index_sorted_by_hot_or_not_feed_score_v1
+            .remove(item);
+        removed_post_ids.push(item.post_id);
+    });
+
+    for index in [
+        &mut canister_data.posts_index_sorted_by_home_feed_score,
+        &mut canister_data.posts_index_sorted_by_hot_or_not_feed_score,
+    ] {
+        let post_ids: Vec<u64> = index
+            .item_presence_index
+            .keys()
+            .filter(|(canister_id, _)| *canister_id == publisher_canister_id)
+            .map(|(_, post_id)| *post_id)
+            .collect();
+        post_ids.iter().for_each(|post_id| {
+            index.remove(&PostScoreIndexItem {
+                score: 0,
+                post_id: *post_id,
+                publisher_canister_id,
+            });
+        });
+        removed_post_ids.extend(post_ids);
+    }
+
+    canister_data
+        .home_feed_snapshots
+        .snapshots
+        .values_mut()
+        .for_each(|snapshot| {
+            snapshot.retain(|(_, (canister_id, _))| *canister_id != publisher_canister_id)
+        });
+
+    removed_post_ids.sort_unstable();
+    removed_post_ids.dedup();
+
+    Ok(removed_post_ids.len() as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::common::types::top_posts::post_score_index_item::{
+        PostScoreIndexItemV1, PostStatus,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_canister_id_user_index, get_mock_user_alice_canister_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    fn get_item(post_id: u64, publisher_canister_id: Principal) -> PostScoreIndexItemV1 {
+        PostScoreIndexItemV1 {
+            score: post_id,
+            post_id,
+            publisher_canister_id,
+            is_nsfw: false,
+            created_at: None,
+            status: PostStatus::ReadyToView,
+        }
+    }
+
+    #[test]
+    fn test_remove_feed_entries_of_deleted_user_canister_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            get_mock_canister_id_user_index(),
+        );
+
+        for item in [
+            get_item(0, get_mock_user_alice_canister_id()),
+            get_item(1, get_mock_user_alice_canister_id()),
+            get_item(0, get_mock_user_bob_canister_id()),
+        ] {
+            canister_data
+                .posts_index_sorted_by_home_feed_score_v1
+                .replace(&item);
+            canister_data
+                .posts_index_sorted_by_hot_or_not_feed_score_v1
+                .replace(&item);
+            canister_data
+                .posts_index_sorted_by_home_feed_score
+                .replace(&PostScoreIndexItem {
+                    score: item.score,
+                    post_id: item.post_id,
+                    publisher_canister_id: item.publisher_canister_id,
+                });
+        }
+        canister_data.home_feed_snapshots.take_snapshot(
+            canister_data
+                .posts_index_sorted_by_home_feed_score_v1
+                .item_presence_index
+                .values(),
+        );
+
+        assert_eq!(
+            remove_feed_entries_of_deleted_user_canister_impl(
+                &mut canister_data,
+                get_mock_user_alice_canister_id(),
+                get_mock_user_alice_canister_id(),
+            ),
+            Err("Unauthorized".to_string())
+        );
+
+        assert_eq!(
+            remove_feed_entries_of_deleted_user_canister_impl(
+                &mut canister_data,
+                get_mock_canister_id_user_index(),
+                get_mock_user_alice_canister_id(),
+            ),
+            Ok(2)
+        );
+
+        let is_from_bob = |canister_id: &Principal| *canister_id == get_mock_user_bob_canister_id();
+        assert!(canister_data
+            .posts_index_sorted_by_home_feed_score_v1
+            .iter()
+            .all(|item| is_from_bob(&item.publisher_canister_id)));
+        assert!(canister_data
+            .posts_index_sorted_by_hot_or_not_feed_score_v1
+            .iter()
+            .all(|item| is_from_bob(&item.publisher_canister_id)));
+        assert_eq!(
+            canister_data
+                .posts_index_sorted_by_home_feed_score
+                .iter()
+                .count(),
+            1
+        );
+        assert_eq!(
+            canister_data
+                .home_feed_snapshots
+                .get(canister_data.home_feed_snapshots.current_generation)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -1,7 +1,4 @@
-use std::{
-    cell::RefCell,
-    time::{Duration, SystemTime},
-};
+use std::time::{Duration, SystemTime};
 
 use candid::Principal;
 use ic_cdk::notify;
@@ -15,6 +12,7 @@ use crate::{data_model::CanisterData, CANISTER_DATA};
 const TRIGGER_UPDATE_HOT_OR_NOT_INDEX: Duration = Duration::from_secs(60 * 60);
 const TRIGGER_RECONCILE_SCORES: Duration = Duration::from_secs(60 * 60 * 5);
 const RECONCILE_SCORES_UPTO: usize = 100;
+const TRIGGER_REFRESH_HOME_FEED_SNAPSHOT: Duration = Duration::from_secs(60 * 10);
 
 pub fn trigger_update_hot_or_not_index() {
     let last_updated_hot_or_not_timestamp_index = CANISTER_DATA.with(|canister_data| {
@@ -75,6 +73,38 @@ pub fn trigger_update_hot_or_not_index() {
     }
 }
 
+pub fn trigger_refresh_home_feed_snapshot() {
+    CANISTER_DATA.with(|canister_data| {
+        let mut canister_data = canister_data.borrow_mut();
+
+        refresh_home_feed_snapshot_if_due(&mut canister_data, get_current_system_time());
+    });
+}
+
+fn refresh_home_feed_snapshot_if_due(canister_data: &mut CanisterData, now: SystemTime) {
+    let is_due = match canister_data.metadata.last_updated_home_feed_snapshot {
+        Some(last_updated) => {
+            now.duration_since(last_updated).unwrap_or_default()
+                >= TRIGGER_REFRESH_HOME_FEED_SNAPSHOT
+        }
+        None => true,
+    };
+
+    if !is_due {
+        return;
+    }
+
+    let CanisterData {
+        posts_index_sorted_by_home_feed_score_v1,
+        home_feed_snapshots,
+        metadata,
+        ..
+    } = canister_data;
+
+    home_feed_snapshots.take_snapshot(posts_index_sorted_by_home_feed_score_v1.iter());
+    metadata.last_updated_home_feed_snapshot = Some(now);
+}
+
 // TODO: Add integration tests
 pub fn trigger_reconcile_scores() {
     let last_updated_reconcile_scores = CANISTER_DATA.with(|canister_data| {
@@ -159,6 +189,59 @@ pub fn trigger_reconcile_scores() {
     }
 }
 
+#[cfg(test)]
+mod test {
+    use candid::Principal;
+    use shared_utils::common::types::top_posts::post_score_index_item::PostStatus;
+
+    use crate::data_model::MAX_FEED_SNAPSHOTS_RETAINED;
+
+    use super::*;
+
+    #[test]
+    fn test_refresh_home_feed_snapshot_if_due() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        canister_data
+            .posts_index_sorted_by_home_feed_score_v1
+            .replace(&PostScoreIndexItemV1 {
+                score: 1,
+                post_id: 1,
+                publisher_canister_id: Principal::from_text("w4nuc-waaaa-aaaao-aal2a-cai").unwrap(),
+                is_nsfw: false,
+                status: PostStatus::ReadyToView,
+                created_at: Some(now),
+            });
+
+        // * first snapshot is taken right away
+        refresh_home_feed_snapshot_if_due(&mut canister_data, now);
+        assert_eq!(canister_data.home_feed_snapshots.current_generation, 1);
+        assert_eq!(canister_data.home_feed_snapshots.get(1).unwrap().len(), 1);
+
+        // * no new snapshot before the refresh interval elapses
+        refresh_home_feed_snapshot_if_due(&mut canister_data, now + Duration::from_secs(60));
+        assert_eq!(canister_data.home_feed_snapshots.current_generation, 1);
+
+        // * only the latest snapshots are retained
+        for i in 1..=MAX_FEED_SNAPSHOTS_RETAINED as u32 + 1 {
+            refresh_home_feed_snapshot_if_due(
+                &mut canister_data,
+                now + TRIGGER_REFRESH_HOME_FEED_SNAPSHOT * i,
+            );
+        }
+        assert_eq!(
+            canister_data.home_feed_snapshots.current_generation,
+            MAX_FEED_SNAPSHOTS_RETAINED as u64 + 2
+        );
+        assert_eq!(
+            canister_data.home_feed_snapshots.snapshots.len(),
+            MAX_FEED_SNAPSHOTS_RETAINED
+        );
+        assert!(canister_data.home_feed_snapshots.get(1).is_none());
+    }
+}
+
 #[cfg(all(test, feature = "mockdata"))]
 mod tests {
 
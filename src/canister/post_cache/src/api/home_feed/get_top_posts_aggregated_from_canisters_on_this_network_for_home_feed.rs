@@ -1,8 +1,13 @@
 use crate::{data_model::CanisterData, CANISTER_DATA};
 use shared_utils::{
-    common::types::top_posts::post_score_index_item::{
-        PostScoreIndexItem, PostScoreIndexItemV1, PostStatus,
+    canister_specific::post_cache::types::feed_cursor::{
+        feed_order_key, FeedCursor, FeedCursorToken, FeedPage,
     },
+    common::types::top_posts::{
+        post_score_index_item::{PostScoreIndexItem, PostScoreIndexItemV1, PostStatus},
+        GlobalPostId, Score,
+    },
+    constant::MAX_POSTS_IN_ONE_REQUEST,
     pagination::{self, PaginationError},
     types::canister_specific::post_cache::error_types::TopPostsFetchError,
 };
@@ -122,6 +127,137 @@ fn get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_cursor_
         .collect::<Vec<PostScoreIndexItemV1>>())
 }
 
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor(
+    cursor: Option<FeedCursorToken>,
+    limit: u64,
+    is_nsfw: Option<bool>,
+    status: Option<PostStatus>,
+) -> Result<FeedPage, TopPostsFetchError> {
+    CANISTER_DATA.with(|canister_data| {
+        let canister_data = canister_data.borrow();
+
+        get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+            cursor,
+            limit,
+            &canister_data,
+            is_nsfw,
+            status,
+        )
+    })
+}
+
+fn get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+    cursor: Option<FeedCursorToken>,
+    limit: u64,
+    canister_data: &CanisterData,
+    is_nsfw: Option<bool>,
+    status: Option<PostStatus>,
+) -> Result<FeedPage, TopPostsFetchError> {
+    if limit == 0 {
+        return Err(TopPostsFetchError::InvalidBoundsPassed);
+    }
+
+    if limit > MAX_POSTS_IN_ONE_REQUEST {
+        return Err(TopPostsFetchError::ExceededMaxNumberOfItemsAllowedInOneRequest);
+    }
+
+    let cursor = match cursor {
+        Some(token) => {
+            Some(FeedCursor::from_token(&token).ok_or(TopPostsFetchError::InvalidCursor)?)
+        }
+        None => None,
+    };
+
+    let live_index = &canister_data.posts_index_sorted_by_home_feed_score_v1;
+    let snapshots = &canister_data.home_feed_snapshots;
+
+    // * Continue over the snapshot the cursor was issued against. If it has been
+    // * evicted, resume from the same position in the latest snapshot instead.
+    let live_ordering: Vec<(Score, GlobalPostId)>;
+    let (snapshot_generation, ordering) = match cursor
+        .as_ref()
+        .and_then(|cursor| {
+            snapshots
+                .get(cursor.snapshot_generation)
+                .map(|snapshot| (cursor.snapshot_generation, snapshot))
+        })
+        .or_else(|| {
+            snapshots
+                .get(snapshots.current_generation)
+                .map(|snapshot| (snapshots.current_generation, snapshot))
+        }) {
+        Some((generation, snapshot)) => (generation, snapshot.as_slice()),
+        None => {
+            let mut ordering: Vec<(Score, GlobalPostId)> = live_index
+                .iter()
+                .map(|item| (item.score, (item.publisher_canister_id, item.post_id)))
+                .collect();
+            ordering.sort_by_key(|(score, global_post_id)| feed_order_key(*score, global_post_id));
+            live_ordering = ordering;
+            (snapshots.current_generation, live_ordering.as_slice())
+        }
+    };
+
+    let filter_fn = |post_item: &PostScoreIndexItemV1| {
+        if let Some(is_nsfw) = is_nsfw {
+            if post_item.is_nsfw != is_nsfw {
+                return false;
+            }
+        }
+
+        if let Some(status) = status {
+            if post_item.status != status {
+                return false;
+            }
+        }
+
+        true
+    };
+
+    let mut remaining_posts = ordering
+        .iter()
+        .filter(|(score, global_post_id)| match &cursor {
+            Some(cursor) => cursor.is_item_after(*score, global_post_id),
+            None => true,
+        })
+        .filter_map(|(score, global_post_id)| {
+            live_index
+                .item_presence_index
+                .get(global_post_id)
+                .map(|post_item| (*score, post_item))
+        })
+        .filter(|(_, post_item)| filter_fn(post_item));
+
+    let page: Vec<(Score, &PostScoreIndexItemV1)> =
+        remaining_posts.by_ref().take(limit as usize).collect();
+
+    let Some((last_seen_score, last_seen_post_item)) = page.last() else {
+        return Err(TopPostsFetchError::ReachedEndOfItemsList);
+    };
+
+    let next_cursor = remaining_posts.next().map(|_| {
+        FeedCursor {
+            snapshot_generation,
+            last_seen_score: *last_seen_score,
+            last_seen_post: (
+                last_seen_post_item.publisher_canister_id,
+                last_seen_post_item.post_id,
+            ),
+        }
+        .to_token()
+    });
+
+    Ok(FeedPage {
+        posts: page
+            .into_iter()
+            .map(|(_, post_item)| post_item.clone())
+            .collect(),
+        next_cursor,
+    })
+}
+
 #[cfg(test)]
 mod test {
     use candid::Principal;
@@ -443,4 +579,165 @@ mod test {
         assert_eq!(posts.len(), 1);
         assert_eq!(posts[0].post_id, 3);
     }
+
+    fn get_mock_home_feed_post(post_id: u64, score: u64) -> PostScoreIndexItemV1 {
+        PostScoreIndexItemV1 {
+            post_id,
+            score,
+            publisher_canister_id: Principal::anonymous(),
+            is_nsfw: false,
+            status: PostStatus::ReadyToView,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+    ) {
+        let mut canister_data = CanisterData::default();
+
+        for post_id in 1..=5 {
+            canister_data
+                .posts_index_sorted_by_home_feed_score_v1
+                .replace(&get_mock_home_feed_post(post_id, post_id * 10));
+        }
+        canister_data.home_feed_snapshots.take_snapshot(
+            canister_data
+                .posts_index_sorted_by_home_feed_score_v1
+                .iter(),
+        );
+
+        let first_page = super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+            None,
+            2,
+            &canister_data,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            first_page
+                .posts
+                .iter()
+                .map(|post| post.post_id)
+                .collect::<Vec<_>>(),
+            vec![5, 4]
+        );
+        assert!(first_page.next_cursor.is_some());
+
+        // * a new post lands on top and an already served post drops below the cursor
+        canister_data
+            .posts_index_sorted_by_home_feed_score_v1
+            .replace(&get_mock_home_feed_post(6, 100));
+        canister_data
+            .posts_index_sorted_by_home_feed_score_v1
+            .replace(&get_mock_home_feed_post(5, 1));
+
+        let second_page = super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+            first_page.next_cursor,
+            2,
+            &canister_data,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            second_page
+                .posts
+                .iter()
+                .map(|post| post.post_id)
+                .collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        // * posts carry their live details
+        assert_eq!(second_page.posts[0].score, 30);
+
+        let third_page = super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+            second_page.next_cursor,
+            2,
+            &canister_data,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            third_page
+                .posts
+                .iter()
+                .map(|post| post.post_id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(third_page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl_falls_back_to_latest_snapshot(
+    ) {
+        let mut canister_data = CanisterData::default();
+
+        for post_id in 1..=4 {
+            canister_data
+                .posts_index_sorted_by_home_feed_score_v1
+                .replace(&get_mock_home_feed_post(post_id, post_id * 10));
+        }
+
+        // * without any snapshot the live index is paged
+        let first_page = super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+            None,
+            2,
+            &canister_data,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(first_page.posts[0].post_id, 4);
+
+        for _ in 0..=crate::data_model::MAX_FEED_SNAPSHOTS_RETAINED {
+            canister_data.home_feed_snapshots.take_snapshot(
+                canister_data
+                    .posts_index_sorted_by_home_feed_score_v1
+                    .iter(),
+            );
+        }
+
+        let second_page = super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+            first_page.next_cursor,
+            2,
+            &canister_data,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            second_page
+                .posts
+                .iter()
+                .map(|post| post.post_id)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(second_page.next_cursor, None);
+
+        assert_eq!(
+            super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+                Some(vec![0, 1, 2]),
+                2,
+                &canister_data,
+                None,
+                None,
+            ),
+            Err(TopPostsFetchError::InvalidCursor)
+        );
+        assert_eq!(
+            super::get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_stable_cursor_impl(
+                None,
+                MAX_POSTS_IN_ONE_REQUEST + 1,
+                &canister_data,
+                None,
+                None,
+            ),
+            Err(TopPostsFetchError::ExceededMaxNumberOfItemsAllowedInOneRequest)
+        );
+    }
 }
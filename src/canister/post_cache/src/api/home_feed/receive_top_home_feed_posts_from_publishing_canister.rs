@@ -1,14 +1,20 @@
-use shared_utils::common::types::top_posts::post_score_index_item::{
-    PostScoreIndexItem, PostScoreIndexItemV1,
+use shared_utils::{
+    canister_specific::post_cache::types::score_sync::PostScoresReceivedAck,
+    common::types::top_posts::post_score_index_item::{PostScoreIndexItem, PostScoreIndexItemV1},
 };
 
-use crate::{data_model::CanisterData, CANISTER_DATA};
+use crate::{
+    api::feed::trigger_update_indexes::trigger_refresh_home_feed_snapshot,
+    data_model::CanisterData, CANISTER_DATA,
+};
 
 #[ic_cdk::update]
 #[candid::candid_method(update)]
 fn receive_top_home_feed_posts_from_publishing_canister(
     top_posts_from_publishing_canister: Vec<PostScoreIndexItemV1>,
-) {
+) -> PostScoresReceivedAck {
+    let number_of_posts_received = top_posts_from_publishing_canister.len() as u64;
+
     CANISTER_DATA.with(|canister_data| {
         let mut canister_data = canister_data.borrow_mut();
 
@@ -17,6 +23,12 @@ fn receive_top_home_feed_posts_from_publishing_canister(
             &mut canister_data,
         );
     });
+
+    trigger_refresh_home_feed_snapshot();
+
+    PostScoresReceivedAck {
+        number_of_posts_received,
+    }
 }
 
 fn receive_top_home_feed_posts_from_publishing_canister_impl(
@@ -1,6 +1,9 @@
 use shared_utils::common::types::top_posts::post_score_index_item::PostScoreIndexItemV1;
 
-use crate::{data_model::CanisterData, CANISTER_DATA};
+use crate::{
+    api::feed::trigger_update_indexes::trigger_refresh_home_feed_snapshot,
+    data_model::CanisterData, CANISTER_DATA,
+};
 
 #[ic_cdk::update]
 #[candid::candid_method(update)]
@@ -10,6 +13,8 @@ fn update_post_home_feed(post: PostScoreIndexItemV1) {
 
         update_post_home_feed_impl(post, &mut canister_data);
     });
+
+    trigger_refresh_home_feed_snapshot();
 }
 
 fn update_post_home_feed_impl(post: PostScoreIndexItemV1, canister_data: &mut CanisterData) {
@@ -1,5 +1,6 @@
-use shared_utils::common::types::top_posts::post_score_index_item::{
-    PostScoreIndexItem, PostScoreIndexItemV1,
+use shared_utils::{
+    canister_specific::post_cache::types::score_sync::PostScoresReceivedAck,
+    common::types::top_posts::post_score_index_item::{PostScoreIndexItem, PostScoreIndexItemV1},
 };
 
 use crate::{
@@ -14,7 +15,9 @@ use crate::{
 #[candid::candid_method(update)]
 fn receive_top_hot_or_not_feed_posts_from_publishing_canister(
     top_posts_from_publishing_canister: Vec<PostScoreIndexItemV1>,
-) {
+) -> PostScoresReceivedAck {
+    let number_of_posts_received = top_posts_from_publishing_canister.len() as u64;
+
     CANISTER_DATA.with(|canister_data| {
         let mut canister_data = canister_data.borrow_mut();
 
@@ -26,6 +29,10 @@ fn receive_top_hot_or_not_feed_posts_from_publishing_canister(
 
     trigger_update_hot_or_not_index();
     trigger_reconcile_scores();
+
+    PostScoresReceivedAck {
+        number_of_posts_received,
+    }
 }
 
 fn receive_top_hot_or_not_feed_posts_from_publishing_canister_impl(
@@ -1,14 +1,18 @@
-use std::time::SystemTime;
+use std::{collections::BTreeMap, time::SystemTime};
 
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
-use shared_utils::common::types::{
-    known_principal::KnownPrincipalMap,
-    top_posts::{
-        post_score_home_index::PostScoreHomeIndex,
-        post_score_hot_or_not_index::PostScoreHotOrNotIndex, post_score_index::PostScoreIndex,
+use shared_utils::{
+    canister_specific::post_cache::types::feed_cursor::feed_order_key,
+    common::types::{
+        known_principal::KnownPrincipalMap,
+        top_posts::{
+            post_score_home_index::PostScoreHomeIndex,
+            post_score_hot_or_not_index::PostScoreHotOrNotIndex, post_score_index::PostScoreIndex,
+            post_score_index_item::PostScoreIndexItemV1, GlobalPostId, Score,
+        },
+        version_details::VersionDetails,
     },
-    version_details::VersionDetails,
 };
 
 #[derive(Default, CandidType, Deserialize, Serialize)]
@@ -24,10 +28,45 @@ pub struct CanisterData {
     pub metadata: Metadata,
     #[serde(default)]
     pub version_details: VersionDetails,
+    #[serde(default)]
+    pub home_feed_snapshots: FeedSnapshots,
 }
 
 #[derive(Default, CandidType, Deserialize, Serialize)]
 pub struct Metadata {
     pub last_updated_hot_or_not_timestamp_index: Option<SystemTime>,
     pub last_updated_reconcile_scores: Option<SystemTime>,
+    #[serde(default)]
+    pub last_updated_home_feed_snapshot: Option<SystemTime>,
+}
+
+pub const MAX_FEED_SNAPSHOTS_RETAINED: usize = 3;
+
+/// Frozen orderings of a feed index, keyed by generation. Cursors carry the generation
+/// they were issued against so that paging continues over the same ordering even
+/// while scores in the live index keep changing.
+#[derive(Default, CandidType, Deserialize, Serialize)]
+pub struct FeedSnapshots {
+    pub current_generation: u64,
+    pub snapshots: BTreeMap<u64, Vec<(Score, GlobalPostId)>>,
+}
+
+impl FeedSnapshots {
+    pub fn take_snapshot<'a>(&mut self, items: impl Iterator<Item = &'a PostScoreIndexItemV1>) {
+        let mut snapshot: Vec<(Score, GlobalPostId)> = items
+            .map(|item| (item.score, (item.publisher_canister_id, item.post_id)))
+            .collect();
+        snapshot.sort_by_key(|(score, global_post_id)| feed_order_key(*score, global_post_id));
+
+        self.current_generation += 1;
+        self.snapshots.insert(self.current_generation, snapshot);
+
+        while self.snapshots.len() > MAX_FEED_SNAPSHOTS_RETAINED {
+            self.snapshots.pop_first();
+        }
+    }
+
+    pub fn get(&self, generation: u64) -> Option<&Vec<(Score, GlobalPostId)>> {
+        self.snapshots.get(&generation)
+    }
 }
@@ -4,7 +4,11 @@ use candid::{export_service, Principal};
 
 use data_model::CanisterData;
 use shared_utils::{
-    canister_specific::post_cache::types::arg::PostCacheInitArgs,
+    canister_specific::post_cache::types::{
+        arg::PostCacheInitArgs,
+        feed_cursor::{FeedCursorToken, FeedPage},
+        score_sync::PostScoresReceivedAck,
+    },
     common::types::{
         known_principal::KnownPrincipalType,
         top_posts::post_score_index_item::{PostScoreIndexItem, PostScoreIndexItemV1, PostStatus},
@@ -0,0 +1,75 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::common::utils::system_time;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn cancel_my_account_deletion() -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        cancel_my_account_deletion_impl(
+            api_caller,
+            &mut canister_data_ref_cell.borrow_mut(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn cancel_my_account_deletion_impl(
+    caller: Principal,
+    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+) -> Result<(), String> {
+    canister_data
+        .account_deletions
+        .cancel(&caller, current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use crate::data_model::account_deletion::ACCOUNT_DELETION_COOLDOWN;
+
+    use super::*;
+
+    #[test]
+    fn test_cancel_my_account_deletion_impl() {
+        let mut canister_data = CanisterData::default();
+        let alice = get_mock_user_alice_principal_id();
+        let now = SystemTime::now();
+
+        assert_eq!(
+            cancel_my_account_deletion_impl(alice, &mut canister_data, now),
+            Err("No account deletion requested".to_string())
+        );
+
+        canister_data
+            .account_deletions
+            .request(alice, get_mock_user_alice_canister_id(), now)
+            .unwrap();
+        assert_eq!(
+            cancel_my_account_deletion_impl(alice, &mut canister_data, now),
+            Ok(())
+        );
+        assert!(!canister_data.account_deletions.is_pending(&alice));
+
+        canister_data
+            .account_deletions
+            .request(alice, get_mock_user_alice_canister_id(), now)
+            .unwrap();
+        canister_data
+            .account_deletions
+            .start_due_deletions(now + ACCOUNT_DELETION_COOLDOWN);
+        assert_eq!(
+            cancel_my_account_deletion_impl(alice, &mut canister_data, now),
+            Err("Account deletion already in progress".to_string())
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{
+    data_model::{account_deletion::AccountDeletionAuditLogEntry, CanisterData},
+    CANISTER_DATA,
+};
+
+const MAX_AUDIT_LOG_ENTRIES_PER_PAGE: u64 = 100;
+
+/// Entries of the audit log of account deletions, oldest first
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_account_deletion_audit_log(
+    start_index: u64,
+    limit: u64,
+) -> Result<Vec<AccountDeletionAuditLogEntry>, String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_account_deletion_audit_log_impl(
+            api_caller,
+            start_index,
+            limit,
+            &canister_data_ref_cell.borrow(),
+        )
+    })
+}
+
+fn get_account_deletion_audit_log_impl(
+    caller: Principal,
+    start_index: u64,
+    limit: u64,
+    canister_data: &CanisterData,
+) -> Result<Vec<AccountDeletionAuditLogEntry>, String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(canister_data
+        .account_deletions
+        .get_audit_log_entries(start_index, limit.min(MAX_AUDIT_LOG_ENTRIES_PER_PAGE)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id,
+    };
+
+    use crate::data_model::account_deletion::AccountDeletionEvent;
+
+    use super::*;
+
+    #[test]
+    fn test_get_account_deletion_audit_log_impl() {
+        let mut canister_data = CanisterData::default();
+        let alice = get_mock_user_alice_principal_id();
+        let now = SystemTime::now();
+
+        assert_eq!(
+            get_account_deletion_audit_log_impl(alice, 0, 10, &canister_data),
+            Err("Super admin not found in internal records".to_string())
+        );
+
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        assert_eq!(
+            get_account_deletion_audit_log_impl(alice, 0, 10, &canister_data),
+            Err("Unauthorized".to_string())
+        );
+
+        canister_data
+            .account_deletions
+            .request(alice, get_mock_user_alice_canister_id(), now)
+            .unwrap();
+        canister_data.account_deletions.cancel(&alice, now).unwrap();
+
+        let audit_log = get_account_deletion_audit_log_impl(
+            get_global_super_admin_principal_id(),
+            1,
+            10,
+            &canister_data,
+        )
+        .unwrap();
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(audit_log[0].event, AccountDeletionEvent::Cancelled);
+    }
+}
@@ -0,0 +1,61 @@
+use candid::Principal;
+
+use crate::{
+    data_model::{account_deletion::AccountDeletion, CanisterData},
+    CANISTER_DATA,
+};
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_my_account_deletion() -> Option<AccountDeletion> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_my_account_deletion_impl(api_caller, &canister_data_ref_cell.borrow())
+    })
+}
+
+fn get_my_account_deletion_impl(
+    caller: Principal,
+    canister_data: &CanisterData,
+) -> Option<AccountDeletion> {
+    canister_data
+        .account_deletions
+        .user_principal_id_to_account_deletion
+        .get(&caller)
+        .cloned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_my_account_deletion_impl() {
+        let mut canister_data = CanisterData::default();
+        let account_deletion = canister_data
+            .account_deletions
+            .request(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                SystemTime::now(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            get_my_account_deletion_impl(get_mock_user_alice_principal_id(), &canister_data),
+            Some(account_deletion)
+        );
+        assert_eq!(
+            get_my_account_deletion_impl(get_mock_user_bob_principal_id(), &canister_data),
+            None
+        );
+    }
+}
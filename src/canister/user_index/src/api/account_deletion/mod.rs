@@ -0,0 +1,4 @@
+pub mod cancel_my_account_deletion;
+pub mod get_account_deletion_audit_log;
+pub mod get_my_account_deletion;
+pub mod request_my_account_deletion;
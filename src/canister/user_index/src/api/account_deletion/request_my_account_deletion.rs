@@ -0,0 +1,103 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use shared_utils::common::utils::system_time;
+
+use crate::{
+    data_model::{account_deletion::AccountDeletion, CanisterData},
+    CANISTER_DATA,
+};
+
+/// Schedules the deletion of the caller's account once the cooldown is over. Until then it can
+/// be cancelled with `cancel_my_account_deletion`.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn request_my_account_deletion() -> Result<AccountDeletion, String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        request_my_account_deletion_impl(
+            api_caller,
+            &mut canister_data_ref_cell.borrow_mut(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn request_my_account_deletion_impl(
+    caller: Principal,
+    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+) -> Result<AccountDeletion, String> {
+    if caller == Principal::anonymous() {
+        return Err("Unauthorized".to_string());
+    }
+
+    let user_canister_id = *canister_data
+        .user_principal_id_to_canister_id_map
+        .get(&caller)
+        .ok_or("No canister found for the caller")?;
+
+    canister_data
+        .account_deletions
+        .request(caller, user_canister_id, current_time)
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use crate::data_model::account_deletion::{AccountDeletionStatus, ACCOUNT_DELETION_COOLDOWN};
+
+    use super::*;
+
+    #[test]
+    fn test_request_my_account_deletion_impl() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        assert_eq!(
+            request_my_account_deletion_impl(Principal::anonymous(), &mut canister_data, now),
+            Err("Unauthorized".to_string())
+        );
+        assert_eq!(
+            request_my_account_deletion_impl(
+                get_mock_user_alice_principal_id(),
+                &mut canister_data,
+                now
+            ),
+            Err("No canister found for the caller".to_string())
+        );
+
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        let account_deletion = request_my_account_deletion_impl(
+            get_mock_user_alice_principal_id(),
+            &mut canister_data,
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            account_deletion.user_canister_id,
+            get_mock_user_alice_canister_id()
+        );
+        assert_eq!(account_deletion.status, AccountDeletionStatus::Scheduled);
+        assert_eq!(
+            account_deletion.scheduled_for,
+            now + ACCOUNT_DELETION_COOLDOWN
+        );
+
+        assert_eq!(
+            request_my_account_deletion_impl(
+                get_mock_user_alice_principal_id(),
+                &mut canister_data,
+                now
+            ),
+            Err("Account deletion already requested".to_string())
+        );
+    }
+}
@@ -1,8 +1,12 @@
 use ic_cdk::api::call::{self, CallResult};
-use shared_utils::common::types::known_principal::KnownPrincipalType;
+use shared_utils::common::{
+    types::known_principal::KnownPrincipalType, utils::task::run_task_concurrently,
+};
 
 use crate::CANISTER_DATA;
 
+const MAX_CONCURRENT_CANISTER_BACKUPS: usize = 10;
+
 #[ic_cdk::update]
 #[candid::candid_method(update)]
 async fn backup_all_individual_user_canisters() {
@@ -29,13 +33,32 @@ async fn backup_all_individual_user_canisters() {
             .clone()
     });
 
-    for (user_principal_id, user_canister_principal_id) in all_individual_user_canister_ids.iter() {
-        let upgrade_response: CallResult<()> = call::call(
-            *user_canister_principal_id,
-            "backup_data_to_backup_canister",
-            (*user_principal_id, *user_canister_principal_id),
-        )
-        .await;
-        upgrade_response.ok();
-    }
+    let backup_futures = all_individual_user_canister_ids.into_iter().map(
+        |(user_principal_id, user_canister_principal_id)| async move {
+            let backup_response: CallResult<()> = call::call(
+                user_canister_principal_id,
+                "backup_data_to_backup_canister",
+                (user_principal_id, user_canister_principal_id),
+            )
+            .await;
+            backup_response.map_err(|e| (user_canister_principal_id, e.1))
+        },
+    );
+
+    // * canisters only send what changed since their last backup, so many can run at once
+    run_task_concurrently(
+        backup_futures,
+        MAX_CONCURRENT_CANISTER_BACKUPS,
+        |backup_result| {
+            if let Err((user_canister_principal_id, e)) = backup_result {
+                ic_cdk::print(format!(
+                    "Failed to back up canister {}: {}",
+                    user_canister_principal_id.to_text(),
+                    e
+                ));
+            }
+        },
+        || false,
+    )
+    .await;
 }
@@ -45,9 +45,7 @@ fn receive_data_from_backup_canister_and_restore_data_to_heap_impl(
         .insert(user_principal_id, user_canister_id);
 
     if !unique_user_name.trim().is_empty() {
-        canister_data
-            .unique_user_name_to_user_principal_id_map
-            .insert(unique_user_name, user_principal_id);
+        canister_data.insert_unique_user_name(unique_user_name, user_principal_id);
     }
 }
 
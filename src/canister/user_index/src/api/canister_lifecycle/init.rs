@@ -1,6 +1,6 @@
 use shared_utils::canister_specific::user_index::types::args::UserIndexInitArgs;
 
-use crate::{data_model::CanisterData, CANISTER_DATA};
+use crate::{data_model::CanisterData, util::{account_deletion, canister_pool, canister_reclamation, cycle_top_up}, CANISTER_DATA};
 
 #[ic_cdk::init]
 #[candid::candid_method(init)]
@@ -9,6 +9,11 @@ fn init(init_args: UserIndexInitArgs) {
         let mut data = canister_data_ref_cell.borrow_mut();
         init_impl(init_args, &mut data);
     });
+
+    account_deletion::enqueue_timer_for_processing_account_deletions();
+    canister_pool::enqueue_timer_for_topping_up_canister_pool();
+    canister_reclamation::enqueue_timer_for_reclaiming_inactive_canisters();
+    cycle_top_up::enqueue_timer_for_topping_up_canister_cycles();
 }
 
 fn init_impl(init_args: UserIndexInitArgs, data: &mut CanisterData) {
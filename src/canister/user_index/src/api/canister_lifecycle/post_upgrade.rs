@@ -1,8 +1,14 @@
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
+use candid::Principal;
+use ic_cdk::api::call;
+use ic_stable_structures::Memory;
 use shared_utils::{
     canister_specific::user_index::types::args::UserIndexInitArgs,
-    common::utils::{stable_memory_serializer_deserializer, system_time},
+    common::utils::{
+        stable_memory_serializer_deserializer, system_time, task::run_task_concurrently,
+        user_name::normalize_user_name,
+    },
 };
 
 use crate::{
@@ -10,7 +16,10 @@ use crate::{
         upgrade_individual_user_template::update_user_index_upgrade_user_canisters_with_latest_wasm,
         well_known_principal::update_locally_stored_well_known_principals,
     },
-    data_model::{canister_upgrade::UpgradeStatus, configuration::Configuration, CanisterData},
+    data_model::{
+        canister_upgrade::UpgradeStatus, configuration::Configuration, memory, CanisterData,
+    },
+    util::{account_deletion, canister_pool, canister_reclamation, cycle_top_up},
     CANISTER_DATA,
 };
 
@@ -19,41 +28,223 @@ use super::pre_upgrade::BUFFER_SIZE_BYTES;
 #[ic_cdk::post_upgrade]
 fn post_upgrade() {
     restore_data_from_stable_memory();
+    normalize_existing_unique_user_names();
+    send_user_name_updates_to_owners();
     update_version_from_args();
     upgrade_all_indexed_user_canisters();
+    account_deletion::enqueue_timer_for_processing_account_deletions();
+    canister_pool::enqueue_timer_for_topping_up_canister_pool();
+    canister_reclamation::enqueue_timer_for_reclaiming_inactive_canisters();
+    cycle_top_up::enqueue_timer_for_topping_up_canister_cycles();
 }
 
 fn update_version_from_args() {
     let (upgrade_args,) = ic_cdk::api::call::arg_data::<(UserIndexInitArgs,)>();
     CANISTER_DATA.with(|canister_data_ref| {
         let last_upgrade_status = canister_data_ref.borrow().last_run_upgrade_status.clone();
+
+        // * the progress of a stopped run is kept so that it can be resumed
+        if canister_data_ref.borrow().upgrade_cursor.is_some() {
+            canister_data_ref.borrow_mut().last_run_upgrade_status = UpgradeStatus {
+                version: upgrade_args.version,
+                ..last_upgrade_status
+            };
+            return;
+        }
+
         let upgrade_status = UpgradeStatus {
             last_run_on: system_time::get_current_system_time_from_ic(),
             failed_canister_ids: vec![],
             version_number: last_upgrade_status.version_number,
             successful_upgrade_count: 0,
             version: upgrade_args.version,
+            ..Default::default()
         };
         canister_data_ref.borrow_mut().last_run_upgrade_status = upgrade_status;
     })
 }
 
 fn restore_data_from_stable_memory() {
-    match stable_memory_serializer_deserializer::deserialize_from_stable_memory::<CanisterData>(
-        BUFFER_SIZE_BYTES,
-    ) {
-        Ok(canister_data) => {
-            CANISTER_DATA.with(|canister_data_ref_cell| {
-                *canister_data_ref_cell.borrow_mut() = canister_data;
-            });
-        }
+    let canister_data = if memory::is_stable_memory_managed() {
+        read_canister_data_from_upgrades_memory()
+    } else {
+        stable_memory_serializer_deserializer::deserialize_from_stable_memory::<CanisterData>(
+            BUFFER_SIZE_BYTES,
+        )
+        .map_err(|e| e.to_string())
+    };
+
+    let mut canister_data = match canister_data {
+        Ok(canister_data) => canister_data,
         Err(e) => {
             ic_cdk::print(format!("Error: {:?}", e));
             panic!("Failed to restore canister data from stable memory");
         }
-    }
+    };
+
+    move_individual_user_template_wasms_to_stable_memory(&mut canister_data)
+        .expect("Failed to move individual user template wasms to stable memory");
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        *canister_data_ref_cell.borrow_mut() = canister_data;
+    });
+}
+
+/// Wasms kept in the heap by earlier versions are moved to stable memory
+fn move_individual_user_template_wasms_to_stable_memory(
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let legacy_individual_user_template_wasm_store =
+        std::mem::take(&mut canister_data.legacy_individual_user_template_wasm_store);
+
+    canister_data
+        .individual_user_template_wasm_store
+        .migrate_legacy_store(legacy_individual_user_template_wasm_store)
 }
 
+fn read_canister_data_from_upgrades_memory() -> Result<CanisterData, String> {
+    let upgrades_memory = memory::get_upgrades_memory();
+
+    // * Read the length of the heap data state.
+    // * Since heap can be at max 4 GiB, 4 bytes are enough to store the length.
+    let mut canister_data_len_bytes = [0; 4];
+    upgrades_memory.read(0, &mut canister_data_len_bytes);
+    let canister_data_len = u32::from_le_bytes(canister_data_len_bytes) as usize;
+
+    let mut canister_data_bytes = vec![0; canister_data_len];
+    upgrades_memory.read(4, &mut canister_data_bytes);
+
+    stable_memory_serializer_deserializer::deserialize(&*canister_data_bytes)
+        .map_err(|e| e.to_string())
+}
+
+/// Usernames set before they were case folded are lowercased. When two of them fold to the same
+/// name, the one that was already lowercase keeps it and the other one is released. The skeleton
+/// index, which is not persisted, is then built from the result.
+fn normalize_existing_unique_user_names() {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        normalize_existing_unique_user_names_impl(
+            &mut canister_data,
+            system_time::get_current_system_time_from_ic(),
+        );
+        canister_data.rebuild_user_name_skeleton_index();
+    });
+}
+
+fn normalize_existing_unique_user_names_impl(
+    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+) {
+    let existing_user_names =
+        std::mem::take(&mut canister_data.unique_user_name_to_user_principal_id_map);
+    let (already_normalized, not_normalized): (Vec<_>, Vec<_>) = existing_user_names
+        .into_iter()
+        .partition(|(user_name, _)| *user_name == normalize_user_name(user_name));
+
+    already_normalized
+        .into_iter()
+        .for_each(|(user_name, user_principal_id)| {
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .insert(user_name, user_principal_id);
+        });
+
+    not_normalized
+        .into_iter()
+        .for_each(|(user_name, user_principal_id)| {
+            let normalized_user_name = normalize_user_name(&user_name);
+            if canister_data
+                .unique_user_name_to_user_principal_id_map
+                .contains_key(&normalized_user_name)
+            {
+                canister_data.user_name_history.record_release(
+                    user_principal_id,
+                    user_name,
+                    current_time,
+                );
+                return;
+            }
+
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .insert(normalized_user_name.clone(), user_principal_id);
+            canister_data
+                .user_name_history
+                .record_normalization(user_principal_id, normalized_user_name);
+        });
+}
+
+const DELAY_FOR_SENDING_USER_NAME_UPDATES_TO_OWNERS: Duration = Duration::from_secs(1);
+/// Tells the canisters of users whose username the user index changed about it. The ones that
+/// could not be told are tried again after the next upgrade.
+fn send_user_name_updates_to_owners() {
+    ic_cdk_timers::set_timer(DELAY_FOR_SENDING_USER_NAME_UPDATES_TO_OWNERS, || {
+        ic_cdk::spawn(async {
+            let user_name_updates = CANISTER_DATA.with(|canister_data_ref_cell| {
+                let canister_data = canister_data_ref_cell.borrow();
+                canister_data
+                    .user_name_history
+                    .user_name_updates_to_send_to_owners
+                    .iter()
+                    .filter_map(|(user_principal_id, user_name)| {
+                        canister_data
+                            .user_principal_id_to_canister_id_map
+                            .get(user_principal_id)
+                            .map(|user_canister_id| {
+                                (*user_principal_id, *user_canister_id, user_name.clone())
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            let result_callback = |(user_principal_id, user_canister_id, result): (
+                Principal,
+                Principal,
+                Result<(), String>,
+            )| {
+                match result {
+                    Ok(()) => CANISTER_DATA.with(|canister_data_ref_cell| {
+                        canister_data_ref_cell
+                            .borrow_mut()
+                            .user_name_history
+                            .user_name_updates_to_send_to_owners
+                            .remove(&user_principal_id);
+                    }),
+                    Err(e) => ic_cdk::print(format!(
+                        "Failed to send the username update to canister {}: {}",
+                        user_canister_id.to_text(),
+                        e
+                    )),
+                }
+            };
+
+            run_task_concurrently(
+                user_name_updates.into_iter().map(
+                    |(user_principal_id, user_canister_id, user_name)| async move {
+                        let result = call::call::<_, (Result<(), String>,)>(
+                            user_canister_id,
+                            "update_profile_unique_user_name_from_user_index",
+                            (user_name,),
+                        )
+                        .await
+                        .map_err(|e| e.1)
+                        .and_then(|(result,)| result);
+
+                        (user_principal_id, user_canister_id, result)
+                    },
+                ),
+                MAX_CONCURRENT_USER_NAME_UPDATES,
+                result_callback,
+                || false,
+            )
+            .await;
+        })
+    });
+}
+
+const MAX_CONCURRENT_USER_NAME_UPDATES: usize = 10;
+
 const DELAY_FOR_REFETCHING_WELL_KNOWN_PRINCIPALS: Duration = Duration::from_secs(1);
 fn refetch_well_known_principals() {
     ic_cdk_timers::set_timer(DELAY_FOR_REFETCHING_WELL_KNOWN_PRINCIPALS, || {
@@ -67,3 +258,153 @@ fn upgrade_all_indexed_user_canisters() {
         ic_cdk::spawn(update_user_index_upgrade_user_canisters_with_latest_wasm::upgrade_user_canisters_with_latest_wasm())
     });
 }
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_principal_id,
+    };
+
+    use serde::Serialize;
+
+    use crate::data_model::{
+        individual_user_template_wasm::IndividualUserTemplateWasm,
+        user_name_history::ReleasedUserName,
+    };
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct WasmsKeptInHeap {
+        current: Option<IndividualUserTemplateWasm>,
+        previous: Option<IndividualUserTemplateWasm>,
+    }
+
+    #[derive(Serialize)]
+    struct CanisterDataWithWasmsKeptInHeap<'a> {
+        #[serde(flatten)]
+        canister_data: &'a CanisterData,
+        individual_user_template_wasm_store: WasmsKeptInHeap,
+    }
+
+    #[test]
+    fn test_move_individual_user_template_wasms_to_stable_memory() {
+        let wasm = IndividualUserTemplateWasm {
+            version: "v1.0.0".to_string(),
+            wasm_hash: vec![1; 32],
+            wasm_module: vec![1; 8],
+        };
+        let mut canister_data_bytes = vec![];
+        stable_memory_serializer_deserializer::serialize(
+            CanisterDataWithWasmsKeptInHeap {
+                canister_data: &CanisterData::default(),
+                individual_user_template_wasm_store: WasmsKeptInHeap {
+                    current: Some(wasm.clone()),
+                    previous: None,
+                },
+            },
+            &mut canister_data_bytes,
+        )
+        .unwrap();
+
+        let mut canister_data: CanisterData =
+            stable_memory_serializer_deserializer::deserialize(&*canister_data_bytes).unwrap();
+        move_individual_user_template_wasms_to_stable_memory(&mut canister_data).unwrap();
+
+        assert_eq!(
+            canister_data
+                .individual_user_template_wasm_store
+                .get_current_wasm(),
+            Ok(Some(wasm))
+        );
+
+        // * the wasms are not written to the heap data again
+        let mut canister_data_bytes = vec![];
+        stable_memory_serializer_deserializer::serialize(&canister_data, &mut canister_data_bytes)
+            .unwrap();
+        let mut canister_data: CanisterData =
+            stable_memory_serializer_deserializer::deserialize(&*canister_data_bytes).unwrap();
+        assert!(canister_data
+            .legacy_individual_user_template_wasm_store
+            .current
+            .is_none());
+        assert_eq!(
+            canister_data
+                .individual_user_template_wasm_store
+                .get_current_wasm()
+                .unwrap()
+                .unwrap()
+                .version,
+            "v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_normalize_existing_unique_user_names_impl() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+        let charlie = get_mock_user_charlie_principal_id();
+
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("alice".to_string(), alice);
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("Alice".to_string(), bob);
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("Charlie".to_string(), charlie);
+        canister_data
+            .user_name_history
+            .record_change(bob, None, "Alice".to_string(), now);
+        canister_data
+            .user_name_history
+            .record_change(charlie, None, "Charlie".to_string(), now);
+
+        normalize_existing_unique_user_names_impl(&mut canister_data, now);
+
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .clone()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![
+                ("alice".to_string(), alice),
+                ("charlie".to_string(), charlie)
+            ]
+        );
+
+        let user_name_history = &canister_data.user_name_history;
+        assert_eq!(
+            user_name_history.released_user_names,
+            vec![ReleasedUserName {
+                user_name: "Alice".to_string(),
+                user_principal_id: bob,
+                released_at: now,
+            }]
+        );
+        assert!(!user_name_history
+            .user_principal_id_to_user_name_changes
+            .contains_key(&bob));
+        assert_eq!(
+            user_name_history.user_principal_id_to_user_name_changes[&charlie].current_user_name,
+            "charlie"
+        );
+        assert_eq!(
+            user_name_history.user_name_updates_to_send_to_owners.len(),
+            2
+        );
+        assert_eq!(
+            user_name_history.user_name_updates_to_send_to_owners[&bob],
+            None
+        );
+        assert_eq!(
+            user_name_history.user_name_updates_to_send_to_owners[&charlie],
+            Some("charlie".to_string())
+        );
+    }
+}
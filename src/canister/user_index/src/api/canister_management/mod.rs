@@ -40,14 +40,18 @@ pub async fn set_permission_to_upgrade_individual_canisters(flag: bool) -> Strin
 pub async fn start_upgrades_for_individual_canisters() -> String {
     let api_caller = ic_cdk::caller();
     let known_principal_ids = CANISTER_DATA.with(|canister_data_ref_cell| canister_data_ref_cell.borrow().configuration.known_principal_ids.clone());
-    if *known_principal_ids
-        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
-        .unwrap()
-        != api_caller
+    // * the platform orchestrator starts upgrades on every user_index at once
+    if ![KnownPrincipalType::UserIdGlobalSuperAdmin, KnownPrincipalType::CanisterIdPlatformOrchestrator]
+        .iter()
+        .any(|principal_type| known_principal_ids.get(principal_type) == Some(&api_caller))
     {
         return "Unauthorized caller".to_string();
     };
 
+    if canister_management::is_rollback_in_progress() {
+        return "A rollback is in progress".to_string();
+    }
+
     CANISTER_DATA.with(|canister_data_ref| {
         canister_data_ref.borrow_mut().allow_upgrades_for_individual_canisters = true;
     });
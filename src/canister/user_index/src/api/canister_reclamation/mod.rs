@@ -0,0 +1 @@
+pub mod set_inactivity_period_before_canister_reclamation;
@@ -0,0 +1,95 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Canisters of users that haven't posted, bet, followed or updated their profile for this many
+/// seconds are reclaimed by a daily job. Passing `None` turns reclamation off.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_inactivity_period_before_canister_reclamation(
+    inactivity_period_in_seconds: Option<u64>,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_inactivity_period_before_canister_reclamation_impl(
+            api_caller,
+            inactivity_period_in_seconds,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn set_inactivity_period_before_canister_reclamation_impl(
+    caller: Principal,
+    inactivity_period_in_seconds: Option<u64>,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data
+        .configuration
+        .inactivity_period_before_canister_reclamation_in_seconds = inactivity_period_in_seconds;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_inactivity_period_before_canister_reclamation_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result = set_inactivity_period_before_canister_reclamation_impl(
+            get_mock_user_alice_principal_id(),
+            Some(60),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = set_inactivity_period_before_canister_reclamation_impl(
+            get_global_super_admin_principal_id(),
+            Some(60),
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data
+                .configuration
+                .inactivity_period_before_canister_reclamation_in_seconds,
+            Some(60)
+        );
+
+        let result = set_inactivity_period_before_canister_reclamation_impl(
+            get_global_super_admin_principal_id(),
+            None,
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data
+                .configuration
+                .inactivity_period_before_canister_reclamation_in_seconds,
+            None
+        );
+    }
+}
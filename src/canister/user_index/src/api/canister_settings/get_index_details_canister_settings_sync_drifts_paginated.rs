@@ -0,0 +1,81 @@
+use std::ops::Bound;
+
+use candid::Principal;
+
+use crate::{
+    data_model::{canister_settings::CanisterSettingsDrift, CanisterData},
+    CANISTER_DATA,
+};
+
+const MAX_CANISTER_SETTINGS_SYNC_DRIFTS_PER_PAGE: u64 = 1000;
+
+/// The canisters the last settings sync found drifted, in order of canister id, starting after
+/// `start_after`
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_index_details_canister_settings_sync_drifts_paginated(
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Vec<CanisterSettingsDrift> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_index_details_canister_settings_sync_drifts_paginated_impl(
+            start_after,
+            limit,
+            &canister_data_ref_cell.borrow(),
+        )
+    })
+}
+
+fn get_index_details_canister_settings_sync_drifts_paginated_impl(
+    start_after: Option<Principal>,
+    limit: u64,
+    canister_data: &CanisterData,
+) -> Vec<CanisterSettingsDrift> {
+    let lower_bound = match start_after {
+        Some(start_after) => Bound::Excluded(start_after),
+        None => Bound::Unbounded,
+    };
+
+    canister_data
+        .canister_settings_sync_drifts
+        .range((lower_bound, Bound::Unbounded))
+        .take(limit.min(MAX_CANISTER_SETTINGS_SYNC_DRIFTS_PER_PAGE) as usize)
+        .map(|(_, drift)| drift.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_drift(i: u8) -> CanisterSettingsDrift {
+        CanisterSettingsDrift {
+            canister_id: Principal::from_slice(&[i]),
+            settings_before_sync: Default::default(),
+            updated: false,
+            verified: false,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_get_index_details_canister_settings_sync_drifts_paginated_impl() {
+        let mut canister_data = CanisterData::default();
+        for i in 0..5 {
+            canister_data
+                .canister_settings_sync_drifts
+                .insert(Principal::from_slice(&[i]), get_drift(i));
+        }
+
+        let first_page =
+            get_index_details_canister_settings_sync_drifts_paginated_impl(None, 3, &canister_data);
+        assert_eq!(first_page, (0..3).map(get_drift).collect::<Vec<_>>());
+
+        let second_page = get_index_details_canister_settings_sync_drifts_paginated_impl(
+            first_page.last().map(|drift| drift.canister_id),
+            3,
+            &canister_data,
+        );
+        assert_eq!(second_page, (3..5).map(get_drift).collect::<Vec<_>>());
+    }
+}
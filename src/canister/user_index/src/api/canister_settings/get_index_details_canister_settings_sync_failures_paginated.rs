@@ -0,0 +1,81 @@
+use std::ops::Bound;
+
+use candid::Principal;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+const MAX_CANISTER_SETTINGS_SYNC_FAILURES_PER_PAGE: u64 = 1000;
+
+/// The canisters of the last settings sync whose settings could not be read, with the error, in
+/// order of canister id, starting after `start_after`
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_index_details_canister_settings_sync_failures_paginated(
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Vec<(Principal, String)> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_index_details_canister_settings_sync_failures_paginated_impl(
+            start_after,
+            limit,
+            &canister_data_ref_cell.borrow(),
+        )
+    })
+}
+
+fn get_index_details_canister_settings_sync_failures_paginated_impl(
+    start_after: Option<Principal>,
+    limit: u64,
+    canister_data: &CanisterData,
+) -> Vec<(Principal, String)> {
+    let lower_bound = match start_after {
+        Some(start_after) => Bound::Excluded(start_after),
+        None => Bound::Unbounded,
+    };
+
+    canister_data
+        .canister_settings_sync_failures
+        .range((lower_bound, Bound::Unbounded))
+        .take(limit.min(MAX_CANISTER_SETTINGS_SYNC_FAILURES_PER_PAGE) as usize)
+        .map(|(canister_id, error)| (*canister_id, error.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_index_details_canister_settings_sync_failures_paginated_impl() {
+        let mut canister_data = CanisterData::default();
+        for i in 0..5 {
+            canister_data
+                .canister_settings_sync_failures
+                .insert(Principal::from_slice(&[i]), format!("error {}", i));
+        }
+
+        let first_page = get_index_details_canister_settings_sync_failures_paginated_impl(
+            None,
+            3,
+            &canister_data,
+        );
+        assert_eq!(
+            first_page,
+            (0..3)
+                .map(|i| (Principal::from_slice(&[i]), format!("error {}", i)))
+                .collect::<Vec<_>>()
+        );
+
+        let second_page = get_index_details_canister_settings_sync_failures_paginated_impl(
+            first_page.last().map(|(canister_id, _)| *canister_id),
+            3,
+            &canister_data,
+        );
+        assert_eq!(
+            second_page,
+            (3..5)
+                .map(|i| (Principal::from_slice(&[i]), format!("error {}", i)))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use crate::{data_model::canister_settings::CanisterSettingsSyncStatus, CANISTER_DATA};
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_index_details_last_canister_settings_sync_status() -> CanisterSettingsSyncStatus {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .last_run_canister_settings_sync_status
+            .clone()
+    })
+}
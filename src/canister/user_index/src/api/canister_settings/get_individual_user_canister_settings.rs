@@ -0,0 +1,13 @@
+use crate::{data_model::canister_settings::IndividualUserCanisterSettings, CANISTER_DATA};
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_individual_user_canister_settings() -> IndividualUserCanisterSettings {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .configuration
+            .individual_user_canister_settings
+            .clone()
+    })
+}
@@ -0,0 +1,6 @@
+pub mod get_index_details_canister_settings_sync_drifts_paginated;
+pub mod get_index_details_canister_settings_sync_failures_paginated;
+pub mod get_index_details_last_canister_settings_sync_status;
+pub mod get_individual_user_canister_settings;
+pub mod set_individual_user_canister_settings;
+pub mod start_individual_user_canister_settings_sync;
@@ -0,0 +1,114 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{
+    data_model::{canister_settings::IndividualUserCanisterSettings, CanisterData},
+    CANISTER_DATA,
+};
+
+/// Settings and co-controllers new individual user canisters are created with. Existing canisters
+/// are brought in line with `start_individual_user_canister_settings_sync`.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_individual_user_canister_settings(
+    canister_settings: IndividualUserCanisterSettings,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_individual_user_canister_settings_impl(
+            api_caller,
+            canister_settings,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn set_individual_user_canister_settings_impl(
+    caller: Principal,
+    canister_settings: IndividualUserCanisterSettings,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_settings.validate(&canister_data.configuration.known_principal_ids)?;
+    canister_data
+        .configuration
+        .individual_user_canister_settings = canister_settings;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_individual_user_canister_settings_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let canister_settings = IndividualUserCanisterSettings {
+            freezing_threshold: Some(2_592_000),
+            memory_allocation: None,
+            compute_allocation: Some(1),
+            co_controllers: vec![KnownPrincipalType::CanisterIdSNSController],
+        };
+
+        let result = set_individual_user_canister_settings_impl(
+            get_mock_user_alice_principal_id(),
+            canister_settings.clone(),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = set_individual_user_canister_settings_impl(
+            get_global_super_admin_principal_id(),
+            IndividualUserCanisterSettings {
+                compute_allocation: Some(200),
+                ..Default::default()
+            },
+            &mut canister_data,
+        );
+        assert!(result.is_err());
+
+        // * co-controllers have to be known
+        let result = set_individual_user_canister_settings_impl(
+            get_global_super_admin_principal_id(),
+            canister_settings.clone(),
+            &mut canister_data,
+        );
+        assert!(result.is_err());
+
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdSNSController,
+            get_mock_user_alice_principal_id(),
+        );
+        let result = set_individual_user_canister_settings_impl(
+            get_global_super_admin_principal_id(),
+            canister_settings.clone(),
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data
+                .configuration
+                .individual_user_canister_settings,
+            canister_settings
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{
+    data_model::CanisterData,
+    util::canister_settings_sync::{
+        is_canister_settings_sync_in_progress, sync_individual_user_canister_settings,
+    },
+    CANISTER_DATA,
+};
+
+/// Checks the settings and controllers of all individual user canisters against the configured
+/// ones. The canisters that drifted are counted in
+/// `get_index_details_last_canister_settings_sync_status`, listed by
+/// `get_index_details_canister_settings_sync_drifts_paginated` and, with
+/// `update_drifted_canisters`, migrated to the configured settings and verified.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn start_individual_user_canister_settings_sync(
+    update_drifted_canisters: bool,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        start_individual_user_canister_settings_sync_impl(
+            api_caller,
+            &canister_data_ref_cell.borrow(),
+        )
+    })?;
+
+    ic_cdk::spawn(sync_individual_user_canister_settings(
+        update_drifted_canisters,
+    ));
+
+    Ok(())
+}
+
+fn start_individual_user_canister_settings_sync_impl(
+    caller: Principal,
+    canister_data: &CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data
+        .configuration
+        .individual_user_canister_settings
+        .validate(&canister_data.configuration.known_principal_ids)?;
+
+    if is_canister_settings_sync_in_progress() {
+        return Err("A canister settings sync is already in progress".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_start_individual_user_canister_settings_sync_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result = start_individual_user_canister_settings_sync_impl(
+            get_mock_user_alice_principal_id(),
+            &canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = start_individual_user_canister_settings_sync_impl(
+            get_global_super_admin_principal_id(),
+            &canister_data,
+        );
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{data_model::cycle_burn_tracking::CanisterCycleBurnReport, CANISTER_DATA};
+
+const MAX_CANISTERS_IN_CYCLE_BURN_REPORT: u64 = 100;
+
+/// Canisters sorted by how many cycles they burn per day, heaviest first
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_heaviest_cycle_burning_canisters(limit: u64) -> Vec<CanisterCycleBurnReport> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .cycle_burn_tracking
+            .get_heaviest_burners(limit.min(MAX_CANISTERS_IN_CYCLE_BURN_REPORT) as usize)
+    })
+}
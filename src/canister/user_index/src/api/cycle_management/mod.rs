@@ -1 +1,3 @@
+pub mod get_heaviest_cycle_burning_canisters;
 pub mod get_user_index_canister_cycle_balance;
+pub mod set_cycle_top_up_horizon_in_days;
@@ -0,0 +1,76 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Canisters predicted to fall below their freezing threshold within this many days are topped
+/// up by an hourly job. Passing `None` goes back to the default horizon.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_cycle_top_up_horizon_in_days(horizon_in_days: Option<u64>) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_cycle_top_up_horizon_in_days_impl(
+            api_caller,
+            horizon_in_days,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn set_cycle_top_up_horizon_in_days_impl(
+    caller: Principal,
+    horizon_in_days: Option<u64>,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.configuration.cycle_top_up_horizon_in_days = horizon_in_days;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_cycle_top_up_horizon_in_days_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result = set_cycle_top_up_horizon_in_days_impl(
+            get_mock_user_alice_principal_id(),
+            Some(14),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = set_cycle_top_up_horizon_in_days_impl(
+            get_global_super_admin_principal_id(),
+            Some(14),
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data.configuration.cycle_top_up_horizon_in_days,
+            Some(14)
+        );
+    }
+}
@@ -1,5 +1,8 @@
+pub mod account_deletion;
 pub mod backup_and_restore;
 pub mod canister_lifecycle;
+pub mod canister_reclamation;
+pub mod canister_settings;
 pub mod cycle_management;
 pub mod upgrade_individual_user_template;
 pub mod user_record;
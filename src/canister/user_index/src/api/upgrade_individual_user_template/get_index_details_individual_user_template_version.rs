@@ -0,0 +1,93 @@
+use std::time::UNIX_EPOCH;
+
+use shared_utils::common::types::version_details::VersionDetails;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// The version of the individual user canister wasm that the last completed upgrade or rollback
+/// run installed. None while a run is in progress or halted, as the canisters then run different
+/// versions.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_index_details_individual_user_template_version() -> Option<VersionDetails> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_index_details_individual_user_template_version_impl(&canister_data_ref_cell.borrow())
+    })
+}
+
+fn get_index_details_individual_user_template_version_impl(
+    canister_data: &CanisterData,
+) -> Option<VersionDetails> {
+    if canister_data.upgrade_cursor.is_some() {
+        return None;
+    }
+
+    let upgrade_status = &canister_data.last_run_upgrade_status;
+    let rollback_status = &canister_data.last_run_rollback_status;
+    let last_run_status = if rollback_status.last_run_on > upgrade_status.last_run_on {
+        rollback_status
+    } else {
+        upgrade_status
+    };
+
+    if last_run_status.last_run_on == UNIX_EPOCH || last_run_status.halted_reason.is_some() {
+        return None;
+    }
+
+    Some(VersionDetails {
+        version_number: last_run_status.version_number,
+        version: last_run_status.version.clone(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::data_model::canister_upgrade::UpgradeStatus;
+
+    use super::*;
+
+    #[test]
+    fn test_get_index_details_individual_user_template_version_impl() {
+        let mut canister_data = CanisterData::default();
+        assert_eq!(
+            get_index_details_individual_user_template_version_impl(&canister_data),
+            None
+        );
+
+        canister_data.last_run_upgrade_status = UpgradeStatus {
+            version_number: 3,
+            version: "v3".to_string(),
+            last_run_on: UNIX_EPOCH + Duration::from_secs(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_index_details_individual_user_template_version_impl(&canister_data)
+                .unwrap()
+                .version_number,
+            3
+        );
+
+        // * a later rollback put the canisters back on the previous version
+        canister_data.last_run_rollback_status = UpgradeStatus {
+            version_number: 2,
+            version: "v2".to_string(),
+            last_run_on: UNIX_EPOCH + Duration::from_secs(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_index_details_individual_user_template_version_impl(&canister_data)
+                .unwrap()
+                .version_number,
+            2
+        );
+
+        canister_data.last_run_rollback_status.halted_reason =
+            Some("Too many failures".to_string());
+        assert_eq!(
+            get_index_details_individual_user_template_version_impl(&canister_data),
+            None
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use shared_utils::canister_specific::user_index::types::individual_user_template_wasm::IndividualUserTemplateWasmStoreDetails;
+
+use crate::CANISTER_DATA;
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_index_details_individual_user_template_wasms() -> IndividualUserTemplateWasmStoreDetails {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        (&canister_data_ref_cell
+            .borrow()
+            .individual_user_template_wasm_store)
+            .into()
+    })
+}
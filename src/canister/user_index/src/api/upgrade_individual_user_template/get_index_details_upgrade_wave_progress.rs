@@ -0,0 +1,13 @@
+use crate::{data_model::canister_upgrade::UpgradeWaveProgress, CANISTER_DATA};
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_index_details_upgrade_wave_progress() -> Vec<UpgradeWaveProgress> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .last_run_upgrade_status
+            .waves
+            .clone()
+    })
+}
@@ -1,3 +1,11 @@
+pub mod get_index_details_individual_user_template_version;
+pub mod get_index_details_individual_user_template_wasms;
+pub mod get_index_details_last_rollback_status;
 pub mod get_index_details_last_upgrade_status;
+pub mod get_index_details_upgrade_wave_progress;
+pub mod retry_failed_individual_canister_upgrades;
+pub mod rollback_individual_user_canisters_to_previous_wasm;
+pub mod set_upgrade_rollout_plan;
 pub mod update_user_index_upgrade_user_canisters_with_latest_wasm;
 pub mod upgrade_specific_individual_user_canister_with_latest_wasm;
+pub mod upload_individual_user_template_wasm_chunk;
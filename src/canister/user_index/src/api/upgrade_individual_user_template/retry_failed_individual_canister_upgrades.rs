@@ -0,0 +1,136 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, util::canister_management, CANISTER_DATA};
+
+use super::update_user_index_upgrade_user_canisters_with_latest_wasm;
+
+/// Upgrades only the canisters that failed in the last completed upgrade run, as long as they
+/// have attempts left. Progress is reported in `get_index_details_last_upgrade_status`.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn retry_failed_individual_canister_upgrades() -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    if update_user_index_upgrade_user_canisters_with_latest_wasm::is_upgrade_in_progress() {
+        return Err("An upgrade run is in progress".to_string());
+    }
+
+    if canister_management::is_rollback_in_progress() {
+        return Err("A rollback is in progress".to_string());
+    }
+
+    let failed_canisters = CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_retryable_failed_canisters_impl(api_caller, &canister_data_ref_cell.borrow())
+    })?;
+
+    ic_cdk::spawn(
+        update_user_index_upgrade_user_canisters_with_latest_wasm::retry_failed_upgrades(
+            failed_canisters,
+        ),
+    );
+
+    Ok(())
+}
+
+fn get_retryable_failed_canisters_impl(
+    caller: Principal,
+    canister_data: &CanisterData,
+) -> Result<Vec<(Principal, Principal)>, String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    if canister_data.upgrade_cursor.is_some() {
+        return Err(
+            "The last upgrade run did not complete. Resume it with start_upgrades_for_individual_canisters first"
+                .to_string(),
+        );
+    }
+
+    let failed_canisters: Vec<(Principal, Principal)> = canister_data
+        .last_run_upgrade_status
+        .failed_upgrades
+        .iter()
+        .filter(|failed_upgrade| failed_upgrade.can_be_retried())
+        .map(|failed_upgrade| (failed_upgrade.user_principal_id, failed_upgrade.canister_id))
+        .collect();
+
+    if failed_canisters.is_empty() {
+        return Err("No failed upgrades with attempts left".to_string());
+    }
+
+    Ok(failed_canisters)
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use crate::data_model::canister_upgrade::{FailedUpgrade, UpgradeCursor, MAX_UPGRADE_ATTEMPTS};
+
+    use super::*;
+
+    #[test]
+    fn test_get_retryable_failed_canisters_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result =
+            get_retryable_failed_canisters_impl(get_mock_user_alice_principal_id(), &canister_data);
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = get_retryable_failed_canisters_impl(
+            get_global_super_admin_principal_id(),
+            &canister_data,
+        );
+        assert!(result.is_err());
+
+        let mut bob_failed_upgrade = FailedUpgrade::new(
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+            "Canister is out of cycles".to_string(),
+        );
+        bob_failed_upgrade.attempt_count = MAX_UPGRADE_ATTEMPTS;
+        canister_data.last_run_upgrade_status.failed_upgrades = vec![
+            FailedUpgrade::new(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                "Canister is out of cycles".to_string(),
+            ),
+            bob_failed_upgrade,
+        ];
+
+        let result = get_retryable_failed_canisters_impl(
+            get_global_super_admin_principal_id(),
+            &canister_data,
+        );
+        assert_eq!(
+            result,
+            Ok(vec![(
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id()
+            )])
+        );
+
+        canister_data.upgrade_cursor = Some(UpgradeCursor::new(0, vec![], vec![]));
+        let result = get_retryable_failed_canisters_impl(
+            get_global_super_admin_principal_id(),
+            &canister_data,
+        );
+        assert!(result.is_err());
+    }
+}
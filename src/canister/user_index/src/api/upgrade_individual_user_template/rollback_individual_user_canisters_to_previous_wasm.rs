@@ -0,0 +1,481 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use candid::Principal;
+use ic_cdk::api::management_canister::main::CanisterInstallMode;
+use shared_utils::{
+    canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs,
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{
+            system_time,
+            task::{run_task_concurrently, InProgressGuard},
+        },
+    },
+};
+
+use crate::{
+    data_model::{
+        canister_upgrade::UpgradeStatus, individual_user_template_wasm::IndividualUserTemplateWasm,
+        CanisterData,
+    },
+    util::canister_management::{self, IS_FULL_ROLLBACK_IN_PROGRESS, IS_ROLLBACK_IN_PROGRESS},
+    CANISTER_DATA,
+};
+
+use super::update_user_index_upgrade_user_canisters_with_latest_wasm;
+
+const MAX_CONCURRENCY: usize = 11;
+
+struct Rollback {
+    wasm: IndividualUserTemplateWasm,
+    version_number: u64,
+    /// Canister ids with their owners. Canisters in the pool have no owner.
+    canisters: Vec<(Option<Principal>, Principal)>,
+    is_full_rollback: bool,
+}
+
+/// Installs the previous individual user template wasm on all canisters, or only on the passed
+/// canisters. The wasm is installed in upgrade mode so that canister state is kept. Upgrade runs
+/// are stopped first, and when all canisters are rolled back the previous wasm becomes the current
+/// one. Until then, canisters created during a full rollback get the previous wasm as well. The
+/// rollback stops at the first canister that fails to roll back, and the wasms are then left as
+/// they are. It is rejected while an upgrade run is in progress, and upgrades, pool top-ups and
+/// wasm uploads are rejected while it runs. Progress is reported by
+/// `get_index_details_last_rollback_status`.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn rollback_individual_user_canisters_to_previous_wasm(
+    canister_ids: Option<Vec<Principal>>,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    if update_user_index_upgrade_user_canisters_with_latest_wasm::is_upgrade_in_progress() {
+        return Err("An upgrade run is in progress".to_string());
+    }
+
+    let Some(rollback_in_progress_guard) = InProgressGuard::acquire(&IS_ROLLBACK_IN_PROGRESS)
+    else {
+        return Err("A rollback is already in progress".to_string());
+    };
+
+    let rollback = CANISTER_DATA.with(|canister_data_ref_cell| {
+        prepare_rollback_impl(
+            api_caller,
+            canister_ids,
+            &mut canister_data_ref_cell.borrow_mut(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })?;
+
+    let full_rollback_in_progress_guard = rollback
+        .is_full_rollback
+        .then(|| InProgressGuard::acquire(&IS_FULL_ROLLBACK_IN_PROGRESS))
+        .flatten();
+
+    ic_cdk::spawn(async move {
+        let _guards = (rollback_in_progress_guard, full_rollback_in_progress_guard);
+        run_rollback(rollback).await
+    });
+
+    Ok(())
+}
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn validate_rollback_individual_user_canisters_to_previous_wasm(
+    _canister_ids: Option<Vec<Principal>>,
+) -> Result<String, String> {
+    let caller_id = ic_cdk::caller();
+    let governance_canister_id = CANISTER_DATA
+        .with(|canister_data_ref| {
+            canister_data_ref
+                .borrow()
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdSnsGovernance)
+                .cloned()
+        })
+        .ok_or("Governance Canister Id not found")?;
+
+    if caller_id != governance_canister_id {
+        return Err("This Proposal can only be executed through DAO".to_string());
+    };
+
+    Ok("Success".to_string())
+}
+
+fn prepare_rollback_impl(
+    caller: Principal,
+    canister_ids: Option<Vec<Principal>>,
+    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+) -> Result<Rollback, String> {
+    let known_principal_ids = &canister_data.configuration.known_principal_ids;
+    let is_allowed_caller = [
+        KnownPrincipalType::UserIdGlobalSuperAdmin,
+        KnownPrincipalType::CanisterIdSnsGovernance,
+    ]
+    .iter()
+    .any(|principal_type| known_principal_ids.get(principal_type) == Some(&caller));
+
+    if !is_allowed_caller {
+        return Err("Unauthorized".to_string());
+    }
+
+    let wasm = canister_data
+        .individual_user_template_wasm_store
+        .get_previous_wasm()?
+        .ok_or("No previous individual user template wasm stored")?;
+
+    let canisters = match &canister_ids {
+        None => canister_data
+            .user_principal_id_to_canister_id_map
+            .iter()
+            .map(|(user_principal_id, canister_id)| (Some(*user_principal_id), *canister_id))
+            .chain(
+                canister_data
+                    .available_canisters
+                    .iter()
+                    .map(|canister_id| (None, *canister_id)),
+            )
+            .collect(),
+        Some(canister_ids) => {
+            let canister_id_to_user_principal_id_map: BTreeMap<Principal, Principal> =
+                canister_data
+                    .user_principal_id_to_canister_id_map
+                    .iter()
+                    .map(|(user_principal_id, canister_id)| (*canister_id, *user_principal_id))
+                    .collect();
+
+            canister_ids
+                .iter()
+                .map(
+                    |canister_id| match canister_id_to_user_principal_id_map.get(canister_id) {
+                        Some(user_principal_id) => Ok((Some(*user_principal_id), *canister_id)),
+                        None if canister_data.available_canisters.contains(canister_id) => {
+                            Ok((None, *canister_id))
+                        }
+                        None => Err(format!(
+                            "{} is not an individual user canister",
+                            canister_id.to_text()
+                        )),
+                    },
+                )
+                .collect::<Result<Vec<_>, String>>()?
+        }
+    };
+
+    canister_data.allow_upgrades_for_individual_canisters = false;
+    canister_data.last_run_rollback_status = UpgradeStatus {
+        version_number: canister_data.last_run_upgrade_status.version_number,
+        last_run_on: current_time,
+        version: wasm.version.clone(),
+        ..Default::default()
+    };
+
+    Ok(Rollback {
+        wasm,
+        version_number: canister_data.last_run_upgrade_status.version_number,
+        canisters,
+        is_full_rollback: canister_ids.is_none(),
+    })
+}
+
+async fn run_rollback(rollback: Rollback) {
+    let configuration = CANISTER_DATA
+        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().configuration.clone());
+
+    let rollback_futures = rollback
+        .canisters
+        .iter()
+        .map(|(user_principal_id, canister_id)| {
+            let install_arg = IndividualUserTemplateInitArgs {
+                known_principal_ids: Some(configuration.known_principal_ids.clone()),
+                profile_owner: *user_principal_id,
+                upgrade_version_number: Some(rollback.version_number),
+                url_to_send_canister_metrics_to: Some(
+                    configuration.url_to_send_canister_metrics_to.clone(),
+                ),
+                version: rollback.wasm.version.clone(),
+            };
+            let wasm_module = rollback.wasm.wasm_module.clone();
+
+            async move {
+                canister_management::install_individual_user_canister_wasm(
+                    *canister_id,
+                    CanisterInstallMode::Upgrade,
+                    install_arg,
+                    wasm_module,
+                )
+                .await
+                .map_err(|e| (*user_principal_id, *canister_id, e.1))
+            }
+        });
+
+    let result_callback = |rollback_result: Result<(), (Option<Principal>, Principal, String)>| {
+        if let Err((_, canister_id, err)) = &rollback_result {
+            ic_cdk::print(format!(
+                "Failed to roll back canister: {} with error: {}",
+                canister_id.to_text(),
+                err
+            ));
+        }
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            record_rollback_result(&mut canister_data_ref_cell.borrow_mut(), rollback_result)
+        });
+    };
+
+    // * rollbacks already started are awaited, no new ones are started after a failure
+    let breaking_condition = || {
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow()
+                .last_run_rollback_status
+                .halted_reason
+                .is_some()
+        })
+    };
+
+    run_task_concurrently(
+        rollback_futures,
+        MAX_CONCURRENCY,
+        result_callback,
+        breaking_condition,
+    )
+    .await;
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        finish_rollback(
+            &mut canister_data_ref_cell.borrow_mut(),
+            rollback.is_full_rollback,
+            system_time::get_current_system_time_from_ic(),
+        )
+    });
+}
+
+/// Canisters in the pool are reported with the anonymous principal as owner. The first failure
+/// halts the rollback.
+fn record_rollback_result(
+    canister_data: &mut CanisterData,
+    rollback_result: Result<(), (Option<Principal>, Principal, String)>,
+) {
+    let rollback_status = &mut canister_data.last_run_rollback_status;
+    match rollback_result {
+        Ok(()) => rollback_status.successful_upgrade_count += 1,
+        Err((user_principal_id, canister_id, err)) => {
+            if rollback_status.halted_reason.is_none() {
+                rollback_status.halted_reason = Some(format!(
+                    "Rolling back canister {} failed: {}",
+                    canister_id.to_text(),
+                    err
+                ));
+            }
+            rollback_status.failed_canister_ids.push((
+                user_principal_id.unwrap_or_else(Principal::anonymous),
+                canister_id,
+                err,
+            ));
+        }
+    }
+}
+
+/// The previous wasm only becomes the current one once every canister was rolled back to it
+fn finish_rollback(
+    canister_data: &mut CanisterData,
+    is_full_rollback: bool,
+    current_time: SystemTime,
+) {
+    canister_data.last_run_rollback_status.last_run_on = current_time;
+
+    if !is_full_rollback
+        || canister_data
+            .last_run_rollback_status
+            .halted_reason
+            .is_some()
+    {
+        return;
+    }
+
+    if let Err(e) = canister_data
+        .individual_user_template_wasm_store
+        .swap_current_and_previous()
+    {
+        canister_data.last_run_rollback_status.halted_reason = Some(e);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_canister_id,
+    };
+
+    use super::*;
+
+    fn get_wasm(version: &str, hash: u8) -> IndividualUserTemplateWasm {
+        IndividualUserTemplateWasm {
+            version: version.to_string(),
+            wasm_hash: vec![hash; 32],
+            wasm_module: vec![hash; 8],
+        }
+    }
+
+    fn get_canister_data() -> CanisterData {
+        let mut canister_data = CanisterData {
+            allow_upgrades_for_individual_canisters: true,
+            ..Default::default()
+        };
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        canister_data
+            .available_canisters
+            .insert(get_mock_user_charlie_canister_id());
+        canister_data
+    }
+
+    #[test]
+    fn test_prepare_rollback_impl() {
+        let mut canister_data = get_canister_data();
+
+        let result = prepare_rollback_impl(
+            get_mock_user_alice_principal_id(),
+            None,
+            &mut canister_data,
+            SystemTime::now(),
+        );
+        assert_eq!(result.err(), Some("Unauthorized".to_string()));
+
+        let result = prepare_rollback_impl(
+            get_global_super_admin_principal_id(),
+            None,
+            &mut canister_data,
+            SystemTime::now(),
+        );
+        assert!(result.is_err());
+
+        canister_data
+            .individual_user_template_wasm_store
+            .store_new_wasm(get_wasm("v1.0.0", 1))
+            .unwrap();
+        canister_data
+            .individual_user_template_wasm_store
+            .store_new_wasm(get_wasm("v1.1.0", 2))
+            .unwrap();
+
+        let result = prepare_rollback_impl(
+            get_global_super_admin_principal_id(),
+            Some(vec![get_mock_user_alice_principal_id()]),
+            &mut canister_data,
+            SystemTime::now(),
+        );
+        assert!(result.is_err());
+        assert!(canister_data.allow_upgrades_for_individual_canisters);
+
+        let rollback = prepare_rollback_impl(
+            get_global_super_admin_principal_id(),
+            Some(vec![
+                get_mock_user_alice_canister_id(),
+                get_mock_user_charlie_canister_id(),
+            ]),
+            &mut canister_data,
+            SystemTime::now(),
+        )
+        .unwrap();
+        assert_eq!(rollback.wasm, get_wasm("v1.0.0", 1));
+        assert_eq!(
+            rollback.canisters,
+            vec![
+                (
+                    Some(get_mock_user_alice_principal_id()),
+                    get_mock_user_alice_canister_id()
+                ),
+                (None, get_mock_user_charlie_canister_id()),
+            ]
+        );
+        assert!(!canister_data.allow_upgrades_for_individual_canisters);
+        assert_eq!(canister_data.last_run_rollback_status.version, "v1.0.0");
+        // * a partial rollback keeps the current wasm
+        assert_eq!(
+            canister_data.individual_user_template_wasm_store.current,
+            Some((&get_wasm("v1.1.0", 2)).into())
+        );
+
+        let rollback = prepare_rollback_impl(
+            get_global_super_admin_principal_id(),
+            None,
+            &mut canister_data,
+            SystemTime::now(),
+        )
+        .unwrap();
+        assert_eq!(rollback.canisters.len(), 3);
+        assert!(rollback.is_full_rollback);
+        // * the wasms are only swapped once all canisters are rolled back
+        assert_eq!(
+            canister_data.individual_user_template_wasm_store.current,
+            Some((&get_wasm("v1.1.0", 2)).into())
+        );
+    }
+
+    #[test]
+    fn test_finish_rollback_swaps_wasms_only_after_a_full_rollback_without_failures() {
+        let mut canister_data = get_canister_data();
+        canister_data
+            .individual_user_template_wasm_store
+            .store_new_wasm(get_wasm("v1.0.0", 1))
+            .unwrap();
+        canister_data
+            .individual_user_template_wasm_store
+            .store_new_wasm(get_wasm("v1.1.0", 2))
+            .unwrap();
+
+        record_rollback_result(&mut canister_data, Ok(()));
+        finish_rollback(&mut canister_data, false, SystemTime::now());
+        assert_eq!(
+            canister_data.individual_user_template_wasm_store.current,
+            Some((&get_wasm("v1.1.0", 2)).into())
+        );
+
+        record_rollback_result(
+            &mut canister_data,
+            Err((
+                Some(get_mock_user_bob_principal_id()),
+                get_mock_user_bob_canister_id(),
+                "Canister is stopped".to_string(),
+            )),
+        );
+        finish_rollback(&mut canister_data, true, SystemTime::now());
+        assert!(canister_data
+            .last_run_rollback_status
+            .halted_reason
+            .is_some());
+        assert_eq!(
+            canister_data
+                .last_run_rollback_status
+                .successful_upgrade_count,
+            1
+        );
+        assert_eq!(
+            canister_data.individual_user_template_wasm_store.current,
+            Some((&get_wasm("v1.1.0", 2)).into())
+        );
+
+        canister_data.last_run_rollback_status = UpgradeStatus::default();
+        finish_rollback(&mut canister_data, true, SystemTime::now());
+        assert_eq!(
+            canister_data.individual_user_template_wasm_store.current,
+            Some((&get_wasm("v1.0.0", 1)).into())
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{
+    data_model::{canister_upgrade::UpgradeRolloutPlan, CanisterData},
+    CANISTER_DATA,
+};
+
+/// Sets the canary canisters, wave percentages and failure rate threshold used by the next
+/// upgrade run of individual user canisters.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_upgrade_rollout_plan(upgrade_rollout_plan: UpgradeRolloutPlan) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_upgrade_rollout_plan_impl(
+            api_caller,
+            upgrade_rollout_plan,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn set_upgrade_rollout_plan_impl(
+    caller: Principal,
+    upgrade_rollout_plan: UpgradeRolloutPlan,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    upgrade_rollout_plan.validate()?;
+
+    canister_data.upgrade_rollout_plan = upgrade_rollout_plan;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_canister_id,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_upgrade_rollout_plan_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        let upgrade_rollout_plan = UpgradeRolloutPlan {
+            canary_canister_ids: vec![get_mock_user_alice_canister_id()],
+            wave_percentages: vec![5, 25, 100],
+            max_failure_rate_percentage: 5,
+        };
+
+        let result = set_upgrade_rollout_plan_impl(
+            get_mock_user_alice_principal_id(),
+            upgrade_rollout_plan.clone(),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = set_upgrade_rollout_plan_impl(
+            get_global_super_admin_principal_id(),
+            UpgradeRolloutPlan {
+                wave_percentages: vec![5, 25],
+                ..upgrade_rollout_plan.clone()
+            },
+            &mut canister_data,
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            canister_data.upgrade_rollout_plan,
+            UpgradeRolloutPlan::default()
+        );
+
+        let result = set_upgrade_rollout_plan_impl(
+            get_global_super_admin_principal_id(),
+            upgrade_rollout_plan.clone(),
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(canister_data.upgrade_rollout_plan, upgrade_rollout_plan);
+    }
+}
@@ -1,27 +1,75 @@
-use std::time::SystemTime;
+use std::{cell::Cell, collections::BTreeMap, time::SystemTime};
 
 use candid::Principal;
-use ic_cdk::api::management_canister::{
-    main::{self, CanisterInstallMode},
-    provisional::CanisterIdRecord,
+use ic_cdk::api::{
+    call::{self, CallResult},
+    management_canister::{
+        main::{self, CanisterInstallMode},
+        provisional::CanisterIdRecord,
+    },
 };
 
 use shared_utils::{
-    canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs,
-    common::utils::{system_time, task},
+    canister_specific::{
+        data_backup::types::backup_snapshot::BackupSnapshotKind,
+        individual_user_template::types::arg::IndividualUserTemplateInitArgs,
+    },
+    common::{
+        types::{known_principal::KnownPrincipalType, version_details::VersionDetails},
+        utils::{
+            system_time,
+            task::{self, InProgressGuard},
+        },
+    },
 };
 
 use crate::{
-    data_model::{configuration::Configuration, CanisterData},
-    util::canister_management::{self, recharge_canister_if_below_threshold},
+    data_model::{
+        canister_upgrade::{
+            FailedUpgrade, UpgradeCursor, UpgradeCursorTracker, UpgradeErrorKind, UpgradeRolloutPlan,
+            UpgradeWaveProgress, UpgradeWaveState, PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX,
+        },
+        configuration::Configuration,
+        CanisterData,
+    },
+    util::{
+        canister_management::{self, recharge_canister_if_below_threshold},
+        pre_upgrade_backup,
+    },
     CANISTER_DATA,
 };
 
 const MAX_CONCURRENCY: usize = 11;
+const MIN_UPGRADES_BEFORE_CHECKING_FAILURE_RATE: usize = 20;
+
+thread_local! {
+    static IS_UPGRADE_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
 
+pub fn is_upgrade_in_progress() -> bool {
+    IS_UPGRADE_IN_PROGRESS.with(|is_in_progress| is_in_progress.get())
+}
+
+/// Upgrades all indexed canisters with the current wasm. A run that was stopped, by
+/// `set_permission_to_upgrade_individual_canisters`, a halt or an upgrade of this canister, resumes
+/// from the persisted `upgrade_cursor` when it is started again with the same wasm.
 pub async fn upgrade_user_canisters_with_latest_wasm() {
-    let mut upgrade_count = 0;
-    let mut failed_canister_ids = Vec::new();
+    let Some(wasm_hash) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell.borrow().individual_user_template_wasm_store.current.as_ref().map(|wasm| wasm.wasm_hash.clone())
+    }) else {
+        ic_cdk::print("Skipping upgrade of individual user canisters as no wasm is uploaded");
+        return;
+    };
+
+    if canister_management::is_rollback_in_progress() {
+        ic_cdk::print("Skipping upgrade of individual user canisters as a rollback is in progress");
+        return;
+    }
+
+    let Some(_upgrade_in_progress_guard) = InProgressGuard::acquire(&IS_UPGRADE_IN_PROGRESS) else {
+        ic_cdk::print("Skipping upgrade of individual user canisters as a run is already in progress");
+        return;
+    };
 
     let user_principal_id_to_canister_id_map = CANISTER_DATA.with(|canister_data_ref_cell| {
         canister_data_ref_cell
@@ -40,58 +88,348 @@ pub async fn upgrade_user_canisters_with_latest_wasm() {
     let configuration = CANISTER_DATA
         .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().configuration.clone());
 
-   let upgrade_individual_canister_futures = user_principal_id_to_canister_id_map.iter()
-    .map(|(user_principal_id, user_canister_id)| {
-        recharge_and_upgrade(*user_canister_id, *user_principal_id, saved_upgrade_status.version_number, configuration.clone(), saved_upgrade_status.version.clone())
-    });
+    let rollout_plan = CANISTER_DATA
+        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().upgrade_rollout_plan.clone());
 
-    let result_callback = |upgrade_result: Result<Principal, (Principal, String)>| {
+    let (mut upgrade_cursor, waves, is_new_run) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.last_run_upgrade_status.halted_reason = None;
 
-        if upgrade_result.is_err() {
-            let (done_user_principal_id, err) = upgrade_result.err().unwrap();
-            let done_user_canister_id = user_principal_id_to_canister_id_map.get(&done_user_principal_id).unwrap();
-            ic_cdk::print(format!(
-                "Failed to upgrade canister: {:?} with error: {:?}",
-                done_user_canister_id.to_text(),
-                err
-            ));
-            failed_canister_ids.push((done_user_principal_id, *done_user_canister_id, err));
-        }
-        
-        upgrade_count += 1;
-        CANISTER_DATA.with(|canister_data_ref_cell| {
-            update_upgrade_status(
-                &mut canister_data_ref_cell.borrow_mut(),
-                upgrade_count,
-                &failed_canister_ids,
-                None,
-                None,
-            );
+        let resumable_cursor = canister_data.upgrade_cursor.clone().filter(|upgrade_cursor| {
+            upgrade_cursor.is_for_run(saved_upgrade_status.version_number, &wasm_hash)
+                && canister_data.last_run_upgrade_status.waves.len() == upgrade_cursor.planned_waves.len()
         });
-    };
+
+        if let Some(upgrade_cursor) = resumable_cursor {
+            let waves = upgrade_cursor.get_planned_waves(&user_principal_id_to_canister_id_map);
+            return (upgrade_cursor, waves, false);
+        }
+
+        let waves = rollout_plan.plan_waves(&user_principal_id_to_canister_id_map);
+        let last_run_upgrade_status = &mut canister_data.last_run_upgrade_status;
+        last_run_upgrade_status.successful_upgrade_count = 0;
+        last_run_upgrade_status.failed_canister_ids = Vec::new();
+        last_run_upgrade_status.failed_upgrades = Vec::new();
+        last_run_upgrade_status.waves = waves
+            .iter()
+            .map(|(label, wave)| UpgradeWaveProgress::new(label.clone(), wave.len() as u32))
+            .collect();
+
+        let upgrade_cursor = UpgradeCursor::new(saved_upgrade_status.version_number, wasm_hash.clone(), waves.clone());
+        canister_data.upgrade_cursor = Some(upgrade_cursor.clone());
+        (upgrade_cursor, waves, true)
+    });
+    let planned_user_principal_id_to_canister_id_map: BTreeMap<Principal, Principal> = waves.iter().flat_map(|(_, wave)| wave.iter().copied()).collect();
+
+    if is_new_run {
+        take_pre_upgrade_backup_snapshot(&configuration, saved_upgrade_status.version_number, &saved_upgrade_status.version).await;
+    }
+
+    let (mut upgrade_count, mut failed_canister_ids) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let last_run_upgrade_status = &canister_data_ref_cell.borrow().last_run_upgrade_status;
+        (
+            last_run_upgrade_status.successful_upgrade_count,
+            last_run_upgrade_status.failed_canister_ids.clone(),
+        )
+    });
 
     let breaking_condition = || {
         !CANISTER_DATA.with(|canister_data_ref| canister_data_ref.borrow().allow_upgrades_for_individual_canisters)
     };
 
-    task::run_task_concurrently(upgrade_individual_canister_futures, MAX_CONCURRENCY, result_callback, breaking_condition).await;
+    for (wave_index, (_, wave)) in waves.iter().enumerate() {
+        if breaking_condition() {
+            break;
+        }
+
+        if (wave_index as u32) < upgrade_cursor.wave_index {
+            continue;
+        }
+        let remaining_canisters = upgrade_cursor.get_remaining_canisters_of_wave(wave_index as u32, wave);
 
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell.borrow_mut().last_run_upgrade_status.waves[wave_index].state = UpgradeWaveState::InProgress;
+        });
+
+        let mut upgrade_cursor_tracker = UpgradeCursorTracker::new(&remaining_canisters);
+
+        let upgrade_individual_canister_futures = remaining_canisters.iter()
+            .map(|(user_principal_id, user_canister_id)| {
+                recharge_and_upgrade(*user_canister_id, *user_principal_id, saved_upgrade_status.version_number + 1, configuration.clone(), saved_upgrade_status.version.clone())
+            });
+
+        let result_callback = |upgrade_result: Result<Principal, (Principal, String)>| {
+            let done_user_principal_id = match &upgrade_result {
+                Ok(user_principal_id) => *user_principal_id,
+                Err((user_principal_id, _)) => *user_principal_id,
+            };
+            let failed_upgrade = upgrade_result.err().map(|(done_user_principal_id, err)| {
+                let done_user_canister_id = planned_user_principal_id_to_canister_id_map.get(&done_user_principal_id).unwrap();
+                ic_cdk::print(format!(
+                    "Failed to upgrade canister: {:?} with error: {:?}",
+                    done_user_canister_id.to_text(),
+                    err
+                ));
+                failed_canister_ids.push((done_user_principal_id, *done_user_canister_id, err.clone()));
+                FailedUpgrade::new(done_user_principal_id, *done_user_canister_id, err)
+            });
+
+            upgrade_count += 1;
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                let mut canister_data = canister_data_ref_cell.borrow_mut();
+                let wave_progress = &mut canister_data.last_run_upgrade_status.waves[wave_index];
+                match failed_upgrade {
+                    None => wave_progress.successful_upgrade_count += 1,
+                    Some(failed_upgrade) => {
+                        wave_progress.failed_upgrade_count += 1;
+                        canister_data.last_run_upgrade_status.failed_upgrades.push(failed_upgrade);
+                    }
+                }
+
+                if let Some(last_upgraded_user_principal_id) = upgrade_cursor_tracker.mark_done(done_user_principal_id) {
+                    if let Some(upgrade_cursor) = canister_data.upgrade_cursor.as_mut() {
+                        upgrade_cursor.wave_index = wave_index as u32;
+                        upgrade_cursor.last_upgraded_user_principal_id = Some(last_upgraded_user_principal_id);
+                    }
+                }
+
+                update_upgrade_status(
+                    &mut canister_data,
+                    upgrade_count,
+                    &failed_canister_ids,
+                    None,
+                    None,
+                );
+
+                if upgrade_count as usize >= MIN_UPGRADES_BEFORE_CHECKING_FAILURE_RATE {
+                    halt_upgrades_if_failure_rate_above_threshold(&mut canister_data, &rollout_plan, failed_canister_ids.len(), upgrade_count as usize);
+                }
+            });
+        };
+
+        task::run_task_concurrently(upgrade_individual_canister_futures, MAX_CONCURRENCY, result_callback, breaking_condition).await;
+
+        // * waves are small at the start of a rollout, so the failure rate is also checked once each wave is done
+        let is_wave_completed = CANISTER_DATA.with(|canister_data_ref_cell| {
+            let mut canister_data = canister_data_ref_cell.borrow_mut();
+            halt_upgrades_if_failure_rate_above_threshold(&mut canister_data, &rollout_plan, failed_canister_ids.len(), upgrade_count as usize);
+
+            let is_wave_completed = canister_data.allow_upgrades_for_individual_canisters;
+            canister_data.last_run_upgrade_status.waves[wave_index].state = if is_wave_completed {
+                UpgradeWaveState::Completed
+            } else {
+                UpgradeWaveState::Halted
+            };
+
+            if is_wave_completed {
+                if let Some(upgrade_cursor) = canister_data.upgrade_cursor.as_mut() {
+                    upgrade_cursor.wave_index = wave_index as u32 + 1;
+                    upgrade_cursor.last_upgraded_user_principal_id = None;
+                }
+            }
+
+            is_wave_completed
+        });
+
+        if is_wave_completed {
+            upgrade_cursor.wave_index = wave_index as u32 + 1;
+            upgrade_cursor.last_upgraded_user_principal_id = None;
+        }
+    }
+
+    let is_run_completed = !breaking_condition();
+
+    if is_run_completed {
+        upgrade_available_canisters(saved_upgrade_status.version_number, &configuration, &saved_upgrade_status.version).await;
+    }
+
+    // * a stopped run keeps its cursor and version number so that it can be resumed
     CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        if is_run_completed {
+            canister_data.upgrade_cursor = None;
+        }
+
         update_upgrade_status(
-            &mut canister_data_ref_cell.borrow_mut(),
+            &mut canister_data,
             upgrade_count,
             &failed_canister_ids,
-            Some(saved_upgrade_status.version_number + 1),
+            is_run_completed.then_some(saved_upgrade_status.version_number + 1),
             Some(system_time::get_current_system_time_from_ic()),
         );
     });
 }
 
+/// Upgrades the canisters that failed in the last completed run again. Canisters that failed
+/// `MAX_UPGRADE_ATTEMPTS` times are left for manual follow up.
+pub async fn retry_failed_upgrades(failed_canisters: Vec<(Principal, Principal)>) {
+    if canister_management::is_rollback_in_progress() {
+        return;
+    }
+
+    let Some(_upgrade_in_progress_guard) = InProgressGuard::acquire(&IS_UPGRADE_IN_PROGRESS) else {
+        return;
+    };
+
+    let (upgrade_version_number, configuration, version) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
+        (
+            canister_data.last_run_upgrade_status.version_number,
+            canister_data.configuration.clone(),
+            canister_data.last_run_upgrade_status.version.clone(),
+        )
+    });
 
-async fn recharge_and_upgrade(user_canister_id: Principal, user_principal_id: Principal, version_number: u64, configuration: Configuration, version: String) -> Result<Principal, (Principal, String)> {
-    recharge_canister_if_below_threshold(&user_canister_id).await?;
-    
-    upgrade_user_canister(&user_principal_id, &user_canister_id, version_number, &configuration, version).await.map_err(|s| (user_principal_id, s))?;
+    let retry_futures = failed_canisters.iter().map(|(user_principal_id, user_canister_id)| {
+        recharge_and_upgrade(*user_canister_id, *user_principal_id, upgrade_version_number, configuration.clone(), version.clone())
+    });
+
+    let result_callback = |retry_result: Result<Principal, (Principal, String)>| {
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            let mut canister_data = canister_data_ref_cell.borrow_mut();
+            match retry_result {
+                Ok(user_principal_id) => record_retry_result(&mut canister_data, user_principal_id, Ok(())),
+                Err((user_principal_id, err)) => record_retry_result(&mut canister_data, user_principal_id, Err(err)),
+            }
+        });
+    };
+
+    task::run_task_concurrently(retry_futures, MAX_CONCURRENCY, result_callback, || false).await;
+}
+
+fn record_retry_result(canister_data: &mut CanisterData, user_principal_id: Principal, retry_result: Result<(), String>) {
+    let last_run_upgrade_status = &mut canister_data.last_run_upgrade_status;
+    let Some(failed_upgrade_index) = last_run_upgrade_status
+        .failed_upgrades
+        .iter()
+        .position(|failed_upgrade| failed_upgrade.user_principal_id == user_principal_id)
+    else {
+        return;
+    };
+
+    match retry_result {
+        Ok(()) => {
+            last_run_upgrade_status.failed_upgrades.remove(failed_upgrade_index);
+            last_run_upgrade_status
+                .failed_canister_ids
+                .retain(|(failed_user_principal_id, _, _)| *failed_user_principal_id != user_principal_id);
+        }
+        Err(err) => {
+            let failed_upgrade = &mut last_run_upgrade_status.failed_upgrades[failed_upgrade_index];
+            failed_upgrade.attempt_count += 1;
+            failed_upgrade.error_kind = UpgradeErrorKind::from_error_message(&err);
+            failed_upgrade.error_message = err.clone();
+
+            last_run_upgrade_status
+                .failed_canister_ids
+                .iter_mut()
+                .filter(|(failed_user_principal_id, _, _)| *failed_user_principal_id == user_principal_id)
+                .for_each(|(_, _, failed_err)| *failed_err = err.clone());
+        }
+    }
+}
+
+/// Stops the run the same way `set_permission_to_upgrade_individual_canisters` does, so that it
+/// has to be restarted explicitly once the failures are looked into.
+fn halt_upgrades_if_failure_rate_above_threshold(
+    canister_data: &mut CanisterData,
+    rollout_plan: &UpgradeRolloutPlan,
+    failed_count: usize,
+    attempted_count: usize,
+) {
+    if !canister_data.allow_upgrades_for_individual_canisters
+        || !rollout_plan.is_failure_rate_above_threshold(failed_count, attempted_count)
+    {
+        return;
+    }
+
+    canister_data.allow_upgrades_for_individual_canisters = false;
+    canister_data.last_run_upgrade_status.halted_reason = Some(format!(
+        "{} of {} upgrades failed, which is above the allowed failure rate of {}%",
+        failed_count, attempted_count, rollout_plan.max_failure_rate_percentage
+    ));
+}
+
+/// Canisters in the pool have no owner yet. They are upgraded too so that they are on the latest
+/// version when handed out.
+async fn upgrade_available_canisters(version_number: u64, configuration: &Configuration, version: &str) {
+    let available_canisters = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell.borrow().available_canisters.clone()
+    });
+
+    let upgrade_available_canister_futures = available_canisters.into_iter().map(|canister_id| async move {
+        canister_management::upgrade_individual_user_canister(
+            canister_id,
+            CanisterInstallMode::Upgrade,
+            IndividualUserTemplateInitArgs {
+                known_principal_ids: Some(configuration.known_principal_ids.clone()),
+                profile_owner: None,
+                upgrade_version_number: Some(version_number + 1),
+                url_to_send_canister_metrics_to: Some(
+                    configuration.url_to_send_canister_metrics_to.clone(),
+                ),
+                version: version.to_string(),
+            },
+        )
+        .await
+        .map_err(|e| (canister_id, e.1))
+    });
+
+    let result_callback = |upgrade_result: Result<(), (Principal, String)>| {
+        if let Err((canister_id, err)) = upgrade_result {
+            ic_cdk::print(format!(
+                "Failed to upgrade pool canister: {:?} with error: {:?}",
+                canister_id.to_text(),
+                err
+            ));
+        }
+    };
+
+    let breaking_condition = || {
+        !CANISTER_DATA.with(|canister_data_ref| canister_data_ref.borrow().allow_upgrades_for_individual_canisters)
+    };
+
+    task::run_task_concurrently(upgrade_available_canister_futures, MAX_CONCURRENCY, result_callback, breaking_condition).await;
+}
+
+/// Keeps the backups as they were before the run, so that they survive upgraded canisters
+/// backing up bad data. The run goes ahead if the snapshot cannot be taken.
+async fn take_pre_upgrade_backup_snapshot(configuration: &Configuration, version_number: u64, version: &str) {
+    let Some(data_backup_canister_id) = configuration.known_principal_ids.get(&KnownPrincipalType::CanisterIdDataBackup) else {
+        return;
+    };
+
+    let source_wasm_version = VersionDetails {
+        version_number,
+        version: version.to_string(),
+    };
+    let snapshot_result: CallResult<(Result<u64, String>,)> = call::call(
+        *data_backup_canister_id,
+        "take_backup_snapshot",
+        (BackupSnapshotKind::PreUpgrade, Some(source_wasm_version)),
+    )
+    .await;
+
+    match snapshot_result {
+        Ok((Ok(_),)) => {}
+        Ok((Err(e),)) | Err((_, e)) => ic_cdk::print(format!("Failed to take pre-upgrade backup snapshot: {}", e)),
+    }
+}
+
+/// Canisters are backed up, and the backup verified, before they are upgraded. A canister whose
+/// backup fails is skipped and reported as a failed upgrade.
+pub(crate) async fn recharge_and_upgrade(user_canister_id: Principal, user_principal_id: Principal, upgrade_version_number: u64, configuration: Configuration, version: String) -> Result<Principal, (Principal, String)> {
+    recharge_canister_if_below_threshold(&user_canister_id).await.map_err(|(_, s)| (user_principal_id, s))?;
+
+    let data_backup_canister_id = configuration.known_principal_ids.get(&KnownPrincipalType::CanisterIdDataBackup).copied().ok_or((
+        user_principal_id,
+        format!("{}: data backup canister not found in internal records", PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX),
+    ))?;
+    let backup_key_counts = pre_upgrade_backup::back_up_canister_before_upgrade(user_principal_id, user_canister_id, data_backup_canister_id).await.map_err(|s| (user_principal_id, s))?;
+
+    upgrade_user_canister(&user_principal_id, &user_canister_id, upgrade_version_number, &configuration, version).await.map_err(|s| (user_principal_id, s))?;
+
+    pre_upgrade_backup::validate_canister_after_upgrade(user_canister_id, &backup_key_counts).await.map_err(|s| (user_principal_id, s))?;
 
     Ok(user_principal_id)
 }
@@ -99,7 +437,7 @@ async fn recharge_and_upgrade(user_canister_id: Principal, user_principal_id: Pr
 async fn upgrade_user_canister(
     user_principal_id: &Principal,
     canister_id: &Principal,
-    version_number: u64,
+    upgrade_version_number: u64,
     configuration: &Configuration,
     version: String
 ) -> Result<(), String> {
@@ -109,7 +447,7 @@ async fn upgrade_user_canister(
         IndividualUserTemplateInitArgs {
             known_principal_ids: Some(configuration.known_principal_ids.clone()),
             profile_owner: Some(*user_principal_id),
-            upgrade_version_number: Some(version_number + 1),
+            upgrade_version_number: Some(upgrade_version_number),
             url_to_send_canister_metrics_to: Some(
                 configuration.url_to_send_canister_metrics_to.clone(),
             ),
@@ -138,3 +476,58 @@ fn update_upgrade_status(
 
     canister_data.last_run_upgrade_status = last_run_upgrade_status;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_halt_upgrades_if_failure_rate_above_threshold() {
+        let mut canister_data = CanisterData {
+            allow_upgrades_for_individual_canisters: true,
+            ..Default::default()
+        };
+        let rollout_plan = UpgradeRolloutPlan {
+            canary_canister_ids: vec![],
+            wave_percentages: vec![100],
+            max_failure_rate_percentage: 10,
+        };
+
+        halt_upgrades_if_failure_rate_above_threshold(&mut canister_data, &rollout_plan, 1, 10);
+        assert!(canister_data.allow_upgrades_for_individual_canisters);
+        assert_eq!(canister_data.last_run_upgrade_status.halted_reason, None);
+
+        halt_upgrades_if_failure_rate_above_threshold(&mut canister_data, &rollout_plan, 2, 10);
+        assert!(!canister_data.allow_upgrades_for_individual_canisters);
+        assert!(canister_data.last_run_upgrade_status.halted_reason.is_some());
+    }
+
+    #[test]
+    fn test_record_retry_result() {
+        let alice_principal_id = Principal::self_authenticating([1]);
+        let alice_canister_id = Principal::from_slice(&[1]);
+        let bob_principal_id = Principal::self_authenticating([2]);
+        let bob_canister_id = Principal::from_slice(&[2]);
+
+        let mut canister_data = CanisterData::default();
+        canister_data.last_run_upgrade_status.failed_canister_ids = vec![
+            (alice_principal_id, alice_canister_id, "Canister is out of cycles".to_string()),
+            (bob_principal_id, bob_canister_id, "Canister is out of cycles".to_string()),
+        ];
+        canister_data.last_run_upgrade_status.failed_upgrades = vec![
+            FailedUpgrade::new(alice_principal_id, alice_canister_id, "Canister is out of cycles".to_string()),
+            FailedUpgrade::new(bob_principal_id, bob_canister_id, "Canister is out of cycles".to_string()),
+        ];
+
+        record_retry_result(&mut canister_data, alice_principal_id, Ok(()));
+        record_retry_result(&mut canister_data, bob_principal_id, Err("Canister is stopped".to_string()));
+
+        let last_run_upgrade_status = &canister_data.last_run_upgrade_status;
+        assert_eq!(last_run_upgrade_status.failed_canister_ids.len(), 1);
+        assert_eq!(last_run_upgrade_status.failed_canister_ids[0].2, "Canister is stopped");
+        assert_eq!(last_run_upgrade_status.failed_upgrades.len(), 1);
+        assert_eq!(last_run_upgrade_status.failed_upgrades[0].user_principal_id, bob_principal_id);
+        assert_eq!(last_run_upgrade_status.failed_upgrades[0].attempt_count, 2);
+        assert_eq!(last_run_upgrade_status.failed_upgrades[0].error_kind, UpgradeErrorKind::CanisterStopped);
+    }
+}
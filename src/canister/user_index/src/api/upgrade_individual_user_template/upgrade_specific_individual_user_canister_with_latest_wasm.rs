@@ -29,6 +29,10 @@ async fn upgrade_specific_individual_user_canister_with_latest_wasm(
         return "Unauthorized caller".to_string();
     };
 
+    if canister_management::is_rollback_in_progress() {
+        return "A rollback is in progress".to_string();
+    }
+
     let saved_upgrade_status = CANISTER_DATA.with(|canister_data_ref_cell| {
         canister_data_ref_cell
             .borrow()
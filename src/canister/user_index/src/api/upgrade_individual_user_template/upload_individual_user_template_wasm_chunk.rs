@@ -0,0 +1,139 @@
+use candid::Principal;
+use shared_utils::{
+    canister_specific::user_index::types::individual_user_template_wasm::{
+        IndividualUserTemplateWasmChunk, IndividualUserTemplateWasmDetails,
+    },
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data_model::CanisterData, util::canister_management, CANISTER_DATA};
+
+use super::update_user_index_upgrade_user_canisters_with_latest_wasm;
+
+/// Uploads the individual user template wasm in chunks. Once the last chunk is in and the sha256
+/// matches, the wasm becomes the one new canisters are created with and upgrade runs install, and
+/// its details are returned. Callable by the super admin or through an SNS proposal. Rejected while
+/// an upgrade run or a rollback is in progress, so that a run keeps installing the wasm it started
+/// with and a rollback's swap of the stored wasms is not undone.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn upload_individual_user_template_wasm_chunk(
+    wasm_chunk: IndividualUserTemplateWasmChunk,
+) -> Result<Option<IndividualUserTemplateWasmDetails>, String> {
+    let api_caller = ic_cdk::caller();
+
+    if update_user_index_upgrade_user_canisters_with_latest_wasm::is_upgrade_in_progress() {
+        return Err("An upgrade run is in progress".to_string());
+    }
+
+    if canister_management::is_rollback_in_progress() {
+        return Err("A rollback is in progress".to_string());
+    }
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        upload_individual_user_template_wasm_chunk_impl(
+            api_caller,
+            wasm_chunk,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn validate_upload_individual_user_template_wasm_chunk(
+    _wasm_chunk: IndividualUserTemplateWasmChunk,
+) -> Result<String, String> {
+    let caller_id = ic_cdk::caller();
+    let governance_canister_id = CANISTER_DATA
+        .with(|canister_data_ref| {
+            canister_data_ref
+                .borrow()
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdSnsGovernance)
+                .cloned()
+        })
+        .ok_or("Governance Canister Id not found")?;
+
+    if caller_id != governance_canister_id {
+        return Err("This Proposal can only be executed through DAO".to_string());
+    };
+
+    Ok("Success".to_string())
+}
+
+fn upload_individual_user_template_wasm_chunk_impl(
+    caller: Principal,
+    wasm_chunk: IndividualUserTemplateWasmChunk,
+    canister_data: &mut CanisterData,
+) -> Result<Option<IndividualUserTemplateWasmDetails>, String> {
+    let known_principal_ids = &canister_data.configuration.known_principal_ids;
+    let is_allowed_caller = [
+        KnownPrincipalType::UserIdGlobalSuperAdmin,
+        KnownPrincipalType::CanisterIdSnsGovernance,
+    ]
+    .iter()
+    .any(|principal_type| known_principal_ids.get(principal_type) == Some(&caller));
+
+    if !is_allowed_caller {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data
+        .individual_user_template_wasm_store
+        .receive_wasm_chunk(wasm_chunk)
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_sns,
+        get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_upload_individual_user_template_wasm_chunk_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdSnsGovernance,
+            get_mock_canister_id_sns(),
+        );
+        let chunks = IndividualUserTemplateWasmChunk::split_wasm_module("v1.0.0", &[1; 10], 6);
+
+        let result = upload_individual_user_template_wasm_chunk_impl(
+            get_mock_user_alice_principal_id(),
+            chunks[0].clone(),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = upload_individual_user_template_wasm_chunk_impl(
+            get_global_super_admin_principal_id(),
+            chunks[0].clone(),
+            &mut canister_data,
+        );
+        assert_eq!(result, Ok(None));
+
+        let result = upload_individual_user_template_wasm_chunk_impl(
+            get_mock_canister_id_sns(),
+            chunks[1].clone(),
+            &mut canister_data,
+        );
+        assert_eq!(result.unwrap().unwrap().wasm_size_in_bytes, 10);
+        assert_eq!(
+            canister_data
+                .individual_user_template_wasm_store
+                .current
+                .unwrap()
+                .version,
+            "v1.0.0"
+        );
+    }
+}
@@ -1,12 +1,26 @@
+use shared_utils::common::utils::{system_time, user_name::normalize_user_name};
+
 use crate::CANISTER_DATA;
 
+/// Usernames that were recently replaced stay taken while they are reserved for their
+/// previous owner. Usernames that read the same as a taken one, e.g. `adm1n` for `admin`, are
+/// taken too.
 #[ic_cdk::query]
 #[candid::candid_method(query)]
 fn get_index_details_is_user_name_taken(user_name: String) -> bool {
+    let user_name = normalize_user_name(&user_name);
+
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell
-            .borrow()
+        let canister_data = canister_data_ref_cell.borrow();
+
+        canister_data
             .unique_user_name_to_user_principal_id_map
             .contains_key(&user_name)
+            || !canister_data
+                .get_look_alike_user_name_owners(
+                    &user_name,
+                    system_time::get_current_system_time_from_ic(),
+                )
+                .is_empty()
     })
 }
@@ -1,6 +1,13 @@
-use crate::{util::canister_management::create_users_canister, CANISTER_DATA};
+use crate::{
+    util::{
+        canister_pool::get_canister_from_pool_or_create,
+        canister_reclamation::restore_data_of_reclaimed_user,
+    },
+    CANISTER_DATA,
+};
 use candid::Principal;
 use ic_cdk::api::call;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
 
 #[ic_cdk::update]
 #[candid::candid_method(update)]
@@ -25,8 +32,82 @@ async fn get_requester_principals_canister_id_create_if_not_exists_and_optionall
         // * canister already exists
         Some(canister_id) => canister_id,
         None => {
-            // * create new canister
-            let created_canister_id = create_users_canister(api_caller).await;
+            // * the mapping is cleared part way through a deletion, before the canister is freed
+            if CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow()
+                    .account_deletions
+                    .is_in_progress(&api_caller)
+            }) {
+                panic!("This account is being deleted");
+            }
+
+            let was_canister_reclaimed = CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow()
+                    .canister_reclamation
+                    .reclaimed_user_canisters
+                    .contains_key(&api_caller)
+            });
+
+            // * returning user whose canister was reclaimed, restore their data instead of rewarding a signup
+            if was_canister_reclaimed {
+                return restore_data_of_reclaimed_user(api_caller)
+                    .await
+                    .unwrap_or_else(|e| {
+                        panic!("Failed to restore your data, please retry: {}", e)
+                    });
+            }
+
+            let (signups_open_on_this_subnet, platform_orchestrator_canister_id) = CANISTER_DATA
+                .with(|canister_data_ref_cell| {
+                    let configuration = &canister_data_ref_cell.borrow().configuration;
+                    (
+                        configuration.signups_open_on_this_subnet,
+                        configuration
+                            .known_principal_ids
+                            .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+                            .cloned(),
+                    )
+                });
+
+            if !signups_open_on_this_subnet {
+                panic!("Signups are closed on this subnet");
+            }
+
+            // * claim the user with the platform orchestrator before provisioning, so that a user
+            // * owned by another user_index does not get a second canister here
+            if let Some(platform_orchestrator_canister_id) = platform_orchestrator_canister_id {
+                let (claim_result,): (Result<(), String>,) = call::call(
+                    platform_orchestrator_canister_id,
+                    "report_new_user_of_user_index",
+                    (api_caller,),
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to check with the platform orchestrator, please retry: {}",
+                        e.1
+                    )
+                });
+                if let Err(e) = claim_result {
+                    panic!("{}", e);
+                }
+
+                // * a concurrent call for the same user may have provisioned a canister meanwhile
+                if let Some(canister_id) = CANISTER_DATA.with(|canister_data_ref_cell| {
+                    canister_data_ref_cell
+                        .borrow()
+                        .user_principal_id_to_canister_id_map
+                        .get(&api_caller)
+                        .cloned()
+                }) {
+                    return canister_id;
+                }
+            }
+
+            // * hand out a pre-provisioned canister, or create a new one if none are left
+            let created_canister_id = get_canister_from_pool_or_create(api_caller).await;
 
             CANISTER_DATA.with(|canister_data_ref_cell| {
                 canister_data_ref_cell
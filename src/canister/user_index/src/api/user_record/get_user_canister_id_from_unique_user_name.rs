@@ -1,23 +1,41 @@
+use std::time::SystemTime;
+
 use candid::Principal;
+use shared_utils::common::utils::{system_time, user_name::normalize_user_name};
 
 use crate::{data_model::CanisterData, CANISTER_DATA};
 
+/// Also resolves usernames that were recently replaced, to the canister of the user that
+/// replaced them, for as long as they stay reserved.
 #[ic_cdk::query]
 #[candid::candid_method(query)]
 fn get_user_canister_id_from_unique_user_name(user_name: String) -> Option<Principal> {
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        get_user_canister_id_from_unique_user_name_impl(user_name, &canister_data_ref_cell.borrow())
+        get_user_canister_id_from_unique_user_name_impl(
+            user_name,
+            &canister_data_ref_cell.borrow(),
+            system_time::get_current_system_time_from_ic(),
+        )
     })
 }
 
 fn get_user_canister_id_from_unique_user_name_impl(
     user_name: String,
     canister_data: &CanisterData,
+    current_time: SystemTime,
 ) -> Option<Principal> {
+    let user_name = normalize_user_name(&user_name);
+
     let profile_principal_id = canister_data
         .unique_user_name_to_user_principal_id_map
         .get(&user_name)
-        .cloned()?;
+        .cloned()
+        .or_else(|| {
+            canister_data
+                .user_name_history
+                .get_active_reservation(&user_name, current_time)
+                .map(|reservation| reservation.user_principal_id)
+        })?;
 
     canister_data
         .user_principal_id_to_canister_id_map
@@ -31,6 +49,8 @@ mod test {
         get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
     };
 
+    use crate::data_model::user_name_history::USER_NAME_RESERVATION_GRACE_PERIOD;
+
     use super::*;
 
     #[test]
@@ -41,6 +61,7 @@ mod test {
         let result = get_user_canister_id_from_unique_user_name_impl(
             alice_user_name.clone(),
             &canister_data,
+            SystemTime::now(),
         );
         assert_eq!(result, None);
 
@@ -50,6 +71,7 @@ mod test {
         let result = get_user_canister_id_from_unique_user_name_impl(
             alice_user_name.clone(),
             &canister_data,
+            SystemTime::now(),
         );
         assert_eq!(result, None);
 
@@ -60,7 +82,49 @@ mod test {
         let result = get_user_canister_id_from_unique_user_name_impl(
             alice_user_name.clone(),
             &canister_data,
+            SystemTime::now(),
+        );
+        assert_eq!(result, Some(get_mock_user_alice_canister_id()));
+
+        let result = get_user_canister_id_from_unique_user_name_impl(
+            "Cool_Alice_1234".to_string(),
+            &canister_data,
+            SystemTime::now(),
+        );
+        assert_eq!(result, Some(get_mock_user_alice_canister_id()));
+    }
+
+    #[test]
+    fn test_get_user_canister_id_from_unique_user_name_impl_resolves_replaced_user_name() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("alice_new".to_string(), get_mock_user_alice_principal_id());
+        canister_data.user_name_history.record_change(
+            get_mock_user_alice_principal_id(),
+            Some("alice".to_string()),
+            "alice_new".to_string(),
+            now,
+        );
+
+        let result = get_user_canister_id_from_unique_user_name_impl(
+            "alice".to_string(),
+            &canister_data,
+            now,
         );
         assert_eq!(result, Some(get_mock_user_alice_canister_id()));
+
+        let result = get_user_canister_id_from_unique_user_name_impl(
+            "alice".to_string(),
+            &canister_data,
+            now + USER_NAME_RESERVATION_GRACE_PERIOD,
+        );
+        assert_eq!(result, None);
     }
 }
@@ -0,0 +1,31 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_name_blocklist() -> Result<Vec<String>, String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_name_blocklist_impl(api_caller, &canister_data_ref_cell.borrow())
+    })
+}
+
+fn get_user_name_blocklist_impl(
+    caller: Principal,
+    canister_data: &CanisterData,
+) -> Result<Vec<String>, String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    Ok(canister_data.user_name_blocklist.iter().cloned().collect())
+}
@@ -0,0 +1,139 @@
+use std::ops::Bound;
+
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+const MAX_USER_PRINCIPAL_IDS_PER_PAGE: u64 = 1000;
+
+/// Principals of the users indexed here, in order, starting after `start_after`. Used by the
+/// platform orchestrator to learn which users this user_index owns, and by data_backup to find
+/// the users that have no backup.
+#[ic_cdk::query]
+#[candid::candid_method(query)]
+fn get_user_principal_ids_paginated(
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Result<Vec<Principal>, String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_user_principal_ids_paginated_impl(
+            api_caller,
+            start_after,
+            limit,
+            &canister_data_ref_cell.borrow(),
+        )
+    })
+}
+
+fn get_user_principal_ids_paginated_impl(
+    caller: Principal,
+    start_after: Option<Principal>,
+    limit: u64,
+    canister_data: &CanisterData,
+) -> Result<Vec<Principal>, String> {
+    let is_authorized = [
+        KnownPrincipalType::UserIdGlobalSuperAdmin,
+        KnownPrincipalType::CanisterIdPlatformOrchestrator,
+        KnownPrincipalType::CanisterIdDataBackup,
+    ]
+    .iter()
+    .any(|principal_type| {
+        canister_data
+            .configuration
+            .known_principal_ids
+            .get(principal_type)
+            == Some(&caller)
+    });
+
+    if !is_authorized {
+        return Err("Unauthorized".to_string());
+    }
+
+    let lower_bound = match start_after {
+        Some(start_after) => Bound::Excluded(start_after),
+        None => Bound::Unbounded,
+    };
+
+    Ok(canister_data
+        .user_principal_id_to_canister_id_map
+        .range((lower_bound, Bound::Unbounded))
+        .take(limit.min(MAX_USER_PRINCIPAL_IDS_PER_PAGE) as usize)
+        .map(|(user_principal_id, _)| *user_principal_id)
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_data_backup,
+        get_mock_canister_id_platform_orchestrator, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_principal_ids_paginated_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdPlatformOrchestrator,
+            get_mock_canister_id_platform_orchestrator(),
+        );
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdDataBackup,
+            get_mock_canister_id_data_backup(),
+        );
+        for i in 0..5 {
+            canister_data.user_principal_id_to_canister_id_map.insert(
+                Principal::self_authenticating([i]),
+                Principal::from_slice(&[i]),
+            );
+        }
+        let user_principal_ids: Vec<Principal> = canister_data
+            .user_principal_id_to_canister_id_map
+            .keys()
+            .copied()
+            .collect();
+
+        let result = get_user_principal_ids_paginated_impl(
+            get_mock_user_alice_principal_id(),
+            None,
+            10,
+            &canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let first_page = get_user_principal_ids_paginated_impl(
+            get_mock_canister_id_platform_orchestrator(),
+            None,
+            3,
+            &canister_data,
+        )
+        .unwrap();
+        assert_eq!(first_page, user_principal_ids[..3]);
+
+        let second_page = get_user_principal_ids_paginated_impl(
+            get_global_super_admin_principal_id(),
+            first_page.last().copied(),
+            3,
+            &canister_data,
+        )
+        .unwrap();
+        assert_eq!(second_page, user_principal_ids[3..]);
+
+        let data_backup_page = get_user_principal_ids_paginated_impl(
+            get_mock_canister_id_data_backup(),
+            None,
+            10,
+            &canister_data,
+        )
+        .unwrap();
+        assert_eq!(data_backup_page, user_principal_ids);
+    }
+}
@@ -3,4 +3,8 @@ pub mod get_requester_principals_canister_id_create_if_not_exists_and_optionally
 pub mod get_user_canister_id_from_unique_user_name;
 pub mod get_user_canister_id_from_user_principal_id;
 pub mod get_user_index_canister_count;
+pub mod get_user_name_blocklist;
+pub mod get_user_principal_ids_paginated;
+pub mod search_user_names_by_prefix;
 pub mod update_index_with_unique_user_name_corresponding_to_user_principal_id;
+pub mod update_user_name_blocklist;
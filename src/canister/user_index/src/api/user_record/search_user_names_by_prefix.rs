@@ -0,0 +1,310 @@
+use std::{ops::Bound, time::SystemTime};
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::user_index::types::user_name_search::{
+        UserNameSearchPage, UserNameSearchResult,
+    },
+    common::utils::{system_time, user_name::normalize_user_name},
+    constant::{
+        MAX_USER_NAMES_SCANNED_PER_SEARCH_REQUEST, MAX_USER_NAME_SEARCH_RESULTS_PER_REQUEST,
+    },
+    types::canister_specific::user_index::error_types::SearchUserNamesError,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Returns users whose username starts with the passed prefix, in username order. Pass the
+/// `next_cursor` of a page back as `cursor` to get the following page.
+///
+/// Anonymous callers are rejected and every caller can search at most
+/// `MAX_USER_NAME_SEARCHES_PER_CALLER_PER_WINDOW` times per window. This is an update so that
+/// searches can be counted. At most `MAX_USER_NAME_SEARCH_RESULTS_PER_REQUEST` results are
+/// returned and at most `MAX_USER_NAMES_SCANNED_PER_SEARCH_REQUEST` usernames are looked at per
+/// call.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn search_user_names_by_prefix(
+    prefix: String,
+    cursor: Option<String>,
+    limit: u64,
+) -> Result<UserNameSearchPage, SearchUserNamesError> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        search_user_names_by_prefix_impl(
+            api_caller,
+            prefix,
+            cursor,
+            limit,
+            &mut canister_data_ref_cell.borrow_mut(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn search_user_names_by_prefix_impl(
+    caller: Principal,
+    prefix: String,
+    cursor: Option<String>,
+    limit: u64,
+    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+) -> Result<UserNameSearchPage, SearchUserNamesError> {
+    if caller == Principal::anonymous() {
+        return Err(SearchUserNamesError::AnonymousCallerNotAllowed);
+    }
+
+    if !canister_data
+        .user_name_search_rate_limiter
+        .try_record_search(caller, current_time)
+    {
+        return Err(SearchUserNamesError::RateLimitExceeded);
+    }
+
+    let prefix = normalize_user_name(&prefix);
+    if prefix.is_empty() {
+        return Err(SearchUserNamesError::PrefixTooShort);
+    }
+
+    let start = match cursor {
+        Some(cursor) => {
+            let cursor = normalize_user_name(&cursor);
+            if !cursor.starts_with(&prefix) {
+                return Err(SearchUserNamesError::InvalidCursor);
+            }
+            Bound::Excluded(cursor)
+        }
+        None => Bound::Included(prefix.clone()),
+    };
+    let limit = limit.clamp(1, MAX_USER_NAME_SEARCH_RESULTS_PER_REQUEST) as usize;
+
+    let mut results = vec![];
+    let mut number_of_user_names_scanned = 0;
+    let mut last_scanned_user_name = None;
+    let mut matching_user_names = canister_data
+        .unique_user_name_to_user_principal_id_map
+        .range::<String, _>((start, Bound::Unbounded))
+        .take_while(|(user_name, _)| user_name.starts_with(&prefix))
+        .peekable();
+
+    while results.len() < limit
+        && number_of_user_names_scanned < MAX_USER_NAMES_SCANNED_PER_SEARCH_REQUEST
+    {
+        let Some((user_name, user_principal_id)) = matching_user_names.next() else {
+            break;
+        };
+        number_of_user_names_scanned += 1;
+        last_scanned_user_name = Some(user_name.clone());
+
+        // * usernames of users whose canister is not indexed anymore are skipped
+        if let Some(canister_id) = canister_data
+            .user_principal_id_to_canister_id_map
+            .get(user_principal_id)
+        {
+            results.push(UserNameSearchResult {
+                user_name: user_name.clone(),
+                user_principal_id: *user_principal_id,
+                canister_id: *canister_id,
+            });
+        }
+    }
+
+    Ok(UserNameSearchPage {
+        results,
+        next_cursor: matching_user_names.peek().and(last_scanned_user_name),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_canister_id, get_mock_user_charlie_principal_id,
+        get_mock_user_dan_principal_id,
+    };
+
+    use crate::data_model::user_name_search_rate_limiter::MAX_USER_NAME_SEARCHES_PER_CALLER_PER_WINDOW;
+
+    use super::*;
+
+    fn get_canister_data_with_users() -> CanisterData {
+        let mut canister_data = CanisterData::default();
+
+        [
+            (
+                "alice",
+                get_mock_user_alice_principal_id(),
+                Some(get_mock_user_alice_canister_id()),
+            ),
+            (
+                "alice_2",
+                get_mock_user_bob_principal_id(),
+                Some(get_mock_user_bob_canister_id()),
+            ),
+            (
+                "alicia",
+                get_mock_user_charlie_principal_id(),
+                Some(get_mock_user_charlie_canister_id()),
+            ),
+            ("alina", get_mock_user_dan_principal_id(), None),
+            ("bob", Principal::self_authenticating([9]), None),
+        ]
+        .into_iter()
+        .for_each(|(user_name, user_principal_id, canister_id)| {
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .insert(user_name.to_string(), user_principal_id);
+            if let Some(canister_id) = canister_id {
+                canister_data
+                    .user_principal_id_to_canister_id_map
+                    .insert(user_principal_id, canister_id);
+            }
+        });
+
+        canister_data
+    }
+
+    #[test]
+    fn test_search_user_names_by_prefix_impl() {
+        let mut canister_data = get_canister_data_with_users();
+        let now = SystemTime::now();
+        let caller = get_mock_user_alice_principal_id();
+
+        let result = search_user_names_by_prefix_impl(
+            Principal::anonymous(),
+            "al".to_string(),
+            None,
+            10,
+            &mut canister_data,
+            now,
+        );
+        assert_eq!(result, Err(SearchUserNamesError::AnonymousCallerNotAllowed));
+
+        let result = search_user_names_by_prefix_impl(
+            caller,
+            " ".to_string(),
+            None,
+            10,
+            &mut canister_data,
+            now,
+        );
+        assert_eq!(result, Err(SearchUserNamesError::PrefixTooShort));
+
+        let result = search_user_names_by_prefix_impl(
+            caller,
+            "ALI".to_string(),
+            None,
+            10,
+            &mut canister_data,
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            result
+                .results
+                .iter()
+                .map(|result| result.user_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["alice", "alice_2", "alicia"]
+        );
+        assert_eq!(
+            result.results[0].canister_id,
+            get_mock_user_alice_canister_id()
+        );
+        assert_eq!(result.next_cursor, None);
+
+        let result = search_user_names_by_prefix_impl(
+            caller,
+            "z".to_string(),
+            None,
+            10,
+            &mut canister_data,
+            now,
+        )
+        .unwrap();
+        assert!(result.results.is_empty());
+        assert_eq!(result.next_cursor, None);
+    }
+
+    #[test]
+    fn test_search_user_names_by_prefix_impl_paginates() {
+        let mut canister_data = get_canister_data_with_users();
+        let now = SystemTime::now();
+        let caller = get_mock_user_alice_principal_id();
+
+        let first_page = search_user_names_by_prefix_impl(
+            caller,
+            "ali".to_string(),
+            None,
+            2,
+            &mut canister_data,
+            now,
+        )
+        .unwrap();
+        assert_eq!(first_page.results.len(), 2);
+        assert_eq!(first_page.next_cursor, Some("alice_2".to_string()));
+
+        let second_page = search_user_names_by_prefix_impl(
+            caller,
+            "ali".to_string(),
+            first_page.next_cursor,
+            2,
+            &mut canister_data,
+            now,
+        )
+        .unwrap();
+        assert_eq!(second_page.results.len(), 1);
+        assert_eq!(second_page.results[0].user_name, "alicia");
+        assert_eq!(second_page.next_cursor, None);
+
+        let result = search_user_names_by_prefix_impl(
+            caller,
+            "ali".to_string(),
+            Some("bob".to_string()),
+            2,
+            &mut canister_data,
+            now,
+        );
+        assert_eq!(result, Err(SearchUserNamesError::InvalidCursor));
+    }
+
+    #[test]
+    fn test_search_user_names_by_prefix_impl_is_rate_limited_per_caller() {
+        let mut canister_data = get_canister_data_with_users();
+        let now = SystemTime::now();
+
+        for _ in 0..MAX_USER_NAME_SEARCHES_PER_CALLER_PER_WINDOW {
+            assert!(search_user_names_by_prefix_impl(
+                get_mock_user_alice_principal_id(),
+                "ali".to_string(),
+                None,
+                10,
+                &mut canister_data,
+                now,
+            )
+            .is_ok());
+        }
+
+        let result = search_user_names_by_prefix_impl(
+            get_mock_user_alice_principal_id(),
+            "ali".to_string(),
+            None,
+            10,
+            &mut canister_data,
+            now,
+        );
+        assert_eq!(result, Err(SearchUserNamesError::RateLimitExceeded));
+
+        let result = search_user_names_by_prefix_impl(
+            get_mock_user_bob_principal_id(),
+            "ali".to_string(),
+            None,
+            10,
+            &mut canister_data,
+            now,
+        );
+        assert!(result.is_ok());
+    }
+}
@@ -1,8 +1,22 @@
+use std::time::SystemTime;
+
 use candid::Principal;
-use shared_utils::types::canister_specific::user_index::error_types::SetUniqueUsernameError;
+use shared_utils::{
+    common::utils::{
+        system_time,
+        user_name::{is_user_name_blocked, validate_and_normalize_user_name},
+    },
+    types::canister_specific::user_index::error_types::SetUniqueUsernameError,
+};
 
 use crate::{data_model::CanisterData, CANISTER_DATA};
 
+/// Sets or changes the username of a user. A user that already has a username can change it
+/// once per cooldown period. The replaced username stays reserved for them for a grace period
+/// and keeps resolving to their canister in the meantime.
+///
+/// Usernames are stored lowercased and have to pass validation and the blocklist. A username
+/// that reads the same as one taken by someone else, e.g. `adm1n` for `admin`, is taken too.
 #[ic_cdk::update]
 #[candid::candid_method(update)]
 fn update_index_with_unique_user_name_corresponding_to_user_principal_id(
@@ -17,6 +31,7 @@ fn update_index_with_unique_user_name_corresponding_to_user_principal_id(
             user_principal_id,
             request_makers_canister_id,
             &mut canister_data_ref_cell.borrow_mut(),
+            system_time::get_current_system_time_from_ic(),
         )
     })
 }
@@ -26,6 +41,7 @@ fn update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
     user_principal_id: Principal,
     request_makers_canister_id: Principal,
     canister_data: &mut CanisterData,
+    current_time: SystemTime,
 ) -> Result<(), SetUniqueUsernameError> {
     if !canister_data
         .user_principal_id_to_canister_id_map
@@ -42,27 +58,89 @@ fn update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
         return Err(SetUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId);
     }
 
-    if canister_data
+    let unique_user_name = validate_and_normalize_user_name(&unique_user_name)?;
+
+    if is_user_name_blocked(&unique_user_name, &canister_data.user_name_blocklist) {
+        return Err(SetUniqueUsernameError::UsernameIsBlocked);
+    }
+
+    if let Some(owner_principal_id) = canister_data
         .unique_user_name_to_user_principal_id_map
-        .contains_key(&unique_user_name)
+        .get(&unique_user_name)
+    {
+        return if *owner_principal_id == user_principal_id {
+            Ok(())
+        } else {
+            Err(SetUniqueUsernameError::UsernameAlreadyTaken)
+        };
+    }
+
+    // * covers the usernames reserved for their previous owners too
+    if canister_data
+        .get_look_alike_user_name_owners(&unique_user_name, current_time)
+        .iter()
+        .any(|owner_principal_id| *owner_principal_id != user_principal_id)
     {
         return Err(SetUniqueUsernameError::UsernameAlreadyTaken);
     }
 
-    canister_data
-        .unique_user_name_to_user_principal_id_map
-        .insert(unique_user_name.clone(), user_principal_id);
+    let current_user_name = get_current_user_name(canister_data, &user_principal_id);
+
+    if current_user_name.is_some()
+        && canister_data
+            .user_name_history
+            .is_change_on_cooldown(&user_principal_id, current_time)
+    {
+        return Err(SetUniqueUsernameError::UsernameChangeOnCooldown);
+    }
+
+    if let Some(current_user_name) = &current_user_name {
+        canister_data.remove_unique_user_name(current_user_name);
+    }
+
+    canister_data.insert_unique_user_name(unique_user_name.clone(), user_principal_id);
+
+    canister_data.user_name_history.record_change(
+        user_principal_id,
+        current_user_name,
+        unique_user_name,
+        current_time,
+    );
 
     Ok(())
 }
 
+fn get_current_user_name(
+    canister_data: &CanisterData,
+    user_principal_id: &Principal,
+) -> Option<String> {
+    if let Some(user_name_changes) = canister_data
+        .user_name_history
+        .user_principal_id_to_user_name_changes
+        .get(user_principal_id)
+    {
+        return Some(user_name_changes.current_user_name.clone());
+    }
+
+    // * usernames set before changes were tracked
+    canister_data
+        .unique_user_name_to_user_principal_id_map
+        .iter()
+        .find(|(_, principal_id)| *principal_id == user_principal_id)
+        .map(|(user_name, _)| user_name.clone())
+}
+
 #[cfg(test)]
 mod test {
+    use std::time::Duration;
+
     use test_utils::setup::test_constants::{
         get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
         get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
     };
 
+    use crate::data_model::user_name_history::USER_NAME_CHANGE_COOLDOWN;
+
     use super::*;
 
     #[test]
@@ -78,6 +156,7 @@ mod test {
             user_principal_id,
             request_makers_canister_id,
             &mut canister_data,
+            SystemTime::now(),
         );
         assert!(result.is_err());
         assert_eq!(
@@ -94,6 +173,7 @@ mod test {
             user_principal_id,
             get_mock_user_bob_canister_id(),
             &mut canister_data,
+            SystemTime::now(),
         );
         assert!(result.is_err());
         assert_eq!(
@@ -110,6 +190,7 @@ mod test {
             user_principal_id,
             request_makers_canister_id,
             &mut canister_data,
+            SystemTime::now(),
         );
         assert!(result.is_err());
         assert_eq!(
@@ -122,6 +203,7 @@ mod test {
             user_principal_id,
             request_makers_canister_id,
             &mut canister_data,
+            SystemTime::now(),
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -132,4 +214,156 @@ mod test {
             &user_principal_id
         );
     }
+
+    #[test]
+    fn test_update_index_with_unique_user_name_corresponding_to_user_principal_id_impl_validates_user_name(
+    ) {
+        let user_principal_id = get_mock_user_alice_principal_id();
+        let request_makers_canister_id = get_mock_user_alice_canister_id();
+        let mut canister_data = CanisterData::default();
+
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .insert(user_principal_id, request_makers_canister_id);
+        canister_data
+            .user_name_blocklist
+            .insert("badword".to_string());
+        canister_data
+            .insert_unique_user_name("cool_bob".to_string(), get_mock_user_bob_principal_id());
+
+        let mut set_user_name = |user_name: &str| {
+            update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
+                user_name.to_string(),
+                user_principal_id,
+                request_makers_canister_id,
+                &mut canister_data,
+                SystemTime::now(),
+            )
+        };
+
+        assert_eq!(
+            set_user_name("al"),
+            Err(SetUniqueUsernameError::UsernameTooShort)
+        );
+        assert_eq!(
+            set_user_name("alice_with_a_very_long_name"),
+            Err(SetUniqueUsernameError::UsernameTooLong)
+        );
+        assert_eq!(
+            set_user_name("alice!"),
+            Err(SetUniqueUsernameError::UsernameHasInvalidCharacters)
+        );
+        assert_eq!(
+            set_user_name("Adm1n"),
+            Err(SetUniqueUsernameError::UsernameIsBlocked)
+        );
+        assert_eq!(
+            set_user_name("bad_word"),
+            Err(SetUniqueUsernameError::UsernameIsBlocked)
+        );
+        assert_eq!(
+            set_user_name("Cool_Bob"),
+            Err(SetUniqueUsernameError::UsernameAlreadyTaken)
+        );
+        assert_eq!(
+            set_user_name("c00l.b0b"),
+            Err(SetUniqueUsernameError::UsernameAlreadyTaken)
+        );
+        assert_eq!(set_user_name("Cool_Alice"), Ok(()));
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .get("cool_alice"),
+            Some(&user_principal_id)
+        );
+    }
+
+    #[test]
+    fn test_update_index_with_unique_user_name_corresponding_to_user_principal_id_impl_when_changing_user_name(
+    ) {
+        let user_principal_id = get_mock_user_alice_principal_id();
+        let request_makers_canister_id = get_mock_user_alice_canister_id();
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .insert(user_principal_id, request_makers_canister_id);
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+
+        // * usernames set before history was tracked are picked up
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("cool_alice_1234".to_string(), user_principal_id);
+
+        let result = update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
+            "cool_alice_5678".to_string(),
+            user_principal_id,
+            request_makers_canister_id,
+            &mut canister_data,
+            now,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .get("cool_alice_1234"),
+            None
+        );
+
+        // * changing again right away is not allowed
+        let result = update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
+            "cool_alice_9999".to_string(),
+            user_principal_id,
+            request_makers_canister_id,
+            &mut canister_data,
+            now + Duration::from_secs(60),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            SetUniqueUsernameError::UsernameChangeOnCooldown
+        );
+
+        // * the replaced username is reserved for alice during the grace period
+        let result = update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
+            "cool_alice_1234".to_string(),
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+            &mut canister_data,
+            now + Duration::from_secs(60),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            SetUniqueUsernameError::UsernameAlreadyTaken
+        );
+
+        // * once the cooldown elapses alice can reclaim it
+        let result = update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
+            "cool_alice_1234".to_string(),
+            user_principal_id,
+            request_makers_canister_id,
+            &mut canister_data,
+            now + USER_NAME_CHANGE_COOLDOWN,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .get("cool_alice_1234"),
+            Some(&user_principal_id)
+        );
+        assert_eq!(
+            canister_data
+                .user_name_history
+                .user_principal_id_to_user_name_changes
+                .get(&user_principal_id)
+                .unwrap()
+                .previous_user_names
+                .len(),
+            2
+        );
+    }
 }
@@ -0,0 +1,105 @@
+use candid::Principal;
+use shared_utils::common::{
+    types::known_principal::KnownPrincipalType, utils::user_name::normalize_user_name,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Adds and removes names on the blocklist checked when users set their username. Names are
+/// matched case insensitively and so that look-alikes like `adm1n` also match `admin`.
+/// Usernames that are already taken are left as they are.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn update_user_name_blocklist(
+    user_names_to_add: Vec<String>,
+    user_names_to_remove: Vec<String>,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_user_name_blocklist_impl(
+            api_caller,
+            user_names_to_add,
+            user_names_to_remove,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn update_user_name_blocklist_impl(
+    caller: Principal,
+    user_names_to_add: Vec<String>,
+    user_names_to_remove: Vec<String>,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    user_names_to_remove.iter().for_each(|user_name| {
+        canister_data
+            .user_name_blocklist
+            .remove(&normalize_user_name(user_name));
+    });
+
+    canister_data.user_name_blocklist.extend(
+        user_names_to_add
+            .iter()
+            .map(|user_name| normalize_user_name(user_name))
+            .filter(|user_name| !user_name.is_empty()),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_update_user_name_blocklist_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result = update_user_name_blocklist_impl(
+            get_mock_user_alice_principal_id(),
+            vec!["badword".to_string()],
+            vec![],
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+        assert!(canister_data.user_name_blocklist.is_empty());
+
+        let result = update_user_name_blocklist_impl(
+            get_global_super_admin_principal_id(),
+            vec!["BadWord".to_string(), "worseword".to_string()],
+            vec![],
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert!(canister_data.user_name_blocklist.contains("badword"));
+        assert!(canister_data.user_name_blocklist.contains("worseword"));
+
+        let result = update_user_name_blocklist_impl(
+            get_global_super_admin_principal_id(),
+            vec![],
+            vec!["WorseWord".to_string()],
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(canister_data.user_name_blocklist.len(), 1);
+    }
+}
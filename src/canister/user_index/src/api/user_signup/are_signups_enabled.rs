@@ -28,7 +28,8 @@ mod test {
             configuration: Configuration {
                 known_principal_ids: HashMap::default(),
                 signups_open_on_this_subnet: true,
-                url_to_send_canister_metrics_to: String::from("http://example.com")
+                url_to_send_canister_metrics_to: String::from("http://example.com"),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -1,2 +1,3 @@
 pub mod are_signups_enabled;
+pub mod set_canister_pool_target_size;
 pub mod toggle_signups_enabled;
@@ -0,0 +1,78 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, util::canister_pool, CANISTER_DATA};
+
+/// Sets how many canisters without an owner are kept ready for new signups. The pool is
+/// topped up on a timer, and right away after this call.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn set_canister_pool_target_size(canister_pool_target_size: u64) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        set_canister_pool_target_size_impl(
+            api_caller,
+            canister_pool_target_size,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })?;
+
+    ic_cdk::spawn(canister_pool::top_up_canister_pool());
+
+    Ok(())
+}
+
+fn set_canister_pool_target_size_impl(
+    caller: Principal,
+    canister_pool_target_size: u64,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let super_admin = canister_data
+        .configuration
+        .known_principal_ids
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .ok_or("Super admin not found in internal records")?;
+
+    if caller != *super_admin {
+        return Err("Unauthorized".to_string());
+    }
+
+    canister_data.configuration.canister_pool_target_size = canister_pool_target_size;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_canister_pool_target_size_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+
+        let result = set_canister_pool_target_size_impl(
+            get_mock_user_alice_principal_id(),
+            10,
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+        assert_eq!(canister_data.configuration.canister_pool_target_size, 0);
+
+        let result = set_canister_pool_target_size_impl(
+            get_global_super_admin_principal_id(),
+            10,
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(canister_data.configuration.canister_pool_target_size, 10);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod get_well_known_principal_value;
 pub mod update_locally_stored_well_known_principals;
 pub mod get_current_list_of_all_well_known_principal_values;
+pub mod update_well_known_principal;
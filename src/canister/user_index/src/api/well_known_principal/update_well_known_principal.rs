@@ -0,0 +1,127 @@
+use candid::Principal;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Sets a single well-known principal. The platform orchestrator fans this out to every user_index.
+/// Only the super admin may change the principals that grant access to this endpoint.
+#[ic_cdk::update]
+#[candid::candid_method(update)]
+fn update_well_known_principal(
+    principal_type: KnownPrincipalType,
+    principal_value: Principal,
+) -> Result<(), String> {
+    let api_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_well_known_principal_impl(
+            api_caller,
+            principal_type,
+            principal_value,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })
+}
+
+fn update_well_known_principal_impl(
+    caller: Principal,
+    principal_type: KnownPrincipalType,
+    principal_value: Principal,
+    canister_data: &mut CanisterData,
+) -> Result<(), String> {
+    let known_principal_ids = &mut canister_data.configuration.known_principal_ids;
+    let is_super_admin =
+        known_principal_ids.get(&KnownPrincipalType::UserIdGlobalSuperAdmin) == Some(&caller);
+    let is_platform_orchestrator = known_principal_ids
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        == Some(&caller);
+    let is_authorized = is_super_admin
+        || (is_platform_orchestrator && !is_privileged_principal_type(principal_type));
+
+    if !is_authorized {
+        return Err("Unauthorized".to_string());
+    }
+
+    known_principal_ids.insert(principal_type, principal_value);
+
+    Ok(())
+}
+
+/// Principal types that grant access to changing well-known principals
+fn is_privileged_principal_type(principal_type: KnownPrincipalType) -> bool {
+    matches!(
+        principal_type,
+        KnownPrincipalType::UserIdGlobalSuperAdmin
+            | KnownPrincipalType::CanisterIdPlatformOrchestrator
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_global_super_admin_principal_id, get_mock_canister_id_data_backup,
+        get_mock_canister_id_platform_orchestrator, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_update_well_known_principal_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_global_super_admin_principal_id(),
+        );
+        canister_data.configuration.known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdPlatformOrchestrator,
+            get_mock_canister_id_platform_orchestrator(),
+        );
+
+        let result = update_well_known_principal_impl(
+            get_mock_user_alice_principal_id(),
+            KnownPrincipalType::CanisterIdDataBackup,
+            get_mock_canister_id_data_backup(),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+
+        let result = update_well_known_principal_impl(
+            get_mock_canister_id_platform_orchestrator(),
+            KnownPrincipalType::CanisterIdDataBackup,
+            get_mock_canister_id_data_backup(),
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            canister_data
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdDataBackup),
+            Some(&get_mock_canister_id_data_backup())
+        );
+
+        // * only the super admin may change the privileged principal types
+        let result = update_well_known_principal_impl(
+            get_mock_canister_id_platform_orchestrator(),
+            KnownPrincipalType::UserIdGlobalSuperAdmin,
+            get_mock_user_alice_principal_id(),
+            &mut canister_data,
+        );
+        assert_eq!(result, Err("Unauthorized".to_string()));
+        assert_eq!(
+            canister_data
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::UserIdGlobalSuperAdmin),
+            Some(&get_global_super_admin_principal_id())
+        );
+
+        let result = update_well_known_principal_impl(
+            get_global_super_admin_principal_id(),
+            KnownPrincipalType::CanisterIdPlatformOrchestrator,
+            get_mock_user_alice_principal_id(),
+            &mut canister_data,
+        );
+        assert!(result.is_ok());
+    }
+}
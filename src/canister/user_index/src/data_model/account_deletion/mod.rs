@@ -0,0 +1,443 @@
+use std::{
+    borrow::Cow,
+    cell::OnceCell,
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{StableLog, Storable};
+use serde::Serialize;
+
+use super::memory::{self, Memory};
+
+/// How long users have to cancel the deletion of their account before it starts
+pub const ACCOUNT_DELETION_COOLDOWN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+type AccountDeletionAuditLog = StableLog<AccountDeletionAuditLogEntry, Memory, Memory>;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct AccountDeletions {
+    /// Deletions that are scheduled, in progress or completed. Cancelled ones are removed.
+    pub user_principal_id_to_account_deletion: BTreeMap<Principal, AccountDeletion>,
+    /// Every request, cancellation and step of every deletion, oldest first. Kept in stable
+    /// memory, as it only grows. Set up on first use, like the wasm chunk maps.
+    #[serde(skip)]
+    audit_log: OnceCell<AccountDeletionAuditLog>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountDeletion {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+    pub requested_at: SystemTime,
+    pub scheduled_for: SystemTime,
+    pub status: AccountDeletionStatus,
+    /// Why the current step last failed. Cleared once it succeeds.
+    pub last_error: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountDeletionStatus {
+    Scheduled,
+    InProgress { next_step: AccountDeletionStep },
+    Completed { completed_at: SystemTime },
+}
+
+/// The steps of a deletion, in the order they are run
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountDeletionStep {
+    /// Stops the user's canister from taking new posts and bets and from sending backups
+    FreezeCanister,
+    SettleOpenBets,
+    RemovePostsFromPostCache,
+    RemoveFromFollowLists,
+    /// Also frees the user with the platform orchestrator
+    ClearUserIndexMappings,
+    PurgeBackups,
+    RecycleCanister,
+}
+
+impl AccountDeletionStep {
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::FreezeCanister => Some(Self::SettleOpenBets),
+            Self::SettleOpenBets => Some(Self::RemovePostsFromPostCache),
+            Self::RemovePostsFromPostCache => Some(Self::RemoveFromFollowLists),
+            Self::RemoveFromFollowLists => Some(Self::ClearUserIndexMappings),
+            Self::ClearUserIndexMappings => Some(Self::PurgeBackups),
+            Self::PurgeBackups => Some(Self::RecycleCanister),
+            Self::RecycleCanister => None,
+        }
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountDeletionAuditLogEntry {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+    pub event: AccountDeletionEvent,
+    pub recorded_at: SystemTime,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AccountDeletionEvent {
+    Requested {
+        scheduled_for: SystemTime,
+    },
+    Cancelled,
+    Started,
+    StepCompleted {
+        step: AccountDeletionStep,
+        details: Option<String>,
+    },
+    StepFailed {
+        step: AccountDeletionStep,
+        error: String,
+    },
+    Completed,
+}
+
+impl Storable for AccountDeletionAuditLogEntry {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(candid::encode_one(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        candid::decode_one(&bytes).unwrap()
+    }
+}
+
+impl AccountDeletions {
+    /// Whether the user's account is scheduled for deletion or being deleted
+    pub fn is_pending(&self, user_principal_id: &Principal) -> bool {
+        self.user_principal_id_to_account_deletion
+            .get(user_principal_id)
+            .is_some_and(|account_deletion| {
+                !matches!(
+                    account_deletion.status,
+                    AccountDeletionStatus::Completed { .. }
+                )
+            })
+    }
+
+    pub fn is_in_progress(&self, user_principal_id: &Principal) -> bool {
+        self.user_principal_id_to_account_deletion
+            .get(user_principal_id)
+            .is_some_and(|account_deletion| {
+                matches!(
+                    account_deletion.status,
+                    AccountDeletionStatus::InProgress { .. }
+                )
+            })
+    }
+
+    pub fn request(
+        &mut self,
+        user_principal_id: Principal,
+        user_canister_id: Principal,
+        current_time: SystemTime,
+    ) -> Result<AccountDeletion, String> {
+        if self.is_pending(&user_principal_id) {
+            return Err("Account deletion already requested".to_string());
+        }
+
+        let account_deletion = AccountDeletion {
+            user_principal_id,
+            user_canister_id,
+            requested_at: current_time,
+            scheduled_for: current_time + ACCOUNT_DELETION_COOLDOWN,
+            status: AccountDeletionStatus::Scheduled,
+            last_error: None,
+        };
+        self.user_principal_id_to_account_deletion
+            .insert(user_principal_id, account_deletion.clone());
+        self.record_event(
+            &account_deletion,
+            AccountDeletionEvent::Requested {
+                scheduled_for: account_deletion.scheduled_for,
+            },
+            current_time,
+        );
+
+        Ok(account_deletion)
+    }
+
+    /// Deletions can only be cancelled before they start
+    pub fn cancel(
+        &mut self,
+        user_principal_id: &Principal,
+        current_time: SystemTime,
+    ) -> Result<(), String> {
+        match self
+            .user_principal_id_to_account_deletion
+            .get(user_principal_id)
+        {
+            Some(account_deletion)
+                if account_deletion.status == AccountDeletionStatus::Scheduled => {}
+            Some(AccountDeletion {
+                status: AccountDeletionStatus::InProgress { .. },
+                ..
+            }) => return Err("Account deletion already in progress".to_string()),
+            _ => return Err("No account deletion requested".to_string()),
+        }
+
+        let account_deletion = self
+            .user_principal_id_to_account_deletion
+            .remove(user_principal_id)
+            .unwrap();
+        self.record_event(
+            &account_deletion,
+            AccountDeletionEvent::Cancelled,
+            current_time,
+        );
+
+        Ok(())
+    }
+
+    /// Starts the deletions whose cooldown is over. Returns every deletion in progress.
+    pub fn start_due_deletions(&mut self, current_time: SystemTime) -> Vec<AccountDeletion> {
+        let due_user_principal_ids: Vec<Principal> = self
+            .user_principal_id_to_account_deletion
+            .values()
+            .filter(|account_deletion| {
+                account_deletion.status == AccountDeletionStatus::Scheduled
+                    && account_deletion.scheduled_for <= current_time
+            })
+            .map(|account_deletion| account_deletion.user_principal_id)
+            .collect();
+
+        for user_principal_id in due_user_principal_ids {
+            let account_deletion = self
+                .user_principal_id_to_account_deletion
+                .get_mut(&user_principal_id)
+                .unwrap();
+            account_deletion.status = AccountDeletionStatus::InProgress {
+                next_step: AccountDeletionStep::FreezeCanister,
+            };
+            let account_deletion = account_deletion.clone();
+            self.record_event(
+                &account_deletion,
+                AccountDeletionEvent::Started,
+                current_time,
+            );
+        }
+
+        self.user_principal_id_to_account_deletion
+            .values()
+            .filter(|account_deletion| {
+                matches!(
+                    account_deletion.status,
+                    AccountDeletionStatus::InProgress { .. }
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Moves the deletion on to the step after `step`. Returns that step, or `None` once the
+    /// deletion is complete.
+    pub fn record_step_completed(
+        &mut self,
+        user_principal_id: &Principal,
+        step: AccountDeletionStep,
+        details: Option<String>,
+        current_time: SystemTime,
+    ) -> Option<AccountDeletionStep> {
+        let account_deletion = self
+            .user_principal_id_to_account_deletion
+            .get_mut(user_principal_id)?;
+        if account_deletion.status != (AccountDeletionStatus::InProgress { next_step: step }) {
+            return None;
+        }
+
+        let next_step = step.next();
+        account_deletion.status = match next_step {
+            Some(next_step) => AccountDeletionStatus::InProgress { next_step },
+            None => AccountDeletionStatus::Completed {
+                completed_at: current_time,
+            },
+        };
+        account_deletion.last_error = None;
+
+        let account_deletion = account_deletion.clone();
+        self.record_event(
+            &account_deletion,
+            AccountDeletionEvent::StepCompleted { step, details },
+            current_time,
+        );
+        if next_step.is_none() {
+            self.record_event(
+                &account_deletion,
+                AccountDeletionEvent::Completed,
+                current_time,
+            );
+        }
+
+        next_step
+    }
+
+    /// The step is retried on the next run. A failure is only logged when its error differs
+    /// from the previous one, so that waiting on open bets does not flood the log.
+    pub fn record_step_failed(
+        &mut self,
+        user_principal_id: &Principal,
+        step: AccountDeletionStep,
+        error: String,
+        current_time: SystemTime,
+    ) {
+        let Some(account_deletion) = self
+            .user_principal_id_to_account_deletion
+            .get_mut(user_principal_id)
+        else {
+            return;
+        };
+        if account_deletion.last_error.as_ref() == Some(&error) {
+            return;
+        }
+
+        account_deletion.last_error = Some(error.clone());
+        let account_deletion = account_deletion.clone();
+        self.record_event(
+            &account_deletion,
+            AccountDeletionEvent::StepFailed { step, error },
+            current_time,
+        );
+    }
+
+    /// Up to `limit` audit log entries, starting at `start_index`
+    pub fn get_audit_log_entries(
+        &self,
+        start_index: u64,
+        limit: u64,
+    ) -> Vec<AccountDeletionAuditLogEntry> {
+        let audit_log = self.audit_log();
+
+        (start_index..audit_log.len().min(start_index.saturating_add(limit)))
+            .filter_map(|index| audit_log.get(index))
+            .collect()
+    }
+
+    fn audit_log(&self) -> &AccountDeletionAuditLog {
+        self.audit_log.get_or_init(|| {
+            StableLog::init(
+                memory::get_account_deletion_audit_log_index_memory(),
+                memory::get_account_deletion_audit_log_data_memory(),
+            )
+            .expect("The account deletion audit log memory holds something else")
+        })
+    }
+
+    fn record_event(
+        &mut self,
+        account_deletion: &AccountDeletion,
+        event: AccountDeletionEvent,
+        current_time: SystemTime,
+    ) {
+        self.audit_log()
+            .append(&AccountDeletionAuditLogEntry {
+                user_principal_id: account_deletion.user_principal_id,
+                user_canister_id: account_deletion.user_canister_id,
+                event,
+                recorded_at: current_time,
+            })
+            .expect("Out of stable memory for the account deletion audit log");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_account_deletion_lifecycle() {
+        let mut account_deletions = AccountDeletions::default();
+        let alice = get_mock_user_alice_principal_id();
+        let now = SystemTime::now();
+
+        assert_eq!(
+            account_deletions.cancel(&alice, now),
+            Err("No account deletion requested".to_string())
+        );
+
+        account_deletions
+            .request(alice, get_mock_user_alice_canister_id(), now)
+            .unwrap();
+        assert!(account_deletions.is_pending(&alice));
+        assert_eq!(
+            account_deletions.request(alice, get_mock_user_alice_canister_id(), now),
+            Err("Account deletion already requested".to_string())
+        );
+        assert_eq!(account_deletions.cancel(&alice, now), Ok(()));
+        assert!(!account_deletions.is_pending(&alice));
+
+        account_deletions
+            .request(alice, get_mock_user_alice_canister_id(), now)
+            .unwrap();
+        // * still in its cooldown
+        assert!(account_deletions.start_due_deletions(now).is_empty());
+
+        let later = now + ACCOUNT_DELETION_COOLDOWN;
+        assert_eq!(account_deletions.start_due_deletions(later).len(), 1);
+        assert!(account_deletions.is_in_progress(&alice));
+        assert_eq!(
+            account_deletions.cancel(&alice, later),
+            Err("Account deletion already in progress".to_string())
+        );
+
+        // * repeated failures are logged once
+        for _ in 0..2 {
+            account_deletions.record_step_failed(
+                &alice,
+                AccountDeletionStep::FreezeCanister,
+                "Canister unreachable".to_string(),
+                later,
+            );
+        }
+
+        let mut step = AccountDeletionStep::FreezeCanister;
+        while let Some(next_step) =
+            account_deletions.record_step_completed(&alice, step, None, later)
+        {
+            step = next_step;
+        }
+        assert_eq!(step, AccountDeletionStep::RecycleCanister);
+        assert_eq!(
+            account_deletions.user_principal_id_to_account_deletion[&alice].status,
+            AccountDeletionStatus::Completed {
+                completed_at: later
+            }
+        );
+        assert!(!account_deletions.is_pending(&alice));
+
+        let events: Vec<AccountDeletionEvent> = account_deletions
+            .get_audit_log_entries(0, 100)
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect();
+        assert_eq!(events.len(), 13);
+        assert_eq!(
+            events[..5],
+            [
+                AccountDeletionEvent::Requested {
+                    scheduled_for: now + ACCOUNT_DELETION_COOLDOWN
+                },
+                AccountDeletionEvent::Cancelled,
+                AccountDeletionEvent::Requested {
+                    scheduled_for: now + ACCOUNT_DELETION_COOLDOWN
+                },
+                AccountDeletionEvent::Started,
+                AccountDeletionEvent::StepFailed {
+                    step: AccountDeletionStep::FreezeCanister,
+                    error: "Canister unreachable".to_string(),
+                },
+            ]
+        );
+        assert_eq!(events[12], AccountDeletionEvent::Completed);
+        assert_eq!(account_deletions.get_audit_log_entries(12, 100).len(), 1);
+    }
+}
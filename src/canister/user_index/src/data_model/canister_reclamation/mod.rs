@@ -0,0 +1,99 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+use shared_utils::canister_specific::individual_user_template::types::activity::CanisterActivitySummary;
+
+#[derive(Default, CandidType, Serialize, Deserialize, Clone)]
+pub struct CanisterReclamation {
+    /// Users whose canister was reclaimed and whose data is to be restored on their next login
+    pub reclaimed_user_canisters: BTreeMap<Principal, ReclaimedCanister>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReclaimedCanister {
+    pub canister_id: Principal,
+    pub reclaimed_at: SystemTime,
+    /// The canister handed out to the user on their return, kept until their data is restored
+    /// to it so that a failed restore is retried on the same canister
+    #[serde(default)]
+    pub reprovisioned_canister_id: Option<Principal>,
+}
+
+/// Canisters with posts or followers are never reclaimed. Feeds on other canisters refer to
+/// those posts, and followers to the followed user, by canister id, and the canister is handed
+/// out to someone else after reclamation.
+pub fn is_canister_eligible_for_reclamation(
+    activity_summary: &CanisterActivitySummary,
+    inactivity_period: Duration,
+    current_time: SystemTime,
+) -> bool {
+    if activity_summary.created_posts_count > 0 || activity_summary.followers_count > 0 {
+        return false;
+    }
+
+    match activity_summary.last_access_time {
+        Some(last_access_time) => current_time
+            .duration_since(last_access_time)
+            .is_ok_and(|inactive_for| inactive_for >= inactivity_period),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_canister_eligible_for_reclamation() {
+        let now = SystemTime::now();
+        let inactivity_period = Duration::from_secs(90 * 24 * 60 * 60);
+        let mut activity_summary = CanisterActivitySummary {
+            last_access_time: Some(now - inactivity_period),
+            created_posts_count: 0,
+            followers_count: 0,
+        };
+
+        assert!(is_canister_eligible_for_reclamation(
+            &activity_summary,
+            inactivity_period,
+            now
+        ));
+
+        // * recently active
+        assert!(!is_canister_eligible_for_reclamation(
+            &activity_summary,
+            inactivity_period,
+            now - Duration::from_secs(1)
+        ));
+
+        // * has posts
+        activity_summary.created_posts_count = 1;
+        assert!(!is_canister_eligible_for_reclamation(
+            &activity_summary,
+            inactivity_period,
+            now
+        ));
+
+        // * has followers
+        activity_summary.created_posts_count = 0;
+        activity_summary.followers_count = 1;
+        assert!(!is_canister_eligible_for_reclamation(
+            &activity_summary,
+            inactivity_period,
+            now
+        ));
+
+        // * no owner
+        activity_summary.followers_count = 0;
+        activity_summary.last_access_time = None;
+        assert!(!is_canister_eligible_for_reclamation(
+            &activity_summary,
+            inactivity_period,
+            now
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    collections::BTreeSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use candid::{CandidType, Deserialize, Nat, Principal};
+use ic_cdk::api::management_canister::provisional::CanisterSettings;
+use serde::Serialize;
+use shared_utils::common::types::known_principal::{KnownPrincipalMap, KnownPrincipalType};
+
+/// Settings applied to individual user canisters. `None` leaves the setting at the IC default.
+#[derive(Default, CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct IndividualUserCanisterSettings {
+    /// In seconds
+    pub freezing_threshold: Option<u64>,
+    /// In bytes
+    pub memory_allocation: Option<u64>,
+    /// In percent of an execution core
+    pub compute_allocation: Option<u64>,
+    /// Known principals that control individual user canisters alongside user_index, so that
+    /// the canisters stay manageable if user_index is lost
+    #[serde(default)]
+    pub co_controllers: Vec<KnownPrincipalType>,
+}
+
+impl IndividualUserCanisterSettings {
+    pub fn validate(&self, known_principal_ids: &KnownPrincipalMap) -> Result<(), String> {
+        if self
+            .compute_allocation
+            .is_some_and(|compute_allocation| compute_allocation > 100)
+        {
+            return Err("Compute allocation has to be between 0 and 100".to_string());
+        }
+
+        if let Some(co_controller) = self
+            .co_controllers
+            .iter()
+            .find(|co_controller| !known_principal_ids.contains_key(co_controller))
+        {
+            return Err(format!(
+                "Co-controller {:?} not found in internal records",
+                co_controller
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// user_index followed by the co-controllers
+    pub fn get_controllers(
+        &self,
+        user_index_canister_id: Principal,
+        known_principal_ids: &KnownPrincipalMap,
+    ) -> Result<Vec<Principal>, String> {
+        let mut controllers = vec![user_index_canister_id];
+
+        for co_controller in self.co_controllers.iter() {
+            let co_controller_id = known_principal_ids.get(co_controller).ok_or(format!(
+                "Co-controller {:?} not found in internal records",
+                co_controller
+            ))?;
+
+            if !controllers.contains(co_controller_id) {
+                controllers.push(*co_controller_id);
+            }
+        }
+
+        Ok(controllers)
+    }
+
+    /// Settings to pass to the management canister
+    pub fn to_canister_settings(&self, controllers: Vec<Principal>) -> CanisterSettings {
+        CanisterSettings {
+            controllers: Some(controllers),
+            compute_allocation: self.compute_allocation.map(Nat::from),
+            memory_allocation: self.memory_allocation.map(Nat::from),
+            freezing_threshold: self.freezing_threshold.map(Nat::from),
+        }
+    }
+
+    /// Whether the settings a canister has differ from the ones set here or its controllers
+    /// differ from `controllers`
+    pub fn is_drifted(
+        &self,
+        actual_settings: &CanisterSettingsSnapshot,
+        controllers: &[Principal],
+    ) -> bool {
+        let actual_controllers: BTreeSet<&Principal> = actual_settings.controllers.iter().collect();
+        if actual_controllers != controllers.iter().collect() {
+            return true;
+        }
+
+        [
+            (self.freezing_threshold, actual_settings.freezing_threshold),
+            (self.memory_allocation, actual_settings.memory_allocation),
+            (self.compute_allocation, actual_settings.compute_allocation),
+        ]
+        .into_iter()
+        .any(|(expected, actual)| expected.is_some_and(|expected| expected != actual))
+    }
+}
+
+#[derive(Default, CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct CanisterSettingsSnapshot {
+    pub controllers: Vec<Principal>,
+    pub freezing_threshold: u64,
+    pub memory_allocation: u64,
+    pub compute_allocation: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct CanisterSettingsDrift {
+    pub canister_id: Principal,
+    /// The settings the canister had before it was brought in line
+    pub settings_before_sync: CanisterSettingsSnapshot,
+    pub updated: bool,
+    /// Whether the canister reported the configured settings after it was updated
+    #[serde(default)]
+    pub verified: bool,
+    pub error: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct CanisterSettingsSyncStatus {
+    pub started_at: SystemTime,
+    pub finished_at: Option<SystemTime>,
+    pub checked_canister_count: u32,
+    /// The drifted canisters are listed by
+    /// `get_index_details_canister_settings_sync_drifts_paginated`
+    #[serde(default)]
+    pub drifted_canister_count: u32,
+    #[serde(default)]
+    pub updated_canister_count: u32,
+    #[serde(default)]
+    pub verified_canister_count: u32,
+    /// The canisters whose settings could not be read are listed by
+    /// `get_index_details_canister_settings_sync_failures_paginated`
+    #[serde(default)]
+    pub failed_canister_count: u32,
+}
+
+impl Default for CanisterSettingsSyncStatus {
+    fn default() -> Self {
+        Self {
+            started_at: UNIX_EPOCH,
+            finished_at: None,
+            checked_canister_count: 0,
+            drifted_canister_count: 0,
+            updated_canister_count: 0,
+            verified_canister_count: 0,
+            failed_canister_count: 0,
+        }
+    }
+}
+
+impl CanisterSettingsSyncStatus {
+    pub fn count_drift(&mut self, drift: &CanisterSettingsDrift) {
+        self.drifted_canister_count += 1;
+        if drift.updated {
+            self.updated_canister_count += 1;
+        }
+        if drift.verified {
+            self.verified_canister_count += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_individual_user_canister_settings_is_drifted() {
+        let controllers = vec![Principal::from_slice(&[1]), Principal::from_slice(&[2])];
+        let actual_settings = CanisterSettingsSnapshot {
+            controllers: vec![Principal::from_slice(&[2]), Principal::from_slice(&[1])],
+            freezing_threshold: 2_592_000,
+            memory_allocation: 0,
+            compute_allocation: 0,
+        };
+
+        assert!(
+            !IndividualUserCanisterSettings::default().is_drifted(&actual_settings, &controllers)
+        );
+        assert!(IndividualUserCanisterSettings::default()
+            .is_drifted(&actual_settings, &controllers[..1]));
+        assert!(!IndividualUserCanisterSettings {
+            freezing_threshold: Some(2_592_000),
+            ..Default::default()
+        }
+        .is_drifted(&actual_settings, &controllers));
+        assert!(IndividualUserCanisterSettings {
+            freezing_threshold: Some(2_592_000),
+            compute_allocation: Some(1),
+            ..Default::default()
+        }
+        .is_drifted(&actual_settings, &controllers));
+    }
+
+    #[test]
+    fn test_canister_settings_sync_status_count_drift() {
+        let mut sync_status = CanisterSettingsSyncStatus::default();
+        let drift = CanisterSettingsDrift {
+            canister_id: Principal::from_slice(&[1]),
+            settings_before_sync: Default::default(),
+            updated: true,
+            verified: false,
+            error: None,
+        };
+
+        sync_status.count_drift(&drift);
+        sync_status.count_drift(&CanisterSettingsDrift {
+            verified: true,
+            ..drift.clone()
+        });
+        sync_status.count_drift(&CanisterSettingsDrift {
+            updated: false,
+            ..drift
+        });
+
+        assert_eq!(sync_status.drifted_canister_count, 3);
+        assert_eq!(sync_status.updated_canister_count, 2);
+        assert_eq!(sync_status.verified_canister_count, 1);
+    }
+
+    #[test]
+    fn test_individual_user_canister_settings_get_controllers() {
+        let user_index_canister_id = Principal::from_slice(&[1]);
+        let mut known_principal_ids = KnownPrincipalMap::default();
+        known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdSNSController,
+            Principal::from_slice(&[2]),
+        );
+        known_principal_ids.insert(
+            KnownPrincipalType::CanisterIdUserIndex,
+            user_index_canister_id,
+        );
+
+        let canister_settings = IndividualUserCanisterSettings {
+            co_controllers: vec![
+                KnownPrincipalType::CanisterIdSNSController,
+                KnownPrincipalType::CanisterIdUserIndex,
+            ],
+            ..Default::default()
+        };
+        assert!(canister_settings.validate(&known_principal_ids).is_ok());
+        assert_eq!(
+            canister_settings.get_controllers(user_index_canister_id, &known_principal_ids),
+            Ok(vec![user_index_canister_id, Principal::from_slice(&[2])])
+        );
+
+        let canister_settings = IndividualUserCanisterSettings {
+            co_controllers: vec![KnownPrincipalType::CanisterIdRootCanister],
+            ..Default::default()
+        };
+        assert!(canister_settings.validate(&known_principal_ids).is_err());
+        assert!(canister_settings
+            .get_controllers(user_index_canister_id, &known_principal_ids)
+            .is_err());
+    }
+
+    #[test]
+    fn test_individual_user_canister_settings_validate() {
+        let known_principal_ids = KnownPrincipalMap::default();
+        assert!(IndividualUserCanisterSettings::default()
+            .validate(&known_principal_ids)
+            .is_ok());
+        assert!(IndividualUserCanisterSettings {
+            compute_allocation: Some(101),
+            ..Default::default()
+        }
+        .validate(&known_principal_ids)
+        .is_err());
+    }
+}
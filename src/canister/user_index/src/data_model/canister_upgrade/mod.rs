@@ -1,4 +1,5 @@
 use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
     fmt::Display,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -14,6 +15,13 @@ pub struct UpgradeStatus {
     pub failed_canister_ids: Vec<(Principal, Principal, String)>,
     #[serde(default)]
     pub version: String,
+    #[serde(default)]
+    pub waves: Vec<UpgradeWaveProgress>,
+    /// Set when the run was stopped because too many upgrades failed
+    #[serde(default)]
+    pub halted_reason: Option<String>,
+    #[serde(default)]
+    pub failed_upgrades: Vec<FailedUpgrade>,
 }
 
 impl Display for UpgradeStatus {
@@ -29,7 +37,483 @@ impl Default for UpgradeStatus {
             last_run_on: UNIX_EPOCH,
             successful_upgrade_count: 0,
             failed_canister_ids: Vec::new(),
-            version: String::from("v0.0.0")
+            version: String::from("v0.0.0"),
+            waves: Vec::new(),
+            halted_reason: None,
+            failed_upgrades: Vec::new(),
+        }
+    }
+}
+
+
+/// Individual canisters are upgraded in waves. The canary canisters go first, then the rest of
+/// the canisters in waves that each bring the share of upgraded canisters up to the next
+/// percentage. The run halts when the share of failed upgrades goes above the threshold.
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct UpgradeRolloutPlan {
+    pub canary_canister_ids: Vec<Principal>,
+    /// Strictly increasing and ending at 100
+    pub wave_percentages: Vec<u8>,
+    pub max_failure_rate_percentage: u8,
+}
+
+impl Default for UpgradeRolloutPlan {
+    fn default() -> Self {
+        Self {
+            canary_canister_ids: Vec::new(),
+            wave_percentages: vec![100],
+            max_failure_rate_percentage: 100,
+        }
+    }
+}
+
+impl UpgradeRolloutPlan {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.wave_percentages.last() != Some(&100) {
+            return Err("The last wave has to be at 100 percent".to_string());
+        }
+
+        if self
+            .wave_percentages
+            .windows(2)
+            .any(|pair| pair[0] >= pair[1])
+        {
+            return Err("Wave percentages have to be strictly increasing".to_string());
+        }
+
+        if self.wave_percentages[0] == 0 || self.max_failure_rate_percentage > 100 {
+            return Err("Percentages have to be between 1 and 100".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Splits the canisters into waves. Canary canisters not in the passed list are ignored.
+    pub fn plan_waves(
+        &self,
+        user_principal_id_to_canister_id_map: &BTreeMap<Principal, Principal>,
+    ) -> Vec<(String, Vec<(Principal, Principal)>)> {
+        let (canaries, rest): (Vec<_>, Vec<_>) = user_principal_id_to_canister_id_map
+            .iter()
+            .map(|(user_principal_id, canister_id)| (*user_principal_id, *canister_id))
+            .partition(|(_, canister_id)| self.canary_canister_ids.contains(canister_id));
+
+        let mut waves = Vec::new();
+        if !canaries.is_empty() {
+            waves.push(("canary".to_string(), canaries));
+        }
+
+        let mut wave_start = 0;
+        for percentage in &self.wave_percentages {
+            let wave_end = (rest.len() * *percentage as usize).div_ceil(100);
+            waves.push((
+                format!("{}%", percentage),
+                rest[wave_start..wave_end].to_vec(),
+            ));
+            wave_start = wave_end;
+        }
+
+        waves
+    }
+
+    pub fn is_failure_rate_above_threshold(&self, failed_count: usize, attempted_count: usize) -> bool {
+        attempted_count > 0
+            && failed_count * 100 > attempted_count * self.max_failure_rate_percentage as usize
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub enum UpgradeWaveState {
+    Pending,
+    InProgress,
+    Completed,
+    Halted,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct UpgradeWaveProgress {
+    pub label: String,
+    pub canister_count: u32,
+    pub successful_upgrade_count: u32,
+    pub failed_upgrade_count: u32,
+    pub state: UpgradeWaveState,
+}
+
+impl UpgradeWaveProgress {
+    pub fn new(label: String, canister_count: u32) -> Self {
+        Self {
+            label,
+            canister_count,
+            successful_upgrade_count: 0,
+            failed_upgrade_count: 0,
+            state: UpgradeWaveState::Pending,
+        }
+    }
+}
+
+pub const MAX_UPGRADE_ATTEMPTS: u32 = 3;
+
+pub const PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX: &str = "Pre-upgrade backup failed";
+pub const POST_UPGRADE_VALIDATION_FAILED_ERROR_PREFIX: &str = "Post-upgrade validation failed";
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub enum UpgradeErrorKind {
+    OutOfCycles,
+    TrapInPreUpgrade,
+    TrapInPostUpgrade,
+    CanisterStopped,
+    /// The canister was skipped, as its backup could not be taken or verified
+    PreUpgradeBackupFailed,
+    /// The upgraded canister holds fewer or more entries than its backup
+    PostUpgradeValidationFailed,
+    Other,
+}
+
+impl UpgradeErrorKind {
+    /// Classifies the error message returned by the management canister
+    pub fn from_error_message(error_message: &str) -> Self {
+        if error_message.starts_with(PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX) {
+            return Self::PreUpgradeBackupFailed;
+        }
+        if error_message.starts_with(POST_UPGRADE_VALIDATION_FAILED_ERROR_PREFIX) {
+            return Self::PostUpgradeValidationFailed;
+        }
+
+        let error_message = error_message.to_lowercase();
+
+        if error_message.contains("out of cycles") {
+            Self::OutOfCycles
+        } else if error_message.contains("post_upgrade") {
+            Self::TrapInPostUpgrade
+        } else if error_message.contains("pre_upgrade") {
+            Self::TrapInPreUpgrade
+        } else if error_message.contains("is stopped") || error_message.contains("is stopping") {
+            Self::CanisterStopped
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct FailedUpgrade {
+    pub user_principal_id: Principal,
+    pub canister_id: Principal,
+    pub error_kind: UpgradeErrorKind,
+    pub error_message: String,
+    pub attempt_count: u32,
+}
+
+impl FailedUpgrade {
+    pub fn new(user_principal_id: Principal, canister_id: Principal, error_message: String) -> Self {
+        Self {
+            user_principal_id,
+            canister_id,
+            error_kind: UpgradeErrorKind::from_error_message(&error_message),
+            error_message,
+            attempt_count: 1,
+        }
+    }
+
+    /// Canisters that failed validation after the upgrade are not retried, as the retry would
+    /// back up their state over the good backup. They are restored from a snapshot instead.
+    pub fn can_be_retried(&self) -> bool {
+        self.attempt_count < MAX_UPGRADE_ATTEMPTS
+            && self.error_kind != UpgradeErrorKind::PostUpgradeValidationFailed
+    }
+}
+
+/// Where a stopped upgrade run picks up again. All canisters of the waves before `wave_index`,
+/// and the canisters of that wave up to and including `last_upgraded_user_principal_id`, are done.
+/// The cursor only applies to a run installing the same wasm for the same version number.
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct UpgradeCursor {
+    pub version_number: u64,
+    pub wasm_hash: Vec<u8>,
+    pub wave_index: u32,
+    pub last_upgraded_user_principal_id: Option<Principal>,
+    /// The waves as planned when the run started. A resumed run goes through the same waves, as
+    /// planning them again from the current canisters would move canisters between waves.
+    #[serde(default)]
+    pub planned_waves: Vec<(String, Vec<(Principal, Principal)>)>,
+}
+
+impl UpgradeCursor {
+    pub fn new(
+        version_number: u64,
+        wasm_hash: Vec<u8>,
+        planned_waves: Vec<(String, Vec<(Principal, Principal)>)>,
+    ) -> Self {
+        Self {
+            version_number,
+            wasm_hash,
+            wave_index: 0,
+            last_upgraded_user_principal_id: None,
+            planned_waves,
+        }
+    }
+
+    /// Cursors persisted before the waves were kept with them can't be resumed
+    pub fn is_for_run(&self, version_number: u64, wasm_hash: &[u8]) -> bool {
+        self.version_number == version_number
+            && self.wasm_hash == wasm_hash
+            && !self.planned_waves.is_empty()
+    }
+
+    /// The planned waves without the canisters that no longer belong to the user they were
+    /// planned for, as they were reclaimed or deleted since. Users who signed up since got a
+    /// canister with the current wasm.
+    pub fn get_planned_waves(
+        &self,
+        user_principal_id_to_canister_id_map: &BTreeMap<Principal, Principal>,
+    ) -> Vec<(String, Vec<(Principal, Principal)>)> {
+        self.planned_waves
+            .iter()
+            .map(|(label, wave)| {
+                (
+                    label.clone(),
+                    wave.iter()
+                        .filter(|(user_principal_id, canister_id)| {
+                            user_principal_id_to_canister_id_map.get(user_principal_id)
+                                == Some(canister_id)
+                        })
+                        .copied()
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Canisters of the wave that still need to be upgraded. Waves are ordered by user principal id.
+    pub fn get_remaining_canisters_of_wave(
+        &self,
+        wave_index: u32,
+        wave: &[(Principal, Principal)],
+    ) -> Vec<(Principal, Principal)> {
+        match (wave_index.cmp(&self.wave_index), self.last_upgraded_user_principal_id) {
+            (std::cmp::Ordering::Less, _) => Vec::new(),
+            (std::cmp::Ordering::Equal, Some(last_upgraded_user_principal_id)) => wave
+                .iter()
+                .filter(|(user_principal_id, _)| *user_principal_id > last_upgraded_user_principal_id)
+                .copied()
+                .collect(),
+            _ => wave.to_vec(),
+        }
+    }
+}
+
+/// Upgrades finish out of order. The cursor only moves past a canister once every canister
+/// before it in the wave is done too.
+pub struct UpgradeCursorTracker {
+    pending_user_principal_ids: VecDeque<Principal>,
+    done_user_principal_ids: BTreeSet<Principal>,
+}
+
+impl UpgradeCursorTracker {
+    pub fn new(wave: &[(Principal, Principal)]) -> Self {
+        Self {
+            pending_user_principal_ids: wave.iter().map(|(user_principal_id, _)| *user_principal_id).collect(),
+            done_user_principal_ids: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the user principal id the cursor can move to, if it moved
+    pub fn mark_done(&mut self, user_principal_id: Principal) -> Option<Principal> {
+        self.done_user_principal_ids.insert(user_principal_id);
+
+        let mut last_upgraded_user_principal_id = None;
+        while let Some(next_user_principal_id) = self.pending_user_principal_ids.front() {
+            if !self.done_user_principal_ids.remove(next_user_principal_id) {
+                break;
+            }
+            last_upgraded_user_principal_id = self.pending_user_principal_ids.pop_front();
         }
+
+        last_upgraded_user_principal_id
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_canister_map(count: u8) -> BTreeMap<Principal, Principal> {
+        (0..count)
+            .map(|i| {
+                (
+                    Principal::self_authenticating([i]),
+                    Principal::from_slice(&[i]),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_upgrade_rollout_plan_validate() {
+        assert!(UpgradeRolloutPlan::default().validate().is_ok());
+
+        let mut plan = UpgradeRolloutPlan {
+            canary_canister_ids: vec![],
+            wave_percentages: vec![1, 10, 50, 100],
+            max_failure_rate_percentage: 5,
+        };
+        assert!(plan.validate().is_ok());
+
+        plan.wave_percentages = vec![10, 50];
+        assert!(plan.validate().is_err());
+
+        plan.wave_percentages = vec![10, 10, 100];
+        assert!(plan.validate().is_err());
+
+        plan.wave_percentages = vec![0, 100];
+        assert!(plan.validate().is_err());
+    }
+
+    #[test]
+    fn test_upgrade_rollout_plan_plan_waves() {
+        let canister_map = get_canister_map(20);
+        let canary_canister_id = Principal::from_slice(&[3]);
+        let plan = UpgradeRolloutPlan {
+            canary_canister_ids: vec![canary_canister_id, Principal::from_slice(&[200])],
+            wave_percentages: vec![10, 50, 100],
+            max_failure_rate_percentage: 5,
+        };
+
+        let waves = plan.plan_waves(&canister_map);
+
+        assert_eq!(
+            waves
+                .iter()
+                .map(|(label, wave)| (label.as_str(), wave.len()))
+                .collect::<Vec<_>>(),
+            vec![("canary", 1), ("10%", 2), ("50%", 8), ("100%", 9)]
+        );
+        assert_eq!(waves[0].1[0].1, canary_canister_id);
+        assert!(waves[1..]
+            .iter()
+            .all(|(_, wave)| wave.iter().all(|(_, canister_id)| *canister_id != canary_canister_id)));
+
+        let waves = UpgradeRolloutPlan::default().plan_waves(&canister_map);
+        assert_eq!(waves.len(), 1);
+        assert_eq!(waves[0].1.len(), 20);
+    }
+
+    #[test]
+    fn test_upgrade_rollout_plan_is_failure_rate_above_threshold() {
+        let plan = UpgradeRolloutPlan {
+            canary_canister_ids: vec![],
+            wave_percentages: vec![100],
+            max_failure_rate_percentage: 10,
+        };
+
+        assert!(!plan.is_failure_rate_above_threshold(0, 0));
+        assert!(!plan.is_failure_rate_above_threshold(1, 10));
+        assert!(plan.is_failure_rate_above_threshold(2, 10));
+        assert!(!UpgradeRolloutPlan::default().is_failure_rate_above_threshold(10, 10));
+    }
+
+    #[test]
+    fn test_upgrade_error_kind_from_error_message() {
+        assert_eq!(
+            UpgradeErrorKind::from_error_message("Canister abc is out of cycles"),
+            UpgradeErrorKind::OutOfCycles
+        );
+        assert_eq!(
+            UpgradeErrorKind::from_error_message(
+                "Canister abc trapped: panicked at 'Failed to restore', src/api/canister_lifecycle/post_upgrade.rs:20:5"
+            ),
+            UpgradeErrorKind::TrapInPostUpgrade
+        );
+        assert_eq!(
+            UpgradeErrorKind::from_error_message("Canister abc is stopped and therefore does not have a CallContextManager"),
+            UpgradeErrorKind::CanisterStopped
+        );
+        assert_eq!(
+            UpgradeErrorKind::from_error_message(
+                "Pre-upgrade backup failed: Canister abc is out of cycles"
+            ),
+            UpgradeErrorKind::PreUpgradeBackupFailed
+        );
+        assert_eq!(
+            UpgradeErrorKind::from_error_message(
+                "Post-upgrade validation failed: the canister does not match its backup, posts: 0 instead of 2"
+            ),
+            UpgradeErrorKind::PostUpgradeValidationFailed
+        );
+        assert_eq!(
+            UpgradeErrorKind::from_error_message("Couldn't send message"),
+            UpgradeErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_failed_upgrade_can_be_retried() {
+        let mut failed_upgrade = FailedUpgrade::new(
+            Principal::self_authenticating([1]),
+            Principal::from_slice(&[1]),
+            "Pre-upgrade backup failed: Canister abc is stopped".to_string(),
+        );
+        assert!(failed_upgrade.can_be_retried());
+
+        failed_upgrade.attempt_count = MAX_UPGRADE_ATTEMPTS;
+        assert!(!failed_upgrade.can_be_retried());
+
+        let failed_upgrade = FailedUpgrade::new(
+            Principal::self_authenticating([1]),
+            Principal::from_slice(&[1]),
+            "Post-upgrade validation failed: the canister does not match its backup, posts: 0 instead of 2".to_string(),
+        );
+        assert!(!failed_upgrade.can_be_retried());
+    }
+
+    #[test]
+    fn test_upgrade_cursor_get_remaining_canisters_of_wave() {
+        let wave: Vec<(Principal, Principal)> = get_canister_map(5).into_iter().collect();
+        let mut cursor = UpgradeCursor::new(1, vec![1], vec![("100%".to_string(), wave.clone())]);
+        assert!(cursor.is_for_run(1, &[1]));
+        assert!(!cursor.is_for_run(2, &[1]));
+        assert!(!cursor.is_for_run(1, &[2]));
+
+        cursor.wave_index = 1;
+        cursor.last_upgraded_user_principal_id = Some(wave[2].0);
+
+        assert!(cursor.get_remaining_canisters_of_wave(0, &wave).is_empty());
+        assert_eq!(cursor.get_remaining_canisters_of_wave(1, &wave), wave[3..].to_vec());
+        assert_eq!(cursor.get_remaining_canisters_of_wave(2, &wave), wave);
+    }
+
+    #[test]
+    fn test_upgrade_cursor_tracker_mark_done() {
+        let wave: Vec<(Principal, Principal)> = get_canister_map(4).into_iter().collect();
+        let mut tracker = UpgradeCursorTracker::new(&wave);
+
+        assert_eq!(tracker.mark_done(wave[1].0), None);
+        assert_eq!(tracker.mark_done(wave[2].0), None);
+        assert_eq!(tracker.mark_done(wave[0].0), Some(wave[2].0));
+        assert_eq!(tracker.mark_done(wave[3].0), Some(wave[3].0));
+    }
+
+    #[test]
+    fn test_upgrade_cursor_get_planned_waves() {
+        let canister_map = get_canister_map(4);
+        let waves = UpgradeRolloutPlan {
+            canary_canister_ids: vec![],
+            wave_percentages: vec![50, 100],
+            max_failure_rate_percentage: 5,
+        }
+        .plan_waves(&canister_map);
+        let cursor = UpgradeCursor::new(1, vec![1], waves.clone());
+        assert!(cursor.is_for_run(1, &[1]));
+        assert!(!UpgradeCursor::new(1, vec![1], vec![]).is_for_run(1, &[1]));
+
+        // * a new user and a reclaimed canister don't change the planned waves
+        let (reclaimed_user_principal_id, _) = waves[1].1[0];
+        let mut current_canister_map = canister_map.clone();
+        current_canister_map.insert(Principal::self_authenticating([0, 0]), Principal::from_slice(&[9]));
+        current_canister_map.remove(&reclaimed_user_principal_id);
+
+        let planned_waves = cursor.get_planned_waves(&current_canister_map);
+        assert_eq!(planned_waves[0], waves[0]);
+        assert_eq!(planned_waves[1].1, waves[1].1[1..].to_vec());
     }
 }
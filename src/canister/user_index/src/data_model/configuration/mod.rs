@@ -2,9 +2,24 @@ use candid::{CandidType, Deserialize};
 use serde::Serialize;
 use shared_utils::common::types::known_principal::KnownPrincipalMap;
 
+use super::canister_settings::IndividualUserCanisterSettings;
+
 #[derive(Default, Deserialize, CandidType, Serialize, Clone)]
 pub struct Configuration {
     pub known_principal_ids: KnownPrincipalMap,
     pub signups_open_on_this_subnet: bool,
     pub url_to_send_canister_metrics_to: String,
+    /// Number of installed canisters without an owner to keep ready in `available_canisters`
+    #[serde(default)]
+    pub canister_pool_target_size: u64,
+    /// Canisters of users inactive for this long are reclaimed. `None` turns reclamation off.
+    #[serde(default)]
+    pub inactivity_period_before_canister_reclamation_in_seconds: Option<u64>,
+    /// Canisters predicted to freeze within this many days are topped up. `None` uses
+    /// `DEFAULT_CYCLE_TOP_UP_HORIZON_IN_DAYS`.
+    #[serde(default)]
+    pub cycle_top_up_horizon_in_days: Option<u64>,
+    /// Applied to individual user canisters when they are created
+    #[serde(default)]
+    pub individual_user_canister_settings: IndividualUserCanisterSettings,
 }
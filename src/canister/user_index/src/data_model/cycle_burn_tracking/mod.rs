@@ -0,0 +1,235 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+const SECONDS_IN_A_DAY: u128 = 24 * 60 * 60;
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct CanisterCycleBurn {
+    pub cycle_balance: u128,
+    pub observed_at: SystemTime,
+    /// Smoothed over the observations, so that a single busy day does not dominate
+    pub cycles_burned_per_day: u128,
+    /// Cycles the canister has to keep so that it is not frozen
+    pub freezing_threshold_in_cycles: u128,
+    pub cycles_topped_up: u128,
+}
+
+impl Default for CanisterCycleBurn {
+    fn default() -> Self {
+        Self {
+            cycle_balance: 0,
+            observed_at: UNIX_EPOCH,
+            cycles_burned_per_day: 0,
+            freezing_threshold_in_cycles: 0,
+            cycles_topped_up: 0,
+        }
+    }
+}
+
+impl CanisterCycleBurn {
+    /// Whether the balance is predicted to fall below the freezing threshold within `horizon`
+    pub fn is_predicted_to_freeze_within(&self, horizon: Duration) -> bool {
+        let cycles_burned_within_horizon =
+            self.cycles_burned_per_day * horizon.as_secs() as u128 / SECONDS_IN_A_DAY;
+
+        self.cycle_balance
+            < self
+                .freezing_threshold_in_cycles
+                .saturating_add(cycles_burned_within_horizon)
+    }
+
+    /// Enough cycles to last for twice the horizon, and at least `minimum_top_up_amount`. Capped at
+    /// `maximum_top_up_amount`, so that a misreported burn rate can't drain the user index.
+    pub fn get_top_up_amount(
+        &self,
+        horizon: Duration,
+        minimum_top_up_amount: u128,
+        maximum_top_up_amount: u128,
+    ) -> u128 {
+        let cycles_burned_within_twice_the_horizon =
+            self.cycles_burned_per_day * 2 * horizon.as_secs() as u128 / SECONDS_IN_A_DAY;
+
+        self.freezing_threshold_in_cycles
+            .saturating_add(cycles_burned_within_twice_the_horizon)
+            .saturating_sub(self.cycle_balance)
+            .max(minimum_top_up_amount)
+            .min(maximum_top_up_amount)
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct CanisterCycleBurnReport {
+    pub canister_id: Principal,
+    pub cycle_balance: u128,
+    pub cycles_burned_per_day: u128,
+    pub cycles_topped_up: u128,
+}
+
+#[derive(Default, CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct CycleBurnTracking {
+    pub canister_cycle_burns: BTreeMap<Principal, CanisterCycleBurn>,
+    /// The next poll starts after this canister
+    pub last_polled_canister_id: Option<Principal>,
+}
+
+impl CycleBurnTracking {
+    pub fn record_cycle_balance(
+        &mut self,
+        canister_id: Principal,
+        cycle_balance: u128,
+        freezing_threshold_in_cycles: u128,
+        current_time: SystemTime,
+    ) -> &CanisterCycleBurn {
+        let canister_cycle_burn =
+            self.canister_cycle_burns
+                .entry(canister_id)
+                .or_insert_with(|| CanisterCycleBurn {
+                    cycle_balance,
+                    observed_at: current_time,
+                    ..Default::default()
+                });
+
+        let elapsed_seconds = current_time
+            .duration_since(canister_cycle_burn.observed_at)
+            .unwrap_or_default()
+            .as_secs() as u128;
+
+        // * a higher balance means the canister was topped up since the last observation, which
+        // * says nothing about its burn rate
+        if elapsed_seconds > 0 && cycle_balance <= canister_cycle_burn.cycle_balance {
+            let observed_cycles_burned_per_day =
+                (canister_cycle_burn.cycle_balance - cycle_balance) * SECONDS_IN_A_DAY
+                    / elapsed_seconds;
+
+            canister_cycle_burn.cycles_burned_per_day = if canister_cycle_burn.cycles_burned_per_day
+                == 0
+            {
+                observed_cycles_burned_per_day
+            } else {
+                (canister_cycle_burn.cycles_burned_per_day * 3 + observed_cycles_burned_per_day) / 4
+            };
+        }
+
+        canister_cycle_burn.cycle_balance = cycle_balance;
+        canister_cycle_burn.observed_at = current_time;
+        canister_cycle_burn.freezing_threshold_in_cycles = freezing_threshold_in_cycles;
+
+        canister_cycle_burn
+    }
+
+    pub fn record_top_up(&mut self, canister_id: Principal, amount: u128) {
+        if let Some(canister_cycle_burn) = self.canister_cycle_burns.get_mut(&canister_id) {
+            canister_cycle_burn.cycle_balance += amount;
+            canister_cycle_burn.cycles_topped_up += amount;
+        }
+    }
+
+    pub fn get_heaviest_burners(&self, limit: usize) -> Vec<CanisterCycleBurnReport> {
+        let mut canister_cycle_burns: Vec<_> = self.canister_cycle_burns.iter().collect();
+        canister_cycle_burns.sort_by_key(|(_, canister_cycle_burn)| {
+            Reverse(canister_cycle_burn.cycles_burned_per_day)
+        });
+
+        canister_cycle_burns
+            .into_iter()
+            .take(limit)
+            .map(
+                |(canister_id, canister_cycle_burn)| CanisterCycleBurnReport {
+                    canister_id: *canister_id,
+                    cycle_balance: canister_cycle_burn.cycle_balance,
+                    cycles_burned_per_day: canister_cycle_burn.cycles_burned_per_day,
+                    cycles_topped_up: canister_cycle_burn.cycles_topped_up,
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ONE_DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn test_record_cycle_balance_tracks_burn_rate() {
+        let canister_id = Principal::from_slice(&[1]);
+        let mut cycle_burn_tracking = CycleBurnTracking::default();
+
+        cycle_burn_tracking.record_cycle_balance(canister_id, 1_000, 100, UNIX_EPOCH);
+        let canister_cycle_burn =
+            cycle_burn_tracking.record_cycle_balance(canister_id, 900, 100, UNIX_EPOCH + ONE_DAY);
+        assert_eq!(canister_cycle_burn.cycles_burned_per_day, 100);
+
+        let canister_cycle_burn = cycle_burn_tracking.record_cycle_balance(
+            canister_id,
+            400,
+            100,
+            UNIX_EPOCH + ONE_DAY * 2,
+        );
+        assert_eq!(canister_cycle_burn.cycles_burned_per_day, 200);
+
+        // * a top up does not change the burn rate
+        let canister_cycle_burn = cycle_burn_tracking.record_cycle_balance(
+            canister_id,
+            5_000,
+            100,
+            UNIX_EPOCH + ONE_DAY * 3,
+        );
+        assert_eq!(canister_cycle_burn.cycles_burned_per_day, 200);
+    }
+
+    #[test]
+    fn test_is_predicted_to_freeze_within_and_get_top_up_amount() {
+        let canister_cycle_burn = CanisterCycleBurn {
+            cycle_balance: 1_000,
+            cycles_burned_per_day: 100,
+            freezing_threshold_in_cycles: 300,
+            ..Default::default()
+        };
+
+        assert!(!canister_cycle_burn.is_predicted_to_freeze_within(ONE_DAY * 7));
+        assert!(canister_cycle_burn.is_predicted_to_freeze_within(ONE_DAY * 8));
+        assert_eq!(
+            canister_cycle_burn.get_top_up_amount(ONE_DAY * 8, 0, u128::MAX),
+            300 + 1_600 - 1_000
+        );
+        assert_eq!(
+            canister_cycle_burn.get_top_up_amount(ONE_DAY * 8, 5_000, u128::MAX),
+            5_000
+        );
+        assert_eq!(
+            canister_cycle_burn.get_top_up_amount(ONE_DAY * 8, 0, 500),
+            500
+        );
+    }
+
+    #[test]
+    fn test_get_heaviest_burners() {
+        let mut cycle_burn_tracking = CycleBurnTracking::default();
+        for (i, cycles_burned_per_day) in [10, 30, 20].into_iter().enumerate() {
+            cycle_burn_tracking.canister_cycle_burns.insert(
+                Principal::from_slice(&[i as u8]),
+                CanisterCycleBurn {
+                    cycles_burned_per_day,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let heaviest_burners = cycle_burn_tracking.get_heaviest_burners(2);
+        assert_eq!(
+            heaviest_burners
+                .iter()
+                .map(|report| report.cycles_burned_per_day)
+                .collect::<Vec<_>>(),
+            vec![30, 20]
+        );
+    }
+}
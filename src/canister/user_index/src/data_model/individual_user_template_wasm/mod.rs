@@ -0,0 +1,550 @@
+use std::{borrow::Cow, cell::OnceCell, collections::BTreeMap};
+
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{BoundedStorable, StableBTreeMap, Storable};
+use serde::Serialize;
+use shared_utils::{
+    canister_specific::user_index::types::individual_user_template_wasm::{
+        get_wasm_hash, IndividualUserTemplateWasmChunk, IndividualUserTemplateWasmDetails,
+        IndividualUserTemplateWasmStoreDetails,
+    },
+    constant::MAX_INDIVIDUAL_USER_TEMPLATE_WASM_SIZE_IN_BYTES,
+};
+
+use super::memory::{self, Memory};
+
+/// 32 KiB
+const STORED_WASM_CHUNK_SIZE_IN_BYTES: usize = 32 * 1024;
+
+#[derive(Default, CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct IndividualUserTemplateWasm {
+    pub version: String,
+    /// sha256 of `wasm_module`
+    pub wasm_hash: Vec<u8>,
+    pub wasm_module: Vec<u8>,
+}
+
+/// A wasm being uploaded in chunks. It is only stored once all chunks are in and the hash matches.
+/// The chunks received so far are kept in stable memory.
+#[derive(Default, CandidType, Deserialize, Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct IndividualUserTemplateWasmUpload {
+    pub version: String,
+    pub wasm_hash: Vec<u8>,
+    pub total_chunks: u32,
+    pub received_chunk_count: u32,
+    pub received_size_in_bytes: u64,
+}
+
+/// The wasms as they were kept in the heap before they moved to stable memory. Only read once
+/// after the upgrade that moves them.
+#[derive(Default, Deserialize, Clone, Debug)]
+pub struct LegacyIndividualUserTemplateWasmStore {
+    pub current: Option<IndividualUserTemplateWasm>,
+    pub previous: Option<IndividualUserTemplateWasm>,
+}
+
+/// The key of a slice of a stored wasm: the sha256 of the wasm followed by the big endian slice
+/// index, so that the slices of a wasm are next to each other and in order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct WasmChunkKey {
+    wasm_hash: [u8; 32],
+    chunk_index: u32,
+}
+
+impl Storable for WasmChunkKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut bytes = self.wasm_hash.to_vec();
+        bytes.extend(self.chunk_index.to_be_bytes());
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let mut wasm_hash = [0; 32];
+        wasm_hash.copy_from_slice(&bytes[..32]);
+        let mut chunk_index = [0; 4];
+        chunk_index.copy_from_slice(&bytes[32..36]);
+
+        Self {
+            wasm_hash,
+            chunk_index: u32::from_be_bytes(chunk_index),
+        }
+    }
+}
+
+impl BoundedStorable for WasmChunkKey {
+    const MAX_SIZE: u32 = 36;
+    const IS_FIXED_SIZE: bool = true;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct WasmChunk(Vec<u8>);
+
+impl Storable for WasmChunk {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(bytes.into_owned())
+    }
+}
+
+impl BoundedStorable for WasmChunk {
+    const MAX_SIZE: u32 = STORED_WASM_CHUNK_SIZE_IN_BYTES as u32;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+type WasmChunkMap = StableBTreeMap<WasmChunkKey, WasmChunk, Memory>;
+
+struct WasmChunkMaps {
+    stored_wasm_chunk_map: WasmChunkMap,
+    upload_chunk_map: WasmChunkMap,
+}
+
+impl Default for WasmChunkMaps {
+    fn default() -> Self {
+        Self {
+            stored_wasm_chunk_map: StableBTreeMap::init(
+                memory::get_individual_user_template_wasm_chunk_map_memory(),
+            ),
+            upload_chunk_map: StableBTreeMap::init(
+                memory::get_individual_user_template_wasm_upload_chunk_map_memory(),
+            ),
+        }
+    }
+}
+
+fn to_wasm_hash_key(wasm_hash: &[u8]) -> Result<[u8; 32], String> {
+    wasm_hash
+        .try_into()
+        .map_err(|_| "Wasm hash has to be a sha256".to_string())
+}
+
+fn insert_chunks(
+    chunk_map: &mut WasmChunkMap,
+    wasm_hash: [u8; 32],
+    first_chunk_index: u32,
+    bytes: &[u8],
+) {
+    bytes
+        .chunks(STORED_WASM_CHUNK_SIZE_IN_BYTES)
+        .enumerate()
+        .for_each(|(offset, chunk)| {
+            chunk_map.insert(
+                WasmChunkKey {
+                    wasm_hash,
+                    chunk_index: first_chunk_index + offset as u32,
+                },
+                WasmChunk(chunk.to_vec()),
+            );
+        });
+}
+
+fn get_chunk_keys(chunk_map: &WasmChunkMap, wasm_hash: [u8; 32]) -> Vec<WasmChunkKey> {
+    chunk_map
+        .range(
+            WasmChunkKey {
+                wasm_hash,
+                chunk_index: 0,
+            }..=WasmChunkKey {
+                wasm_hash,
+                chunk_index: u32::MAX,
+            },
+        )
+        .map(|(chunk_key, _)| chunk_key)
+        .collect()
+}
+
+fn read_chunks(chunk_map: &WasmChunkMap, wasm_hash: [u8; 32]) -> Vec<u8> {
+    get_chunk_keys(chunk_map, wasm_hash)
+        .iter()
+        .filter_map(|chunk_key| chunk_map.get(chunk_key))
+        .flat_map(|chunk| chunk.0)
+        .collect()
+}
+
+fn remove_chunks(chunk_map: &mut WasmChunkMap, wasm_hash: [u8; 32]) {
+    get_chunk_keys(chunk_map, wasm_hash)
+        .iter()
+        .for_each(|chunk_key| {
+            chunk_map.remove(chunk_key);
+        });
+}
+
+/// The wasm the individual user canisters are installed with and the one before it, kept so
+/// that a bad release can be rolled back without building a new wasm. Only their details are
+/// kept in the heap, the wasm modules are in stable memory.
+#[derive(Default, Deserialize, Serialize)]
+pub struct IndividualUserTemplateWasmStore {
+    pub current: Option<IndividualUserTemplateWasmDetails>,
+    pub previous: Option<IndividualUserTemplateWasmDetails>,
+    #[serde(default)]
+    pub upload_in_progress: Option<IndividualUserTemplateWasmUpload>,
+    /// Set up on first use, so that stable memory is not touched while canister data is still
+    /// being read from where it was kept before the memory manager
+    #[serde(skip)]
+    chunk_maps: OnceCell<WasmChunkMaps>,
+    /// Wasm modules by hash, so that every canister install does not read the whole wasm from
+    /// stable memory again
+    #[serde(skip)]
+    wasm_module_cache: BTreeMap<[u8; 32], Vec<u8>>,
+}
+
+impl From<&IndividualUserTemplateWasm> for IndividualUserTemplateWasmDetails {
+    fn from(wasm: &IndividualUserTemplateWasm) -> Self {
+        Self {
+            version: wasm.version.clone(),
+            wasm_hash: wasm.wasm_hash.clone(),
+            wasm_size_in_bytes: wasm.wasm_module.len() as u64,
+        }
+    }
+}
+
+impl From<&IndividualUserTemplateWasmStore> for IndividualUserTemplateWasmStoreDetails {
+    fn from(store: &IndividualUserTemplateWasmStore) -> Self {
+        Self {
+            current: store.current.clone(),
+            previous: store.previous.clone(),
+        }
+    }
+}
+
+impl IndividualUserTemplateWasmStore {
+    fn chunk_maps(&mut self) -> &mut WasmChunkMaps {
+        self.chunk_maps.get_or_init(WasmChunkMaps::default);
+        self.chunk_maps.get_mut().unwrap()
+    }
+
+    fn get_wasm(
+        &mut self,
+        wasm_details: Option<IndividualUserTemplateWasmDetails>,
+    ) -> Result<Option<IndividualUserTemplateWasm>, String> {
+        let Some(wasm_details) = wasm_details else {
+            return Ok(None);
+        };
+
+        let wasm_hash = to_wasm_hash_key(&wasm_details.wasm_hash)?;
+        let wasm_module = match self.wasm_module_cache.get(&wasm_hash) {
+            Some(wasm_module) => wasm_module.clone(),
+            None => {
+                let wasm_module = read_chunks(&self.chunk_maps().stored_wasm_chunk_map, wasm_hash);
+                if wasm_module.len() as u64 != wasm_details.wasm_size_in_bytes {
+                    return Err(format!(
+                        "Stored wasm {} is incomplete",
+                        wasm_details.version
+                    ));
+                }
+                self.wasm_module_cache
+                    .insert(wasm_hash, wasm_module.clone());
+                wasm_module
+            }
+        };
+
+        Ok(Some(IndividualUserTemplateWasm {
+            version: wasm_details.version,
+            wasm_hash: wasm_details.wasm_hash,
+            wasm_module,
+        }))
+    }
+
+    pub fn get_current_wasm(&mut self) -> Result<Option<IndividualUserTemplateWasm>, String> {
+        self.get_wasm(self.current.clone())
+    }
+
+    pub fn get_previous_wasm(&mut self) -> Result<Option<IndividualUserTemplateWasm>, String> {
+        self.get_wasm(self.previous.clone())
+    }
+
+    /// Makes `wasm` the current one and keeps the current one as previous. A wasm that is already
+    /// stored is not added again, so that a rolled back wasm does not come back as current.
+    pub fn store_new_wasm(&mut self, wasm: IndividualUserTemplateWasm) -> Result<bool, String> {
+        let is_already_stored = [&self.current, &self.previous]
+            .into_iter()
+            .flatten()
+            .any(|stored_wasm| stored_wasm.wasm_hash == wasm.wasm_hash);
+
+        if is_already_stored {
+            return Ok(false);
+        }
+
+        let wasm_hash = to_wasm_hash_key(&wasm.wasm_hash)?;
+        let dropped_wasm_hash = self
+            .previous
+            .as_ref()
+            .map(|previous| to_wasm_hash_key(&previous.wasm_hash))
+            .transpose()?;
+
+        let stored_wasm_chunk_map = &mut self.chunk_maps().stored_wasm_chunk_map;
+        if let Some(dropped_wasm_hash) = dropped_wasm_hash {
+            remove_chunks(stored_wasm_chunk_map, dropped_wasm_hash);
+        }
+        insert_chunks(stored_wasm_chunk_map, wasm_hash, 0, &wasm.wasm_module);
+        if let Some(dropped_wasm_hash) = dropped_wasm_hash {
+            self.wasm_module_cache.remove(&dropped_wasm_hash);
+        }
+
+        self.previous = self.current.replace((&wasm).into());
+        Ok(true)
+    }
+
+    /// Moves the wasms kept in the heap before they moved to stable memory
+    pub fn migrate_legacy_store(
+        &mut self,
+        legacy_store: LegacyIndividualUserTemplateWasmStore,
+    ) -> Result<(), String> {
+        [legacy_store.previous, legacy_store.current]
+            .into_iter()
+            .flatten()
+            .try_for_each(|wasm| self.store_new_wasm(wasm).map(|_| ()))
+    }
+
+    /// Makes the previous wasm the current one, so that new canisters and later upgrade runs use
+    /// the rolled back wasm
+    pub fn swap_current_and_previous(&mut self) -> Result<(), String> {
+        if self.previous.is_none() {
+            return Err("No previous individual user template wasm stored".to_string());
+        }
+
+        std::mem::swap(&mut self.current, &mut self.previous);
+        Ok(())
+    }
+
+    fn discard_upload(&mut self) {
+        let upload_chunk_map = &mut self.chunk_maps().upload_chunk_map;
+        let chunk_keys: Vec<WasmChunkKey> = upload_chunk_map
+            .iter()
+            .map(|(chunk_key, _)| chunk_key)
+            .collect();
+        chunk_keys.iter().for_each(|chunk_key| {
+            upload_chunk_map.remove(chunk_key);
+        });
+
+        self.upload_in_progress = None;
+    }
+
+    /// Adds a chunk to the upload in progress. Once the last chunk is in, the wasm is checked
+    /// against its hash and stored as the current wasm, and its details are returned.
+    pub fn receive_wasm_chunk(
+        &mut self,
+        wasm_chunk: IndividualUserTemplateWasmChunk,
+    ) -> Result<Option<IndividualUserTemplateWasmDetails>, String> {
+        if wasm_chunk.chunk_index >= wasm_chunk.total_chunks {
+            return Err("Chunk index has to be below the total number of chunks".to_string());
+        }
+
+        if wasm_chunk.chunk_index == 0 {
+            to_wasm_hash_key(&wasm_chunk.wasm_hash)?;
+            self.discard_upload();
+            self.upload_in_progress = Some(IndividualUserTemplateWasmUpload {
+                version: wasm_chunk.version.clone(),
+                wasm_hash: wasm_chunk.wasm_hash.clone(),
+                total_chunks: wasm_chunk.total_chunks,
+                ..Default::default()
+            });
+        }
+
+        let upload = self
+            .upload_in_progress
+            .clone()
+            .ok_or("No upload in progress. Start with chunk 0")?;
+
+        if upload.version != wasm_chunk.version
+            || upload.wasm_hash != wasm_chunk.wasm_hash
+            || upload.total_chunks != wasm_chunk.total_chunks
+        {
+            return Err("Chunk does not belong to the upload in progress".to_string());
+        }
+
+        if upload.received_chunk_count != wasm_chunk.chunk_index {
+            return Err(format!(
+                "Expected chunk {} but got chunk {}",
+                upload.received_chunk_count, wasm_chunk.chunk_index
+            ));
+        }
+
+        if upload.received_size_in_bytes as usize + wasm_chunk.chunk.len()
+            > MAX_INDIVIDUAL_USER_TEMPLATE_WASM_SIZE_IN_BYTES
+        {
+            self.discard_upload();
+            return Err("Wasm is larger than the maximum allowed size".to_string());
+        }
+
+        let wasm_hash = to_wasm_hash_key(&upload.wasm_hash)?;
+        let upload_chunk_map = &mut self.chunk_maps().upload_chunk_map;
+        let next_stored_chunk_index = upload_chunk_map.len() as u32;
+        insert_chunks(
+            upload_chunk_map,
+            wasm_hash,
+            next_stored_chunk_index,
+            &wasm_chunk.chunk,
+        );
+
+        let upload = IndividualUserTemplateWasmUpload {
+            received_chunk_count: upload.received_chunk_count + 1,
+            received_size_in_bytes: upload.received_size_in_bytes + wasm_chunk.chunk.len() as u64,
+            ..upload
+        };
+
+        if upload.received_chunk_count < upload.total_chunks {
+            self.upload_in_progress = Some(upload);
+            return Ok(None);
+        }
+
+        let wasm_module = read_chunks(&self.chunk_maps().upload_chunk_map, wasm_hash);
+        self.discard_upload();
+        if get_wasm_hash(&wasm_module) != upload.wasm_hash {
+            return Err("sha256 of the uploaded wasm does not match the expected hash".to_string());
+        }
+
+        let wasm = IndividualUserTemplateWasm {
+            version: upload.version,
+            wasm_hash: upload.wasm_hash,
+            wasm_module,
+        };
+        let wasm_details = (&wasm).into();
+
+        if !self.store_new_wasm(wasm)? {
+            return Err("This wasm is already stored".to_string());
+        }
+
+        Ok(Some(wasm_details))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_wasm(version: &str, hash: u8) -> IndividualUserTemplateWasm {
+        IndividualUserTemplateWasm {
+            version: version.to_string(),
+            wasm_hash: vec![hash; 32],
+            wasm_module: vec![hash; STORED_WASM_CHUNK_SIZE_IN_BYTES + 8],
+        }
+    }
+
+    #[test]
+    fn test_store_new_wasm_and_swap_current_and_previous() {
+        let mut store = IndividualUserTemplateWasmStore::default();
+        assert!(store.swap_current_and_previous().is_err());
+        assert_eq!(store.get_current_wasm(), Ok(None));
+
+        assert_eq!(store.store_new_wasm(get_wasm("v1.0.0", 1)), Ok(true));
+        assert_eq!(store.store_new_wasm(get_wasm("v1.0.0", 1)), Ok(false));
+        assert_eq!(store.store_new_wasm(get_wasm("v1.1.0", 2)), Ok(true));
+        assert_eq!(store.get_current_wasm(), Ok(Some(get_wasm("v1.1.0", 2))));
+        assert_eq!(store.get_previous_wasm(), Ok(Some(get_wasm("v1.0.0", 1))));
+
+        assert!(store.swap_current_and_previous().is_ok());
+        assert_eq!(store.get_current_wasm(), Ok(Some(get_wasm("v1.0.0", 1))));
+        assert_eq!(store.get_previous_wasm(), Ok(Some(get_wasm("v1.1.0", 2))));
+
+        // * the rolled back wasm is not made current again
+        assert_eq!(store.store_new_wasm(get_wasm("v1.1.0", 2)), Ok(false));
+        assert_eq!(store.current, Some((&get_wasm("v1.0.0", 1)).into()));
+
+        // * the wasm that is no longer kept is removed from stable memory
+        assert_eq!(store.store_new_wasm(get_wasm("v1.2.0", 3)), Ok(true));
+        assert_eq!(store.get_previous_wasm(), Ok(Some(get_wasm("v1.0.0", 1))));
+        assert!(read_chunks(&store.chunk_maps().stored_wasm_chunk_map, [2; 32]).is_empty());
+
+        assert!(store
+            .store_new_wasm(IndividualUserTemplateWasm {
+                wasm_hash: vec![4; 8],
+                ..get_wasm("v1.3.0", 4)
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_wasm_modules_are_cached_until_the_wasm_is_dropped() {
+        let mut store = IndividualUserTemplateWasmStore::default();
+        store.store_new_wasm(get_wasm("v1.0.0", 1)).unwrap();
+        store.store_new_wasm(get_wasm("v1.1.0", 2)).unwrap();
+        assert!(store.wasm_module_cache.is_empty());
+
+        assert_eq!(store.get_current_wasm(), Ok(Some(get_wasm("v1.1.0", 2))));
+        assert_eq!(
+            store.wasm_module_cache.keys().collect::<Vec<_>>(),
+            vec![&[2; 32]]
+        );
+
+        // * served from the cache once read
+        remove_chunks(&mut store.chunk_maps().stored_wasm_chunk_map, [2; 32]);
+        assert_eq!(store.get_current_wasm(), Ok(Some(get_wasm("v1.1.0", 2))));
+
+        store.store_new_wasm(get_wasm("v1.2.0", 3)).unwrap();
+        store.store_new_wasm(get_wasm("v1.3.0", 4)).unwrap();
+        assert!(store.wasm_module_cache.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_legacy_store() {
+        let mut store = IndividualUserTemplateWasmStore::default();
+
+        store
+            .migrate_legacy_store(LegacyIndividualUserTemplateWasmStore {
+                current: Some(get_wasm("v1.1.0", 2)),
+                previous: Some(get_wasm("v1.0.0", 1)),
+            })
+            .unwrap();
+
+        assert_eq!(store.get_current_wasm(), Ok(Some(get_wasm("v1.1.0", 2))));
+        assert_eq!(store.get_previous_wasm(), Ok(Some(get_wasm("v1.0.0", 1))));
+    }
+
+    #[test]
+    fn test_receive_wasm_chunk() {
+        let mut store = IndividualUserTemplateWasmStore::default();
+        let wasm_module: Vec<u8> = (0..100_000).map(|byte| byte as u8).collect();
+        let chunks =
+            IndividualUserTemplateWasmChunk::split_wasm_module("v1.0.0", &wasm_module, 40_000);
+        assert_eq!(chunks.len(), 3);
+
+        // * chunks have to come in order
+        assert!(store.receive_wasm_chunk(chunks[1].clone()).is_err());
+        assert_eq!(store.receive_wasm_chunk(chunks[0].clone()), Ok(None));
+        assert!(store.receive_wasm_chunk(chunks[2].clone()).is_err());
+        assert_eq!(store.receive_wasm_chunk(chunks[1].clone()), Ok(None));
+
+        let wasm_details = store
+            .receive_wasm_chunk(chunks[2].clone())
+            .unwrap()
+            .unwrap();
+        assert_eq!(wasm_details.version, "v1.0.0");
+        assert_eq!(wasm_details.wasm_size_in_bytes, 100_000);
+        assert_eq!(
+            store.get_current_wasm().unwrap().unwrap().wasm_module,
+            wasm_module
+        );
+        assert_eq!(store.upload_in_progress, None);
+        assert!(store.chunk_maps().upload_chunk_map.is_empty());
+
+        // * the same wasm is not stored twice
+        store.receive_wasm_chunk(chunks[0].clone()).unwrap();
+        store.receive_wasm_chunk(chunks[1].clone()).unwrap();
+        assert!(store.receive_wasm_chunk(chunks[2].clone()).is_err());
+
+        // * restarting an upload discards the chunks received so far
+        let chunks = IndividualUserTemplateWasmChunk::split_wasm_module("v1.1.0", &[7; 150], 100);
+        store.receive_wasm_chunk(chunks[0].clone()).unwrap();
+        store.receive_wasm_chunk(chunks[0].clone()).unwrap();
+        assert_eq!(
+            store
+                .receive_wasm_chunk(chunks[1].clone())
+                .unwrap()
+                .unwrap()
+                .wasm_size_in_bytes,
+            150
+        );
+
+        // * a corrupted chunk fails the hash check
+        let mut chunks =
+            IndividualUserTemplateWasmChunk::split_wasm_module("v1.2.0", &[9; 150], 100);
+        chunks[1].chunk[0] = 8;
+        store.receive_wasm_chunk(chunks[0].clone()).unwrap();
+        assert!(store.receive_wasm_chunk(chunks[1].clone()).is_err());
+        assert_eq!(store.current.as_ref().unwrap().version, "v1.1.0");
+        assert!(store.chunk_maps().upload_chunk_map.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+
+use ic_stable_structures::{
+    memory_manager::{MemoryId, MemoryManager, VirtualMemory},
+    DefaultMemoryImpl,
+};
+
+thread_local! {
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+}
+
+pub type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+const MEMORY_MANAGER_MAGIC: &[u8; 3] = b"MGR";
+
+/// Canister data used to be serialized to the start of stable memory before the memory manager
+/// was introduced. Has to be checked before the memory manager is first used, as it takes over
+/// stable memory that does not start with its header.
+pub fn is_stable_memory_managed() -> bool {
+    if ic_cdk::api::stable::stable64_size() == 0 {
+        return true;
+    }
+
+    let mut magic = [0; 3];
+    ic_cdk::api::stable::stable64_read(0, &mut magic);
+    magic == *MEMORY_MANAGER_MAGIC
+}
+
+// * Heap data memory.
+const UPGRADES_MEMORY_ID: MemoryId = MemoryId::new(0);
+pub fn get_upgrades_memory() -> Memory {
+    MEMORY_MANAGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell.borrow_mut().get(UPGRADES_MEMORY_ID)
+    })
+}
+
+// * Individual user template wasm chunk map memory.
+const INDIVIDUAL_USER_TEMPLATE_WASM_CHUNK_MAP_MEMORY_ID: MemoryId = MemoryId::new(1);
+pub fn get_individual_user_template_wasm_chunk_map_memory() -> Memory {
+    MEMORY_MANAGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(INDIVIDUAL_USER_TEMPLATE_WASM_CHUNK_MAP_MEMORY_ID)
+    })
+}
+
+// * Individual user template wasm upload chunk map memory.
+const INDIVIDUAL_USER_TEMPLATE_WASM_UPLOAD_CHUNK_MAP_MEMORY_ID: MemoryId = MemoryId::new(2);
+pub fn get_individual_user_template_wasm_upload_chunk_map_memory() -> Memory {
+    MEMORY_MANAGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(INDIVIDUAL_USER_TEMPLATE_WASM_UPLOAD_CHUNK_MAP_MEMORY_ID)
+    })
+}
+
+// * Account deletion audit log index memory.
+const ACCOUNT_DELETION_AUDIT_LOG_INDEX_MEMORY_ID: MemoryId = MemoryId::new(3);
+pub fn get_account_deletion_audit_log_index_memory() -> Memory {
+    MEMORY_MANAGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(ACCOUNT_DELETION_AUDIT_LOG_INDEX_MEMORY_ID)
+    })
+}
+
+// * Account deletion audit log data memory.
+const ACCOUNT_DELETION_AUDIT_LOG_DATA_MEMORY_ID: MemoryId = MemoryId::new(4);
+pub fn get_account_deletion_audit_log_data_memory() -> Memory {
+    MEMORY_MANAGER.with(|memory_manager_ref_cell| {
+        memory_manager_ref_cell
+            .borrow_mut()
+            .get(ACCOUNT_DELETION_AUDIT_LOG_DATA_MEMORY_ID)
+    })
+}
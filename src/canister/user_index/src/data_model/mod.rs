@@ -1,12 +1,34 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use candid::{CandidType, Deserialize, Principal};
+use candid::{Deserialize, Principal};
 use serde::Serialize;
 
-use self::{canister_upgrade::UpgradeStatus, configuration::Configuration};
+use self::{
+    account_deletion::AccountDeletions,
+    canister_reclamation::CanisterReclamation,
+    canister_settings::{CanisterSettingsDrift, CanisterSettingsSyncStatus},
+    canister_upgrade::{UpgradeCursor, UpgradeRolloutPlan, UpgradeStatus},
+    configuration::Configuration,
+    cycle_burn_tracking::CycleBurnTracking,
+    individual_user_template_wasm::{
+        IndividualUserTemplateWasmStore, LegacyIndividualUserTemplateWasmStore,
+    },
+    user_name_history::UserNameHistory,
+    user_name_skeleton_index::UserNameSkeletonIndex,
+    user_name_search_rate_limiter::UserNameSearchRateLimiter,
+};
 
+pub mod account_deletion;
+pub mod canister_reclamation;
+pub mod canister_settings;
 pub mod canister_upgrade;
 pub mod configuration;
+pub mod cycle_burn_tracking;
+pub mod individual_user_template_wasm;
+pub mod memory;
+pub mod user_name_history;
+pub mod user_name_skeleton_index;
+pub mod user_name_search_rate_limiter;
 
 
 const fn _default_true() -> bool {
@@ -17,7 +39,7 @@ fn _default_vec_principal() -> HashSet<Principal> {
     return HashSet::new()
 }
 
-#[derive(Default, CandidType, Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct CanisterData {
     pub configuration: Configuration,
     pub last_run_upgrade_status: UpgradeStatus,
@@ -25,4 +47,37 @@ pub struct CanisterData {
     pub available_canisters: HashSet<Principal>,
     pub user_principal_id_to_canister_id_map: BTreeMap<Principal, Principal>,
     pub unique_user_name_to_user_principal_id_map: BTreeMap<String, Principal>,
+    #[serde(default)]
+    pub user_name_history: UserNameHistory,
+    #[serde(default)]
+    pub user_name_blocklist: BTreeSet<String>,
+    #[serde(default)]
+    pub canister_reclamation: CanisterReclamation,
+    #[serde(default)]
+    pub upgrade_rollout_plan: UpgradeRolloutPlan,
+    /// The wasms as they were kept before they moved to stable memory, moved on the next upgrade
+    #[serde(default, rename = "individual_user_template_wasm_store", skip_serializing)]
+    pub legacy_individual_user_template_wasm_store: LegacyIndividualUserTemplateWasmStore,
+    #[serde(default, rename = "individual_user_template_wasms")]
+    pub individual_user_template_wasm_store: IndividualUserTemplateWasmStore,
+    #[serde(default)]
+    pub last_run_rollback_status: UpgradeStatus,
+    #[serde(default)]
+    pub upgrade_cursor: Option<UpgradeCursor>,
+    #[serde(default)]
+    pub cycle_burn_tracking: CycleBurnTracking,
+    #[serde(default)]
+    pub last_run_canister_settings_sync_status: CanisterSettingsSyncStatus,
+    /// The canisters the last settings sync found drifted
+    #[serde(default)]
+    pub canister_settings_sync_drifts: BTreeMap<Principal, CanisterSettingsDrift>,
+    /// The canisters of the last settings sync whose settings could not be read, with the error
+    #[serde(default)]
+    pub canister_settings_sync_failures: BTreeMap<Principal, String>,
+    #[serde(default)]
+    pub account_deletions: AccountDeletions,
+    #[serde(skip)]
+    pub user_name_search_rate_limiter: UserNameSearchRateLimiter,
+    #[serde(skip)]
+    pub user_name_skeleton_index: UserNameSkeletonIndex,
 }
\ No newline at end of file
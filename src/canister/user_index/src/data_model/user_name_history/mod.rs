@@ -0,0 +1,210 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+/// Minimum time between two username changes of the same user.
+pub const USER_NAME_CHANGE_COOLDOWN: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// How long a replaced username stays reserved for its previous owner.
+pub const USER_NAME_RESERVATION_GRACE_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+#[derive(Default, CandidType, Serialize, Deserialize, Clone)]
+pub struct UserNameHistory {
+    pub user_principal_id_to_user_name_changes: BTreeMap<Principal, UserNameChanges>,
+    pub reserved_user_names: BTreeMap<String, ReservedUserName>,
+    /// Usernames taken away from their owners, oldest first
+    #[serde(default)]
+    pub released_user_names: Vec<ReleasedUserName>,
+    /// Usernames changed by the user index that the canisters of their owners still have to be
+    /// told about. `None` when the username was released.
+    #[serde(default)]
+    pub user_name_updates_to_send_to_owners: BTreeMap<Principal, Option<String>>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserNameChanges {
+    pub current_user_name: String,
+    pub last_changed_at: SystemTime,
+    pub previous_user_names: Vec<PreviousUserName>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PreviousUserName {
+    pub user_name: String,
+    pub replaced_at: SystemTime,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReservedUserName {
+    pub user_principal_id: Principal,
+    pub reserved_until: SystemTime,
+}
+
+/// A username released because it clashed with another one once case folded
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReleasedUserName {
+    pub user_name: String,
+    pub user_principal_id: Principal,
+    pub released_at: SystemTime,
+}
+
+impl UserNameHistory {
+    /// Returns the reservation on a replaced username if its grace period has not run out yet.
+    pub fn get_active_reservation(
+        &self,
+        user_name: &str,
+        current_time: SystemTime,
+    ) -> Option<&ReservedUserName> {
+        self.reserved_user_names
+            .get(user_name)
+            .filter(|reservation| reservation.reserved_until > current_time)
+    }
+
+    pub fn is_change_on_cooldown(
+        &self,
+        user_principal_id: &Principal,
+        current_time: SystemTime,
+    ) -> bool {
+        self.user_principal_id_to_user_name_changes
+            .get(user_principal_id)
+            .is_some_and(|user_name_changes| {
+                current_time
+                    .duration_since(user_name_changes.last_changed_at)
+                    .unwrap_or_default()
+                    < USER_NAME_CHANGE_COOLDOWN
+            })
+    }
+
+    /// Records that `user_principal_id` now goes by `new_user_name`. The replaced username,
+    /// if any, is reserved for the same user until the grace period runs out.
+    pub fn record_change(
+        &mut self,
+        user_principal_id: Principal,
+        previous_user_name: Option<String>,
+        new_user_name: String,
+        current_time: SystemTime,
+    ) {
+        self.reserved_user_names.remove(&new_user_name);
+        self.remove_expired_reservations(current_time);
+
+        let user_name_changes = self
+            .user_principal_id_to_user_name_changes
+            .entry(user_principal_id)
+            .or_insert_with(|| UserNameChanges {
+                current_user_name: new_user_name.clone(),
+                last_changed_at: current_time,
+                previous_user_names: vec![],
+            });
+
+        if let Some(previous_user_name) = previous_user_name {
+            self.reserved_user_names.insert(
+                previous_user_name.clone(),
+                ReservedUserName {
+                    user_principal_id,
+                    reserved_until: current_time + USER_NAME_RESERVATION_GRACE_PERIOD,
+                },
+            );
+            user_name_changes
+                .previous_user_names
+                .push(PreviousUserName {
+                    user_name: previous_user_name,
+                    replaced_at: current_time,
+                });
+        }
+
+        user_name_changes.current_user_name = new_user_name;
+        user_name_changes.last_changed_at = current_time;
+    }
+
+    /// Records that the username of `user_principal_id` was case folded to `user_name`. This
+    /// does not count as a change towards the cooldown.
+    pub fn record_normalization(&mut self, user_principal_id: Principal, user_name: String) {
+        if let Some(user_name_changes) = self
+            .user_principal_id_to_user_name_changes
+            .get_mut(&user_principal_id)
+        {
+            user_name_changes.current_user_name = user_name.clone();
+        }
+
+        self.user_name_updates_to_send_to_owners
+            .insert(user_principal_id, Some(user_name));
+    }
+
+    /// Records that the username of `user_principal_id` was taken away, leaving them without one
+    pub fn record_release(
+        &mut self,
+        user_principal_id: Principal,
+        user_name: String,
+        current_time: SystemTime,
+    ) {
+        self.user_principal_id_to_user_name_changes
+            .remove(&user_principal_id);
+        self.released_user_names.push(ReleasedUserName {
+            user_name,
+            user_principal_id,
+            released_at: current_time,
+        });
+        self.user_name_updates_to_send_to_owners
+            .insert(user_principal_id, None);
+    }
+
+    fn remove_expired_reservations(&mut self, current_time: SystemTime) {
+        self.reserved_user_names
+            .retain(|_, reservation| reservation.reserved_until > current_time);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::get_mock_user_alice_principal_id;
+
+    use super::*;
+
+    #[test]
+    fn test_record_change_reserves_previous_user_name_for_grace_period() {
+        let mut user_name_history = UserNameHistory::default();
+        let alice_principal_id = get_mock_user_alice_principal_id();
+        let now = SystemTime::now();
+
+        user_name_history.record_change(alice_principal_id, None, "alice".to_string(), now);
+        assert!(user_name_history.reserved_user_names.is_empty());
+        assert!(user_name_history.is_change_on_cooldown(&alice_principal_id, now));
+
+        let later = now + USER_NAME_CHANGE_COOLDOWN;
+        assert!(!user_name_history.is_change_on_cooldown(&alice_principal_id, later));
+
+        user_name_history.record_change(
+            alice_principal_id,
+            Some("alice".to_string()),
+            "alice_new".to_string(),
+            later,
+        );
+
+        let user_name_changes = user_name_history
+            .user_principal_id_to_user_name_changes
+            .get(&alice_principal_id)
+            .unwrap();
+        assert_eq!(user_name_changes.current_user_name, "alice_new");
+        assert_eq!(
+            user_name_changes.previous_user_names,
+            vec![PreviousUserName {
+                user_name: "alice".to_string(),
+                replaced_at: later,
+            }]
+        );
+
+        assert_eq!(
+            user_name_history
+                .get_active_reservation("alice", later)
+                .unwrap()
+                .user_principal_id,
+            alice_principal_id
+        );
+        assert!(user_name_history
+            .get_active_reservation("alice", later + USER_NAME_RESERVATION_GRACE_PERIOD)
+            .is_none());
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+pub const USER_NAME_SEARCH_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+pub const MAX_USER_NAME_SEARCHES_PER_CALLER_PER_WINDOW: u32 = 30;
+/// Windows that ran out are only cleared once this many callers are tracked
+const MAX_TRACKED_CALLERS_BEFORE_PRUNING: usize = 1000;
+
+/// The username searches of each caller in their current window
+#[derive(Default, CandidType, Serialize, Deserialize, Clone)]
+pub struct UserNameSearchRateLimiter {
+    caller_to_search_window: BTreeMap<Principal, SearchWindow>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone)]
+struct SearchWindow {
+    started_at: SystemTime,
+    search_count: u32,
+}
+
+impl SearchWindow {
+    fn has_ended(&self, current_time: SystemTime) -> bool {
+        current_time
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+            >= USER_NAME_SEARCH_RATE_LIMIT_WINDOW
+    }
+}
+
+impl UserNameSearchRateLimiter {
+    /// Counts a search by `caller`. Returns false, without counting it, if the caller already
+    /// searched as often as allowed in their current window.
+    pub fn try_record_search(&mut self, caller: Principal, current_time: SystemTime) -> bool {
+        if self.caller_to_search_window.len() >= MAX_TRACKED_CALLERS_BEFORE_PRUNING {
+            self.caller_to_search_window
+                .retain(|_, search_window| !search_window.has_ended(current_time));
+        }
+
+        let search_window = self
+            .caller_to_search_window
+            .entry(caller)
+            .or_insert(SearchWindow {
+                started_at: current_time,
+                search_count: 0,
+            });
+
+        if search_window.has_ended(current_time) {
+            *search_window = SearchWindow {
+                started_at: current_time,
+                search_count: 0,
+            };
+        }
+
+        if search_window.search_count >= MAX_USER_NAME_SEARCHES_PER_CALLER_PER_WINDOW {
+            return false;
+        }
+
+        search_window.search_count += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_try_record_search_limits_searches_per_window() {
+        let mut rate_limiter = UserNameSearchRateLimiter::default();
+        let alice = get_mock_user_alice_principal_id();
+        let now = SystemTime::now();
+
+        for _ in 0..MAX_USER_NAME_SEARCHES_PER_CALLER_PER_WINDOW {
+            assert!(rate_limiter.try_record_search(alice, now));
+        }
+        assert!(!rate_limiter.try_record_search(alice, now));
+        assert!(rate_limiter.try_record_search(get_mock_user_bob_principal_id(), now));
+
+        assert!(rate_limiter.try_record_search(alice, now + USER_NAME_SEARCH_RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn test_try_record_search_prunes_windows_that_ended() {
+        let mut rate_limiter = UserNameSearchRateLimiter::default();
+        let now = SystemTime::now();
+
+        for index in 0..MAX_TRACKED_CALLERS_BEFORE_PRUNING as u64 {
+            rate_limiter
+                .try_record_search(Principal::self_authenticating(index.to_le_bytes()), now);
+        }
+        rate_limiter.try_record_search(
+            get_mock_user_alice_principal_id(),
+            now + USER_NAME_SEARCH_RATE_LIMIT_WINDOW,
+        );
+
+        assert_eq!(rate_limiter.caller_to_search_window.len(), 1);
+    }
+}
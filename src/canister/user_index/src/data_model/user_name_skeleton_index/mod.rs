@@ -0,0 +1,150 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::SystemTime,
+};
+
+use candid::Principal;
+use shared_utils::common::utils::user_name::get_user_name_skeleton;
+
+use super::CanisterData;
+
+/// The usernames in use grouped by their skeleton, so that a look-alike of a taken username,
+/// e.g. `adm1n` for `admin`, counts as taken too. It is not persisted but rebuilt from the
+/// usernames after an upgrade.
+#[derive(Default)]
+pub struct UserNameSkeletonIndex {
+    skeleton_to_user_names: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl UserNameSkeletonIndex {
+    pub fn insert(&mut self, user_name: &str) {
+        self.skeleton_to_user_names
+            .entry(get_user_name_skeleton(user_name))
+            .or_default()
+            .insert(user_name.to_string());
+    }
+
+    pub fn remove(&mut self, user_name: &str) {
+        let skeleton = get_user_name_skeleton(user_name);
+        let Some(user_names) = self.skeleton_to_user_names.get_mut(&skeleton) else {
+            return;
+        };
+
+        user_names.remove(user_name);
+        if user_names.is_empty() {
+            self.skeleton_to_user_names.remove(&skeleton);
+        }
+    }
+
+    /// The usernames in use that read the same as `user_name`, including `user_name` itself
+    pub fn get_look_alikes(&self, user_name: &str) -> impl Iterator<Item = &String> {
+        self.skeleton_to_user_names
+            .get(&get_user_name_skeleton(user_name))
+            .into_iter()
+            .flatten()
+    }
+}
+
+impl CanisterData {
+    /// Gives the username to the user, keeping the skeleton index in step
+    pub fn insert_unique_user_name(&mut self, user_name: String, user_principal_id: Principal) {
+        self.user_name_skeleton_index.insert(&user_name);
+        self.unique_user_name_to_user_principal_id_map
+            .insert(user_name, user_principal_id);
+    }
+
+    /// Frees the username, keeping the skeleton index in step
+    pub fn remove_unique_user_name(&mut self, user_name: &str) {
+        self.user_name_skeleton_index.remove(user_name);
+        self.unique_user_name_to_user_principal_id_map
+            .remove(user_name);
+    }
+
+    pub fn rebuild_user_name_skeleton_index(&mut self) {
+        let mut user_name_skeleton_index = UserNameSkeletonIndex::default();
+        self.unique_user_name_to_user_principal_id_map
+            .keys()
+            .for_each(|user_name| user_name_skeleton_index.insert(user_name));
+        self.user_name_skeleton_index = user_name_skeleton_index;
+    }
+
+    /// The users that have, or have reserved, a username that reads the same as `user_name`
+    pub fn get_look_alike_user_name_owners(
+        &self,
+        user_name: &str,
+        current_time: SystemTime,
+    ) -> BTreeSet<Principal> {
+        let skeleton = get_user_name_skeleton(user_name);
+
+        // * only recently replaced usernames are reserved, so there are few of them
+        let reservation_owners = self
+            .user_name_history
+            .reserved_user_names
+            .iter()
+            .filter(|(reserved_user_name, reservation)| {
+                reservation.reserved_until > current_time
+                    && get_user_name_skeleton(reserved_user_name) == skeleton
+            })
+            .map(|(_, reservation)| reservation.user_principal_id);
+
+        self.user_name_skeleton_index
+            .get_look_alikes(user_name)
+            .filter_map(|look_alike| {
+                self.unique_user_name_to_user_principal_id_map
+                    .get(look_alike)
+                    .copied()
+            })
+            .chain(reservation_owners)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_look_alike_user_name_owners() {
+        let mut canister_data = CanisterData::default();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+        let now = SystemTime::now();
+
+        canister_data.insert_unique_user_name("cool_alice".to_string(), alice);
+        assert_eq!(
+            canister_data.get_look_alike_user_name_owners("c00l.al1ce", now),
+            BTreeSet::from([alice])
+        );
+
+        // * a replaced username stays taken while it is reserved
+        canister_data.user_name_history.record_change(
+            bob,
+            Some("bob".to_string()),
+            "robert".to_string(),
+            now,
+        );
+        assert_eq!(
+            canister_data.get_look_alike_user_name_owners("b0b", now),
+            BTreeSet::from([bob])
+        );
+
+        canister_data.remove_unique_user_name("cool_alice");
+        assert!(canister_data
+            .get_look_alike_user_name_owners("c00l.al1ce", now)
+            .is_empty());
+
+        // * the index is not persisted
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("cool_alice".to_string(), alice);
+        canister_data.rebuild_user_name_skeleton_index();
+        assert_eq!(
+            canister_data.get_look_alike_user_name_owners("cool_al1ce", now),
+            BTreeSet::from([alice])
+        );
+    }
+}
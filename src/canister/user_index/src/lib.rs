@@ -1,12 +1,29 @@
 use std::cell::RefCell;
 
 use candid::{export_service, Principal};
-use data_model::{canister_upgrade::UpgradeStatus, CanisterData};
+use data_model::{
+    account_deletion::{AccountDeletion, AccountDeletionAuditLogEntry},
+    canister_settings::{
+        CanisterSettingsDrift, CanisterSettingsSyncStatus, IndividualUserCanisterSettings,
+    },
+    canister_upgrade::{UpgradeRolloutPlan, UpgradeStatus, UpgradeWaveProgress},
+    cycle_burn_tracking::CanisterCycleBurnReport,
+    CanisterData,
+};
 use ic_cdk::api::{management_canister::main::{CanisterInstallMode, CanisterStatusResponse}, call::CallResult};
 use shared_utils::{
-    canister_specific::user_index::types::args::UserIndexInitArgs,
-    common::types::known_principal::KnownPrincipalType,
-    types::canister_specific::user_index::error_types::SetUniqueUsernameError,
+    canister_specific::user_index::types::{
+        args::UserIndexInitArgs,
+        individual_user_template_wasm::{
+            IndividualUserTemplateWasmChunk, IndividualUserTemplateWasmDetails,
+            IndividualUserTemplateWasmStoreDetails,
+        },
+        user_name_search::UserNameSearchPage,
+    },
+    common::types::{known_principal::KnownPrincipalType, version_details::VersionDetails},
+    types::canister_specific::user_index::error_types::{
+        SearchUserNamesError, SetUniqueUsernameError,
+    },
 };
 
 mod api;
@@ -0,0 +1,355 @@
+use std::{cell::Cell, time::Duration};
+
+use candid::Principal;
+use ic_cdk::api::call;
+use shared_utils::{
+    canister_specific::individual_user_template::types::hot_or_not::OpenHotOrNotBetCounts,
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{
+            system_time,
+            task::{run_task_concurrently, InProgressGuard},
+        },
+    },
+};
+
+use crate::{
+    data_model::{
+        account_deletion::{AccountDeletion, AccountDeletionStatus, AccountDeletionStep},
+        CanisterData,
+    },
+    util::canister_reclamation::reinstall_individual_user_canister_without_owner,
+    CANISTER_DATA,
+};
+
+const ACCOUNT_DELETION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_CONCURRENT_ACCOUNT_DELETIONS: usize = 10;
+
+thread_local! {
+    static IS_ACCOUNT_DELETION_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enqueue_timer_for_processing_account_deletions() {
+    ic_cdk_timers::set_timer_interval(ACCOUNT_DELETION_INTERVAL, || {
+        ic_cdk::spawn(process_account_deletions())
+    });
+}
+
+/// Starts the deletions whose cooldown is over and runs the steps of every deletion in progress.
+/// A deletion stops at the first step that fails, and picks up from that step on the next run.
+pub async fn process_account_deletions() {
+    let Some(_in_progress_guard) = InProgressGuard::acquire(&IS_ACCOUNT_DELETION_IN_PROGRESS)
+    else {
+        return;
+    };
+
+    let account_deletions = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .account_deletions
+            .start_due_deletions(system_time::get_current_system_time_from_ic())
+    });
+
+    run_task_concurrently(
+        account_deletions
+            .into_iter()
+            .map(run_account_deletion_steps),
+        MAX_CONCURRENT_ACCOUNT_DELETIONS,
+        |_| {},
+        || false,
+    )
+    .await;
+}
+
+async fn run_account_deletion_steps(account_deletion: AccountDeletion) {
+    let AccountDeletionStatus::InProgress { mut next_step } = account_deletion.status else {
+        return;
+    };
+
+    loop {
+        let step_result = run_account_deletion_step(&account_deletion, next_step).await;
+
+        let step_after = CANISTER_DATA.with(|canister_data_ref_cell| {
+            let account_deletions = &mut canister_data_ref_cell.borrow_mut().account_deletions;
+            let current_time = system_time::get_current_system_time_from_ic();
+
+            match step_result {
+                Ok(details) => account_deletions.record_step_completed(
+                    &account_deletion.user_principal_id,
+                    next_step,
+                    details,
+                    current_time,
+                ),
+                Err(e) => {
+                    account_deletions.record_step_failed(
+                        &account_deletion.user_principal_id,
+                        next_step,
+                        e,
+                        current_time,
+                    );
+                    None
+                }
+            }
+        });
+
+        match step_after {
+            Some(step_after) => next_step = step_after,
+            None => return,
+        }
+    }
+}
+
+/// Runs a step, which can safely be run again if it failed part way. Returns details to record
+/// in the audit log.
+async fn run_account_deletion_step(
+    account_deletion: &AccountDeletion,
+    step: AccountDeletionStep,
+) -> Result<Option<String>, String> {
+    let user_principal_id = account_deletion.user_principal_id;
+    let user_canister_id = account_deletion.user_canister_id;
+
+    match step {
+        AccountDeletionStep::FreezeCanister => {
+            let (result,): (Result<(), String>,) =
+                call::call(user_canister_id, "freeze_for_account_deletion", ())
+                    .await
+                    .map_err(|e| e.1)?;
+            result?;
+
+            Ok(None)
+        }
+        AccountDeletionStep::SettleOpenBets => {
+            check_open_bets_settled(user_canister_id).await?;
+
+            Ok(None)
+        }
+        AccountDeletionStep::RemovePostsFromPostCache => {
+            let post_cache_canister_id =
+                get_known_principal_id(KnownPrincipalType::CanisterIdPostCache)?;
+            let (removed_post_count,): (Result<u64, String>,) = call::call(
+                post_cache_canister_id,
+                "remove_feed_entries_of_deleted_user_canister",
+                (user_canister_id,),
+            )
+            .await
+            .map_err(|e| e.1)?;
+
+            Ok(Some(format!(
+                "Removed {} posts from the feeds",
+                removed_post_count?
+            )))
+        }
+        AccountDeletionStep::RemoveFromFollowLists => {
+            let (result,): (Result<(), String>,) = call::call(
+                user_canister_id,
+                "remove_this_profile_from_follow_lists_of_others",
+                (),
+            )
+            .await
+            .map_err(|e| e.1)?;
+            result?;
+
+            Ok(None)
+        }
+        AccountDeletionStep::ClearUserIndexMappings => {
+            // * free the user with the platform orchestrator first, so that a failure leaves the
+            // * mappings here for the retry
+            let platform_orchestrator_canister_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow()
+                    .configuration
+                    .known_principal_ids
+                    .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+                    .copied()
+            });
+            if let Some(platform_orchestrator_canister_id) = platform_orchestrator_canister_id {
+                let (result,): (Result<(), String>,) = call::call(
+                    platform_orchestrator_canister_id,
+                    "report_deleted_user_of_user_index",
+                    (user_principal_id,),
+                )
+                .await
+                .map_err(|e| e.1)?;
+                result?;
+            }
+
+            let released_user_names = CANISTER_DATA.with(|canister_data_ref_cell| {
+                clear_mappings_of_deleted_user(
+                    &mut canister_data_ref_cell.borrow_mut(),
+                    user_principal_id,
+                )
+            });
+
+            Ok(Some(format!(
+                "Released usernames: [{}]",
+                released_user_names.join(", ")
+            )))
+        }
+        AccountDeletionStep::PurgeBackups => {
+            let data_backup_canister_id =
+                get_known_principal_id(KnownPrincipalType::CanisterIdDataBackup)?;
+            let (result,): (Result<(), String>,) = call::call(
+                data_backup_canister_id,
+                "purge_backups_of_deleted_user",
+                (user_principal_id,),
+            )
+            .await
+            .map_err(|e| e.1)?;
+            result?;
+
+            Ok(None)
+        }
+        AccountDeletionStep::RecycleCanister => {
+            // * the steps before this one can take a while, so check again right before the data goes
+            check_open_bets_settled(user_canister_id).await?;
+
+            reinstall_individual_user_canister_without_owner(user_canister_id)
+                .await
+                .map_err(|e| e.1)?;
+
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                canister_data_ref_cell
+                    .borrow_mut()
+                    .available_canisters
+                    .insert(user_canister_id);
+            });
+
+            Ok(None)
+        }
+    }
+}
+
+async fn check_open_bets_settled(user_canister_id: Principal) -> Result<(), String> {
+    let (open_bet_counts,): (OpenHotOrNotBetCounts,) =
+        call::call(user_canister_id, "get_open_hot_or_not_bet_counts", ())
+            .await
+            .map_err(|e| e.1)?;
+
+    if !open_bet_counts.is_settled() {
+        return Err(format!(
+            "Waiting on {} bets awaiting their result and {} posts with open bets",
+            open_bet_counts.bets_awaiting_result_count, open_bet_counts.posts_with_open_bets_count
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_known_principal_id(principal_type: KnownPrincipalType) -> Result<Principal, String> {
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .configuration
+            .known_principal_ids
+            .get(&principal_type)
+            .copied()
+            .ok_or(format!(
+                "{:?} not found in internal records",
+                principal_type
+            ))
+    })
+}
+
+/// Forgets the user's canister and frees their usernames. Returns the usernames freed.
+fn clear_mappings_of_deleted_user(
+    canister_data: &mut CanisterData,
+    user_principal_id: Principal,
+) -> Vec<String> {
+    canister_data
+        .user_principal_id_to_canister_id_map
+        .remove(&user_principal_id);
+    canister_data
+        .canister_reclamation
+        .reclaimed_user_canisters
+        .remove(&user_principal_id);
+
+    let released_user_names: Vec<String> = canister_data
+        .unique_user_name_to_user_principal_id_map
+        .iter()
+        .filter(|(_, owner_principal_id)| **owner_principal_id == user_principal_id)
+        .map(|(user_name, _)| user_name.clone())
+        .collect();
+    released_user_names.iter().for_each(|user_name| {
+        canister_data.remove_unique_user_name(user_name);
+    });
+
+    let user_name_history = &mut canister_data.user_name_history;
+    user_name_history
+        .user_principal_id_to_user_name_changes
+        .remove(&user_principal_id);
+    user_name_history
+        .reserved_user_names
+        .retain(|_, reservation| reservation.user_principal_id != user_principal_id);
+
+    released_user_names
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_clear_mappings_of_deleted_user() {
+        let mut canister_data = CanisterData::default();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+        let now = SystemTime::now();
+
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .insert(alice, get_mock_user_alice_canister_id());
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .insert(bob, get_mock_user_bob_canister_id());
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("alice_new".to_string(), alice);
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("bob".to_string(), bob);
+        canister_data
+            .user_name_history
+            .record_change(alice, None, "alice".to_string(), now);
+        canister_data.user_name_history.record_change(
+            alice,
+            Some("alice".to_string()),
+            "alice_new".to_string(),
+            now,
+        );
+
+        assert_eq!(
+            clear_mappings_of_deleted_user(&mut canister_data, alice),
+            vec!["alice_new".to_string()]
+        );
+
+        assert_eq!(
+            canister_data
+                .user_principal_id_to_canister_id_map
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&bob]
+        );
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .values()
+                .collect::<Vec<_>>(),
+            vec![&bob]
+        );
+        assert!(canister_data
+            .user_name_history
+            .user_principal_id_to_user_name_changes
+            .is_empty());
+        assert!(canister_data
+            .user_name_history
+            .get_active_reservation("alice", now)
+            .is_none());
+    }
+}
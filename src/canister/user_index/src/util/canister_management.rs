@@ -1,10 +1,12 @@
+use std::cell::Cell;
+
 use candid::{Principal, CandidType};
 use ic_cdk::api::{
     self,
     call::RejectionCode,
     management_canister::{
         main::{self, CanisterInstallMode, CreateCanisterArgument, WasmModule, InstallCodeArgument, stop_canister, start_canister},
-        provisional::{CanisterSettings, CanisterIdRecord},
+        provisional::CanisterIdRecord,
     },
 };
 use serde::{Serialize, Deserialize};
@@ -13,11 +15,10 @@ use shared_utils::{
     constant::{INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT, CYCLES_THRESHOLD_TO_INITIATE_RECHARGE},
 };
 
-use crate::CANISTER_DATA;
-
-const INDIVIDUAL_USER_TEMPLATE_CANISTER_WASM: &[u8] = include_bytes!(
-    "../../../../../target/wasm32-unknown-unknown/release/individual_user_template.wasm.gz"
-);
+use crate::{
+    data_model::individual_user_template_wasm::IndividualUserTemplateWasm,
+    CANISTER_DATA,
+};
 
 #[derive( CandidType, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 struct CustomInstallCodeArgument {
@@ -35,25 +36,65 @@ struct CustomInstallCodeArgument {
     pub unsafe_drop_stable_memory: Option<bool>,
 }
 
+thread_local! {
+    pub static IS_ROLLBACK_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+    /// Set while all canisters are rolled back, so that canisters created in the meantime get the
+    /// wasm the others are being rolled back to
+    pub static IS_FULL_ROLLBACK_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn is_rollback_in_progress() -> bool {
+    IS_ROLLBACK_IN_PROGRESS.with(|is_in_progress| is_in_progress.get())
+}
+
+/// The wasm new canisters are created with and upgrade runs install. It is uploaded with
+/// `upload_individual_user_template_wasm_chunk`. During a full rollback it is the previous wasm,
+/// which only becomes the current one once the rollback completes.
+pub fn get_current_individual_user_template_wasm() -> Result<IndividualUserTemplateWasm, String> {
+    let is_full_rollback_in_progress =
+        IS_FULL_ROLLBACK_IN_PROGRESS.with(|is_in_progress| is_in_progress.get());
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let individual_user_template_wasm_store =
+            &mut canister_data_ref_cell.borrow_mut().individual_user_template_wasm_store;
+        if is_full_rollback_in_progress {
+            individual_user_template_wasm_store.get_previous_wasm()
+        } else {
+            individual_user_template_wasm_store.get_current_wasm()
+        }
+    })?
+    .ok_or("No individual user template wasm uploaded".to_string())
+}
+
 pub async fn create_users_canister(profile_owner: Principal) -> Principal {
-    // * config for provisioning canister
+    provision_individual_user_canister(Some(profile_owner)).await.unwrap()
+}
+
+/// Creates and installs an individual user canister. Canisters created without an owner go
+/// into the pool of `available_canisters` and get their owner once handed out.
+pub async fn provision_individual_user_canister(profile_owner: Option<Principal>) -> Result<Principal, String> {
+    let wasm = get_current_individual_user_template_wasm()?;
+
+    // * config for provisioning canister. Controlled by this user_index canister and the
+    // * configured co-controllers
+    let canister_settings = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let configuration = &canister_data_ref_cell.borrow().configuration;
+        let controllers = configuration
+            .individual_user_canister_settings
+            .get_controllers(api::id(), &configuration.known_principal_ids)?;
+
+        Ok::<_, String>(configuration.individual_user_canister_settings.to_canister_settings(controllers))
+    })?;
+
     let arg = CreateCanisterArgument {
-        settings: Some(CanisterSettings {
-            controllers: Some(vec![
-                // * this user_index canister
-                api::id(),
-            ]),
-            compute_allocation: None,
-            memory_allocation: None,
-            freezing_threshold: None,
-        }),
+        settings: Some(canister_settings),
     };
 
     // * provisioned canister
     let canister_id: Principal =
         main::create_canister(arg, INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT)
             .await
-            .unwrap()
+            .map_err(|e| e.1)?
             .0
             .canister_id;
 
@@ -61,12 +102,12 @@ pub async fn create_users_canister(profile_owner: Principal) -> Principal {
         .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().configuration.clone());
 
     let individual_user_tempalate_init_args = IndividualUserTemplateInitArgs {
-        profile_owner: Some(profile_owner),
+        profile_owner,
         known_principal_ids: Some(CANISTER_DATA.with(|canister_data_ref_cell| {
             canister_data_ref_cell.borrow().configuration.known_principal_ids.clone()
         })),
         upgrade_version_number: Some(0),
-        version: CANISTER_DATA.with(|canister_data_ref_cell| canister_data_ref_cell.borrow().last_run_upgrade_status.version.clone()),
+        version: wasm.version,
         url_to_send_canister_metrics_to: Some(configuration.url_to_send_canister_metrics_to),
     };
 
@@ -78,19 +119,43 @@ pub async fn create_users_canister(profile_owner: Principal) -> Principal {
     main::install_code(InstallCodeArgument {
         mode: CanisterInstallMode::Install,
         canister_id,
-        wasm_module: INDIVIDUAL_USER_TEMPLATE_CANISTER_WASM.into(),
+        wasm_module: wasm.wasm_module,
         arg,
     })
     .await
-    .unwrap();
+    .map_err(|e| e.1)?;
+
+    Ok(canister_id)
+}
+
+pub async fn set_owner_of_individual_user_canister(canister_id: Principal, profile_owner: Principal) -> Result<(), String> {
+    let (response,): (Result<(), String>,) = ic_cdk::call(canister_id, "update_profile_owner", (profile_owner,))
+        .await
+        .map_err(|e| e.1)?;
 
-    canister_id
+    response
 }
 
+/// Installs the current wasm. The version in `arg` is replaced with the version of the wasm.
 pub async fn upgrade_individual_user_canister(
     canister_id: Principal,
     install_mode: CanisterInstallMode,
     arg: IndividualUserTemplateInitArgs,
+) -> Result<(), (RejectionCode, String)> {
+    let wasm = get_current_individual_user_template_wasm().map_err(|e| (RejectionCode::CanisterError, e))?;
+    let arg = IndividualUserTemplateInitArgs {
+        version: wasm.version,
+        ..arg
+    };
+
+    install_individual_user_canister_wasm(canister_id, install_mode, arg, wasm.wasm_module).await
+}
+
+pub async fn install_individual_user_canister_wasm(
+    canister_id: Principal,
+    install_mode: CanisterInstallMode,
+    arg: IndividualUserTemplateInitArgs,
+    wasm_module: WasmModule,
 ) -> Result<(), (RejectionCode, String)> {
     stop_canister(CanisterIdRecord {canister_id: canister_id.clone()}).await?;
     let serialized_arg =
@@ -99,7 +164,7 @@ pub async fn upgrade_individual_user_canister(
         main::install_code(InstallCodeArgument {
             mode: install_mode,
             canister_id,
-            wasm_module: INDIVIDUAL_USER_TEMPLATE_CANISTER_WASM.into(),
+            wasm_module,
             arg: serialized_arg,
         })
         .await?;
@@ -144,4 +209,36 @@ pub async fn recharge_canister(canister_id: &Principal) -> Result<(), String> {
     )
     .await
     .map_err(|e| e.1)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use shared_utils::common::utils::task::InProgressGuard;
+
+    use super::*;
+
+    fn get_wasm(version: &str, hash: u8) -> IndividualUserTemplateWasm {
+        IndividualUserTemplateWasm {
+            version: version.to_string(),
+            wasm_hash: vec![hash; 32],
+            wasm_module: vec![hash; 8],
+        }
+    }
+
+    #[test]
+    fn test_new_canisters_get_the_previous_wasm_during_a_full_rollback() {
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            let individual_user_template_wasm_store =
+                &mut canister_data_ref_cell.borrow_mut().individual_user_template_wasm_store;
+            individual_user_template_wasm_store.store_new_wasm(get_wasm("v1.0.0", 1)).unwrap();
+            individual_user_template_wasm_store.store_new_wasm(get_wasm("v1.1.0", 2)).unwrap();
+        });
+
+        assert_eq!(get_current_individual_user_template_wasm(), Ok(get_wasm("v1.1.0", 2)));
+
+        let full_rollback_in_progress_guard = InProgressGuard::acquire(&IS_FULL_ROLLBACK_IN_PROGRESS);
+        assert_eq!(get_current_individual_user_template_wasm(), Ok(get_wasm("v1.0.0", 1)));
+
+        drop(full_rollback_in_progress_guard);
+        assert_eq!(get_current_individual_user_template_wasm(), Ok(get_wasm("v1.1.0", 2)));
+    }
+}
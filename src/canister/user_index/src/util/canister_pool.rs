@@ -0,0 +1,200 @@
+use std::{cell::Cell, time::Duration};
+
+use candid::Principal;
+use shared_utils::common::utils::task::{run_task_concurrently, InProgressGuard};
+
+use crate::{
+    data_model::CanisterData,
+    util::canister_management::{
+        self, create_users_canister, provision_individual_user_canister,
+        set_owner_of_individual_user_canister,
+    },
+    CANISTER_DATA,
+};
+
+const CANISTER_POOL_TOP_UP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const MAX_CANISTERS_PROVISIONED_PER_TOP_UP: u64 = 50;
+const MAX_CONCURRENT_CANISTER_PROVISIONS: usize = 10;
+
+thread_local! {
+    static IS_TOP_UP_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enqueue_timer_for_topping_up_canister_pool() {
+    ic_cdk_timers::set_timer_interval(CANISTER_POOL_TOP_UP_INTERVAL, || {
+        ic_cdk::spawn(top_up_canister_pool())
+    });
+}
+
+/// Provisions canisters without an owner until the pool reaches its configured target size.
+/// Overlapping runs are skipped so a slow run doesn't overshoot the target, and no run happens
+/// while a rollback is in progress, as the rollback only covers the canisters pooled when it
+/// started.
+pub async fn top_up_canister_pool() {
+    if canister_management::is_rollback_in_progress() {
+        return;
+    }
+
+    let Some(_top_up_in_progress_guard) = InProgressGuard::acquire(&IS_TOP_UP_IN_PROGRESS) else {
+        return;
+    };
+
+    let number_of_canisters_to_provision = CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_number_of_canisters_to_provision(&canister_data_ref_cell.borrow())
+    });
+
+    let provision_futures =
+        (0..number_of_canisters_to_provision).map(|_| provision_individual_user_canister(None));
+
+    let result_callback = |provision_result: Result<Principal, String>| match provision_result {
+        Ok(canister_id) => CANISTER_DATA.with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow_mut()
+                .available_canisters
+                .insert(canister_id);
+        }),
+        Err(e) => ic_cdk::print(format!("Failed to provision pool canister: {}", e)),
+    };
+
+    run_task_concurrently(
+        provision_futures,
+        MAX_CONCURRENT_CANISTER_PROVISIONS,
+        result_callback,
+        canister_management::is_rollback_in_progress,
+    )
+    .await;
+}
+
+fn get_number_of_canisters_to_provision(canister_data: &CanisterData) -> u64 {
+    canister_data
+        .configuration
+        .canister_pool_target_size
+        .saturating_sub(canister_data.available_canisters.len() as u64)
+        .min(MAX_CANISTERS_PROVISIONED_PER_TOP_UP)
+}
+
+fn take_canister_from_pool(canister_data: &mut CanisterData) -> Option<Principal> {
+    let canister_id = *canister_data.available_canisters.iter().next()?;
+    canister_data.available_canisters.remove(&canister_id);
+
+    Some(canister_id)
+}
+
+/// The error an individual user canister returns when its owner was set before
+const PROFILE_OWNER_ALREADY_SET_ERROR: &str = "Profile owner is already set";
+
+/// Hands out a canister from the pool and sets its owner. Falls back to creating one when the
+/// pool is empty or setting the owner fails. A canister whose owner could not be set goes back
+/// into the pool, unless it reports that it already has an owner.
+pub async fn get_canister_from_pool_or_create(profile_owner: Principal) -> Principal {
+    let pooled_canister_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+        take_canister_from_pool(&mut canister_data_ref_cell.borrow_mut())
+    });
+
+    if let Some(canister_id) = pooled_canister_id {
+        match set_owner_of_individual_user_canister(canister_id, profile_owner).await {
+            Ok(()) => return canister_id,
+            Err(e) => {
+                ic_cdk::print(format!(
+                    "Failed to set owner of pool canister {}: {}",
+                    canister_id.to_text(),
+                    e
+                ));
+                CANISTER_DATA.with(|canister_data_ref_cell| {
+                    return_canister_to_pool_after_failed_handout(
+                        &mut canister_data_ref_cell.borrow_mut(),
+                        canister_id,
+                        &e,
+                    )
+                });
+            }
+        }
+    }
+
+    create_users_canister(profile_owner).await
+}
+
+fn return_canister_to_pool_after_failed_handout(
+    canister_data: &mut CanisterData,
+    canister_id: Principal,
+    set_owner_error: &str,
+) {
+    if set_owner_error == PROFILE_OWNER_ALREADY_SET_ERROR {
+        return;
+    }
+
+    canister_data.available_canisters.insert(canister_id);
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_number_of_canisters_to_provision() {
+        let mut canister_data = CanisterData::default();
+        assert_eq!(get_number_of_canisters_to_provision(&canister_data), 0);
+
+        canister_data.configuration.canister_pool_target_size = 5;
+        canister_data
+            .available_canisters
+            .insert(get_mock_user_alice_canister_id());
+        assert_eq!(get_number_of_canisters_to_provision(&canister_data), 4);
+
+        canister_data.configuration.canister_pool_target_size = 1_000;
+        assert_eq!(
+            get_number_of_canisters_to_provision(&canister_data),
+            MAX_CANISTERS_PROVISIONED_PER_TOP_UP
+        );
+
+        canister_data.configuration.canister_pool_target_size = 0;
+        assert_eq!(get_number_of_canisters_to_provision(&canister_data), 0);
+    }
+
+    #[test]
+    fn test_take_canister_from_pool() {
+        let mut canister_data = CanisterData::default();
+        assert_eq!(take_canister_from_pool(&mut canister_data), None);
+
+        canister_data
+            .available_canisters
+            .insert(get_mock_user_alice_canister_id());
+        canister_data
+            .available_canisters
+            .insert(get_mock_user_bob_canister_id());
+
+        let first = take_canister_from_pool(&mut canister_data).unwrap();
+        let second = take_canister_from_pool(&mut canister_data).unwrap();
+        assert_ne!(first, second);
+        assert!(canister_data.available_canisters.is_empty());
+        assert_eq!(take_canister_from_pool(&mut canister_data), None);
+    }
+
+    #[test]
+    fn test_return_canister_to_pool_after_failed_handout() {
+        let mut canister_data = CanisterData::default();
+
+        return_canister_to_pool_after_failed_handout(
+            &mut canister_data,
+            get_mock_user_alice_canister_id(),
+            "Canister is out of cycles",
+        );
+        return_canister_to_pool_after_failed_handout(
+            &mut canister_data,
+            get_mock_user_bob_canister_id(),
+            PROFILE_OWNER_ALREADY_SET_ERROR,
+        );
+
+        assert_eq!(
+            canister_data
+                .available_canisters
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![get_mock_user_alice_canister_id()]
+        );
+    }
+}
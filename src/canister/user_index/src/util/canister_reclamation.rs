@@ -0,0 +1,566 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    time::{Duration, SystemTime},
+};
+
+use candid::Principal;
+use ic_cdk::api::{
+    call::{self, RejectionCode},
+    management_canister::main::CanisterInstallMode,
+};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        activity::CanisterActivitySummary, arg::IndividualUserTemplateInitArgs,
+    },
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{
+            system_time,
+            task::{run_task_concurrently, InProgressGuard},
+        },
+    },
+};
+
+use crate::{
+    data_model::{
+        canister_reclamation::{is_canister_eligible_for_reclamation, ReclaimedCanister},
+        CanisterData,
+    },
+    util::{
+        canister_management, canister_pool::get_canister_from_pool_or_create, pre_upgrade_backup,
+    },
+    CANISTER_DATA,
+};
+
+const CANISTER_RECLAMATION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const MAX_CONCURRENT_CANISTER_RECLAMATIONS: usize = 10;
+
+thread_local! {
+    static IS_RECLAMATION_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+    static USERS_WITH_RESTORE_IN_PROGRESS: RefCell<BTreeSet<Principal>> =
+        const { RefCell::new(BTreeSet::new()) };
+}
+
+pub fn enqueue_timer_for_reclaiming_inactive_canisters() {
+    ic_cdk_timers::set_timer_interval(CANISTER_RECLAMATION_INTERVAL, || {
+        ic_cdk::spawn(reclaim_inactive_canisters())
+    });
+}
+
+/// Backs up and reinstalls the canisters of users that have been inactive for longer than the
+/// configured period, and returns them to the pool of `available_canisters`. The user gets a
+/// canister with their data restored on their next login.
+pub async fn reclaim_inactive_canisters() {
+    let Some(inactivity_period) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .configuration
+            .inactivity_period_before_canister_reclamation_in_seconds
+            .map(Duration::from_secs)
+    }) else {
+        return;
+    };
+
+    let Some(_guard) = InProgressGuard::acquire(&IS_RECLAMATION_IN_PROGRESS) else {
+        return;
+    };
+
+    let user_principal_id_to_canister_id_map = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .user_principal_id_to_canister_id_map
+            .clone()
+    });
+
+    let reclamation_futures = user_principal_id_to_canister_id_map.into_iter().map(
+        |(user_principal_id, user_canister_id)| {
+            reclaim_canister_if_inactive(user_principal_id, user_canister_id, inactivity_period)
+        },
+    );
+
+    let mut reclaimed_count = 0;
+    let result_callback =
+        |reclamation_result: Result<bool, (Principal, String)>| match reclamation_result {
+            Ok(true) => reclaimed_count += 1,
+            Ok(false) => {}
+            Err((canister_id, e)) => ic_cdk::print(format!(
+                "Failed to reclaim canister {}: {}",
+                canister_id.to_text(),
+                e
+            )),
+        };
+
+    run_task_concurrently(
+        reclamation_futures,
+        MAX_CONCURRENT_CANISTER_RECLAMATIONS,
+        result_callback,
+        || false,
+    )
+    .await;
+
+    ic_cdk::print(format!("Reclaimed {} inactive canisters", reclaimed_count));
+}
+
+async fn reclaim_canister_if_inactive(
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    inactivity_period: Duration,
+) -> Result<bool, (Principal, String)> {
+    if !is_canister_inactive(user_canister_id, inactivity_period).await? {
+        return Ok(false);
+    }
+
+    // * nothing the user does after the final backup would survive the reinstall
+    set_canister_frozen_for_reclamation(user_canister_id, true).await?;
+
+    let reclamation_result =
+        back_up_and_reclaim_frozen_canister(user_principal_id, user_canister_id, inactivity_period)
+            .await;
+
+    if !matches!(reclamation_result, Ok(true)) {
+        if let Err((_, e)) = set_canister_frozen_for_reclamation(user_canister_id, false).await {
+            ic_cdk::print(format!(
+                "Failed to unfreeze canister {} after an aborted reclamation: {}",
+                user_canister_id.to_text(),
+                e
+            ));
+        }
+    }
+
+    reclamation_result
+}
+
+async fn is_canister_inactive(
+    user_canister_id: Principal,
+    inactivity_period: Duration,
+) -> Result<bool, (Principal, String)> {
+    let (activity_summary,): (CanisterActivitySummary,) =
+        call::call(user_canister_id, "get_canister_activity_summary", ())
+            .await
+            .map_err(|e| (user_canister_id, e.1))?;
+
+    Ok(is_canister_eligible_for_reclamation(
+        &activity_summary,
+        inactivity_period,
+        system_time::get_current_system_time_from_ic(),
+    ))
+}
+
+async fn set_canister_frozen_for_reclamation(
+    user_canister_id: Principal,
+    is_frozen: bool,
+) -> Result<(), (Principal, String)> {
+    let (freeze_result,): (Result<(), String>,) =
+        call::call(user_canister_id, "set_frozen_for_reclamation", (is_frozen,))
+            .await
+            .map_err(|e| (user_canister_id, e.1))?;
+
+    freeze_result.map_err(|e| (user_canister_id, e))
+}
+
+/// Takes the final backup of a frozen canister, then wipes it and returns it to the pool
+async fn back_up_and_reclaim_frozen_canister(
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    inactivity_period: Duration,
+) -> Result<bool, (Principal, String)> {
+    // * the user may have come back between the first check and the freeze
+    if !is_canister_inactive(user_canister_id, inactivity_period).await? {
+        return Ok(false);
+    }
+
+    let data_backup_canister_id = CANISTER_DATA
+        .with(|canister_data_ref_cell| {
+            canister_data_ref_cell
+                .borrow()
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdDataBackup)
+                .cloned()
+        })
+        .ok_or((
+            user_canister_id,
+            "Data backup canister not found in internal records".to_string(),
+        ))?;
+
+    // * the canister is wiped below, so it is only reclaimed once its backup is known to be complete
+    let verified_backup_key_counts = pre_upgrade_backup::back_up_and_verify_user_canister(
+        user_principal_id,
+        user_canister_id,
+        data_backup_canister_id,
+    )
+    .await
+    .map_err(|e| (user_canister_id, format!("Backup failed: {}", e)))?;
+    if verified_backup_key_counts.is_none() {
+        return Err((
+            user_canister_id,
+            "Backup could not be verified, the canister does not report its key counts".to_string(),
+        ));
+    }
+
+    let was_marked = CANISTER_DATA.with(|canister_data_ref_cell| {
+        mark_canister_as_reclaimed(
+            &mut canister_data_ref_cell.borrow_mut(),
+            user_principal_id,
+            user_canister_id,
+            system_time::get_current_system_time_from_ic(),
+        )
+    });
+    if !was_marked {
+        return Ok(false);
+    }
+
+    let reinstall_result = reinstall_individual_user_canister_without_owner(user_canister_id).await;
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        match reinstall_result {
+            Ok(()) => {
+                canister_data.available_canisters.insert(user_canister_id);
+                Ok(true)
+            }
+            Err(e) => {
+                unmark_canister_as_reclaimed(
+                    &mut canister_data,
+                    user_principal_id,
+                    user_canister_id,
+                );
+                Err((user_canister_id, e.1))
+            }
+        }
+    })
+}
+
+/// Wipes a canister and leaves it without an owner, so that it can go back into the pool of
+/// `available_canisters`
+pub async fn reinstall_individual_user_canister_without_owner(
+    user_canister_id: Principal,
+) -> Result<(), (RejectionCode, String)> {
+    let (known_principal_ids, url_to_send_canister_metrics_to, upgrade_status) = CANISTER_DATA
+        .with(|canister_data_ref_cell| {
+            let canister_data = canister_data_ref_cell.borrow();
+            (
+                canister_data.configuration.known_principal_ids.clone(),
+                canister_data
+                    .configuration
+                    .url_to_send_canister_metrics_to
+                    .clone(),
+                canister_data.last_run_upgrade_status.clone(),
+            )
+        });
+
+    canister_management::upgrade_individual_user_canister(
+        user_canister_id,
+        CanisterInstallMode::Reinstall,
+        IndividualUserTemplateInitArgs {
+            known_principal_ids: Some(known_principal_ids),
+            profile_owner: None,
+            upgrade_version_number: Some(upgrade_status.version_number),
+            url_to_send_canister_metrics_to: Some(url_to_send_canister_metrics_to),
+            version: upgrade_status.version,
+        },
+    )
+    .await
+}
+
+/// Unlinks the canister from its user. Returns false if the user got linked to a different
+/// canister in the meantime, or is having their account deleted.
+fn mark_canister_as_reclaimed(
+    canister_data: &mut CanisterData,
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    current_time: SystemTime,
+) -> bool {
+    if canister_data
+        .user_principal_id_to_canister_id_map
+        .get(&user_principal_id)
+        != Some(&user_canister_id)
+    {
+        return false;
+    }
+
+    // * the deletion works on the canister the user has when it was requested
+    if canister_data
+        .account_deletions
+        .is_pending(&user_principal_id)
+    {
+        return false;
+    }
+
+    canister_data
+        .user_principal_id_to_canister_id_map
+        .remove(&user_principal_id);
+    canister_data
+        .canister_reclamation
+        .reclaimed_user_canisters
+        .insert(
+            user_principal_id,
+            ReclaimedCanister {
+                canister_id: user_canister_id,
+                reclaimed_at: current_time,
+                reprovisioned_canister_id: None,
+            },
+        );
+
+    true
+}
+
+fn unmark_canister_as_reclaimed(
+    canister_data: &mut CanisterData,
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+) {
+    canister_data
+        .canister_reclamation
+        .reclaimed_user_canisters
+        .remove(&user_principal_id);
+    canister_data
+        .user_principal_id_to_canister_id_map
+        .entry(user_principal_id)
+        .or_insert(user_canister_id);
+}
+
+/// Hands a returning user whose canister was reclaimed a canister, and asks the data backup
+/// canister to restore their data to it. The user is only linked to the canister once the restore
+/// succeeded. Until then they stay marked as reclaimed with the canister recorded, so that the
+/// restore is retried on the same canister on their next login. Only one restore runs per user at
+/// a time, so that concurrent logins do not each take a canister from the pool.
+pub async fn restore_data_of_reclaimed_user(
+    user_principal_id: Principal,
+) -> Result<Principal, String> {
+    let Some(_guard) = UserRestoreGuard::acquire(user_principal_id) else {
+        return Err("A restore of this user's data is already in progress".to_string());
+    };
+
+    let (reprovisioned_canister_id, data_backup_canister_id) =
+        CANISTER_DATA.with(|canister_data_ref_cell| {
+            let canister_data = canister_data_ref_cell.borrow();
+            (
+                canister_data
+                    .canister_reclamation
+                    .reclaimed_user_canisters
+                    .get(&user_principal_id)
+                    .and_then(|reclaimed_canister| reclaimed_canister.reprovisioned_canister_id),
+                canister_data
+                    .configuration
+                    .known_principal_ids
+                    .get(&KnownPrincipalType::CanisterIdDataBackup)
+                    .cloned(),
+            )
+        });
+
+    let data_backup_canister_id =
+        data_backup_canister_id.ok_or("Data backup canister not found in internal records")?;
+
+    let new_user_canister_id = match reprovisioned_canister_id {
+        Some(reprovisioned_canister_id) => reprovisioned_canister_id,
+        None => {
+            let new_user_canister_id = get_canister_from_pool_or_create(user_principal_id).await;
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                record_reprovisioned_canister(
+                    &mut canister_data_ref_cell.borrow_mut(),
+                    user_principal_id,
+                    new_user_canister_id,
+                )
+            });
+            new_user_canister_id
+        }
+    };
+
+    let (restore_result,): (Result<(), String>,) = call::call(
+        data_backup_canister_id,
+        "restore_backed_up_data_to_reprovisioned_individual_user_canister",
+        (user_principal_id, new_user_canister_id),
+    )
+    .await
+    .map_err(|e| e.1)?;
+    restore_result?;
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        link_restored_user_to_canister(
+            &mut canister_data_ref_cell.borrow_mut(),
+            user_principal_id,
+            new_user_canister_id,
+        )
+    });
+
+    Ok(new_user_canister_id)
+}
+
+/// Marks a restore as running for a user, until dropped
+struct UserRestoreGuard {
+    user_principal_id: Principal,
+}
+
+impl UserRestoreGuard {
+    fn acquire(user_principal_id: Principal) -> Option<Self> {
+        USERS_WITH_RESTORE_IN_PROGRESS
+            .with(|users_ref_cell| users_ref_cell.borrow_mut().insert(user_principal_id))
+            .then_some(Self { user_principal_id })
+    }
+}
+
+impl Drop for UserRestoreGuard {
+    fn drop(&mut self) {
+        USERS_WITH_RESTORE_IN_PROGRESS
+            .with(|users_ref_cell| users_ref_cell.borrow_mut().remove(&self.user_principal_id));
+    }
+}
+
+fn record_reprovisioned_canister(
+    canister_data: &mut CanisterData,
+    user_principal_id: Principal,
+    new_user_canister_id: Principal,
+) {
+    if let Some(reclaimed_canister) = canister_data
+        .canister_reclamation
+        .reclaimed_user_canisters
+        .get_mut(&user_principal_id)
+    {
+        reclaimed_canister.reprovisioned_canister_id = Some(new_user_canister_id);
+    }
+}
+
+fn link_restored_user_to_canister(
+    canister_data: &mut CanisterData,
+    user_principal_id: Principal,
+    new_user_canister_id: Principal,
+) {
+    canister_data
+        .canister_reclamation
+        .reclaimed_user_canisters
+        .remove(&user_principal_id);
+    canister_data
+        .user_principal_id_to_canister_id_map
+        .insert(user_principal_id, new_user_canister_id);
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_mark_and_unmark_canister_as_reclaimed() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+
+        // * the user was moved to another canister in the meantime
+        assert!(!mark_canister_as_reclaimed(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_canister_id(),
+            now,
+        ));
+
+        assert!(mark_canister_as_reclaimed(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+            now,
+        ));
+        assert!(canister_data
+            .user_principal_id_to_canister_id_map
+            .is_empty());
+        assert_eq!(
+            canister_data
+                .canister_reclamation
+                .reclaimed_user_canisters
+                .get(&get_mock_user_alice_principal_id()),
+            Some(&ReclaimedCanister {
+                canister_id: get_mock_user_alice_canister_id(),
+                reclaimed_at: now,
+                reprovisioned_canister_id: None,
+            })
+        );
+
+        unmark_canister_as_reclaimed(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        assert!(canister_data
+            .canister_reclamation
+            .reclaimed_user_canisters
+            .is_empty());
+        assert_eq!(
+            canister_data
+                .user_principal_id_to_canister_id_map
+                .get(&get_mock_user_alice_principal_id()),
+            Some(&get_mock_user_alice_canister_id())
+        );
+    }
+
+    #[test]
+    fn test_user_restore_guard_rejects_concurrent_restores_of_the_same_user() {
+        let guard = UserRestoreGuard::acquire(get_mock_user_alice_principal_id());
+        assert!(guard.is_some());
+        assert!(UserRestoreGuard::acquire(get_mock_user_alice_principal_id()).is_none());
+        assert!(UserRestoreGuard::acquire(get_mock_user_bob_principal_id()).is_some());
+
+        drop(guard);
+        assert!(UserRestoreGuard::acquire(get_mock_user_alice_principal_id()).is_some());
+    }
+
+    #[test]
+    fn test_restored_user_is_linked_to_the_reprovisioned_canister() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        mark_canister_as_reclaimed(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+            now,
+        );
+
+        record_reprovisioned_canister(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        // * not linked until the restore succeeded
+        assert!(canister_data
+            .user_principal_id_to_canister_id_map
+            .is_empty());
+        assert_eq!(
+            canister_data
+                .canister_reclamation
+                .reclaimed_user_canisters
+                .get(&get_mock_user_alice_principal_id())
+                .and_then(|reclaimed_canister| reclaimed_canister.reprovisioned_canister_id),
+            Some(get_mock_user_bob_canister_id())
+        );
+
+        link_restored_user_to_canister(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        assert!(canister_data
+            .canister_reclamation
+            .reclaimed_user_canisters
+            .is_empty());
+        assert_eq!(
+            canister_data
+                .user_principal_id_to_canister_id_map
+                .get(&get_mock_user_alice_principal_id()),
+            Some(&get_mock_user_bob_canister_id())
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::cell::Cell;
+
+use candid::{Nat, Principal};
+use ic_cdk::api::{
+    self,
+    management_canister::{
+        main::{self, CanisterStatusResponse, UpdateSettingsArgument},
+        provisional::CanisterIdRecord,
+    },
+};
+use shared_utils::common::utils::{
+    system_time,
+    task::{run_task_concurrently, InProgressGuard},
+};
+
+use crate::{
+    data_model::canister_settings::{
+        CanisterSettingsDrift, CanisterSettingsSnapshot, CanisterSettingsSyncStatus,
+        IndividualUserCanisterSettings,
+    },
+    CANISTER_DATA,
+};
+
+const MAX_CONCURRENT_CANISTER_SETTINGS_CALLS: usize = 10;
+
+thread_local! {
+    static IS_CANISTER_SETTINGS_SYNC_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn is_canister_settings_sync_in_progress() -> bool {
+    IS_CANISTER_SETTINGS_SYNC_IN_PROGRESS.with(|is_in_progress| is_in_progress.get())
+}
+
+/// Compares the settings and controllers of every individual user canister with the configured
+/// ones and records the canisters that drifted. With `update_drifted_canisters`, drifted canisters
+/// are brought in line with `update_settings` and read back to verify the update.
+pub async fn sync_individual_user_canister_settings(update_drifted_canisters: bool) {
+    let Some(_guard) = InProgressGuard::acquire(&IS_CANISTER_SETTINGS_SYNC_IN_PROGRESS) else {
+        return;
+    };
+
+    let sync_args = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        canister_data.last_run_canister_settings_sync_status = CanisterSettingsSyncStatus {
+            started_at: system_time::get_current_system_time_from_ic(),
+            ..Default::default()
+        };
+        canister_data.canister_settings_sync_drifts.clear();
+        canister_data.canister_settings_sync_failures.clear();
+
+        let canister_settings = canister_data
+            .configuration
+            .individual_user_canister_settings
+            .clone();
+        let controllers = canister_settings
+            .get_controllers(api::id(), &canister_data.configuration.known_principal_ids)?;
+
+        let canister_ids: Vec<Principal> = canister_data
+            .user_principal_id_to_canister_id_map
+            .values()
+            .chain(canister_data.available_canisters.iter())
+            .copied()
+            .collect();
+
+        Ok::<_, String>((canister_ids, canister_settings, controllers))
+    });
+
+    let (canister_ids, canister_settings, controllers) = match sync_args {
+        Ok(sync_args) => sync_args,
+        Err(e) => {
+            ic_cdk::print(format!("Failed to start canister settings sync: {}", e));
+            return;
+        }
+    };
+
+    let sync_futures = canister_ids.into_iter().map(|canister_id| {
+        sync_canister_settings(
+            canister_id,
+            canister_settings.clone(),
+            controllers.clone(),
+            update_drifted_canisters,
+        )
+    });
+
+    let result_callback =
+        |sync_result: Result<Option<CanisterSettingsDrift>, (Principal, String)>| {
+            CANISTER_DATA.with(|canister_data_ref_cell| {
+                let canister_data = &mut *canister_data_ref_cell.borrow_mut();
+                let sync_status = &mut canister_data.last_run_canister_settings_sync_status;
+                sync_status.checked_canister_count += 1;
+
+                match sync_result {
+                    Ok(Some(drift)) => {
+                        sync_status.count_drift(&drift);
+                        canister_data
+                            .canister_settings_sync_drifts
+                            .insert(drift.canister_id, drift);
+                    }
+                    Ok(None) => {}
+                    Err((canister_id, e)) => {
+                        sync_status.failed_canister_count += 1;
+                        canister_data
+                            .canister_settings_sync_failures
+                            .insert(canister_id, e);
+                    }
+                }
+            })
+        };
+
+    run_task_concurrently(
+        sync_futures,
+        MAX_CONCURRENT_CANISTER_SETTINGS_CALLS,
+        result_callback,
+        || false,
+    )
+    .await;
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .last_run_canister_settings_sync_status
+            .finished_at = Some(system_time::get_current_system_time_from_ic());
+    });
+}
+
+async fn sync_canister_settings(
+    canister_id: Principal,
+    canister_settings: IndividualUserCanisterSettings,
+    controllers: Vec<Principal>,
+    update_drifted_canister: bool,
+) -> Result<Option<CanisterSettingsDrift>, (Principal, String)> {
+    let settings_before_sync = get_canister_settings_snapshot(canister_id)
+        .await
+        .map_err(|e| (canister_id, e))?;
+
+    if !canister_settings.is_drifted(&settings_before_sync, &controllers) {
+        return Ok(None);
+    }
+
+    let mut drift = CanisterSettingsDrift {
+        canister_id,
+        settings_before_sync,
+        updated: false,
+        verified: false,
+        error: None,
+    };
+
+    if !update_drifted_canister {
+        return Ok(Some(drift));
+    }
+
+    if let Err(e) = main::update_settings(UpdateSettingsArgument {
+        canister_id,
+        settings: canister_settings.to_canister_settings(controllers.clone()),
+    })
+    .await
+    {
+        drift.error = Some(e.1);
+        return Ok(Some(drift));
+    }
+    drift.updated = true;
+
+    // * read the settings back, so that a canister reporting other settings is not taken as
+    // * compliant
+    match get_canister_settings_snapshot(canister_id).await {
+        Ok(settings_after_sync) => {
+            drift.verified = !canister_settings.is_drifted(&settings_after_sync, &controllers)
+        }
+        Err(e) => drift.error = Some(e),
+    }
+
+    Ok(Some(drift))
+}
+
+async fn get_canister_settings_snapshot(
+    canister_id: Principal,
+) -> Result<CanisterSettingsSnapshot, String> {
+    let (canister_status,): (CanisterStatusResponse,) =
+        main::canister_status(CanisterIdRecord { canister_id })
+            .await
+            .map_err(|e| e.1)?;
+
+    let nat_to_u64 = |nat: &Nat| u64::try_from(&nat.0).unwrap_or(u64::MAX);
+    Ok(CanisterSettingsSnapshot {
+        controllers: canister_status.settings.controllers,
+        freezing_threshold: nat_to_u64(&canister_status.settings.freezing_threshold),
+        memory_allocation: nat_to_u64(&canister_status.settings.memory_allocation),
+        compute_allocation: nat_to_u64(&canister_status.settings.compute_allocation),
+    })
+}
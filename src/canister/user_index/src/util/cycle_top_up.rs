@@ -0,0 +1,374 @@
+use std::{cell::Cell, collections::BTreeSet, rc::Rc, time::Duration};
+
+use candid::{Nat, Principal};
+use ic_cdk::api::management_canister::{
+    main::{self, CanisterStatusResponse},
+    provisional::CanisterIdRecord,
+};
+use shared_utils::{
+    common::utils::{
+        system_time,
+        task::{run_task_concurrently, InProgressGuard},
+    },
+    constant::INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT,
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+pub const DEFAULT_CYCLE_TOP_UP_HORIZON_IN_DAYS: u64 = 7;
+const CYCLE_TOP_UP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_CANISTERS_POLLED_PER_RUN: usize = 500;
+const MAX_CONCURRENT_CANISTER_STATUS_CALLS: usize = 10;
+const MAX_CYCLE_TOP_UP_AMOUNT_PER_CANISTER: u128 = 10 * INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT;
+const MAX_CYCLES_TOPPED_UP_PER_RUN: u128 = 100 * INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT;
+/// Top ups stop once they would take the user index's own balance below this
+const MIN_USER_INDEX_CYCLE_BALANCE_AFTER_TOP_UPS: u128 =
+    50 * INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT;
+
+thread_local! {
+    static IS_CYCLE_TOP_UP_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enqueue_timer_for_topping_up_canister_cycles() {
+    ic_cdk_timers::set_timer_interval(CYCLE_TOP_UP_INTERVAL, || {
+        ic_cdk::spawn(top_up_canisters_predicted_to_freeze())
+    });
+}
+
+/// Polls the status of the next batch of canisters, updates their burn rates and tops up the ones
+/// predicted to fall below their freezing threshold within the configured horizon. Each run picks
+/// up where the previous one stopped, so every canister is polled once per full pass. A canister
+/// is only polled again a full pass later, so the horizon is extended by the duration of a pass. A
+/// run tops up at most `MAX_CYCLES_TOPPED_UP_PER_RUN` cycles, and stops once the user index would
+/// go below `MIN_USER_INDEX_CYCLE_BALANCE_AFTER_TOP_UPS`.
+pub async fn top_up_canisters_predicted_to_freeze() {
+    let Some(_guard) = InProgressGuard::acquire(&IS_CYCLE_TOP_UP_IN_PROGRESS) else {
+        return;
+    };
+
+    let (canister_ids, horizon) = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+        let horizon_in_days = canister_data
+            .configuration
+            .cycle_top_up_horizon_in_days
+            .unwrap_or(DEFAULT_CYCLE_TOP_UP_HORIZON_IN_DAYS);
+
+        let full_pass_duration = get_full_pass_duration(
+            canister_data.user_principal_id_to_canister_id_map.len()
+                + canister_data.available_canisters.len(),
+        );
+
+        (
+            get_next_batch_of_canisters_to_poll(&mut canister_data, MAX_CANISTERS_POLLED_PER_RUN),
+            Duration::from_secs(horizon_in_days * 24 * 60 * 60) + full_pass_duration,
+        )
+    });
+
+    let top_up_budget = Rc::new(TopUpBudget::new(MAX_CYCLES_TOPPED_UP_PER_RUN));
+    let top_up_futures = canister_ids.into_iter().map(|canister_id| {
+        poll_and_top_up_canister_if_needed(canister_id, horizon, top_up_budget.clone())
+    });
+
+    let mut topped_up_canister_count = 0;
+    let result_callback = |top_up_result: Result<bool, (Principal, String)>| match top_up_result {
+        Ok(true) => topped_up_canister_count += 1,
+        Ok(false) => {}
+        Err((canister_id, e)) => ic_cdk::print(format!(
+            "Failed to check cycles of canister {}: {}",
+            canister_id.to_text(),
+            e
+        )),
+    };
+
+    run_task_concurrently(
+        top_up_futures,
+        MAX_CONCURRENT_CANISTER_STATUS_CALLS,
+        result_callback,
+        || top_up_budget.is_exhausted(),
+    )
+    .await;
+
+    ic_cdk::print(format!(
+        "Topped up {} canisters predicted to freeze",
+        topped_up_canister_count
+    ));
+}
+
+/// How long it takes until every canister was polled once
+fn get_full_pass_duration(canister_count: usize) -> Duration {
+    CYCLE_TOP_UP_INTERVAL * canister_count.div_ceil(MAX_CANISTERS_POLLED_PER_RUN) as u32
+}
+
+/// What is left to spend on top ups in a run. Shared by the top ups running concurrently.
+struct TopUpBudget {
+    remaining_cycles: Cell<u128>,
+    is_exhausted: Cell<bool>,
+}
+
+impl TopUpBudget {
+    fn new(cycles: u128) -> Self {
+        Self {
+            remaining_cycles: Cell::new(cycles),
+            is_exhausted: Cell::new(false),
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.is_exhausted.get()
+    }
+
+    /// Takes `amount` out of the budget if both the budget and the user index's own balance
+    /// allow for it. Once either doesn't, the budget is exhausted and no further top ups are made.
+    fn try_spend(&self, amount: u128, user_index_cycle_balance: u128) -> Result<(), String> {
+        if self.is_exhausted.get() {
+            return Err("The cycle top up budget of this run is used up".to_string());
+        }
+
+        if amount > self.remaining_cycles.get() {
+            self.is_exhausted.set(true);
+            return Err("The cycle top up budget of this run is used up".to_string());
+        }
+
+        if user_index_cycle_balance.saturating_sub(amount)
+            < MIN_USER_INDEX_CYCLE_BALANCE_AFTER_TOP_UPS
+        {
+            self.is_exhausted.set(true);
+            return Err(
+                "The user index cycle balance is at its reserve, no more canisters are topped up"
+                    .to_string(),
+            );
+        }
+
+        self.remaining_cycles
+            .set(self.remaining_cycles.get() - amount);
+        Ok(())
+    }
+
+    fn refund(&self, amount: u128) {
+        self.remaining_cycles
+            .set(self.remaining_cycles.get().saturating_add(amount));
+    }
+}
+
+fn nat_to_u128(nat: &Nat, name: &str) -> Result<u128, String> {
+    u128::try_from(&nat.0).map_err(|_| format!("{} of {} does not fit in a u128", name, nat))
+}
+
+async fn poll_and_top_up_canister_if_needed(
+    canister_id: Principal,
+    horizon: Duration,
+    top_up_budget: Rc<TopUpBudget>,
+) -> Result<bool, (Principal, String)> {
+    let (canister_status,): (CanisterStatusResponse,) =
+        main::canister_status(CanisterIdRecord { canister_id })
+            .await
+            .map_err(|e| (canister_id, e.1))?;
+
+    let cycle_balance =
+        nat_to_u128(&canister_status.cycles, "Cycle balance").map_err(|e| (canister_id, e))?;
+    let idle_cycles_burned_per_day = nat_to_u128(
+        &canister_status.idle_cycles_burned_per_day,
+        "Idle cycles burned per day",
+    )
+    .map_err(|e| (canister_id, e))?;
+    let freezing_threshold_in_seconds = nat_to_u128(
+        &canister_status.settings.freezing_threshold,
+        "Freezing threshold",
+    )
+    .map_err(|e| (canister_id, e))?;
+    let freezing_threshold_in_cycles =
+        idle_cycles_burned_per_day.saturating_mul(freezing_threshold_in_seconds) / (24 * 60 * 60);
+
+    let top_up_amount = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_cycle_burn = canister_data_ref_cell
+            .borrow_mut()
+            .cycle_burn_tracking
+            .record_cycle_balance(
+                canister_id,
+                cycle_balance,
+                freezing_threshold_in_cycles,
+                system_time::get_current_system_time_from_ic(),
+            )
+            .clone();
+
+        canister_cycle_burn
+            .is_predicted_to_freeze_within(horizon)
+            .then(|| {
+                canister_cycle_burn.get_top_up_amount(
+                    horizon,
+                    INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT,
+                    MAX_CYCLE_TOP_UP_AMOUNT_PER_CANISTER,
+                )
+            })
+    });
+
+    let Some(top_up_amount) = top_up_amount else {
+        return Ok(false);
+    };
+
+    top_up_budget
+        .try_spend(top_up_amount, ic_cdk::api::canister_balance128())
+        .map_err(|e| (canister_id, e))?;
+
+    if let Err(e) = main::deposit_cycles(CanisterIdRecord { canister_id }, top_up_amount).await {
+        top_up_budget.refund(top_up_amount);
+        return Err((canister_id, e.1));
+    }
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow_mut()
+            .cycle_burn_tracking
+            .record_top_up(canister_id, top_up_amount);
+    });
+
+    Ok(true)
+}
+
+/// The canisters after the one polled last, wrapping around once all canisters were polled
+fn get_next_batch_of_canisters_to_poll(
+    canister_data: &mut CanisterData,
+    batch_size: usize,
+) -> Vec<Principal> {
+    let canister_ids: BTreeSet<Principal> = canister_data
+        .user_principal_id_to_canister_id_map
+        .values()
+        .chain(canister_data.available_canisters.iter())
+        .copied()
+        .collect();
+
+    // * canisters that are no longer indexed stop being tracked
+    canister_data
+        .cycle_burn_tracking
+        .canister_cycle_burns
+        .retain(|canister_id, _| canister_ids.contains(canister_id));
+
+    let batch: Vec<Principal> = match canister_data.cycle_burn_tracking.last_polled_canister_id {
+        Some(last_polled_canister_id) => canister_ids
+            .range((
+                std::ops::Bound::Excluded(last_polled_canister_id),
+                std::ops::Bound::Unbounded,
+            ))
+            .take(batch_size)
+            .copied()
+            .collect(),
+        None => canister_ids.iter().take(batch_size).copied().collect(),
+    };
+
+    canister_data.cycle_burn_tracking.last_polled_canister_id = if batch.len() < batch_size {
+        None
+    } else {
+        batch.last().copied()
+    };
+
+    batch
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_next_batch_of_canisters_to_poll() {
+        let mut canister_data = CanisterData::default();
+        for i in 0..5 {
+            canister_data.user_principal_id_to_canister_id_map.insert(
+                Principal::self_authenticating([i]),
+                Principal::from_slice(&[i]),
+            );
+        }
+        canister_data
+            .available_canisters
+            .insert(Principal::from_slice(&[5]));
+        canister_data
+            .cycle_burn_tracking
+            .canister_cycle_burns
+            .insert(Principal::from_slice(&[9]), Default::default());
+
+        let batch = get_next_batch_of_canisters_to_poll(&mut canister_data, 4);
+        assert_eq!(
+            batch,
+            (0..4)
+                .map(|i| Principal::from_slice(&[i]))
+                .collect::<Vec<_>>()
+        );
+        assert!(canister_data
+            .cycle_burn_tracking
+            .canister_cycle_burns
+            .is_empty());
+
+        let batch = get_next_batch_of_canisters_to_poll(&mut canister_data, 4);
+        assert_eq!(
+            batch,
+            vec![Principal::from_slice(&[4]), Principal::from_slice(&[5])]
+        );
+        assert_eq!(
+            canister_data.cycle_burn_tracking.last_polled_canister_id,
+            None
+        );
+
+        let batch = get_next_batch_of_canisters_to_poll(&mut canister_data, 4);
+        assert_eq!(batch[0], Principal::from_slice(&[0]));
+    }
+
+    #[test]
+    fn test_get_full_pass_duration() {
+        assert_eq!(get_full_pass_duration(0), Duration::ZERO);
+        assert_eq!(get_full_pass_duration(1), CYCLE_TOP_UP_INTERVAL);
+        assert_eq!(
+            get_full_pass_duration(MAX_CANISTERS_POLLED_PER_RUN),
+            CYCLE_TOP_UP_INTERVAL
+        );
+        // * about 8 days at 100k canisters, longer than the default horizon
+        assert_eq!(
+            get_full_pass_duration(100_000),
+            Duration::from_secs(200 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn test_top_up_budget_try_spend() {
+        let top_up_budget = TopUpBudget::new(3 * INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT);
+        let user_index_cycle_balance = MIN_USER_INDEX_CYCLE_BALANCE_AFTER_TOP_UPS * 2;
+
+        assert!(top_up_budget
+            .try_spend(
+                2 * INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT,
+                user_index_cycle_balance
+            )
+            .is_ok());
+        top_up_budget.refund(INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT);
+        assert!(top_up_budget
+            .try_spend(
+                2 * INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT,
+                user_index_cycle_balance
+            )
+            .is_ok());
+        assert!(!top_up_budget.is_exhausted());
+
+        // * over the budget of the run
+        assert!(top_up_budget
+            .try_spend(
+                INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT * 2,
+                user_index_cycle_balance
+            )
+            .is_err());
+        assert!(top_up_budget.is_exhausted());
+
+        // * below the reserve of the user index
+        let top_up_budget = TopUpBudget::new(MAX_CYCLES_TOPPED_UP_PER_RUN);
+        assert!(top_up_budget
+            .try_spend(
+                INDIVIDUAL_USER_CANISTER_RECHARGE_AMOUNT,
+                MIN_USER_INDEX_CYCLE_BALANCE_AFTER_TOP_UPS
+            )
+            .is_err());
+        assert!(top_up_budget.is_exhausted());
+    }
+
+    #[test]
+    fn test_nat_to_u128() {
+        assert_eq!(nat_to_u128(&Nat::from(5_u64), "Cycle balance"), Ok(5));
+        assert!(nat_to_u128(&(Nat::from(u128::MAX) + Nat::from(1_u64)), "Cycle balance").is_err());
+    }
+}
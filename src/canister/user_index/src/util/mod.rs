@@ -1 +1,7 @@
+pub mod account_deletion;
 pub mod canister_management;
+pub mod canister_pool;
+pub mod canister_reclamation;
+pub mod canister_settings_sync;
+pub mod cycle_top_up;
+pub mod pre_upgrade_backup;
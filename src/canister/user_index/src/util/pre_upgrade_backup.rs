@@ -0,0 +1,141 @@
+use candid::Principal;
+use ic_cdk::api::call::{self, CallResult};
+use shared_utils::canister_specific::data_backup::types::backup_key_counts::BackupKeyCounts;
+
+use crate::data_model::canister_upgrade::{
+    POST_UPGRADE_VALIDATION_FAILED_ERROR_PREFIX, PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX,
+};
+
+/// Backs up a user's canister to data_backup and checks that the backup holds as many entries as
+/// the canister. Returns the key counts the upgraded canister is validated against. Canisters that
+/// predate `get_backup_key_counts` cannot be verified and are not upgraded.
+pub async fn back_up_canister_before_upgrade(
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    data_backup_canister_id: Principal,
+) -> Result<BackupKeyCounts, String> {
+    let verified_backup_key_counts = back_up_and_verify_user_canister(
+        user_principal_id,
+        user_canister_id,
+        data_backup_canister_id,
+    )
+    .await
+    .map_err(|e| format!("{}: {}", PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX, e))?;
+
+    verified_backup_key_counts.ok_or(format!(
+        "{}: the canister predates get_backup_key_counts, so its backup cannot be verified",
+        PRE_UPGRADE_BACKUP_FAILED_ERROR_PREFIX
+    ))
+}
+
+/// Backs up a user's canister to data_backup and checks that the backup holds as many entries as
+/// the canister. `None` when the canister predates `get_backup_key_counts`, in which case the
+/// backup could not be verified.
+pub async fn back_up_and_verify_user_canister(
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    data_backup_canister_id: Principal,
+) -> Result<Option<BackupKeyCounts>, String> {
+    call::call::<_, ()>(
+        user_canister_id,
+        "backup_data_to_backup_canister",
+        (user_principal_id, user_canister_id),
+    )
+    .await
+    .map_err(|e| e.1)?;
+
+    let backup_key_counts =
+        get_user_backup_key_counts(data_backup_canister_id, user_principal_id).await?;
+
+    let canister_key_counts = match get_canister_backup_key_counts(user_canister_id).await {
+        Ok(canister_key_counts) => canister_key_counts,
+        Err(e) if is_method_missing_error(&e) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    // * canisters running a wasm from before every canister got a backup have none when they hold
+    // * no display name or profile picture, and have nothing else to lose when they have no other
+    // * entries either
+    let backup_key_counts = backup_key_counts.unwrap_or_default();
+    let mismatches = backup_key_counts.get_mismatches(&canister_key_counts);
+    if !mismatches.is_empty() {
+        return Err(format!(
+            "the backup does not match the canister, {}",
+            mismatches.join(", ")
+        ));
+    }
+
+    Ok(Some(backup_key_counts))
+}
+
+/// Checks that the upgraded canister still holds at least as many entries as its backup. The
+/// canister keeps taking posts, bets and follows while it is upgraded, so it may hold more.
+pub async fn validate_canister_after_upgrade(
+    user_canister_id: Principal,
+    backup_key_counts: &BackupKeyCounts,
+) -> Result<(), String> {
+    let canister_key_counts = get_canister_backup_key_counts(user_canister_id)
+        .await
+        .map_err(|e| format!("{}: {}", POST_UPGRADE_VALIDATION_FAILED_ERROR_PREFIX, e))?;
+
+    let shortfalls = canister_key_counts.get_shortfalls(backup_key_counts);
+    if !shortfalls.is_empty() {
+        return Err(format!(
+            "{}: the canister holds fewer entries than its backup, {}",
+            POST_UPGRADE_VALIDATION_FAILED_ERROR_PREFIX,
+            shortfalls.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// `None` when data_backup has no backup of the user
+async fn get_user_backup_key_counts(
+    data_backup_canister_id: Principal,
+    user_principal_id: Principal,
+) -> Result<Option<BackupKeyCounts>, String> {
+    let (response,): (Result<BackupKeyCounts, String>,) = call::call(
+        data_backup_canister_id,
+        "get_user_backup_key_counts",
+        (user_principal_id,),
+    )
+    .await
+    .map_err(|e| e.1)?;
+
+    match response {
+        Ok(backup_key_counts) => Ok(Some(backup_key_counts)),
+        Err(e) if e == "No user data found" => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn get_canister_backup_key_counts(
+    user_canister_id: Principal,
+) -> Result<BackupKeyCounts, String> {
+    let response: CallResult<(BackupKeyCounts,)> =
+        call::call(user_canister_id, "get_backup_key_counts", ()).await;
+
+    response
+        .map(|(canister_key_counts,)| canister_key_counts)
+        .map_err(|e| e.1)
+}
+
+fn is_method_missing_error(error_message: &str) -> bool {
+    error_message.contains("has no update method") || error_message.contains("has no query method")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_method_missing_error() {
+        assert!(is_method_missing_error(
+            "Canister rrkah-fqaaa-aaaaa-aaaaq-cai has no update method 'get_backup_key_counts'"
+        ));
+        assert!(!is_method_missing_error(
+            "Canister rrkah-fqaaa-aaaaa-aaaaq-cai is out of cycles"
+        ));
+    }
+}
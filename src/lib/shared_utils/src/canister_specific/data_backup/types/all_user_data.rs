@@ -1,16 +1,28 @@
 use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet},
+    time::SystemTime,
 };
 
 use candid::{CandidType, Decode, Deserialize, Encode, Principal};
 use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
 
-use crate::canister_specific::individual_user_template::types::{
-    post::Post, profile::UserProfile, token::TokenBalance,
+use crate::{
+    canister_specific::individual_user_template::types::{
+        configuration::IndividualUserConfiguration, follow::FollowData,
+        hot_or_not::PlacedBetDetail, post::Post, post_score_sync::PostScoreSyncQueue,
+        profile::UserProfile, token::TokenBalance,
+    },
+    common::types::{
+        app_primitive_type::PostId, known_principal::KnownPrincipalMap,
+        top_posts::post_score_index::PostScoreIndex, version_details::VersionDetails,
+    },
 };
 
-#[derive(CandidType, Deserialize, Debug)]
+/// All backed up data of a user. Backups are stored split into sections and chunks, and are
+/// reassembled into this for restores. Entries taken before that were stored whole.
+#[derive(CandidType, Deserialize, Serialize, Debug)]
 pub struct AllUserData {
     pub user_principal_id: Principal,
     pub user_canister_id: Principal,
@@ -33,13 +45,26 @@ impl BoundedStorable for AllUserData {
     const IS_FIXED_SIZE: bool = false;
 }
 
-#[derive(Deserialize, CandidType, Default, Debug)]
+#[derive(Deserialize, Serialize, CandidType, Default, Debug)]
 pub struct UserOwnedCanisterData {
     pub all_created_posts: BTreeMap<u64, Post>,
     pub principals_i_follow: BTreeSet<Principal>,
     pub principals_that_follow_me: BTreeSet<Principal>,
     pub profile: UserProfile,
     pub token_data: TokenBalance,
+    /// Format version of the backup this was reassembled from. `None` for backups taken before
+    /// backups were versioned, which hold only the fields above.
+    pub backup_format_version: Option<u32>,
+    // * Added in backup format version 2. `None` when the backup does not hold the section.
+    pub all_hot_or_not_bets_placed: Option<BTreeMap<(Principal, PostId), PlacedBetDetail>>,
+    pub follow_data: Option<FollowData>,
+    pub configuration: Option<IndividualUserConfiguration>,
+    pub known_principal_ids: Option<KnownPrincipalMap>,
+    pub version_details: Option<VersionDetails>,
+    pub posts_index_sorted_by_home_feed_score: Option<PostScoreIndex>,
+    pub posts_index_sorted_by_hot_or_not_feed_score: Option<PostScoreIndex>,
+    pub post_score_sync_queue: Option<PostScoreSyncQueue>,
+    pub last_access_time: Option<Option<SystemTime>>,
 }
 
 #[derive(Deserialize, CandidType, Default, Debug)]
@@ -47,3 +72,52 @@ pub struct ProfileDetails {
     pub display_name: Option<String>,
     pub profile_picture_url: Option<String>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `AllUserData` as it was stored before backups were versioned
+    #[derive(CandidType)]
+    struct AllUserDataV1 {
+        user_principal_id: Principal,
+        user_canister_id: Principal,
+        canister_data: UserOwnedCanisterDataV1,
+    }
+
+    #[derive(CandidType)]
+    struct UserOwnedCanisterDataV1 {
+        all_created_posts: BTreeMap<u64, Post>,
+        principals_i_follow: BTreeSet<Principal>,
+        principals_that_follow_me: BTreeSet<Principal>,
+        profile: UserProfile,
+        token_data: TokenBalance,
+    }
+
+    #[test]
+    fn test_all_user_data_decodes_unversioned_backups() {
+        let all_user_data_v1 = AllUserDataV1 {
+            user_principal_id: Principal::from_slice(&[1]),
+            user_canister_id: Principal::from_slice(&[2]),
+            canister_data: UserOwnedCanisterDataV1 {
+                all_created_posts: BTreeMap::new(),
+                principals_i_follow: BTreeSet::from([Principal::from_slice(&[3])]),
+                principals_that_follow_me: BTreeSet::new(),
+                profile: UserProfile::default(),
+                token_data: TokenBalance::default(),
+            },
+        };
+
+        let all_user_data =
+            AllUserData::from_bytes(Cow::Owned(Encode!(&all_user_data_v1).unwrap()));
+
+        assert_eq!(all_user_data.user_canister_id, Principal::from_slice(&[2]));
+        assert!(all_user_data
+            .canister_data
+            .principals_i_follow
+            .contains(&Principal::from_slice(&[3])));
+        assert_eq!(all_user_data.canister_data.backup_format_version, None);
+        assert!(all_user_data.canister_data.follow_data.is_none());
+        assert!(all_user_data.canister_data.last_access_time.is_none());
+    }
+}
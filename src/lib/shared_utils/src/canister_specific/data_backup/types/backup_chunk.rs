@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+
+use super::backup_manifest::BackupSection;
+
+/// 32 KiB
+pub const BACKUP_CHUNK_SIZE_IN_BYTES: usize = 32 * 1024;
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UserBackupChunkKey {
+    pub user_principal_id: Principal,
+    pub chunk_index: u32,
+}
+
+impl Storable for UserBackupChunkKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, Self).unwrap()
+    }
+}
+
+impl BoundedStorable for UserBackupChunkKey {
+    const MAX_SIZE: u32 = 64;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+/// Longer item keys are rejected, so that item chunk keys stay within their maximum size
+pub const MAX_BACKUP_ITEM_KEY_SIZE_IN_BYTES: usize = 64;
+
+/// Identifies a chunk of a single item of a section, such as one of the posts. The items of a
+/// user's section are next to each other in key order.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UserBackupItemChunkKey {
+    pub user_principal_id: Principal,
+    pub section: BackupSection,
+    /// Candid encoded key of the item in its section
+    pub item_key: Vec<u8>,
+    pub chunk_index: u32,
+}
+
+impl Storable for UserBackupItemChunkKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, Self).unwrap()
+    }
+}
+
+impl BoundedStorable for UserBackupItemChunkKey {
+    const MAX_SIZE: u32 = 256;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+/// A slice of the candid encoded bytes of a backup section or item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackupChunk(pub Vec<u8>);
+
+impl Storable for BackupChunk {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(bytes.into_owned())
+    }
+}
+
+impl BoundedStorable for BackupChunk {
+    const MAX_SIZE: u32 = BACKUP_CHUNK_SIZE_IN_BYTES as u32;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_user_backup_chunk_key_fits_max_size() {
+        let key = UserBackupChunkKey {
+            user_principal_id: Principal::from_slice(&[u8::MAX; 29]),
+            chunk_index: u32::MAX,
+        };
+
+        assert!(key.to_bytes().len() <= UserBackupChunkKey::MAX_SIZE as usize);
+        assert_eq!(UserBackupChunkKey::from_bytes(key.to_bytes()), key);
+    }
+
+    #[test]
+    fn test_user_backup_item_chunk_key_fits_max_size() {
+        let key = UserBackupItemChunkKey {
+            user_principal_id: Principal::from_slice(&[u8::MAX; 29]),
+            section: BackupSection::PostsIndexSortedByHotOrNotFeedScore,
+            item_key: vec![u8::MAX; MAX_BACKUP_ITEM_KEY_SIZE_IN_BYTES],
+            chunk_index: u32::MAX,
+        };
+
+        assert!(key.to_bytes().len() <= UserBackupItemChunkKey::MAX_SIZE as usize);
+        assert_eq!(UserBackupItemChunkKey::from_bytes(key.to_bytes()), key);
+    }
+}
@@ -0,0 +1,130 @@
+use candid::{CandidType, Deserialize};
+
+use super::all_user_data::UserOwnedCanisterData;
+
+/// The number of entries in the collections of a user's canister state. Upgrades compare them
+/// between a canister and its backup to check that nothing was lost.
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BackupKeyCounts {
+    pub post_count: u64,
+    pub token_event_count: u64,
+    pub hot_or_not_bet_count: u64,
+    pub following_count: u64,
+    pub follower_count: u64,
+}
+
+impl BackupKeyCounts {
+    /// A description of each count that differs from `expected`
+    pub fn get_mismatches(&self, expected: &BackupKeyCounts) -> Vec<String> {
+        self.describe_counts(expected, |count, expected_count| count != expected_count)
+    }
+
+    /// A description of each count that is below `expected`. Entries added after `expected` was
+    /// taken are not counted as mismatches.
+    pub fn get_shortfalls(&self, expected: &BackupKeyCounts) -> Vec<String> {
+        self.describe_counts(expected, |count, expected_count| count < expected_count)
+    }
+
+    fn describe_counts(
+        &self,
+        expected: &BackupKeyCounts,
+        is_reported: impl Fn(u64, u64) -> bool,
+    ) -> Vec<String> {
+        [
+            ("posts", self.post_count, expected.post_count),
+            (
+                "token events",
+                self.token_event_count,
+                expected.token_event_count,
+            ),
+            (
+                "hot or not bets",
+                self.hot_or_not_bet_count,
+                expected.hot_or_not_bet_count,
+            ),
+            ("following", self.following_count, expected.following_count),
+            ("followers", self.follower_count, expected.follower_count),
+        ]
+        .into_iter()
+        .filter(|(_, count, expected_count)| is_reported(*count, *expected_count))
+        .map(|(key, count, expected_count)| {
+            format!("{}: {} instead of {}", key, count, expected_count)
+        })
+        .collect()
+    }
+}
+
+impl From<&UserOwnedCanisterData> for BackupKeyCounts {
+    fn from(canister_data: &UserOwnedCanisterData) -> Self {
+        Self {
+            post_count: canister_data.all_created_posts.len() as u64,
+            token_event_count: canister_data
+                .token_data
+                .utility_token_transaction_history
+                .len() as u64,
+            hot_or_not_bet_count: canister_data
+                .all_hot_or_not_bets_placed
+                .as_ref()
+                .map_or(0, |all_hot_or_not_bets_placed| {
+                    all_hot_or_not_bets_placed.len() as u64
+                }),
+            following_count: canister_data.follow_data.as_ref().map_or(0, |follow_data| {
+                follow_data.following.sorted_index.len() as u64
+            }),
+            follower_count: canister_data.follow_data.as_ref().map_or(0, |follow_data| {
+                follow_data.follower.sorted_index.len() as u64
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_mismatches() {
+        let backup_key_counts = BackupKeyCounts {
+            post_count: 3,
+            token_event_count: 10,
+            ..Default::default()
+        };
+
+        assert!(backup_key_counts
+            .get_mismatches(&backup_key_counts)
+            .is_empty());
+        assert_eq!(
+            BackupKeyCounts {
+                post_count: 2,
+                token_event_count: 10,
+                follower_count: 1,
+                ..Default::default()
+            }
+            .get_mismatches(&backup_key_counts),
+            vec![
+                "posts: 2 instead of 3".to_string(),
+                "followers: 1 instead of 0".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_shortfalls() {
+        let backup_key_counts = BackupKeyCounts {
+            post_count: 3,
+            token_event_count: 10,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            BackupKeyCounts {
+                post_count: 2,
+                token_event_count: 11,
+                follower_count: 1,
+                ..Default::default()
+            }
+            .get_shortfalls(&backup_key_counts),
+            vec!["posts: 2 instead of 3".to_string()]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::{borrow::Cow, collections::BTreeMap, time::SystemTime};
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use crate::common::types::version_details::VersionDetails;
+
+use super::backup_section_digests::BackupSectionDigests;
+
+/// The backup format written today. Version 1 backups only hold the sections up to
+/// `PrincipalsThatFollowMe`; version 2 added the rest of the individual user canister state.
+pub const CURRENT_BACKUP_FORMAT_VERSION: u32 = 2;
+
+/// The parts a user's backup is split into. Each section is stored in its own stable map.
+#[derive(
+    CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum BackupSection {
+    Profile,
+    Posts,
+    TokenData,
+    PrincipalsIFollow,
+    PrincipalsThatFollowMe,
+    HotOrNotBetsPlaced,
+    FollowData,
+    Configuration,
+    KnownPrincipalIds,
+    VersionDetails,
+    PostsIndexSortedByHomeFeedScore,
+    PostsIndexSortedByHotOrNotFeedScore,
+    PostScoreSyncQueue,
+    LastAccessTime,
+}
+
+impl BackupSection {
+    pub const ALL: [BackupSection; 14] = [
+        BackupSection::Profile,
+        BackupSection::Posts,
+        BackupSection::TokenData,
+        BackupSection::PrincipalsIFollow,
+        BackupSection::PrincipalsThatFollowMe,
+        BackupSection::HotOrNotBetsPlaced,
+        BackupSection::FollowData,
+        BackupSection::Configuration,
+        BackupSection::KnownPrincipalIds,
+        BackupSection::VersionDetails,
+        BackupSection::PostsIndexSortedByHomeFeedScore,
+        BackupSection::PostsIndexSortedByHotOrNotFeedScore,
+        BackupSection::PostScoreSyncQueue,
+        BackupSection::LastAccessTime,
+    ];
+
+    /// The backup format version the section was introduced in
+    pub fn get_format_version(&self) -> u32 {
+        match self {
+            BackupSection::Profile
+            | BackupSection::Posts
+            | BackupSection::TokenData
+            | BackupSection::PrincipalsIFollow
+            | BackupSection::PrincipalsThatFollowMe => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// Sections holding collections, such as the posts, store each item on its own so that a batch
+/// only writes the items it changes. The other sections are stored as a single value.
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BackupSectionManifest {
+    /// Chunks of the value of the section
+    pub chunk_count: u32,
+    /// Size of the value of the section
+    pub size_in_bytes: u64,
+    pub item_count: u64,
+    pub item_size_in_bytes: u64,
+}
+
+/// Lists the chunks a user's backup is made of, so that it can be reassembled
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserBackupManifest {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+    pub sections: BTreeMap<BackupSection, BackupSectionManifest>,
+    /// Change sequence number of the user's canister up to which the backup is complete. `None`
+    /// until the first backup run finishes, and after a restore, so that the next run sends
+    /// everything.
+    pub last_acknowledged_change_sequence_number: Option<u64>,
+    /// Backup epoch of the user's canister the acknowledged change sequence number belongs to. A
+    /// reinstalled canister starts a new epoch, so the acknowledgement no longer applies to it.
+    pub last_acknowledged_backup_epoch: Option<u64>,
+    /// Digests the user's canister computed over its data when it last finished a backup run.
+    /// `None` for backups taken before canisters sent digests.
+    pub section_digests: Option<BackupSectionDigests>,
+    /// `None` until a backup run finishes after runs started being recorded
+    pub last_completed_run: Option<UserBackupRunSummary>,
+    pub last_error: Option<UserBackupError>,
+}
+
+/// What the user's backup held when a backup run finished
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserBackupRunSummary {
+    pub completed_at: SystemTime,
+    pub post_count: u64,
+    pub token_event_count: u64,
+    /// Version of the individual user canister wasm the backup was taken from
+    pub source_wasm_version: Option<VersionDetails>,
+}
+
+/// Something found wrong with the user's backup, such as by the verification job
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserBackupError {
+    pub message: String,
+    pub occurred_at: SystemTime,
+}
+
+/// Longer messages are cut, so that the manifest stays within its maximum size
+pub const MAX_USER_BACKUP_ERROR_MESSAGE_LENGTH: usize = 1000;
+
+impl UserBackupError {
+    pub fn new(message: &str, occurred_at: SystemTime) -> Self {
+        Self {
+            message: message
+                .chars()
+                .take(MAX_USER_BACKUP_ERROR_MESSAGE_LENGTH)
+                .collect(),
+            occurred_at,
+        }
+    }
+}
+
+impl UserBackupManifest {
+    pub fn new(user_principal_id: Principal, user_canister_id: Principal) -> Self {
+        Self {
+            user_principal_id,
+            user_canister_id,
+            sections: BTreeMap::new(),
+            last_acknowledged_change_sequence_number: None,
+            last_acknowledged_backup_epoch: None,
+            section_digests: None,
+            last_completed_run: None,
+            last_error: None,
+        }
+    }
+
+    /// The format version of the backup, going by the newest section it holds
+    pub fn get_format_version(&self) -> u32 {
+        self.sections
+            .keys()
+            .map(BackupSection::get_format_version)
+            .max()
+            .unwrap_or(1)
+    }
+
+    pub fn get_total_size_in_bytes(&self) -> u64 {
+        self.sections
+            .values()
+            .map(|section_manifest| {
+                section_manifest.size_in_bytes + section_manifest.item_size_in_bytes
+            })
+            .sum()
+    }
+}
+
+impl Storable for UserBackupManifest {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, Self).unwrap()
+    }
+}
+
+impl BoundedStorable for UserBackupManifest {
+    // * leaves room for the manifest to grow
+    const MAX_SIZE: u32 = 10_000;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_user_backup_manifest_get_format_version() {
+        let mut manifest =
+            UserBackupManifest::new(Principal::from_slice(&[1]), Principal::from_slice(&[2]));
+        assert_eq!(manifest.get_format_version(), 1);
+
+        manifest
+            .sections
+            .insert(BackupSection::Posts, BackupSectionManifest::default());
+        assert_eq!(manifest.get_format_version(), 1);
+
+        manifest
+            .sections
+            .insert(BackupSection::FollowData, BackupSectionManifest::default());
+        assert_eq!(manifest.get_format_version(), CURRENT_BACKUP_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_user_backup_manifest_fits_max_size() {
+        let mut manifest = UserBackupManifest::new(
+            Principal::from_slice(&[u8::MAX; 29]),
+            Principal::from_slice(&[u8::MAX; 29]),
+        );
+        manifest.sections = BackupSection::ALL
+            .into_iter()
+            .map(|section| {
+                (
+                    section,
+                    BackupSectionManifest {
+                        chunk_count: u32::MAX,
+                        size_in_bytes: u64::MAX,
+                        item_count: u64::MAX,
+                        item_size_in_bytes: u64::MAX,
+                    },
+                )
+            })
+            .collect();
+        manifest.last_acknowledged_change_sequence_number = Some(u64::MAX);
+        manifest.last_acknowledged_backup_epoch = Some(u64::MAX);
+        manifest.section_digests = Some(BackupSectionDigests {
+            digests: BackupSection::ALL
+                .into_iter()
+                .map(|section| (section, vec![u8::MAX; 32]))
+                .collect(),
+        });
+        manifest.last_completed_run = Some(UserBackupRunSummary {
+            completed_at: SystemTime::UNIX_EPOCH,
+            post_count: u64::MAX,
+            token_event_count: u64::MAX,
+            source_wasm_version: Some(VersionDetails {
+                version_number: u64::MAX,
+                version: "v".repeat(100),
+            }),
+        });
+        manifest.last_error = Some(UserBackupError::new(
+            &"🥫".repeat(2 * MAX_USER_BACKUP_ERROR_MESSAGE_LENGTH),
+            SystemTime::UNIX_EPOCH,
+        ));
+
+        assert!(manifest.to_bytes().len() <= UserBackupManifest::MAX_SIZE as usize);
+    }
+}
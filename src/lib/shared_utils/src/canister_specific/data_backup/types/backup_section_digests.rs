@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use candid::{CandidType, Deserialize, Encode, Principal};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::canister_specific::individual_user_template::types::{
+    follow::FollowData, post::Post, profile::UserProfile, token::TokenBalance,
+};
+
+use super::{all_user_data::UserOwnedCanisterData, backup_manifest::BackupSection};
+
+/// The sections that are digested to verify backups against their source canister
+pub const DIGESTED_BACKUP_SECTIONS: [BackupSection; 4] = [
+    BackupSection::Profile,
+    BackupSection::Posts,
+    BackupSection::TokenData,
+    BackupSection::FollowData,
+];
+
+/// A sha256 digest of each section in `DIGESTED_BACKUP_SECTIONS`. The digests are computed over a
+/// canonical encoding of the data, so that a canister and a backup holding the same data always
+/// get the same digests.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BackupSectionDigests {
+    pub digests: BTreeMap<BackupSection, Vec<u8>>,
+}
+
+impl BackupSectionDigests {
+    pub fn new(
+        profile: &UserProfile,
+        all_created_posts: &BTreeMap<u64, Post>,
+        token_balance: &TokenBalance,
+        follow_data: &FollowData,
+    ) -> Self {
+        Self {
+            digests: BTreeMap::from([
+                (BackupSection::Profile, get_profile_digest(profile)),
+                (BackupSection::Posts, get_posts_digest(all_created_posts)),
+                (
+                    BackupSection::TokenData,
+                    get_token_data_digest(token_balance),
+                ),
+                (
+                    BackupSection::FollowData,
+                    get_follow_data_digest(follow_data),
+                ),
+            ]),
+        }
+    }
+
+    /// The sections whose digest differs from `other`. Sections missing from either side are not
+    /// compared.
+    pub fn get_mismatched_sections(&self, other: &BackupSectionDigests) -> Vec<BackupSection> {
+        self.digests
+            .iter()
+            .filter(|(section, digest)| {
+                other
+                    .digests
+                    .get(section)
+                    .is_some_and(|other_digest| other_digest != *digest)
+            })
+            .map(|(section, _)| *section)
+            .collect()
+    }
+}
+
+impl From<&UserOwnedCanisterData> for BackupSectionDigests {
+    fn from(canister_data: &UserOwnedCanisterData) -> Self {
+        Self::new(
+            &canister_data.profile,
+            &canister_data.all_created_posts,
+            &canister_data.token_data,
+            &canister_data.follow_data.clone().unwrap_or_default(),
+        )
+    }
+}
+
+pub fn get_profile_digest(profile: &UserProfile) -> Vec<u8> {
+    Sha256::digest(Encode!(profile).unwrap()).to_vec()
+}
+
+pub fn get_posts_digest(all_created_posts: &BTreeMap<u64, Post>) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for post in all_created_posts.values() {
+        // * likes are held in a `HashSet`, whose iteration order differs between copies
+        let mut likes: Vec<Principal> = post.likes.iter().copied().collect();
+        likes.sort_unstable();
+        let post_without_likes = Post {
+            likes: Default::default(),
+            ..post.clone()
+        };
+
+        hasher.update(Encode!(&post_without_likes, &likes).unwrap());
+    }
+
+    hasher.finalize().to_vec()
+}
+
+/// Covers the balance and the transaction history, which are what gets backed up
+pub fn get_token_data_digest(token_balance: &TokenBalance) -> Vec<u8> {
+    Sha256::digest(
+        Encode!(
+            &token_balance.utility_token_balance,
+            &token_balance.utility_token_transaction_history
+        )
+        .unwrap(),
+    )
+    .to_vec()
+}
+
+/// Only covers the sorted indexes, as the members maps are `HashMap`s derived from them
+pub fn get_follow_data_digest(follow_data: &FollowData) -> Vec<u8> {
+    Sha256::digest(
+        Encode!(
+            &follow_data.follower.sorted_index,
+            &follow_data.following.sorted_index
+        )
+        .unwrap(),
+    )
+    .to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, time::SystemTime};
+
+    use crate::common::types::top_posts::post_score_index_item::PostStatus;
+
+    use super::*;
+
+    fn get_post(likes: Vec<Principal>) -> Post {
+        Post {
+            id: 0,
+            description: "description".to_string(),
+            hashtags: vec![],
+            video_uid: "video_uid".to_string(),
+            status: PostStatus::ReadyToView,
+            created_at: SystemTime::UNIX_EPOCH,
+            likes: likes.into_iter().collect::<HashSet<_>>(),
+            share_count: 0,
+            view_stats: Default::default(),
+            home_feed_score: Default::default(),
+            creator_consent_for_inclusion_in_hot_or_not: false,
+            hot_or_not_details: None,
+            is_nsfw: false,
+        }
+    }
+
+    #[test]
+    fn test_backup_section_digests_ignore_likes_order() {
+        let likes: Vec<Principal> = (0..20u8).map(|i| Principal::from_slice(&[i])).collect();
+        let posts = BTreeMap::from([(0, get_post(likes.clone()))]);
+        let reversed_posts = BTreeMap::from([(0, get_post(likes.into_iter().rev().collect()))]);
+
+        let digests = BackupSectionDigests::new(
+            &UserProfile::default(),
+            &posts,
+            &TokenBalance::default(),
+            &FollowData::default(),
+        );
+
+        assert_eq!(
+            digests,
+            BackupSectionDigests::new(
+                &UserProfile::default(),
+                &reversed_posts,
+                &TokenBalance::default(),
+                &FollowData::default(),
+            )
+        );
+        assert_eq!(digests.digests.len(), DIGESTED_BACKUP_SECTIONS.len());
+    }
+
+    #[test]
+    fn test_get_mismatched_sections() {
+        let digests = BackupSectionDigests::new(
+            &UserProfile::default(),
+            &BTreeMap::new(),
+            &TokenBalance::default(),
+            &FollowData::default(),
+        );
+        let other_digests = BackupSectionDigests::new(
+            &UserProfile {
+                display_name: Some("alice".to_string()),
+                ..Default::default()
+            },
+            &BTreeMap::new(),
+            &TokenBalance {
+                lifetime_earnings: 100,
+                ..Default::default()
+            },
+            &FollowData::default(),
+        );
+
+        assert!(digests.get_mismatched_sections(&digests).is_empty());
+        assert_eq!(
+            digests.get_mismatched_sections(&other_digests),
+            vec![BackupSection::Profile]
+        );
+        assert!(digests
+            .get_mismatched_sections(&BackupSectionDigests::default())
+            .is_empty());
+    }
+}
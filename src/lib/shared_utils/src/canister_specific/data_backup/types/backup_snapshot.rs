@@ -0,0 +1,245 @@
+use std::{borrow::Cow, collections::BTreeMap, time::SystemTime};
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use crate::common::types::version_details::VersionDetails;
+
+/// Why a snapshot was taken. Retention is counted separately for each kind.
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackupSnapshotKind {
+    Daily,
+    PreUpgrade,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackupSnapshotStatus {
+    InProgress,
+    Complete,
+}
+
+/// A point in time copy of the backups of every user
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct BackupSnapshot {
+    pub snapshot_id: u64,
+    pub kind: BackupSnapshotKind,
+    pub taken_at: SystemTime,
+    /// Version of the individual user canister wasm that the backups were taken from, when known
+    pub source_wasm_version: Option<VersionDetails>,
+    pub status: BackupSnapshotStatus,
+    pub user_count: u64,
+    /// Users whose backup could not be read, and which the snapshot does not hold
+    #[serde(default)]
+    pub unreadable_user_count: u64,
+}
+
+/// The number of complete snapshots kept for each kind. Older ones are deleted.
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BackupSnapshotRetentionPolicy {
+    pub daily_snapshots_to_keep: u32,
+    pub pre_upgrade_snapshots_to_keep: u32,
+}
+
+impl Default for BackupSnapshotRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            daily_snapshots_to_keep: 3,
+            pre_upgrade_snapshots_to_keep: 2,
+        }
+    }
+}
+
+impl BackupSnapshotRetentionPolicy {
+    fn get_snapshots_to_keep(&self, kind: BackupSnapshotKind) -> usize {
+        match kind {
+            BackupSnapshotKind::Daily => self.daily_snapshots_to_keep as usize,
+            BackupSnapshotKind::PreUpgrade => self.pre_upgrade_snapshots_to_keep as usize,
+        }
+    }
+
+    /// Complete snapshots beyond the newest ones kept for their kind
+    pub fn get_snapshot_ids_to_delete(
+        &self,
+        snapshots: &BTreeMap<u64, BackupSnapshot>,
+    ) -> Vec<u64> {
+        let mut snapshot_ids_to_delete: Vec<u64> =
+            [BackupSnapshotKind::Daily, BackupSnapshotKind::PreUpgrade]
+                .into_iter()
+                .flat_map(|kind| {
+                    snapshots
+                        .values()
+                        .rev()
+                        .filter(move |snapshot| {
+                            snapshot.kind == kind
+                                && snapshot.status == BackupSnapshotStatus::Complete
+                        })
+                        .skip(self.get_snapshots_to_keep(kind))
+                        .map(|snapshot| snapshot.snapshot_id)
+                })
+                .collect();
+        snapshot_ids_to_delete.sort_unstable();
+
+        snapshot_ids_to_delete
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BackupSnapshotUserKey {
+    pub snapshot_id: u64,
+    pub user_principal_id: Principal,
+}
+
+impl Storable for BackupSnapshotUserKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, Self).unwrap()
+    }
+}
+
+impl BoundedStorable for BackupSnapshotUserKey {
+    const MAX_SIZE: u32 = 80;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+/// Where the copy of a user's backup in a snapshot is stored. The backup is candid encoded as a
+/// whole and split into chunks.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackupSnapshotUserEntry {
+    pub user_canister_id: Principal,
+    pub chunk_count: u32,
+    pub size_in_bytes: u64,
+}
+
+impl Storable for BackupSnapshotUserEntry {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, Self).unwrap()
+    }
+}
+
+impl BoundedStorable for BackupSnapshotUserEntry {
+    const MAX_SIZE: u32 = 100;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BackupSnapshotChunkKey {
+    pub snapshot_id: u64,
+    pub user_principal_id: Principal,
+    pub chunk_index: u32,
+}
+
+impl Storable for BackupSnapshotChunkKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, Self).unwrap()
+    }
+}
+
+impl BoundedStorable for BackupSnapshotChunkKey {
+    const MAX_SIZE: u32 = 96;
+    const IS_FIXED_SIZE: bool = false;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_snapshot(
+        snapshot_id: u64,
+        kind: BackupSnapshotKind,
+        status: BackupSnapshotStatus,
+    ) -> BackupSnapshot {
+        BackupSnapshot {
+            snapshot_id,
+            kind,
+            taken_at: SystemTime::UNIX_EPOCH,
+            source_wasm_version: None,
+            status,
+            user_count: 0,
+            unreadable_user_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_get_snapshot_ids_to_delete() {
+        let snapshots: BTreeMap<u64, BackupSnapshot> = [
+            get_snapshot(0, BackupSnapshotKind::Daily, BackupSnapshotStatus::Complete),
+            get_snapshot(
+                1,
+                BackupSnapshotKind::PreUpgrade,
+                BackupSnapshotStatus::Complete,
+            ),
+            get_snapshot(2, BackupSnapshotKind::Daily, BackupSnapshotStatus::Complete),
+            get_snapshot(
+                3,
+                BackupSnapshotKind::PreUpgrade,
+                BackupSnapshotStatus::Complete,
+            ),
+            get_snapshot(4, BackupSnapshotKind::Daily, BackupSnapshotStatus::Complete),
+            get_snapshot(
+                5,
+                BackupSnapshotKind::PreUpgrade,
+                BackupSnapshotStatus::Complete,
+            ),
+            get_snapshot(6, BackupSnapshotKind::Daily, BackupSnapshotStatus::Complete),
+            get_snapshot(
+                7,
+                BackupSnapshotKind::Daily,
+                BackupSnapshotStatus::InProgress,
+            ),
+        ]
+        .into_iter()
+        .map(|snapshot| (snapshot.snapshot_id, snapshot))
+        .collect();
+
+        assert_eq!(
+            BackupSnapshotRetentionPolicy::default().get_snapshot_ids_to_delete(&snapshots),
+            vec![0, 1]
+        );
+        assert_eq!(
+            BackupSnapshotRetentionPolicy {
+                daily_snapshots_to_keep: 1,
+                pre_upgrade_snapshots_to_keep: 0,
+            }
+            .get_snapshot_ids_to_delete(&snapshots),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_keys_fit_max_size() {
+        let user_key = BackupSnapshotUserKey {
+            snapshot_id: u64::MAX,
+            user_principal_id: Principal::from_slice(&[u8::MAX; 29]),
+        };
+        let chunk_key = BackupSnapshotChunkKey {
+            snapshot_id: u64::MAX,
+            user_principal_id: Principal::from_slice(&[u8::MAX; 29]),
+            chunk_index: u32::MAX,
+        };
+        let user_entry = BackupSnapshotUserEntry {
+            user_canister_id: Principal::from_slice(&[u8::MAX; 29]),
+            chunk_count: u32::MAX,
+            size_in_bytes: u64::MAX,
+        };
+
+        assert!(user_key.to_bytes().len() <= BackupSnapshotUserKey::MAX_SIZE as usize);
+        assert!(chunk_key.to_bytes().len() <= BackupSnapshotChunkKey::MAX_SIZE as usize);
+        assert!(user_entry.to_bytes().len() <= BackupSnapshotUserEntry::MAX_SIZE as usize);
+        assert_eq!(
+            BackupSnapshotChunkKey::from_bytes(chunk_key.to_bytes()),
+            chunk_key
+        );
+    }
+}
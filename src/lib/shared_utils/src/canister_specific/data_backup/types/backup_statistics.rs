@@ -1,6 +1,199 @@
-use candid::{CandidType, Deserialize};
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+use crate::common::types::version_details::VersionDetails;
+
+use super::backup_manifest::{BackupSection, UserBackupError, UserBackupManifest};
 
 #[derive(CandidType, Deserialize)]
 pub struct BackupStatistics {
     pub number_of_user_entries: u64,
+    pub stable_memory_size_in_bytes: u64,
+    /// Time since the least recently finished backup run, as of the last coverage scan
+    pub oldest_backup_age_in_seconds: Option<u64>,
+    pub last_completed_backup_coverage: Option<BackupCoverageSummary>,
+    pub last_completed_backup_verification: Option<BackupVerificationReport>,
+    pub backup_verification_in_progress: Option<BackupVerificationReport>,
+}
+
+/// The state of a user's backup
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserBackupStatus {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+    pub last_backed_up_at: Option<SystemTime>,
+    pub size_in_bytes: u64,
+    pub post_count: Option<u64>,
+    pub token_event_count: Option<u64>,
+    pub source_wasm_version: Option<VersionDetails>,
+    pub last_error: Option<UserBackupError>,
+}
+
+impl From<&UserBackupManifest> for UserBackupStatus {
+    fn from(manifest: &UserBackupManifest) -> Self {
+        let last_completed_run = manifest.last_completed_run.as_ref();
+
+        Self {
+            user_principal_id: manifest.user_principal_id,
+            user_canister_id: manifest.user_canister_id,
+            last_backed_up_at: last_completed_run.map(|run| run.completed_at),
+            size_in_bytes: manifest.get_total_size_in_bytes(),
+            post_count: last_completed_run.map(|run| run.post_count),
+            token_event_count: last_completed_run.map(|run| run.token_event_count),
+            source_wasm_version: last_completed_run.and_then(|run| run.source_wasm_version.clone()),
+            last_error: manifest.last_error.clone(),
+        }
+    }
+}
+
+/// Aggregates over the backups of every user, computed by the backup coverage scan
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackupCoverageSummary {
+    pub started_at: SystemTime,
+    pub completed_at: Option<SystemTime>,
+    /// When the least recently finished backup run finished. Backups without a recorded run are
+    /// not considered.
+    pub oldest_backup_completed_at: Option<SystemTime>,
+    pub total_backup_size_in_bytes: u64,
+    pub users_with_completed_run_count: u64,
+    pub users_with_error_count: u64,
+    /// Users of the user index that have no backup at all
+    pub users_without_backup_count: u64,
+}
+
+impl BackupCoverageSummary {
+    pub fn new(started_at: SystemTime) -> Self {
+        Self {
+            started_at,
+            completed_at: None,
+            oldest_backup_completed_at: None,
+            total_backup_size_in_bytes: 0,
+            users_with_completed_run_count: 0,
+            users_with_error_count: 0,
+            users_without_backup_count: 0,
+        }
+    }
+
+    pub fn record_manifest(&mut self, manifest: &UserBackupManifest) {
+        self.total_backup_size_in_bytes += manifest.get_total_size_in_bytes();
+        if manifest.last_error.is_some() {
+            self.users_with_error_count += 1;
+        }
+
+        let Some(last_completed_run) = &manifest.last_completed_run else {
+            return;
+        };
+        self.users_with_completed_run_count += 1;
+        self.oldest_backup_completed_at = Some(
+            self.oldest_backup_completed_at
+                .map_or(last_completed_run.completed_at, |oldest| {
+                    oldest.min(last_completed_run.completed_at)
+                }),
+        );
+    }
+}
+
+/// What a user's backup was found to differ from
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackupDigestMismatchKind {
+    /// The stored data does not match the digests the canister sent with its last backup run
+    StoredDataDiffersFromSource,
+    /// The live canister does not match the stored data, as when it changed after its last backup
+    /// run
+    LiveCanisterDiffersFromBackup,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackupDigestMismatch {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+    pub kind: BackupDigestMismatchKind,
+    pub sections: Vec<BackupSection>,
+}
+
+/// The outcome of a run of the backup verification job
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackupVerificationReport {
+    pub started_at: SystemTime,
+    pub completed_at: Option<SystemTime>,
+    pub verified_user_count: u64,
+    pub mismatched_user_count: u64,
+    /// Users whose canister could not be asked for its digests
+    pub failed_user_count: u64,
+    /// The first `MAX_REPORTED_BACKUP_DIGEST_MISMATCHES` mismatches found
+    pub mismatches: Vec<BackupDigestMismatch>,
+}
+
+pub const MAX_REPORTED_BACKUP_DIGEST_MISMATCHES: usize = 1000;
+
+impl BackupVerificationReport {
+    pub fn new(started_at: SystemTime) -> Self {
+        Self {
+            started_at,
+            completed_at: None,
+            verified_user_count: 0,
+            mismatched_user_count: 0,
+            failed_user_count: 0,
+            mismatches: vec![],
+        }
+    }
+
+    pub fn record_verified_user(&mut self, mismatches: Vec<BackupDigestMismatch>) {
+        self.verified_user_count += 1;
+        if mismatches.is_empty() {
+            return;
+        }
+
+        self.mismatched_user_count += 1;
+        let reportable_mismatch_count =
+            MAX_REPORTED_BACKUP_DIGEST_MISMATCHES.saturating_sub(self.mismatches.len());
+        self.mismatches
+            .extend(mismatches.into_iter().take(reportable_mismatch_count));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::canister_specific::data_backup::types::backup_manifest::UserBackupRunSummary;
+
+    use super::*;
+
+    #[test]
+    fn test_backup_coverage_summary_record_manifest() {
+        let now = SystemTime::now();
+        let mut summary = BackupCoverageSummary::new(now);
+        let mut manifest =
+            UserBackupManifest::new(Principal::from_slice(&[1]), Principal::from_slice(&[2]));
+
+        summary.record_manifest(&manifest);
+
+        manifest.last_completed_run = Some(UserBackupRunSummary {
+            completed_at: now - Duration::from_secs(60),
+            post_count: 1,
+            token_event_count: 2,
+            source_wasm_version: None,
+        });
+        summary.record_manifest(&manifest);
+
+        manifest.last_completed_run.as_mut().unwrap().completed_at = now;
+        manifest.last_error = Some(UserBackupError::new("Out of cycles", now));
+        summary.record_manifest(&manifest);
+
+        assert_eq!(
+            summary,
+            BackupCoverageSummary {
+                started_at: now,
+                completed_at: None,
+                oldest_backup_completed_at: Some(now - Duration::from_secs(60)),
+                total_backup_size_in_bytes: 0,
+                users_with_completed_run_count: 2,
+                users_with_error_count: 1,
+                users_without_backup_count: 0,
+            }
+        );
+    }
 }
@@ -0,0 +1,52 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize, Principal};
+
+use crate::{
+    canister_specific::individual_user_template::types::{
+        configuration::IndividualUserConfiguration, follow::FollowData,
+        hot_or_not::PlacedBetDetail, post_score_sync::PostScoreSyncQueue,
+    },
+    common::types::{
+        app_primitive_type::PostId, known_principal::KnownPrincipalMap,
+        top_posts::post_score_index::PostScoreIndex, version_details::VersionDetails,
+    },
+};
+
+use super::backup_manifest::BackupSection;
+
+/// A part of the individual user canister state that is not covered by the dedicated backup
+/// endpoints. Sent to data_backup when backing up and back to the canister when restoring.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum IndividualUserCanisterDataSection {
+    /// A chunk of the bets placed, upserted into what was received before
+    HotOrNotBetsPlaced(Vec<((Principal, PostId), PlacedBetDetail)>),
+    FollowData(FollowData),
+    Configuration(IndividualUserConfiguration),
+    KnownPrincipalIds(KnownPrincipalMap),
+    VersionDetails(VersionDetails),
+    PostsIndexSortedByHomeFeedScore(PostScoreIndex),
+    PostsIndexSortedByHotOrNotFeedScore(PostScoreIndex),
+    PostScoreSyncQueue(PostScoreSyncQueue),
+    LastAccessTime(Option<SystemTime>),
+}
+
+impl IndividualUserCanisterDataSection {
+    pub fn get_backup_section(&self) -> BackupSection {
+        match self {
+            Self::HotOrNotBetsPlaced(_) => BackupSection::HotOrNotBetsPlaced,
+            Self::FollowData(_) => BackupSection::FollowData,
+            Self::Configuration(_) => BackupSection::Configuration,
+            Self::KnownPrincipalIds(_) => BackupSection::KnownPrincipalIds,
+            Self::VersionDetails(_) => BackupSection::VersionDetails,
+            Self::PostsIndexSortedByHomeFeedScore(_) => {
+                BackupSection::PostsIndexSortedByHomeFeedScore
+            }
+            Self::PostsIndexSortedByHotOrNotFeedScore(_) => {
+                BackupSection::PostsIndexSortedByHotOrNotFeedScore
+            }
+            Self::PostScoreSyncQueue(_) => BackupSection::PostScoreSyncQueue,
+            Self::LastAccessTime(_) => BackupSection::LastAccessTime,
+        }
+    }
+}
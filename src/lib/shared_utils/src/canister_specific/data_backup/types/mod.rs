@@ -1,3 +1,11 @@
 pub mod all_user_data;
 pub mod args;
+pub mod backup_chunk;
+pub mod backup_key_counts;
+pub mod backup_manifest;
+pub mod backup_section_digests;
+pub mod backup_snapshot;
 pub mod backup_statistics;
+pub mod individual_user_canister_data_section;
+pub mod restore_run;
+pub mod user_data_archive;
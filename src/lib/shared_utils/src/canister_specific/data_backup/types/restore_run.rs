@@ -0,0 +1,52 @@
+use std::{collections::BTreeSet, time::SystemTime};
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+/// The users a restore run restores
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum RestoreRunScope {
+    /// Every user with a backup
+    AllUsers,
+    SelectedUsers(BTreeSet<Principal>),
+}
+
+/// The progress of a run of the restore orchestrator
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct RestoreRunReport {
+    pub scope: RestoreRunScope,
+    /// How many users are restored at the same time
+    pub max_concurrent_restores: u64,
+    pub started_at: SystemTime,
+    pub completed_at: Option<SystemTime>,
+    pub restored_user_count: u64,
+    pub failed_user_count: u64,
+}
+
+impl RestoreRunReport {
+    pub fn new(
+        scope: RestoreRunScope,
+        max_concurrent_restores: u64,
+        started_at: SystemTime,
+    ) -> Self {
+        Self {
+            scope,
+            max_concurrent_restores,
+            started_at,
+            completed_at: None,
+            restored_user_count: 0,
+            failed_user_count: 0,
+        }
+    }
+}
+
+/// The outcome of restoring a user in the latest restore run
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserRestoreResult {
+    pub user_principal_id: Principal,
+    /// The canister the data was restored to, when the user has a backup
+    pub user_canister_id: Option<Principal>,
+    pub finished_at: SystemTime,
+    /// Why the restore failed, `None` when it succeeded
+    pub error: Option<String>,
+}
@@ -0,0 +1,213 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::{all_user_data::AllUserData, backup_chunk::BACKUP_CHUNK_SIZE_IN_BYTES};
+
+/// Version 1 archives are the CBOR encoding (RFC 8949) of `AllUserData`, with structs encoded as
+/// maps keyed by field name
+pub const CURRENT_USER_DATA_ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Archives are downloaded and uploaded in chunks of this size. The last chunk may be smaller.
+pub const USER_DATA_ARCHIVE_CHUNK_SIZE_IN_BYTES: usize = BACKUP_CHUNK_SIZE_IN_BYTES;
+
+/// Archives larger than this are not imported
+pub const MAX_USER_DATA_ARCHIVE_SIZE_IN_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Describes a portable archive of a user's backup. The archive is the concatenation of its
+/// `chunk_count` chunks, whose sha256 digest is `sha256`.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserDataArchiveManifest {
+    pub format_version: u32,
+    pub user_principal_id: Principal,
+    /// The canister the backup was taken from
+    pub source_user_canister_id: Principal,
+    pub exported_at: SystemTime,
+    pub chunk_count: u32,
+    pub size_in_bytes: u64,
+    pub sha256: Vec<u8>,
+}
+
+/// Encodes `all_user_data` into an archive and the manifest describing it
+pub fn encode_user_data_archive(
+    all_user_data: &AllUserData,
+    exported_at: SystemTime,
+) -> Result<(UserDataArchiveManifest, Vec<u8>), String> {
+    let mut archive_bytes = vec![];
+    ciborium::ser::into_writer(all_user_data, &mut archive_bytes).map_err(|e| e.to_string())?;
+
+    let manifest = UserDataArchiveManifest {
+        format_version: CURRENT_USER_DATA_ARCHIVE_FORMAT_VERSION,
+        user_principal_id: all_user_data.user_principal_id,
+        source_user_canister_id: all_user_data.user_canister_id,
+        exported_at,
+        chunk_count: archive_bytes
+            .len()
+            .div_ceil(USER_DATA_ARCHIVE_CHUNK_SIZE_IN_BYTES) as u32,
+        size_in_bytes: archive_bytes.len() as u64,
+        sha256: Sha256::digest(&archive_bytes).to_vec(),
+    };
+
+    Ok((manifest, archive_bytes))
+}
+
+impl UserDataArchiveManifest {
+    /// Checks what can be checked before the archive is uploaded
+    pub fn validate(&self) -> Result<(), String> {
+        if self.format_version != CURRENT_USER_DATA_ARCHIVE_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported archive format version {}",
+                self.format_version
+            ));
+        }
+
+        if self.size_in_bytes > MAX_USER_DATA_ARCHIVE_SIZE_IN_BYTES {
+            return Err("The archive is too large".to_string());
+        }
+
+        if self.chunk_count as u64
+            != self
+                .size_in_bytes
+                .div_ceil(USER_DATA_ARCHIVE_CHUNK_SIZE_IN_BYTES as u64)
+        {
+            return Err("The chunk count does not match the archive size".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Decodes an archive described by this manifest, after checking its size and digest
+    pub fn decode_archive(&self, archive_bytes: &[u8]) -> Result<AllUserData, String> {
+        self.validate()?;
+
+        if archive_bytes.len() as u64 != self.size_in_bytes {
+            return Err("The archive size does not match its manifest".to_string());
+        }
+
+        if Sha256::digest(archive_bytes).as_slice() != self.sha256.as_slice() {
+            return Err("The archive digest does not match its manifest".to_string());
+        }
+
+        let all_user_data: AllUserData =
+            ciborium::de::from_reader(archive_bytes).map_err(|e| e.to_string())?;
+
+        if all_user_data.user_principal_id != self.user_principal_id {
+            return Err("The archive belongs to a different user".to_string());
+        }
+
+        Ok(all_user_data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::canister_specific::{
+        data_backup::types::all_user_data::UserOwnedCanisterData,
+        individual_user_template::types::{
+            follow::FollowData,
+            hot_or_not::{BetDirection, BetOutcomeForBetMaker, PlacedBetDetail},
+            token::TokenBalance,
+        },
+    };
+
+    use super::*;
+
+    fn get_all_user_data() -> AllUserData {
+        AllUserData {
+            user_principal_id: Principal::from_slice(&[1]),
+            user_canister_id: Principal::from_slice(&[2]),
+            canister_data: UserOwnedCanisterData {
+                token_data: TokenBalance {
+                    utility_token_balance: 1000,
+                    ..Default::default()
+                },
+                backup_format_version: Some(2),
+                all_hot_or_not_bets_placed: Some(BTreeMap::from([(
+                    (Principal::from_slice(&[3]), 4),
+                    PlacedBetDetail {
+                        canister_id: Principal::from_slice(&[3]),
+                        post_id: 4,
+                        slot_id: 1,
+                        room_id: 1,
+                        amount_bet: 10,
+                        bet_direction: BetDirection::Hot,
+                        bet_placed_at: SystemTime::UNIX_EPOCH,
+                        outcome_received: BetOutcomeForBetMaker::default(),
+                    },
+                )])),
+                follow_data: Some(FollowData::default()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_user_data_archive_round_trip() {
+        let (manifest, archive_bytes) =
+            encode_user_data_archive(&get_all_user_data(), SystemTime::UNIX_EPOCH).unwrap();
+
+        assert_eq!(manifest.chunk_count, 1);
+        assert_eq!(manifest.size_in_bytes, archive_bytes.len() as u64);
+
+        let all_user_data = manifest.decode_archive(&archive_bytes).unwrap();
+        assert_eq!(
+            all_user_data.canister_data.token_data.utility_token_balance,
+            1000
+        );
+        assert_eq!(
+            all_user_data
+                .canister_data
+                .all_hot_or_not_bets_placed
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_user_data_archive_rejects_tampered_archives() {
+        let (manifest, mut archive_bytes) =
+            encode_user_data_archive(&get_all_user_data(), SystemTime::UNIX_EPOCH).unwrap();
+
+        assert_eq!(
+            manifest.decode_archive(&archive_bytes[1..]).unwrap_err(),
+            "The archive size does not match its manifest"
+        );
+
+        let last_byte = archive_bytes.len() - 1;
+        archive_bytes[last_byte] ^= 1;
+        assert_eq!(
+            manifest.decode_archive(&archive_bytes).unwrap_err(),
+            "The archive digest does not match its manifest"
+        );
+
+        assert_eq!(
+            UserDataArchiveManifest {
+                format_version: 2,
+                ..manifest.clone()
+            }
+            .validate(),
+            Err("Unsupported archive format version 2".to_string())
+        );
+        assert_eq!(
+            UserDataArchiveManifest {
+                chunk_count: 1000,
+                ..manifest.clone()
+            }
+            .validate(),
+            Err("The chunk count does not match the archive size".to_string())
+        );
+        assert_eq!(
+            UserDataArchiveManifest {
+                size_in_bytes: MAX_USER_DATA_ARCHIVE_SIZE_IN_BYTES + 1,
+                ..manifest
+            }
+            .validate(),
+            Err("The archive is too large".to_string())
+        );
+    }
+}
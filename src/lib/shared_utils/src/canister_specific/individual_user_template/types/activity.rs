@@ -0,0 +1,11 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize};
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CanisterActivitySummary {
+    /// `None` for canisters that don't have an owner yet
+    pub last_access_time: Option<SystemTime>,
+    pub created_posts_count: u64,
+    pub followers_count: u64,
+}
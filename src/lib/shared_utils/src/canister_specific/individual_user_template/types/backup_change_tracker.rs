@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    canister_specific::data_backup::types::backup_manifest::BackupSection,
+    common::types::app_primitive_type::PostId,
+};
+
+use super::token::TokenBalance;
+
+/// Change sequence numbers of the backed up parts of an individual user canister. Every code path
+/// that modifies backed up state records the change here. A run sends what changed after the
+/// sequence number data_backup acknowledged at the end of the previous run. Sequence numbers start
+/// over when the canister is reinstalled, so an acknowledgement only counts for the epoch it was
+/// made in.
+#[derive(Default, Deserialize, Serialize)]
+pub struct BackupChangeTracker {
+    /// Set once per install of the canister. `0` until set.
+    #[serde(default)]
+    epoch: u64,
+    last_change_sequence_number: u64,
+    sections: BTreeMap<BackupSection, TrackedChange>,
+    posts: BTreeMap<PostId, TrackedChange>,
+    token_events: BTreeMap<u64, TrackedChange>,
+    hot_or_not_bets_placed: BTreeMap<(Principal, PostId), TrackedChange>,
+}
+
+#[derive(Default, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct TrackedChange {
+    change_sequence_number: u64,
+    /// Deleted items are kept until data_backup acknowledges a run that removed them
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+impl BackupChangeTracker {
+    pub fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Sets the epoch, unless it is already set. Pass a value no earlier install of the canister
+    /// used, such as the install time.
+    pub fn start_epoch_if_unset(&mut self, epoch: u64) {
+        if self.epoch == 0 {
+            self.epoch = epoch;
+        }
+    }
+
+    pub fn get_last_change_sequence_number(&self) -> u64 {
+        self.last_change_sequence_number
+    }
+
+    pub fn record_section_change(&mut self, section: BackupSection) {
+        let change_sequence_number = self.get_next_change_sequence_number();
+        self.sections.insert(
+            section,
+            TrackedChange {
+                change_sequence_number,
+                is_deleted: false,
+            },
+        );
+    }
+
+    pub fn record_post_change(&mut self, post_id: PostId) {
+        let change_sequence_number = self.get_next_change_sequence_number();
+        record_item_change(&mut self.posts, post_id, change_sequence_number, false);
+    }
+
+    /// Records the balance along with the token events `TokenBalance::handle_token_event` added
+    /// and trimmed, given the first and last token event ids from before it ran
+    pub fn record_token_balance_change(
+        &mut self,
+        token_event_ids_before: Option<(u64, u64)>,
+        token_balance: &TokenBalance,
+    ) {
+        self.record_section_change(BackupSection::TokenData);
+
+        let utility_token_transaction_history = &token_balance.utility_token_transaction_history;
+        let first_token_event_id = utility_token_transaction_history
+            .first_key_value()
+            .map(|(token_event_id, _)| *token_event_id);
+
+        let added_token_event_ids = match token_event_ids_before {
+            Some((first_token_event_id_before, last_token_event_id_before)) => {
+                let trimmed_up_to = first_token_event_id
+                    .unwrap_or(u64::MAX)
+                    .min(last_token_event_id_before + 1);
+                (first_token_event_id_before..trimmed_up_to)
+                    .for_each(|token_event_id| self.record_token_event_deletion(token_event_id));
+
+                utility_token_transaction_history
+                    .range(last_token_event_id_before + 1..)
+                    .map(|(token_event_id, _)| *token_event_id)
+                    .collect::<Vec<_>>()
+            }
+            None => utility_token_transaction_history.keys().copied().collect(),
+        };
+
+        added_token_event_ids
+            .into_iter()
+            .for_each(|token_event_id| self.record_token_event_change(token_event_id));
+    }
+
+    pub fn record_token_event_change(&mut self, token_event_id: u64) {
+        let change_sequence_number = self.get_next_change_sequence_number();
+        record_item_change(
+            &mut self.token_events,
+            token_event_id,
+            change_sequence_number,
+            false,
+        );
+    }
+
+    pub fn record_token_event_deletion(&mut self, token_event_id: u64) {
+        let change_sequence_number = self.get_next_change_sequence_number();
+        record_item_change(
+            &mut self.token_events,
+            token_event_id,
+            change_sequence_number,
+            true,
+        );
+    }
+
+    pub fn record_hot_or_not_bet_placed_change(&mut self, bet_key: (Principal, PostId)) {
+        let change_sequence_number = self.get_next_change_sequence_number();
+        record_item_change(
+            &mut self.hot_or_not_bets_placed,
+            bet_key,
+            change_sequence_number,
+            false,
+        );
+    }
+
+    /// Whether the section changed after `since`
+    pub fn has_section_changed_since(&self, section: BackupSection, since: u64) -> bool {
+        self.sections
+            .get(&section)
+            .is_some_and(|tracked_change| tracked_change.change_sequence_number > since)
+    }
+
+    pub fn get_posts_changed_since(&self, since: u64) -> Vec<PostId> {
+        get_items_changed_since(&self.posts, since, false)
+    }
+
+    pub fn get_token_events_changed_since(&self, since: u64) -> Vec<u64> {
+        get_items_changed_since(&self.token_events, since, false)
+    }
+
+    pub fn get_token_events_deleted_since(&self, since: u64) -> Vec<u64> {
+        get_items_changed_since(&self.token_events, since, true)
+    }
+
+    pub fn get_hot_or_not_bets_placed_changed_since(&self, since: u64) -> Vec<(Principal, PostId)> {
+        get_items_changed_since(&self.hot_or_not_bets_placed, since, false)
+    }
+
+    /// Forgets deletions data_backup already applied. Token events are the only items that get
+    /// deleted, when the transaction history is trimmed.
+    pub fn forget_deletions_acknowledged_by_backup(&mut self, acknowledged: u64) {
+        self.token_events.retain(|_, tracked_change| {
+            !tracked_change.is_deleted || tracked_change.change_sequence_number > acknowledged
+        });
+    }
+
+    fn get_next_change_sequence_number(&mut self) -> u64 {
+        self.last_change_sequence_number += 1;
+        self.last_change_sequence_number
+    }
+}
+
+/// The first and last token event ids, to pass to
+/// [`BackupChangeTracker::record_token_balance_change`] after handling token events
+pub fn get_token_event_id_bounds(token_balance: &TokenBalance) -> Option<(u64, u64)> {
+    let utility_token_transaction_history = &token_balance.utility_token_transaction_history;
+
+    Some((
+        *utility_token_transaction_history.first_key_value()?.0,
+        *utility_token_transaction_history.last_key_value()?.0,
+    ))
+}
+
+fn record_item_change<K: Ord>(
+    tracked_items: &mut BTreeMap<K, TrackedChange>,
+    key: K,
+    change_sequence_number: u64,
+    is_deleted: bool,
+) {
+    tracked_items.insert(
+        key,
+        TrackedChange {
+            change_sequence_number,
+            is_deleted,
+        },
+    );
+}
+
+fn get_items_changed_since<K: Copy>(
+    tracked_items: &BTreeMap<K, TrackedChange>,
+    since: u64,
+    is_deleted: bool,
+) -> Vec<K> {
+    tracked_items
+        .iter()
+        .filter(|(_, tracked_change)| {
+            tracked_change.is_deleted == is_deleted && tracked_change.change_sequence_number > since
+        })
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::types::utility_token::token_event::TokenEvent;
+
+    use super::*;
+
+    #[test]
+    fn test_record_section_change() {
+        let mut backup_change_tracker = BackupChangeTracker::default();
+        assert!(!backup_change_tracker.has_section_changed_since(BackupSection::Profile, 0));
+
+        backup_change_tracker.record_section_change(BackupSection::Profile);
+        assert_eq!(backup_change_tracker.get_last_change_sequence_number(), 1);
+        assert!(backup_change_tracker.has_section_changed_since(BackupSection::Profile, 0));
+        assert!(!backup_change_tracker.has_section_changed_since(BackupSection::Profile, 1));
+        assert!(!backup_change_tracker.has_section_changed_since(BackupSection::TokenData, 0));
+
+        backup_change_tracker.record_section_change(BackupSection::Profile);
+        assert_eq!(backup_change_tracker.get_last_change_sequence_number(), 2);
+        assert!(backup_change_tracker.has_section_changed_since(BackupSection::Profile, 1));
+    }
+
+    #[test]
+    fn test_start_epoch_if_unset() {
+        let mut backup_change_tracker = BackupChangeTracker::default();
+        assert_eq!(backup_change_tracker.get_epoch(), 0);
+
+        backup_change_tracker.start_epoch_if_unset(5);
+        backup_change_tracker.start_epoch_if_unset(7);
+        assert_eq!(backup_change_tracker.get_epoch(), 5);
+    }
+
+    #[test]
+    fn test_record_token_event_changes_and_deletions() {
+        let mut backup_change_tracker = BackupChangeTracker::default();
+        (0..3).for_each(|token_event_id| {
+            backup_change_tracker.record_token_event_change(token_event_id)
+        });
+        assert_eq!(
+            backup_change_tracker.get_token_events_changed_since(0),
+            vec![0, 1, 2]
+        );
+        assert!(backup_change_tracker
+            .get_token_events_changed_since(3)
+            .is_empty());
+
+        backup_change_tracker.record_token_event_change(1);
+        backup_change_tracker.record_token_event_change(3);
+        backup_change_tracker.record_token_event_deletion(0);
+
+        assert_eq!(
+            backup_change_tracker.get_token_events_changed_since(3),
+            vec![1, 3]
+        );
+        assert_eq!(
+            backup_change_tracker.get_token_events_deleted_since(3),
+            vec![0]
+        );
+        assert_eq!(backup_change_tracker.get_last_change_sequence_number(), 6);
+
+        // * a deletion is kept until a run that sent it is acknowledged
+        backup_change_tracker.forget_deletions_acknowledged_by_backup(5);
+        assert_eq!(
+            backup_change_tracker.get_token_events_deleted_since(3),
+            vec![0]
+        );
+        backup_change_tracker.forget_deletions_acknowledged_by_backup(6);
+        assert!(backup_change_tracker
+            .get_token_events_deleted_since(0)
+            .is_empty());
+        assert_eq!(
+            backup_change_tracker.get_token_events_changed_since(0),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_record_token_balance_change() {
+        let mut backup_change_tracker = BackupChangeTracker::default();
+        let mut token_balance = TokenBalance::default();
+
+        (0..1501).for_each(|_| token_balance.handle_token_event(TokenEvent::Burn));
+        backup_change_tracker.record_token_balance_change(None, &token_balance);
+        assert!(backup_change_tracker.has_section_changed_since(BackupSection::TokenData, 0));
+        assert_eq!(
+            backup_change_tracker
+                .get_token_events_changed_since(0)
+                .len(),
+            1501
+        );
+        let since = backup_change_tracker.get_last_change_sequence_number();
+
+        // * handling an event past 1500 entries trims the oldest ones
+        let token_event_ids_before = get_token_event_id_bounds(&token_balance);
+        token_balance.handle_token_event(TokenEvent::Burn);
+        backup_change_tracker.record_token_balance_change(token_event_ids_before, &token_balance);
+
+        assert!(backup_change_tracker.has_section_changed_since(BackupSection::TokenData, since));
+        assert_eq!(
+            backup_change_tracker.get_token_events_changed_since(since),
+            vec![1502]
+        );
+        assert_eq!(
+            backup_change_tracker.get_token_events_deleted_since(since),
+            (1..=501).collect::<Vec<_>>()
+        );
+        assert_eq!(token_balance.utility_token_transaction_history.len(), 1001);
+    }
+}
@@ -1,6 +1,7 @@
+use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Deserialize, Serialize)]
+#[derive(Default, Deserialize, Serialize, CandidType, Clone, Debug)]
 pub struct IndividualUserConfiguration {
     pub url_to_send_canister_metrics_to: Option<String>,
 }
@@ -126,7 +126,7 @@ pub enum RoomBetPossibleOutcomes {
     Draw,
 }
 
-#[derive(Deserialize, Serialize, Clone, CandidType)]
+#[derive(Deserialize, Serialize, Clone, CandidType, Debug)]
 pub struct PlacedBetDetail {
     pub canister_id: CanisterId,
     pub post_id: PostId,
@@ -138,6 +138,22 @@ pub struct PlacedBetDetail {
     pub outcome_received: BetOutcomeForBetMaker,
 }
 
+/// Bets of a canister that are yet to be settled. Its user's account can only be deleted once
+/// both counts are zero.
+#[derive(CandidType, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenHotOrNotBetCounts {
+    /// Bets placed by the user whose outcome has not been received yet
+    pub bets_awaiting_result_count: u64,
+    /// Posts of the user that still accept bets or have rooms whose outcome is not tabulated
+    pub posts_with_open_bets_count: u64,
+}
+
+impl OpenHotOrNotBetCounts {
+    pub fn is_settled(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, CandidType, PartialEq, Eq, Clone, Debug)]
 pub enum BetOutcomeForBetMaker {
     #[default]
@@ -1,8 +1,11 @@
+pub mod activity;
 pub mod arg;
+pub mod backup_change_tracker;
 pub mod configuration;
 pub mod error;
 pub mod follow;
 pub mod hot_or_not;
 pub mod post;
+pub mod post_score_sync;
 pub mod profile;
 pub mod token;
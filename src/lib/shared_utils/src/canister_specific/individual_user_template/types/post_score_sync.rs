@@ -0,0 +1,201 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::types::{
+        app_primitive_type::PostId, top_posts::post_score_index_item::PostScoreIndexItemV1,
+    },
+    constant::MAX_POST_SCORES_IN_ONE_SYNC_BATCH,
+};
+
+/// Score updates waiting to be pushed to the post cache. Only the latest score of a post
+/// is kept, so a burst of interactions on the same post collapses into a single entry.
+#[derive(Default, Serialize, Deserialize, CandidType, Clone, Debug)]
+pub struct PostScoreSyncQueue {
+    pub home_feed: BTreeMap<PostId, PostScoreIndexItemV1>,
+    pub hot_or_not_feed: BTreeMap<PostId, PostScoreIndexItemV1>,
+    #[serde(skip)]
+    pub is_flush_scheduled: bool,
+    /// Flushes that failed in a row since the last one that went through
+    #[serde(skip)]
+    pub consecutive_failed_flush_count: u32,
+}
+
+impl PostScoreSyncQueue {
+    pub fn enqueue(
+        &mut self,
+        home_feed_item: Option<PostScoreIndexItemV1>,
+        hot_or_not_feed_item: Option<PostScoreIndexItemV1>,
+    ) {
+        if let Some(item) = home_feed_item {
+            self.home_feed.insert(item.post_id, item);
+        }
+
+        if let Some(item) = hot_or_not_feed_item {
+            self.hot_or_not_feed.insert(item.post_id, item);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.home_feed.is_empty() && self.hot_or_not_feed.is_empty()
+    }
+
+    pub fn is_batch_full(&self) -> bool {
+        self.home_feed.len() >= MAX_POST_SCORES_IN_ONE_SYNC_BATCH
+            || self.hot_or_not_feed.len() >= MAX_POST_SCORES_IN_ONE_SYNC_BATCH
+    }
+
+    pub fn record_flush_result(&mut self, succeeded: bool) {
+        if succeeded {
+            self.consecutive_failed_flush_count = 0;
+        } else {
+            self.consecutive_failed_flush_count =
+                self.consecutive_failed_flush_count.saturating_add(1);
+        }
+    }
+
+    /// `base_delay`, doubled for every flush that failed in a row, up to `max_delay`
+    pub fn get_flush_delay(&self, base_delay: Duration, max_delay: Duration) -> Duration {
+        base_delay
+            .checked_mul(2_u32.saturating_pow(self.consecutive_failed_flush_count))
+            .unwrap_or(max_delay)
+            .min(max_delay)
+    }
+
+    /// Removes and returns up to one batch of items per feed.
+    pub fn take_batch(&mut self) -> (Vec<PostScoreIndexItemV1>, Vec<PostScoreIndexItemV1>) {
+        (
+            Self::take_from(&mut self.home_feed),
+            Self::take_from(&mut self.hot_or_not_feed),
+        )
+    }
+
+    /// Puts back items whose flush failed. Items queued again since the flush started
+    /// carry a newer score and are kept instead.
+    pub fn requeue_home_feed(&mut self, items: Vec<PostScoreIndexItemV1>) {
+        Self::requeue_into(&mut self.home_feed, items);
+    }
+
+    pub fn requeue_hot_or_not_feed(&mut self, items: Vec<PostScoreIndexItemV1>) {
+        Self::requeue_into(&mut self.hot_or_not_feed, items);
+    }
+
+    fn take_from(queue: &mut BTreeMap<PostId, PostScoreIndexItemV1>) -> Vec<PostScoreIndexItemV1> {
+        let post_ids: Vec<PostId> = queue
+            .keys()
+            .take(MAX_POST_SCORES_IN_ONE_SYNC_BATCH)
+            .cloned()
+            .collect();
+
+        post_ids
+            .iter()
+            .filter_map(|post_id| queue.remove(post_id))
+            .collect()
+    }
+
+    fn requeue_into(
+        queue: &mut BTreeMap<PostId, PostScoreIndexItemV1>,
+        items: Vec<PostScoreIndexItemV1>,
+    ) {
+        for item in items {
+            queue.entry(item.post_id).or_insert(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use candid::Principal;
+
+    use super::*;
+
+    fn get_mock_post_score_index_item(post_id: u64, score: u64) -> PostScoreIndexItemV1 {
+        PostScoreIndexItemV1 {
+            score,
+            post_id,
+            publisher_canister_id: Principal::from_text("w4nuc-waaaa-aaaao-aal2a-cai").unwrap(),
+            is_nsfw: false,
+            created_at: None,
+            status: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_enqueue_keeps_only_latest_score_per_post() {
+        let mut queue = PostScoreSyncQueue::default();
+
+        queue.enqueue(Some(get_mock_post_score_index_item(0, 10)), None);
+        queue.enqueue(
+            Some(get_mock_post_score_index_item(0, 20)),
+            Some(get_mock_post_score_index_item(0, 5)),
+        );
+
+        assert_eq!(queue.home_feed.len(), 1);
+        assert_eq!(queue.home_feed.get(&0).unwrap().score, 20);
+        assert_eq!(queue.hot_or_not_feed.len(), 1);
+        assert!(!queue.is_batch_full());
+    }
+
+    #[test]
+    fn test_take_batch_is_bounded_by_batch_size() {
+        let mut queue = PostScoreSyncQueue::default();
+
+        for post_id in 0..(MAX_POST_SCORES_IN_ONE_SYNC_BATCH as u64 + 5) {
+            queue.enqueue(Some(get_mock_post_score_index_item(post_id, post_id)), None);
+        }
+        assert!(queue.is_batch_full());
+
+        let (home_feed_items, hot_or_not_feed_items) = queue.take_batch();
+        assert_eq!(home_feed_items.len(), MAX_POST_SCORES_IN_ONE_SYNC_BATCH);
+        assert!(hot_or_not_feed_items.is_empty());
+        assert_eq!(queue.home_feed.len(), 5);
+        assert!(!queue.is_batch_full());
+    }
+
+    #[test]
+    fn test_get_flush_delay_backs_off_after_failed_flushes() {
+        let mut queue = PostScoreSyncQueue::default();
+        let base_delay = Duration::from_secs(10);
+        let max_delay = Duration::from_secs(600);
+
+        assert_eq!(queue.get_flush_delay(base_delay, max_delay), base_delay);
+
+        queue.record_flush_result(false);
+        queue.record_flush_result(false);
+        assert_eq!(
+            queue.get_flush_delay(base_delay, max_delay),
+            Duration::from_secs(40)
+        );
+
+        for _ in 0..40 {
+            queue.record_flush_result(false);
+        }
+        assert_eq!(queue.get_flush_delay(base_delay, max_delay), max_delay);
+
+        queue.record_flush_result(true);
+        assert_eq!(queue.get_flush_delay(base_delay, max_delay), base_delay);
+    }
+
+    #[test]
+    fn test_requeue_does_not_overwrite_newer_scores() {
+        let mut queue = PostScoreSyncQueue::default();
+
+        queue.enqueue(
+            Some(get_mock_post_score_index_item(0, 10)),
+            Some(get_mock_post_score_index_item(1, 10)),
+        );
+        let (home_feed_items, hot_or_not_feed_items) = queue.take_batch();
+        assert!(queue.is_empty());
+
+        // * a newer score for post 0 arrives while the flush is in flight
+        queue.enqueue(Some(get_mock_post_score_index_item(0, 30)), None);
+
+        queue.requeue_home_feed(home_feed_items);
+        queue.requeue_hot_or_not_feed(hot_or_not_feed_items);
+
+        assert_eq!(queue.home_feed.get(&0).unwrap().score, 30);
+        assert_eq!(queue.hot_or_not_feed.get(&1).unwrap().score, 10);
+    }
+}
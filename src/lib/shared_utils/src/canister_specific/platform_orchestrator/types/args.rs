@@ -0,0 +1,8 @@
+use candid::{CandidType, Deserialize};
+
+use crate::common::types::known_principal::KnownPrincipalMap;
+
+#[derive(Deserialize, CandidType, Default)]
+pub struct PlatformOrchestratorInitArgs {
+    pub known_principal_ids: Option<KnownPrincipalMap>,
+}
@@ -0,0 +1,91 @@
+use std::cmp::{Ordering, Reverse};
+
+use candid::{CandidType, Decode, Deserialize, Encode};
+
+use crate::common::types::top_posts::{
+    post_score_index_item::PostScoreIndexItemV1, GlobalPostId, Score,
+};
+
+/// Opaque token handed out to clients. Clients are expected to pass it back as is
+/// to fetch the next page and must not rely on its contents.
+pub type FeedCursorToken = Vec<u8>;
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeedCursor {
+    pub snapshot_generation: u64,
+    pub last_seen_score: Score,
+    pub last_seen_post: GlobalPostId,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct FeedPage {
+    pub posts: Vec<PostScoreIndexItemV1>,
+    pub next_cursor: Option<FeedCursorToken>,
+}
+
+impl FeedCursor {
+    pub fn to_token(&self) -> FeedCursorToken {
+        Encode!(self).unwrap()
+    }
+
+    pub fn from_token(token: &[u8]) -> Option<Self> {
+        Decode!(token, Self).ok()
+    }
+
+    /// Whether an item with the given score and id comes strictly after this cursor
+    /// in feed order, i.e. score descending with ties broken by global post id.
+    pub fn is_item_after(&self, score: Score, global_post_id: &GlobalPostId) -> bool {
+        feed_order_key(self.last_seen_score, &self.last_seen_post)
+            .cmp(&feed_order_key(score, global_post_id))
+            == Ordering::Less
+    }
+}
+
+pub fn feed_order_key(
+    score: Score,
+    global_post_id: &GlobalPostId,
+) -> (Reverse<Score>, GlobalPostId) {
+    (Reverse(score), *global_post_id)
+}
+
+#[cfg(test)]
+mod test {
+    use candid::Principal;
+
+    use super::*;
+
+    #[test]
+    fn test_feed_cursor_token_roundtrip() {
+        let cursor = FeedCursor {
+            snapshot_generation: 7,
+            last_seen_score: 42,
+            last_seen_post: (
+                Principal::from_text("w4nuc-waaaa-aaaao-aal2a-cai").unwrap(),
+                3,
+            ),
+        };
+
+        assert_eq!(FeedCursor::from_token(&cursor.to_token()), Some(cursor));
+        assert_eq!(FeedCursor::from_token(&[1, 2, 3]), None);
+    }
+
+    #[test]
+    fn test_feed_cursor_is_item_after() {
+        let publisher_canister_id = Principal::from_text("w4nuc-waaaa-aaaao-aal2a-cai").unwrap();
+        let cursor = FeedCursor {
+            snapshot_generation: 0,
+            last_seen_score: 10,
+            last_seen_post: (publisher_canister_id, 5),
+        };
+
+        // * lower scores come later in the feed
+        assert!(cursor.is_item_after(9, &(publisher_canister_id, 1)));
+        // * higher scores were already served
+        assert!(!cursor.is_item_after(11, &(publisher_canister_id, 9)));
+        // * ties are broken by post id
+        assert!(cursor.is_item_after(10, &(publisher_canister_id, 6)));
+        assert!(!cursor.is_item_after(10, &(publisher_canister_id, 4)));
+        // * the cursor item itself is not served again
+        assert!(!cursor.is_item_after(10, &(publisher_canister_id, 5)));
+    }
+}
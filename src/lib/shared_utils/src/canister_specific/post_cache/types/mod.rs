@@ -1 +1,3 @@
 pub mod arg;
+pub mod feed_cursor;
+pub mod score_sync;
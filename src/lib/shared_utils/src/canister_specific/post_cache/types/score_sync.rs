@@ -0,0 +1,8 @@
+use candid::{CandidType, Deserialize};
+
+/// Returned by the post cache once a batch of scores has been indexed, so that the
+/// publishing canister knows it can drop the batch from its queue.
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PostScoresReceivedAck {
+    pub number_of_posts_received: u64,
+}
@@ -0,0 +1,52 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// One chunk of an individual user template wasm upload. Chunks have to be sent in order,
+/// starting at index 0. Sending index 0 again restarts the upload.
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndividualUserTemplateWasmChunk {
+    pub version: String,
+    /// sha256 of the whole wasm
+    pub wasm_hash: Vec<u8>,
+    pub chunk_index: u32,
+    pub total_chunks: u32,
+    pub chunk: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndividualUserTemplateWasmDetails {
+    pub version: String,
+    pub wasm_hash: Vec<u8>,
+    pub wasm_size_in_bytes: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndividualUserTemplateWasmStoreDetails {
+    pub current: Option<IndividualUserTemplateWasmDetails>,
+    pub previous: Option<IndividualUserTemplateWasmDetails>,
+}
+
+pub fn get_wasm_hash(wasm_module: &[u8]) -> Vec<u8> {
+    Sha256::digest(wasm_module).to_vec()
+}
+
+impl IndividualUserTemplateWasmChunk {
+    pub fn split_wasm_module(version: &str, wasm_module: &[u8], chunk_size: usize) -> Vec<Self> {
+        let wasm_hash = get_wasm_hash(wasm_module);
+        let chunks: Vec<&[u8]> = wasm_module.chunks(chunk_size).collect();
+        let total_chunks = chunks.len() as u32;
+
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(chunk_index, chunk)| Self {
+                version: version.to_string(),
+                wasm_hash: wasm_hash.clone(),
+                chunk_index: chunk_index as u32,
+                total_chunks,
+                chunk: chunk.to_vec(),
+            })
+            .collect()
+    }
+}
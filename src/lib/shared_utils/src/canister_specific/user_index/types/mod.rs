@@ -1 +1,3 @@
 pub mod args;
+pub mod individual_user_template_wasm;
+pub mod user_name_search;
@@ -0,0 +1,15 @@
+use candid::{CandidType, Deserialize, Principal};
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserNameSearchResult {
+    pub user_name: String,
+    pub user_principal_id: Principal,
+    pub canister_id: Principal,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct UserNameSearchPage {
+    pub results: Vec<UserNameSearchResult>,
+    /// Pass back as the cursor to get the next page. `None` once all matches were returned.
+    pub next_cursor: Option<String>,
+}
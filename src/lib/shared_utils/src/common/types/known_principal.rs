@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
 
-#[derive(CandidType, Deserialize, PartialEq, Eq, Hash, Serialize, Copy, Clone)]
+#[derive(CandidType, Deserialize, PartialEq, Eq, Hash, Serialize, Copy, Clone, Debug)]
 pub enum KnownPrincipalType {
     UserIdGlobalSuperAdmin,
     CanisterIdConfiguration,
@@ -15,6 +15,7 @@ pub enum KnownPrincipalType {
     CanisterIdTopicCacheIndex,
     CanisterIdUserIndex,
     CanisterIdSnsGovernance,
+    CanisterIdPlatformOrchestrator,
 }
 
 pub type KnownPrincipalMap = HashMap<KnownPrincipalType, Principal>;
@@ -1,7 +1,7 @@
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
 
-#[derive(Default, CandidType, Deserialize, Serialize)]
+#[derive(Default, CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct VersionDetails {
     pub version_number: u64,
     #[serde(default)]
@@ -1,3 +1,4 @@
 pub mod stable_memory_serializer_deserializer;
 pub mod task;
+pub mod user_name;
 pub mod system_time;
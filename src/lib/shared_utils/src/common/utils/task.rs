@@ -1,4 +1,4 @@
-use std::pin::Pin;
+use std::{cell::Cell, pin::Pin, thread::LocalKey};
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 
 pub async fn run_task_concurrently<T>(
@@ -37,4 +37,49 @@ pub async fn run_task_concurrently<T>(
             }
         }
 
-}
\ No newline at end of file
+}
+
+/// Marks a job as in progress until dropped. The future of a job is dropped when one of its
+/// callbacks traps, so a trapped run does not block later runs.
+pub struct InProgressGuard {
+    is_in_progress: &'static LocalKey<Cell<bool>>,
+}
+
+impl InProgressGuard {
+    /// None while the job is already in progress
+    pub fn acquire(is_in_progress: &'static LocalKey<Cell<bool>>) -> Option<Self> {
+        if is_in_progress.with(|is_in_progress| is_in_progress.replace(true)) {
+            return None;
+        }
+
+        Some(Self { is_in_progress })
+    }
+}
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        self.is_in_progress
+            .with(|is_in_progress| is_in_progress.set(false));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    thread_local! {
+        static IS_JOB_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+    }
+
+    #[test]
+    fn test_in_progress_guard() {
+        let in_progress_guard = InProgressGuard::acquire(&IS_JOB_IN_PROGRESS);
+        assert!(in_progress_guard.is_some());
+        assert!(IS_JOB_IN_PROGRESS.with(|is_in_progress| is_in_progress.get()));
+        assert!(InProgressGuard::acquire(&IS_JOB_IN_PROGRESS).is_none());
+
+        drop(in_progress_guard);
+        assert!(!IS_JOB_IN_PROGRESS.with(|is_in_progress| is_in_progress.get()));
+        assert!(InProgressGuard::acquire(&IS_JOB_IN_PROGRESS).is_some());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::types::canister_specific::user_index::error_types::SetUniqueUsernameError;
+
+pub const MIN_USER_NAME_LENGTH: usize = 3;
+pub const MAX_USER_NAME_LENGTH: usize = 20;
+
+/// Names no user can claim regardless of what is on the admin managed blocklist
+pub const DEFAULT_BLOCKED_USER_NAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "help",
+    "hotornot",
+    "moderator",
+    "official",
+    "root",
+    "security",
+    "staff",
+    "support",
+    "system",
+];
+
+/// Case folds a username. Usernames are stored and looked up in this form.
+pub fn normalize_user_name(user_name: &str) -> String {
+    user_name.trim().to_lowercase()
+}
+
+/// Normalizes a username and checks its length and characters. Only lowercase ASCII letters,
+/// digits, `_` and `.` are allowed, which keeps out look-alike characters from other scripts.
+/// The name has to start with a letter or a digit.
+pub fn validate_and_normalize_user_name(user_name: &str) -> Result<String, SetUniqueUsernameError> {
+    let normalized_user_name = normalize_user_name(user_name);
+    let length = normalized_user_name.chars().count();
+
+    if length < MIN_USER_NAME_LENGTH {
+        return Err(SetUniqueUsernameError::UsernameTooShort);
+    }
+
+    if length > MAX_USER_NAME_LENGTH {
+        return Err(SetUniqueUsernameError::UsernameTooLong);
+    }
+
+    let has_only_allowed_characters = normalized_user_name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.');
+    let starts_with_alphanumeric =
+        normalized_user_name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit());
+
+    if !has_only_allowed_characters || !starts_with_alphanumeric {
+        return Err(SetUniqueUsernameError::UsernameHasInvalidCharacters);
+    }
+
+    Ok(normalized_user_name)
+}
+
+/// Reduces a username to the form it reads as, so that `adm1n` or `a_d_m_i_n` match `admin`
+pub fn get_user_name_skeleton(user_name: &str) -> String {
+    normalize_user_name(user_name)
+        .chars()
+        .filter(|c| *c != '_' && *c != '.')
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'l',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            'i' => 'l',
+            c => c,
+        })
+        .collect()
+}
+
+/// Checks a username against the default blocked names and the passed in blocklist
+pub fn is_user_name_blocked<'a>(
+    user_name: &str,
+    blocklist: impl IntoIterator<Item = &'a String>,
+) -> bool {
+    let skeleton = get_user_name_skeleton(user_name);
+
+    DEFAULT_BLOCKED_USER_NAMES
+        .iter()
+        .any(|blocked_user_name| get_user_name_skeleton(blocked_user_name) == skeleton)
+        || blocklist
+            .into_iter()
+            .any(|blocked_user_name| get_user_name_skeleton(blocked_user_name) == skeleton)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_normalize_user_name() {
+        assert_eq!(
+            validate_and_normalize_user_name(" Cool_Alice.1234 "),
+            Ok("cool_alice.1234".to_string())
+        );
+        assert_eq!(
+            validate_and_normalize_user_name("ab"),
+            Err(SetUniqueUsernameError::UsernameTooShort)
+        );
+        assert_eq!(
+            validate_and_normalize_user_name("a_really_long_username_1234"),
+            Err(SetUniqueUsernameError::UsernameTooLong)
+        );
+        assert_eq!(
+            validate_and_normalize_user_name("_alice"),
+            Err(SetUniqueUsernameError::UsernameHasInvalidCharacters)
+        );
+        assert_eq!(
+            validate_and_normalize_user_name("alice bob"),
+            Err(SetUniqueUsernameError::UsernameHasInvalidCharacters)
+        );
+        // * cyrillic 'а' looks like latin 'a'
+        assert_eq!(
+            validate_and_normalize_user_name("\u{0430}lice"),
+            Err(SetUniqueUsernameError::UsernameHasInvalidCharacters)
+        );
+    }
+
+    #[test]
+    fn test_is_user_name_blocked() {
+        let blocklist = vec!["badword".to_string()];
+
+        assert!(is_user_name_blocked("admin", &blocklist));
+        assert!(is_user_name_blocked("adm1n", &blocklist));
+        assert!(is_user_name_blocked("a_d_m_i_n", &blocklist));
+        assert!(is_user_name_blocked("BadW0rd", &blocklist));
+        assert!(!is_user_name_blocked("admiral", &blocklist));
+        assert!(!is_user_name_blocked("cool_alice", std::iter::empty()));
+    }
+}
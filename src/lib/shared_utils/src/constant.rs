@@ -9,6 +9,11 @@ pub const MAX_USERS_IN_FOLLOWER_FOLLOWING_LIST: u64 = 10000;
 pub const MAX_POSTS_IN_ONE_REQUEST: u64 = 100;
 pub const HOME_FEED_DIFFERENCE_TO_INITIATE_SYNCHRONISATION: u64 = 100;
 pub const HOT_OR_NOT_FEED_DIFFERENCE_TO_INITIATE_SYNCHRONISATION: u64 = 100;
+pub const MAX_POST_SCORES_IN_ONE_SYNC_BATCH: usize = 50;
+pub const MAX_USER_NAME_SEARCH_RESULTS_PER_REQUEST: u64 = 20;
+pub const MAX_USER_NAMES_SCANNED_PER_SEARCH_REQUEST: usize = 500;
+// * install_code payloads are limited to 2 MiB
+pub const MAX_INDIVIDUAL_USER_TEMPLATE_WASM_SIZE_IN_BYTES: usize = 2 * 1024 * 1024;
 // * Important Principal IDs
 pub const GOVERNANCE_CANISTER_ID: &str = "6wcax-haaaa-aaaaq-aaava-cai";
 
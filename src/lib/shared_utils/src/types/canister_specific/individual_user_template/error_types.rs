@@ -7,6 +7,11 @@ pub enum UpdateProfileSetUniqueUsernameError {
     SendingCanisterDoesNotMatchUserCanisterId,
     UserCanisterEntryDoesNotExist,
     UserIndexCrossCanisterCallFailed,
+    UsernameChangeOnCooldown,
+    UsernameTooShort,
+    UsernameTooLong,
+    UsernameHasInvalidCharacters,
+    UsernameIsBlocked,
 }
 
 #[derive(CandidType, Debug, Deserialize)]
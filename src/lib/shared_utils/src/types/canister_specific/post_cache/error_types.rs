@@ -5,4 +5,5 @@ pub enum TopPostsFetchError {
     InvalidBoundsPassed,
     ReachedEndOfItemsList,
     ExceededMaxNumberOfItemsAllowedInOneRequest,
+    InvalidCursor,
 }
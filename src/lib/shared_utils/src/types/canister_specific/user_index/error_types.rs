@@ -5,4 +5,17 @@ pub enum SetUniqueUsernameError {
     UsernameAlreadyTaken,
     SendingCanisterDoesNotMatchUserCanisterId,
     UserCanisterEntryDoesNotExist,
+    UsernameChangeOnCooldown,
+    UsernameTooShort,
+    UsernameTooLong,
+    UsernameHasInvalidCharacters,
+    UsernameIsBlocked,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum SearchUserNamesError {
+    AnonymousCallerNotAllowed,
+    PrefixTooShort,
+    InvalidCursor,
+    RateLimitExceeded,
 }
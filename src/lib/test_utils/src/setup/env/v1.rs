@@ -1,19 +1,26 @@
 use std::{collections::HashMap, env, path::Path};
 
 use candid::Principal;
-use ic_test_state_machine_client::{CanisterSettings, StateMachine};
+use ic_test_state_machine_client::{CanisterSettings, StateMachine, WasmResult};
 use shared_utils::{
     access_control::UserAccessRole,
     canister_specific::{
         configuration::types::args::ConfigurationInitArgs,
-        data_backup::types::args::DataBackupInitArgs, post_cache::types::arg::PostCacheInitArgs,
-        user_index::types::args::UserIndexInitArgs,
+        data_backup::types::args::DataBackupInitArgs,
+        post_cache::types::arg::PostCacheInitArgs,
+        user_index::types::{
+            args::UserIndexInitArgs,
+            individual_user_template_wasm::{
+                IndividualUserTemplateWasmChunk, IndividualUserTemplateWasmDetails,
+            },
+        },
     },
     common::types::known_principal::{KnownPrincipalMap, KnownPrincipalType},
 };
 
 use crate::setup::test_constants::{
-    get_canister_wasm, get_global_super_admin_principal_id, get_mock_canister_id_sns,
+    get_canister_wasm, get_global_super_admin_principal_id,
+    get_individual_user_template_canister_wasm, get_mock_canister_id_sns,
     v1::{
         CANISTER_INITIAL_CYCLES_FOR_NON_SPAWNING_CANISTERS,
         CANISTER_INITIAL_CYCLES_FOR_SPAWNING_CANISTERS,
@@ -157,9 +164,60 @@ pub fn get_initialized_env_with_provisioned_known_canisters(
         .unwrap(),
     );
 
+    upload_individual_user_template_wasm_to_user_index(
+        state_machine,
+        *known_principal_map_with_all_canisters
+            .get(&KnownPrincipalType::CanisterIdUserIndex)
+            .unwrap(),
+    );
+
+    state_machine
+        .update_call(
+            *known_principal_map_with_all_canisters
+                .get(&KnownPrincipalType::CanisterIdUserIndex)
+                .unwrap(),
+            get_global_super_admin_principal_id(),
+            "toggle_signups_enabled",
+            candid::encode_one(()).unwrap(),
+        )
+        .unwrap();
+
     known_principal_map_with_all_canisters
 }
 
+const INDIVIDUAL_USER_TEMPLATE_WASM_CHUNK_SIZE_IN_BYTES: usize = 512 * 1024;
+
+pub fn upload_individual_user_template_wasm_to_user_index(
+    state_machine: &StateMachine,
+    user_index_canister_id: Principal,
+) {
+    let wasm_chunks = IndividualUserTemplateWasmChunk::split_wasm_module(
+        "v1.0.0",
+        &get_individual_user_template_canister_wasm(),
+        INDIVIDUAL_USER_TEMPLATE_WASM_CHUNK_SIZE_IN_BYTES,
+    );
+
+    for wasm_chunk in wasm_chunks {
+        let upload_result = state_machine
+            .update_call(
+                user_index_canister_id,
+                get_global_super_admin_principal_id(),
+                "upload_individual_user_template_wasm_chunk",
+                candid::encode_one(wasm_chunk).unwrap(),
+            )
+            .map(|reply_payload| match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one::<
+                    Result<Option<IndividualUserTemplateWasmDetails>, String>,
+                >(&payload)
+                .unwrap(),
+                _ => panic!("\n🛑 upload_individual_user_template_wasm_chunk failed\n"),
+            })
+            .unwrap();
+
+        assert!(upload_result.is_ok());
+    }
+}
+
 pub fn get_canister_id_of_specific_type_from_principal_id_map(
     principal_id_map: &KnownPrincipalMap,
     canister_type: KnownPrincipalType,
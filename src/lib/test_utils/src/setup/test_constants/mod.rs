@@ -1,7 +1,12 @@
 use candid::Principal;
 use ic_cdk::api::management_canister::provisional::CanisterId;
-use shared_utils::common::types::known_principal::KnownPrincipalType;
-use std::{fs::File, io::Read, path::PathBuf};
+use shared_utils::{
+    canister_specific::individual_user_template::types::hot_or_not::{
+        BetDirection, BetOutcomeForBetMaker, PlacedBetDetail,
+    },
+    common::types::known_principal::KnownPrincipalType,
+};
+use std::{fs::File, io::Read, path::PathBuf, time::SystemTime};
 
 pub mod v1;
 
@@ -53,6 +58,10 @@ pub fn get_mock_canister_id_data_backup() -> Principal {
     CanisterId::from_slice(&7_usize.to_ne_bytes())
 }
 
+pub fn get_mock_canister_id_platform_orchestrator() -> Principal {
+    CanisterId::from_slice(&12_usize.to_ne_bytes())
+}
+
 pub fn get_mock_user_alice_canister_id() -> Principal {
     CanisterId::from_slice(&8_usize.to_ne_bytes())
 }
@@ -69,6 +78,22 @@ pub fn get_mock_user_dan_canister_id() -> Principal {
     CanisterId::from_slice(&11_usize.to_ne_bytes())
 }
 
+pub fn get_mock_placed_bet_detail(post_id: u64) -> ((Principal, u64), PlacedBetDetail) {
+    (
+        (get_mock_user_bob_canister_id(), post_id),
+        PlacedBetDetail {
+            canister_id: get_mock_user_bob_canister_id(),
+            post_id,
+            slot_id: 1,
+            room_id: 1,
+            amount_bet: 100,
+            bet_direction: BetDirection::Hot,
+            bet_placed_at: SystemTime::UNIX_EPOCH,
+            outcome_received: BetOutcomeForBetMaker::AwaitingResult,
+        },
+    )
+}
+
 pub fn get_user_index_canister_wasm() -> Vec<u8> {
     let mut file_path = PathBuf::from(
         std::env::var("CARGO_MANIFEST_DIR")
@@ -83,6 +108,21 @@ pub fn get_user_index_canister_wasm() -> Vec<u8> {
     bytes
 }
 
+pub fn get_individual_user_template_canister_wasm() -> Vec<u8> {
+    let mut file_path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("Failed to read CARGO_MANIFEST_DIR env variable"),
+    );
+    file_path
+        .push("../../../target/wasm32-unknown-unknown/release/individual_user_template.wasm.gz");
+
+    let mut file = File::open(&file_path)
+        .unwrap_or_else(|_| panic!("Failed to open file: {}", file_path.to_str().unwrap()));
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).expect("Failed to read file");
+    bytes
+}
+
 pub fn get_configuration_canister_wasm() -> Vec<u8> {
     let mut file_path = PathBuf::from(
         std::env::var("CARGO_MANIFEST_DIR")